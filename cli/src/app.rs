@@ -5,6 +5,8 @@
 //! Adding a new cross-cutting concern (e.g. `--verbose`, telemetry) requires
 //! only one field change here — zero command signatures change.
 
+use std::io::IsTerminal;
+
 use anyhow::Result;
 
 use crate::infra::assets::EmbeddedAssets;
@@ -15,7 +17,8 @@ use crate::infra::network::TokioNetworkProbe;
 use crate::infra::provisioner::MultipassProvisioner;
 use crate::infra::ssh::SshConfigManager;
 use crate::infra::state::StateManager;
-use crate::output::{HumanRenderer, JsonRenderer, OutputContext, Renderer};
+use crate::infra::trace::TracingRunner;
+use crate::output::{HumanRenderer, JsonRenderer, ListFormat, OutputContext, Renderer};
 
 /// Output rendering mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -27,6 +30,7 @@ pub enum OutputMode {
 }
 
 /// Output rendering flags.
+#[allow(clippy::struct_excessive_bools)] // Mirrors independent CLI flags
 pub struct OutputFlags {
     /// Disable ANSI color output.
     pub no_color: bool,
@@ -34,12 +38,16 @@ pub struct OutputFlags {
     pub quiet: bool,
     /// Enable JSON output mode.
     pub json: bool,
+    /// Disable spinners and progress bars only.
+    pub no_progress: bool,
 }
 
 /// Behaviour flags.
 pub struct BehaviourFlags {
     /// Skip interactive prompts (also set by `CI` / `POLIS_YES` env vars).
     pub yes: bool,
+    /// Disable network calls (also set by the `POLIS_OFFLINE` env var).
+    pub offline: bool,
 }
 
 /// Flags passed from the top-level CLI to `AppContext::new`.
@@ -60,7 +68,7 @@ pub struct AppContext {
     /// Output rendering mode (human vs JSON).
     pub mode: OutputMode,
     /// Multipass VM provisioner.
-    pub provisioner: MultipassProvisioner<TokioCommandRunner>,
+    pub provisioner: MultipassProvisioner<TracingRunner<TokioCommandRunner>>,
     /// Workspace state manager.
     pub state_mgr: StateManager,
     /// Embedded assets extractor.
@@ -72,6 +80,11 @@ pub struct AppContext {
     /// Set when `--yes` / `-y` is passed, or when the `CI` or `POLIS_YES`
     /// environment variables are present.
     pub non_interactive: bool,
+    /// When `true`, skip every network call and work from cached assets.
+    ///
+    /// Set when `--offline` is passed, or when `POLIS_OFFLINE` is set to a
+    /// value other than empty or `0`.
+    pub offline: bool,
 
     /// Command runner for local process execution.
     pub cmd_runner: TokioCommandRunner,
@@ -92,6 +105,8 @@ impl AppContext {
     pub fn new(flags: &AppFlags) -> Result<Self> {
         let ci_env = std::env::var("CI").is_ok() || std::env::var("POLIS_YES").is_ok();
         let non_interactive = flags.behaviour.yes || ci_env;
+        let offline = flags.behaviour.offline
+            || std::env::var("POLIS_OFFLINE").is_ok_and(|v| !v.is_empty() && v != "0");
 
         let mode = if flags.output.json {
             OutputMode::Json
@@ -100,13 +115,19 @@ impl AppContext {
         };
 
         Ok(Self {
-            output: OutputContext::new(flags.output.no_color, flags.output.quiet),
+            // JSON goes to programs, not people: never colorize it.
+            output: OutputContext::new(
+                flags.output.no_color || flags.output.json,
+                flags.output.quiet,
+            )
+            .with_no_progress(flags.output.no_progress),
             mode,
             provisioner: MultipassProvisioner::default_runner(),
             state_mgr: StateManager::new()?,
             assets: EmbeddedAssets,
             ssh: SshConfigManager::new()?,
             non_interactive,
+            offline,
             cmd_runner: TokioCommandRunner::new(DEFAULT_CMD_TIMEOUT),
             network_probe: TokioNetworkProbe,
             local_fs: LocalFs,
@@ -130,6 +151,17 @@ impl AppContext {
         }
     }
 
+    /// Format for a list-like command: `--json` wins, then the command's
+    /// `--format`, then a table.
+    #[must_use]
+    pub fn list_format(&self, requested: Option<ListFormat>) -> ListFormat {
+        if self.is_json() {
+            ListFormat::Json
+        } else {
+            requested.unwrap_or(ListFormat::Table)
+        }
+    }
+
     /// Ask the user for confirmation.
     ///
     /// When `non_interactive` is `true` (CI, `--yes` flag, or `POLIS_YES` env),
@@ -137,9 +169,10 @@ impl AppContext {
     ///
     /// # Errors
     ///
-    /// Returns an error if the terminal prompt fails (e.g. no TTY available).
+    /// Returns an error if stdin is not a terminal (so nobody can answer),
+    /// or if the terminal prompt fails.
     pub fn confirm(&self, prompt: &str, default: bool) -> Result<bool> {
-        if self.non_interactive {
+        if !can_prompt(self.non_interactive, std::io::stdin().is_terminal(), prompt)? {
             return Ok(default);
         }
         let confirmed = dialoguer::Confirm::new()
@@ -149,6 +182,22 @@ impl AppContext {
         Ok(confirmed)
     }
 
+    /// Registry prefix for the control-plane images: `POLIS_REGISTRY_PREFIX`,
+    /// then `registry.prefix` from the config file, then the default.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config file cannot be read or the prefix is
+    /// not a well-formed registry path.
+    pub fn registry_prefix(&self) -> Result<String> {
+        use crate::application::ports::ConfigStore;
+        use crate::domain::config::{REGISTRY_PREFIX_ENV, resolve_registry_prefix};
+
+        let config = self.config_store.load()?;
+        let env = std::env::var(REGISTRY_PREFIX_ENV).ok();
+        resolve_registry_prefix(env.as_deref(), &config.registry)
+    }
+
     /// Returns a `TerminalReporter` bound to this context's output.
     #[must_use]
     #[allow(dead_code)] // Not yet called from command handlers
@@ -156,6 +205,14 @@ impl AppContext {
         crate::output::reporter::TerminalReporter::new(&self.output)
     }
 
+    /// Provisioner for the throwaway `polis self-test` VM. Every call it
+    /// makes targets `SELF_TEST_INSTANCE`, never the user's workspace.
+    #[must_use]
+    pub fn self_test_provisioner(&self) -> MultipassProvisioner<TracingRunner<TokioCommandRunner>> {
+        MultipassProvisioner::default_runner()
+            .with_instance(crate::application::ports::SELF_TEST_INSTANCE)
+    }
+
     /// Extract bundled assets to a temp directory and return the path.
     ///
     /// The returned `TempDir` guard must be kept alive until all operations
@@ -170,3 +227,95 @@ impl AppContext {
         Ok((path, guard))
     }
 }
+
+/// Whether a confirmation prompt should be shown.
+///
+/// Returns `Ok(false)` when prompts are skipped (`--yes`, `CI`, `POLIS_YES`).
+///
+/// # Errors
+///
+/// Returns an error with `--yes` guidance when prompts are enabled but stdin
+/// is not a terminal, instead of letting the prompt fail on a closed stdin.
+fn can_prompt(non_interactive: bool, stdin_is_terminal: bool, prompt: &str) -> Result<bool> {
+    if non_interactive {
+        return Ok(false);
+    }
+    anyhow::ensure!(
+        stdin_is_terminal,
+        "cannot ask \"{prompt}\": stdin is not a terminal.\n\
+         Re-run with --yes (or set POLIS_YES=1) to accept the default."
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can_prompt_yes_skips_prompt_without_terminal() {
+        assert!(!can_prompt(true, false, "Update CLI now?").expect("--yes"));
+    }
+
+    #[test]
+    fn test_can_prompt_terminal_prompts() {
+        assert!(can_prompt(false, true, "Update CLI now?").expect("tty"));
+    }
+
+    #[test]
+    fn test_can_prompt_non_terminal_without_yes_errors_with_guidance() {
+        let msg = can_prompt(false, false, "Update CLI now?")
+            .expect_err("non-tty")
+            .to_string();
+        assert!(msg.contains("Update CLI now?"), "{msg}");
+        assert!(msg.contains("--yes"), "{msg}");
+    }
+
+    #[test]
+    fn test_confirm_yes_returns_default() {
+        let app = AppContext::new(&AppFlags {
+            output: OutputFlags {
+                no_color: true,
+                quiet: true,
+                json: false,
+                no_progress: false,
+            },
+            behaviour: BehaviourFlags {
+                yes: true,
+                offline: false,
+            },
+        })
+        .expect("AppContext");
+        assert!(app.confirm("Update CLI now?", true).expect("confirm"));
+        assert!(!app.confirm("Delete?", false).expect("confirm"));
+    }
+
+    #[test]
+    fn test_list_format_json_flag_wins_over_format() {
+        let app = |json| {
+            AppContext::new(&AppFlags {
+                output: OutputFlags {
+                    no_color: true,
+                    quiet: true,
+                    json,
+                    no_progress: false,
+                },
+                behaviour: BehaviourFlags {
+                    yes: true,
+                    offline: false,
+                },
+            })
+            .expect("AppContext")
+        };
+        assert_eq!(app(false).list_format(None), ListFormat::Table);
+        assert_eq!(
+            app(false).list_format(Some(ListFormat::Csv)),
+            ListFormat::Csv
+        );
+        assert_eq!(
+            app(true).list_format(Some(ListFormat::Csv)),
+            ListFormat::Json
+        );
+    }
+}
@@ -4,11 +4,15 @@
 //! This file imports only from `crate::domain` — never from `crate::infra`,
 //! `crate::commands`, or `crate::output`.
 
-use std::collections::HashMap;
 use std::process::Output;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 
+use crate::domain::agent::artifacts::GeneratedFile;
+use crate::domain::instance::{
+    ContainerStatus, InstanceInfo, compose_ps_args, is_missing_instance, parse_compose_ps,
+    parse_instance_info,
+};
 use crate::domain::{DoctorChecks, WorkspaceState};
 
 // ── Constants ─────────────────────────────────────────────────────────────────
@@ -16,6 +20,9 @@ use crate::domain::{DoctorChecks, WorkspaceState};
 /// The canonical VM instance name used by all trait implementations.
 pub const POLIS_INSTANCE: &str = "polis";
 
+/// Throwaway VM used by `polis self-test`; never the user's workspace.
+pub const SELF_TEST_INSTANCE: &str = "polis-selftest";
+
 // ── Value Types ───────────────────────────────────────────────────────────────
 
 /// Launch parameters for creating a new VM instance.
@@ -72,11 +79,29 @@ pub trait InstanceLifecycle {
     /// # Errors
     /// This function will return an error if the underlying operations fail.
     async fn purge(&self) -> Result<Output>;
+    /// Take a snapshot of the (stopped) VM instance called `name`.
+    /// # Errors
+    /// This function will return an error if the underlying operations fail.
+    async fn snapshot(&self, name: &str) -> Result<Output>;
+    /// Roll the (stopped) VM instance back to snapshot `name`, discarding its
+    /// current state.
+    /// # Errors
+    /// This function will return an error if the underlying operations fail.
+    async fn restore(&self, name: &str) -> Result<Output>;
+    /// List snapshots as JSON.
+    /// # Errors
+    /// This function will return an error if the underlying operations fail.
+    async fn list_snapshots(&self) -> Result<Output>;
 }
 
 /// VM state inspection: query info and version.
 #[allow(async_fn_in_trait)]
 pub trait InstanceInspector {
+    /// Name of the VM instance being inspected; the key under `info` in
+    /// `multipass info --format json` output.
+    fn instance_name(&self) -> &str {
+        POLIS_INSTANCE
+    }
     /// Get VM instance info as JSON.
     /// # Errors
     /// This function will return an error if the underlying operations fail.
@@ -85,6 +110,22 @@ pub trait InstanceInspector {
     /// # Errors
     /// This function will return an error if the underlying operations fail.
     async fn version(&self) -> Result<Output>;
+    /// Query [`Self::info`] and parse the entry for [`Self::instance_name`].
+    /// A missing instance has state `NotFound`.
+    /// # Errors
+    /// Returns an error if multipass cannot be run, fails for any reason other
+    /// than a missing instance, or prints invalid JSON.
+    async fn instance_info(&self) -> Result<InstanceInfo> {
+        let output = self.info().await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if is_missing_instance(&stderr) {
+                return Ok(InstanceInfo::not_found());
+            }
+            anyhow::bail!("multipass info failed: {}", stderr.trim());
+        }
+        parse_instance_info(&output.stdout, self.instance_name()).context("parsing multipass info")
+    }
 }
 
 /// Host-to-VM file transfer operations.
@@ -120,6 +161,21 @@ pub trait ShellExecutor {
     /// # Errors
     /// This function will return an error if the underlying operations fail.
     async fn exec_status(&self, args: &[&str]) -> Result<std::process::ExitStatus>;
+    /// List containers via `docker compose ps`, limited to `services` (all
+    /// services when empty).
+    /// # Errors
+    /// Returns an error if the command fails or prints invalid JSON.
+    async fn container_status(&self, services: &[&str]) -> Result<Vec<ContainerStatus>> {
+        let args = compose_ps_args(services);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = self.exec(&args).await?;
+        anyhow::ensure!(
+            output.status.success(),
+            "docker compose ps failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        parse_compose_ps(&output.stdout).context("parsing docker compose ps")
+    }
 }
 
 /// Composite trait — any type implementing all four sub-traits is a `VmProvisioner`.
@@ -170,6 +226,42 @@ pub trait CommandRunner {
     async fn run_status(&self, program: &str, args: &[&str]) -> Result<std::process::ExitStatus>;
 }
 
+/// Blanket implementation so decorators can wrap a borrowed runner.
+impl<R: CommandRunner> CommandRunner for &R {
+    async fn run(&self, program: &str, args: &[&str]) -> Result<Output> {
+        (**self).run(program, args).await
+    }
+    async fn run_with_timeout(
+        &self,
+        program: &str,
+        args: &[&str],
+        timeout: std::time::Duration,
+    ) -> Result<Output> {
+        (**self).run_with_timeout(program, args, timeout).await
+    }
+    async fn run_with_stdin(&self, program: &str, args: &[&str], stdin: &[u8]) -> Result<Output> {
+        (**self).run_with_stdin(program, args, stdin).await
+    }
+    fn spawn(&self, program: &str, args: &[&str]) -> Result<tokio::process::Child> {
+        (**self).spawn(program, args)
+    }
+    async fn run_status(&self, program: &str, args: &[&str]) -> Result<std::process::ExitStatus> {
+        (**self).run_status(program, args).await
+    }
+}
+
+// ── HTTP Client Port ──────────────────────────────────────────────────────────
+
+/// Abstracts blocking HTTP GETs so release and signature handling can be
+/// tested against canned responses.
+pub trait HttpClient {
+    /// Fetch `url` with extra request `headers` and return its body, reading
+    /// at most `max_bytes`.
+    /// # Errors
+    /// Returns an error on connection failure or a non-2xx status.
+    fn get(&self, url: &str, headers: &[(&str, String)], max_bytes: u64) -> Result<Vec<u8>>;
+}
+
 // ── Progress Reporting Port ───────────────────────────────────────────────────
 
 /// Abstracts progress reporting so services can emit events without
@@ -212,20 +304,38 @@ pub trait WorkspaceStateStore {
     /// # Errors
     /// This function will return an error if the underlying operations fail.
     async fn clear_async(&self) -> Result<()>;
+    /// Atomically replace the stored state with `new` if it still equals
+    /// `expected` (`None` meaning no state is stored). Returns `false`, leaving
+    /// the state untouched, when another operation changed it first.
+    /// # Errors
+    /// This function will return an error if the underlying operations fail.
+    async fn compare_and_set(
+        &self,
+        expected: Option<&WorkspaceState>,
+        new: &WorkspaceState,
+    ) -> Result<bool>;
 }
 
-/// Abstracts writing agent artifact files to the local filesystem.
-#[allow(async_fn_in_trait)]
-#[allow(dead_code)] // Not yet wired from command handlers
+/// Abstracts writing generated agent artifacts, so services can be tested
+/// against an in-memory writer instead of a temp dir.
 pub trait LocalArtifactWriter {
-    /// Write agent artifact files and return the directory path.
+    /// Write `files` into `dir`, creating the directory if needed.
     /// # Errors
     /// This function will return an error if the underlying operations fail.
-    async fn write_agent_artifacts(
-        &self,
-        agent_name: &str,
-        files: HashMap<String, String>,
-    ) -> Result<std::path::PathBuf>;
+    fn write_agent_artifacts(&self, dir: &std::path::Path, files: &[GeneratedFile]) -> Result<()>;
+}
+
+/// Any local filesystem writes artifacts as plain files.
+impl<T: LocalFs> LocalArtifactWriter for T {
+    fn write_agent_artifacts(&self, dir: &std::path::Path, files: &[GeneratedFile]) -> Result<()> {
+        self.create_dir_all(dir)
+            .with_context(|| format!("creating {}", dir.display()))?;
+        for file in files {
+            self.write(&dir.join(&file.file), file.content.clone())
+                .with_context(|| format!("writing {}", file.file))?;
+        }
+        Ok(())
+    }
 }
 
 // ── Network Probe Port ────────────────────────────────────────────────────────
@@ -317,6 +427,10 @@ pub trait LocalFs {
     /// # Errors
     /// This function will return an error if the underlying operations fail.
     fn write(&self, path: &std::path::Path, content: String) -> Result<()>;
+    /// Write binary content to a file.
+    /// # Errors
+    /// This function will return an error if the underlying operations fail.
+    fn write_bytes(&self, path: &std::path::Path, content: &[u8]) -> Result<()>;
     /// Read content from a file.
     /// # Errors
     /// This function will return an error if the underlying operations fail.
@@ -343,6 +457,22 @@ pub trait ConfigStore {
     fn path(&self) -> Result<std::path::PathBuf>;
 }
 
+// ── Agent Bundle Signing Port ─────────────────────────────────────────────────
+
+/// Signs and verifies agent bundles (`polis agent bundle` / `install`).
+pub trait BundleSigner {
+    /// Compress `archive` (an uncompressed agent tar) and sign it with the
+    /// ed25519 keypair file at `key_path`.
+    /// # Errors
+    /// This function will return an error if the key cannot be read or signing fails.
+    fn sign(&self, archive: &[u8], key_path: &std::path::Path) -> Result<Vec<u8>>;
+    /// Verify `bundle` against any of `trusted_keys` (base64 ed25519 public
+    /// keys) and return the uncompressed agent tar it carries.
+    /// # Errors
+    /// This function will return an error if no trusted key signed the bundle.
+    fn open(&self, bundle: &[u8], trusted_keys: &[String]) -> Result<Vec<u8>>;
+}
+
 // ── Host Key Extraction Port ──────────────────────────────────────────────────
 
 /// Abstracts extraction of the workspace SSH host key.
@@ -398,4 +528,20 @@ pub trait SshConfigurator {
     /// # Errors
     /// This function will return an error if the user SSH config cannot be read or written.
     async fn remove_include_directive(&self) -> Result<()>;
+
+    /// Writes `block` into `~/.ssh/config`, replacing any previous polis block.
+    /// Returns the path of the backup taken of the existing config, if any.
+    /// # Errors
+    /// This function will return an error if the config is not a regular file
+    /// or cannot be backed up, read, or written.
+    async fn write_host_block(&self, block: &str) -> Result<Option<std::path::PathBuf>>;
+
+    /// Runs `ssh -N` to the workspace with one `-L` per forward, until ssh
+    /// exits. Returns ssh's exit status.
+    /// # Errors
+    /// This function will return an error if ssh cannot be started.
+    async fn forward_ports(
+        &self,
+        forwards: &[crate::domain::ssh::PortForward],
+    ) -> Result<std::process::ExitStatus>;
 }
@@ -0,0 +1,582 @@
+//! Application service — move agents between machines as tar archives.
+//!
+//! `export_agent` packs `agents/<name>/` from the VM into a reproducible
+//! host `.tar.gz`; `import_agent` checks such a tarball, unpacks it and
+//! installs it like `polis agent add`. `bundle_agent` and `install_bundle`
+//! do the same for signed bundles built from a local agent folder.
+//! `add_agent` and `preview_add` serve `polis agent add`, which takes either
+//! a folder or an exported archive.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::{Compression, GzBuilder};
+
+use crate::application::ports::{
+    BundleSigner, FileTransfer, InstanceInspector, LocalFs, ProgressReporter, ShellExecutor,
+    WorkspaceStateStore,
+};
+use crate::application::services::agent_crud::{install_agent, preview_agent_artifacts};
+use crate::application::services::vm::lifecycle::{self as vm, VmState};
+use crate::domain::agent::archive::{
+    GENERATED_DIR, archive_agent_name, check_entry_path, export_tar_args, is_generated_entry,
+};
+use crate::domain::agent::artifacts::GeneratedFile;
+use crate::domain::agent::ports::PortOverride;
+use crate::domain::agent::validate::{
+    is_valid_agent_name, parse_agent_manifest, validate_full_manifest,
+};
+use crate::domain::workspace::VM_ROOT;
+
+/// First two bytes of every gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Write agent `name` from the VM, including `.generated/`, to the gzipped
+/// tarball at `output`.
+///
+/// The manifest must pass `polis agent validate`. The archive is
+/// reproducible: entries are sorted with fixed mtimes and owners, and the
+/// gzip header carries no timestamp.
+///
+/// # Errors
+///
+/// Returns an error if the name is invalid, `output` exists and `force` is
+/// not set, the VM is not running, the agent is not installed, its manifest
+/// is invalid, or the archive cannot be created, checked or written.
+pub async fn export_agent(
+    provisioner: &(impl ShellExecutor + InstanceInspector),
+    local_fs: &impl LocalFs,
+    reporter: &impl ProgressReporter,
+    name: &str,
+    output: &Path,
+    force: bool,
+) -> Result<()> {
+    anyhow::ensure!(is_valid_agent_name(name), "invalid agent name: '{name}'");
+    anyhow::ensure!(
+        force || !local_fs.exists(output),
+        "{} already exists. Use --force to overwrite it.",
+        output.display()
+    );
+    anyhow::ensure!(
+        vm::state(provisioner).await? == VmState::Running,
+        "VM is not running. Start it first: polis start"
+    );
+    let manifest = provisioner
+        .exec(&["cat", &format!("{VM_ROOT}/agents/{name}/agent.yaml")])
+        .await
+        .context("reading agent.yaml from VM")?;
+    anyhow::ensure!(
+        manifest.status.success(),
+        "Agent '{name}' is not installed."
+    );
+    let manifest = String::from_utf8(manifest.stdout).context("parsing agent.yaml as UTF-8")?;
+    validate_full_manifest(&parse_agent_manifest(&manifest)?)?;
+
+    reporter.step(&format!("packing '{name}'..."));
+    let args = export_tar_args(name);
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    let out = provisioner.exec(&arg_refs).await.context("packing agent")?;
+    anyhow::ensure!(
+        out.status.success(),
+        "Failed to pack agent '{name}': {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let packed = archive_entries(out.stdout.as_slice()).and_then(|e| archive_agent_name(&e))?;
+    anyhow::ensure!(
+        packed == name,
+        "packed archive holds '{packed}', expected '{name}'"
+    );
+    local_fs.write_bytes(output, &gzip(&out.stdout)?)?;
+    reporter.success(&format!("agent '{name}' exported to {}", output.display()));
+    Ok(())
+}
+
+/// Check an agent archive (gzipped or plain tar) and return the agent it
+/// holds.
+///
+/// Rejects absolute or `..` entry paths and anything other than a single
+/// `<name>/` folder with an `agent.yaml`.
+///
+/// # Errors
+///
+/// Returns an error if the archive cannot be read or breaks either rule.
+pub fn inspect_agent_archive(archive: &Path) -> Result<String> {
+    let entries = archive_entries(open_archive(archive)?).context("validating agent archive")?;
+    archive_agent_name(&entries)
+}
+
+/// Install the agent packed in `archive` (see [`export_agent`]).
+///
+/// The archive is checked with [`inspect_agent_archive`] and unpacked
+/// without its `.generated/` into a temporary `agents/` folder. It is then
+/// installed with `install_agent`, which validates the manifest, applies
+/// `port_overrides` and regenerates `.generated/`.
+///
+/// # Errors
+///
+/// Returns an error if the archive is unsafe or malformed, or installation
+/// fails.
+pub async fn import_agent(
+    provisioner: &(impl ShellExecutor + FileTransfer + InstanceInspector),
+    state_mgr: &impl WorkspaceStateStore,
+    local_fs: &impl LocalFs,
+    reporter: &impl ProgressReporter,
+    archive: &Path,
+    port_overrides: &[PortOverride],
+) -> Result<String> {
+    let (_tmp, folder) = extract_agent_archive(local_fs, archive)?;
+    install_agent(
+        provisioner,
+        state_mgr,
+        local_fs,
+        reporter,
+        &folder.to_string_lossy(),
+        port_overrides,
+    )
+    .await
+}
+
+/// Where `polis agent add` takes an agent from.
+#[derive(Debug, Clone, Copy)]
+pub enum AgentSource<'a> {
+    /// Folder containing `agent.yaml` (`--path`).
+    Folder(&'a str),
+    /// Archive made by `polis agent export` (`--bundle`).
+    Archive(&'a Path),
+}
+
+/// Install the agent from `source`. Returns the agent name.
+///
+/// # Errors
+///
+/// Returns an error if the folder or archive is invalid, or installation
+/// fails.
+pub async fn add_agent(
+    provisioner: &(impl ShellExecutor + FileTransfer + InstanceInspector),
+    state_mgr: &impl WorkspaceStateStore,
+    local_fs: &impl LocalFs,
+    reporter: &impl ProgressReporter,
+    source: AgentSource<'_>,
+    port_overrides: &[PortOverride],
+) -> Result<String> {
+    match source {
+        AgentSource::Folder(path) => {
+            install_agent(
+                provisioner,
+                state_mgr,
+                local_fs,
+                reporter,
+                path,
+                port_overrides,
+            )
+            .await
+        }
+        AgentSource::Archive(archive) => {
+            import_agent(
+                provisioner,
+                state_mgr,
+                local_fs,
+                reporter,
+                archive,
+                port_overrides,
+            )
+            .await
+        }
+    }
+}
+
+/// Generate the artifacts [`add_agent`] would install for `source`, without
+/// touching the VM.
+///
+/// # Errors
+///
+/// Returns an error if the folder or archive is invalid, or its manifest is
+/// invalid.
+pub fn preview_add(
+    local_fs: &impl LocalFs,
+    source: AgentSource<'_>,
+    port_overrides: &[PortOverride],
+) -> Result<Vec<GeneratedFile>> {
+    match source {
+        AgentSource::Folder(path) => preview_agent_artifacts(local_fs, path, port_overrides),
+        AgentSource::Archive(archive) => preview_agent_archive(local_fs, archive, port_overrides),
+    }
+}
+
+/// Generate the artifacts [`import_agent`] would install for `archive`,
+/// without touching the VM (see `preview_agent_artifacts`).
+///
+/// # Errors
+///
+/// Returns an error if the archive is unsafe or malformed, or its manifest
+/// is invalid.
+pub fn preview_agent_archive(
+    local_fs: &impl LocalFs,
+    archive: &Path,
+    port_overrides: &[PortOverride],
+) -> Result<Vec<GeneratedFile>> {
+    let (_tmp, folder) = extract_agent_archive(local_fs, archive)?;
+    preview_agent_artifacts(local_fs, &folder.to_string_lossy(), port_overrides)
+}
+
+/// Pack the agent folder `dir` into a bundle signed with the ed25519 keypair
+/// at `key`, written to `output` or `<name>.tar.gz`. Returns the bundle path.
+///
+/// The manifest must pass `polis agent validate`; `.generated/` is left out.
+///
+/// # Errors
+///
+/// Returns an error if the manifest is invalid, or packing, signing or
+/// writing the bundle fails.
+pub fn bundle_agent(
+    local_fs: &impl LocalFs,
+    signer: &impl BundleSigner,
+    reporter: &impl ProgressReporter,
+    dir: &Path,
+    key: &Path,
+    output: Option<&Path>,
+) -> Result<PathBuf> {
+    let manifest_path = dir.join("agent.yaml");
+    anyhow::ensure!(
+        local_fs.exists(&manifest_path),
+        "No agent.yaml found in: {}",
+        dir.display()
+    );
+    let manifest = parse_agent_manifest(&local_fs.read_to_string(&manifest_path)?)?;
+    validate_full_manifest(&manifest)?;
+    let name = manifest.metadata.name;
+
+    reporter.step(&format!("packing '{name}'..."));
+    let archive = pack_agent_dir(dir, &name)?;
+    let bundle = signer.sign(&archive, key)?;
+    let output = output.map_or_else(
+        || PathBuf::from(format!("{name}.tar.gz")),
+        Path::to_path_buf,
+    );
+    local_fs.write_bytes(&output, &bundle)?;
+    reporter.success(&format!("agent '{name}' bundled to {}", output.display()));
+    Ok(output)
+}
+
+/// Verify the bundle at `bundle` against `trusted_keys`, then install the
+/// agent it carries like [`import_agent`]. Nothing is unpacked unless the
+/// signature checks out.
+///
+/// # Errors
+///
+/// Returns an error if the bundle is missing, its signature does not match a
+/// trusted key, or the import fails.
+pub async fn install_bundle(
+    provisioner: &(impl ShellExecutor + FileTransfer + InstanceInspector),
+    state_mgr: &impl WorkspaceStateStore,
+    local_fs: &impl LocalFs,
+    signer: &impl BundleSigner,
+    reporter: &impl ProgressReporter,
+    bundle: &Path,
+    trusted_keys: &[String],
+) -> Result<String> {
+    anyhow::ensure!(
+        local_fs.exists(bundle),
+        "Bundle not found: {}",
+        bundle.display()
+    );
+    reporter.step("verifying bundle signature...");
+    let archive = signer.open(&read_bundle(bundle)?, trusted_keys)?;
+    reporter.success("bundle signature verified");
+
+    let tmp = polis_tmp_dir(local_fs, "polis-bundle-")?;
+    let archive_path = tmp.path().join("agent.tar");
+    local_fs.write_bytes(&archive_path, &archive)?;
+    import_agent(
+        provisioner,
+        state_mgr,
+        local_fs,
+        reporter,
+        &archive_path,
+        &[],
+    )
+    .await
+}
+
+/// Check `archive` and unpack it into a temporary `agents/` folder.
+/// Returns the temp dir guard and the unpacked agent folder.
+///
+/// # Errors
+///
+/// Returns an error if the archive is missing, unsafe or malformed, or
+/// cannot be unpacked.
+fn extract_agent_archive(
+    local_fs: &impl LocalFs,
+    archive: &Path,
+) -> Result<(tempfile::TempDir, PathBuf)> {
+    anyhow::ensure!(
+        local_fs.exists(archive),
+        "Archive not found: {}",
+        archive.display()
+    );
+    let name = inspect_agent_archive(archive)?;
+    let tmp = polis_tmp_dir(local_fs, "polis-import-")?;
+    let agents_dir = tmp.path().join("agents");
+    unpack_archive(archive, &agents_dir)?;
+    let folder = agents_dir.join(name);
+    Ok((tmp, folder))
+}
+
+/// Create a temporary directory under `~/polis/tmp` so the Multipass snap
+/// daemon (AppArmor-confined) can read its contents for transfer.
+///
+/// # Errors
+///
+/// Returns an error if the home directory is unknown or the directory
+/// cannot be created.
+fn polis_tmp_dir(local_fs: &impl LocalFs, prefix: &str) -> Result<tempfile::TempDir> {
+    let base = dirs::home_dir()
+        .ok_or_else(|| anyhow::anyhow!("cannot determine home directory"))?
+        .join("polis")
+        .join("tmp");
+    local_fs
+        .create_dir_all(&base)
+        .context("creating ~/polis/tmp")?;
+    tempfile::Builder::new()
+        .prefix(prefix)
+        .tempdir_in(&base)
+        .context("creating temp dir for agent import")
+}
+
+/// Read a bundle file into memory.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+fn read_bundle(bundle: &Path) -> Result<Vec<u8>> {
+    std::fs::read(bundle).with_context(|| format!("reading {}", bundle.display()))
+}
+
+/// Tar the agent folder `dir` under the root `<name>/`, skipping
+/// `.generated/`, in the layout [`archive_agent_name`] accepts.
+///
+/// # Errors
+///
+/// Returns an error if the folder cannot be read.
+fn pack_agent_dir(dir: &Path, name: &str) -> Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(Vec::new());
+    builder.follow_symlinks(false);
+    builder
+        .append_dir(name, dir)
+        .with_context(|| format!("packing {}", dir.display()))?;
+    for entry in std::fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        if file_name == GENERATED_DIR {
+            continue;
+        }
+        let target = Path::new(name).join(&file_name);
+        if entry.file_type()?.is_dir() {
+            builder.append_dir_all(&target, entry.path())
+        } else {
+            builder.append_path_with_name(entry.path(), &target)
+        }
+        .with_context(|| format!("packing {}", entry.path().display()))?;
+    }
+    builder.into_inner().context("finishing agent archive")
+}
+
+/// Open `archive` for reading, decompressing it if it is gzipped.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened or read.
+fn open_archive(archive: &Path) -> Result<Box<dyn Read>> {
+    let file =
+        std::fs::File::open(archive).with_context(|| format!("opening {}", archive.display()))?;
+    let mut reader = BufReader::new(file);
+    let head = reader
+        .fill_buf()
+        .with_context(|| format!("reading {}", archive.display()))?;
+    if head.starts_with(&GZIP_MAGIC) {
+        Ok(Box::new(GzDecoder::new(reader)))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// List the entry paths of the tar stream `reader`, rejecting absolute and
+/// `..` paths.
+///
+/// # Errors
+///
+/// Returns an error if the stream is not a tar or an entry path is unsafe.
+fn archive_entries(reader: impl Read) -> Result<Vec<PathBuf>> {
+    let mut entries = Vec::new();
+    for entry in tar::Archive::new(reader)
+        .entries()
+        .context("reading archive entries")?
+    {
+        let entry = entry.context("reading archive entry")?;
+        let path = entry
+            .path()
+            .context("reading archive entry path")?
+            .into_owned();
+        check_entry_path(&path)?;
+        entries.push(path);
+    }
+    Ok(entries)
+}
+
+/// Gzip `data` with a zeroed header timestamp so output is reproducible.
+///
+/// # Errors
+///
+/// Returns an error if compression fails.
+fn gzip(data: &[u8]) -> Result<Vec<u8>> {
+    let mut gz = GzBuilder::new()
+        .mtime(0)
+        .write(Vec::new(), Compression::default());
+    gz.write_all(data).context("compressing agent archive")?;
+    gz.finish().context("compressing agent archive")
+}
+
+/// Unpack `archive` into `dest`, skipping `.generated/`.
+///
+/// # Errors
+///
+/// Returns an error if the archive cannot be read or unpacked.
+fn unpack_archive(archive: &Path, dest: &Path) -> Result<()> {
+    std::fs::create_dir_all(dest).with_context(|| format!("creating {}", dest.display()))?;
+    let mut tar = tar::Archive::new(open_archive(archive)?);
+    for entry in tar.entries().context("reading archive entries")? {
+        let mut entry = entry.context("reading archive entry")?;
+        if is_generated_entry(&entry.path().context("reading archive entry path")?) {
+            continue;
+        }
+        entry.unpack_in(dest).context("unpacking agent archive")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn write_archive(dir: &Path, entries: &[(&str, &[u8])]) -> PathBuf {
+        let path = dir.join("agent.tar");
+        let file = std::fs::File::create(&path).expect("create tar");
+        let mut builder = tar::Builder::new(file);
+        for (name, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, *data)
+                .expect("append");
+        }
+        builder.finish().expect("finish");
+        path
+    }
+
+    #[test]
+    fn test_inspect_agent_archive_returns_agent_name() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let archive = write_archive(
+            dir.path(),
+            &[
+                ("alpha/agent.yaml", b"kind: AgentPlugin\n"),
+                ("alpha/install.sh", b"#!/bin/sh\n"),
+            ],
+        );
+        assert_eq!(
+            inspect_agent_archive(&archive).expect("valid archive"),
+            "alpha"
+        );
+    }
+
+    #[test]
+    fn test_inspect_agent_archive_rejects_path_traversal() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let archive = dir.path().join("evil.tar");
+        {
+            use std::io::Write;
+            // tar::Builder refuses `..`, so hand-craft the header.
+            let mut file = std::fs::File::create(&archive).expect("create tar");
+            let mut header = [0u8; 512];
+            let name = b"alpha/../../etc/passwd";
+            header[..name.len()].copy_from_slice(name);
+            header[156] = b'0';
+            header[124..135].copy_from_slice(b"00000000000");
+            header[100..107].copy_from_slice(b"0000644");
+            let sum: u32 = header.iter().map(|&b| u32::from(b)).sum::<u32>() + 8 * u32::from(b' ');
+            header[148..156].copy_from_slice(format!("{sum:06o}\0 ").as_bytes());
+            file.write_all(&header).expect("write header");
+            file.write_all(&[0u8; 1024]).expect("write EOF");
+        }
+        let err = format!(
+            "{:#}",
+            inspect_agent_archive(&archive).expect_err("traversal")
+        );
+        assert!(err.contains("path traversal"), "{err}");
+    }
+
+    #[test]
+    fn test_pack_agent_dir_roots_at_name_and_skips_generated() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let agent = dir.path().join("my-folder");
+        std::fs::create_dir_all(agent.join("scripts")).expect("mkdir");
+        std::fs::create_dir_all(agent.join(GENERATED_DIR)).expect("mkdir");
+        std::fs::write(agent.join("agent.yaml"), "kind: AgentPlugin\n").expect("write");
+        std::fs::write(agent.join("scripts/install.sh"), "#!/bin/sh\n").expect("write");
+        std::fs::write(agent.join(GENERATED_DIR).join("alpha.env"), "").expect("write");
+
+        let archive_path = dir.path().join("alpha.tar");
+        std::fs::write(
+            &archive_path,
+            pack_agent_dir(&agent, "alpha").expect("pack"),
+        )
+        .expect("write tar");
+        assert_eq!(
+            inspect_agent_archive(&archive_path).expect("valid archive"),
+            "alpha"
+        );
+    }
+
+    #[test]
+    fn test_gzipped_archive_is_reproducible_and_imports_without_generated() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let tar = std::fs::read(write_archive(
+            dir.path(),
+            &[
+                ("alpha/agent.yaml", b"kind: AgentPlugin\n"),
+                ("alpha/.generated/alpha.service", b"[Unit]\n"),
+            ],
+        ))
+        .expect("read tar");
+        let gz = gzip(&tar).expect("gzip");
+        assert_eq!(gz, gzip(&tar).expect("gzip"));
+        assert!(gz.starts_with(&GZIP_MAGIC));
+
+        let archive = dir.path().join("alpha.tar.gz");
+        std::fs::write(&archive, &gz).expect("write gz");
+        assert_eq!(inspect_agent_archive(&archive).expect("valid"), "alpha");
+
+        let dest = dir.path().join("agents");
+        unpack_archive(&archive, &dest).expect("unpack");
+        assert!(dest.join("alpha/agent.yaml").is_file());
+        assert!(!dest.join("alpha").join(GENERATED_DIR).exists());
+    }
+
+    #[test]
+    fn test_inspect_agent_archive_rejects_foreign_layout() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let archive = write_archive(
+            dir.path(),
+            &[
+                ("alpha/agent.yaml", b"kind: AgentPlugin\n"),
+                ("beta/agent.yaml", b"kind: AgentPlugin\n"),
+            ],
+        );
+        assert!(inspect_agent_archive(&archive).is_err());
+    }
+}
@@ -7,46 +7,54 @@ pub use crate::domain::agent::AgentInfo;
 use anyhow::{Context, Result};
 
 use crate::application::ports::{
-    FileTransfer, InstanceInspector, ProgressReporter, ShellExecutor, WorkspaceStateStore,
+    FileTransfer, InstanceInspector, LocalArtifactWriter, ProgressReporter, ShellExecutor,
+    WorkspaceStateStore,
 };
 use crate::application::services::vm::lifecycle::{self as vm, VmState};
+use crate::domain::agent::artifacts::{GeneratedFile, generated_files};
+use crate::domain::agent::ports::{
+    PORT_OVERRIDES_FILE, PortOverride, apply_port_overrides, parse_port_overrides,
+};
+use crate::domain::agent::validate::{
+    ManifestViolation, manifest_violations, parse_agent_manifest,
+};
 
 /// Write generated agent artifacts to `<generated_dir>/`.
 ///
 /// Shared by `generate_and_write_artifacts` (local install) and
-/// `setup_agent` (VM-based update/start).
+/// `setup_agent` (VM-based update/start). `manifest` must already have
+/// `port_overrides` applied; the overrides are written alongside so the
+/// next regeneration can re-apply them.
 pub(crate) fn write_artifacts_to_dir(
-    local_fs: &impl crate::application::ports::LocalFs,
+    writer: &impl LocalArtifactWriter,
     generated_dir: &std::path::Path,
     name: &str,
     manifest: &polis_common::agent::AgentManifest,
     env_content: String,
+    port_overrides: &[PortOverride],
 ) -> Result<()> {
-    use crate::domain::agent::artifacts;
-
-    local_fs
-        .create_dir_all(generated_dir)
-        .with_context(|| format!("creating {}", generated_dir.display()))?;
-
-    let compose = artifacts::compose_overlay(manifest);
-    local_fs
-        .write(&generated_dir.join("compose.agent.yaml"), compose)
-        .context("writing compose.agent.yaml")?;
-
-    let unit = artifacts::systemd_unit(manifest);
-    let hash = artifacts::service_hash(&unit);
-    local_fs
-        .write(&generated_dir.join(format!("{name}.service")), unit)
-        .context("writing .service file")?;
-    local_fs
-        .write(&generated_dir.join(format!("{name}.service.sha256")), hash)
-        .context("writing .service.sha256 file")?;
-
-    local_fs
-        .write(&generated_dir.join(format!("{name}.env")), env_content)
-        .context("writing .env file")?;
+    let files = generated_files(name, manifest, env_content, port_overrides);
+    writer.write_agent_artifacts(generated_dir, &files)
+}
 
-    Ok(())
+/// Read the host-port overrides persisted for an installed agent. A missing
+/// file means no overrides.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be parsed.
+pub(crate) async fn read_port_overrides(
+    provisioner: &impl ShellExecutor,
+    name: &str,
+) -> Result<Vec<PortOverride>> {
+    let path = format!("{VM_ROOT}/agents/{name}/.generated/{PORT_OVERRIDES_FILE}");
+    match provisioner.exec(&["cat", &path]).await {
+        Ok(out) if out.status.success() => {
+            parse_port_overrides(&String::from_utf8_lossy(&out.stdout))
+                .with_context(|| format!("parsing {path}"))
+        }
+        _ => Ok(Vec::new()),
+    }
 }
 
 /// Generate agent artifacts from `agent.yaml` and write them to
@@ -57,19 +65,20 @@ pub(crate) fn write_artifacts_to_dir(
 ///
 /// # Errors
 ///
-/// Returns an error if the manifest cannot be read/parsed, or if any file
-/// write fails.
+/// Returns an error if the manifest cannot be read/parsed, an override does
+/// not match a declared port, or if any file write fails.
 fn generate_and_write_artifacts(
     local_fs: &impl crate::application::ports::LocalFs,
     polis_dir: &std::path::Path,
     name: &str,
+    port_overrides: &[PortOverride],
 ) -> Result<()> {
     let manifest_path = polis_dir.join("agents").join(name).join("agent.yaml");
     let content = local_fs
         .read_to_string(&manifest_path)
         .with_context(|| format!("reading {}", manifest_path.display()))?;
-    let manifest: polis_common::agent::AgentManifest =
-        serde_yaml::from_str(&content).context("failed to parse agent.yaml")?;
+    let mut manifest = parse_agent_manifest(&content)?;
+    apply_port_overrides(&mut manifest, port_overrides)?;
 
     let generated_dir = polis_dir.join("agents").join(name).join(".generated");
 
@@ -78,14 +87,144 @@ fn generate_and_write_artifacts(
         .unwrap_or_default();
     let filtered = crate::domain::agent::artifacts::filtered_env(&env_content, &manifest);
 
-    write_artifacts_to_dir(local_fs, &generated_dir, name, &manifest, filtered)
+    write_artifacts_to_dir(
+        local_fs,
+        &generated_dir,
+        name,
+        &manifest,
+        filtered,
+        port_overrides,
+    )
 }
 
 /// Path to the polis project root inside the VM.
 use crate::domain::workspace::VM_ROOT;
 
+/// Generate the artifacts `install_agent` would produce for the folder at
+/// `agent_path` and return them, without writing anything to disk or
+/// touching the VM.
+///
+/// # Errors
+///
+/// Returns an error if the manifest is missing, unparsable, or invalid, or
+/// an override does not match a declared port.
+pub fn preview_agent_artifacts(
+    local_fs: &impl crate::application::ports::LocalFs,
+    agent_path: &str,
+    port_overrides: &[PortOverride],
+) -> Result<Vec<GeneratedFile>> {
+    let folder = std::path::Path::new(agent_path);
+    let manifest_path = folder.join("agent.yaml");
+    anyhow::ensure!(
+        local_fs.exists(&manifest_path),
+        "No agent.yaml found in: {agent_path}"
+    );
+    let mut manifest = parse_agent_manifest(&local_fs.read_to_string(&manifest_path)?)?;
+    apply_port_overrides(&mut manifest, port_overrides)?;
+    crate::domain::agent::validate::validate_full_manifest(&manifest)?;
+    let name = manifest.metadata.name.clone();
+
+    // Same `.env` lookup as `generate_and_write_artifacts`.
+    let parent_dir = folder.parent().unwrap_or(folder);
+    let polis_dir = parent_dir.parent().unwrap_or(parent_dir);
+    let env_content = local_fs
+        .read_to_string(&polis_dir.join(".env"))
+        .unwrap_or_default();
+    let filtered = crate::domain::agent::artifacts::filtered_env(&env_content, &manifest);
+
+    let mut files = generated_files(&name, &manifest, filtered, port_overrides);
+    files.retain(|f| f.file != PORT_OVERRIDES_FILE);
+    Ok(files)
+}
+
+/// Lint `<agent_path>/agent.yaml` without touching the VM. A manifest that
+/// does not parse is reported as a single violation on `agent.yaml`.
+///
+/// # Errors
+///
+/// Returns an error if the folder or its `agent.yaml` cannot be read.
+pub fn lint_agent_manifest(
+    local_fs: &impl crate::application::ports::LocalFs,
+    agent_path: &str,
+) -> Result<Vec<ManifestViolation>> {
+    let manifest_path = std::path::Path::new(agent_path).join("agent.yaml");
+    anyhow::ensure!(
+        local_fs.exists(&manifest_path),
+        "No agent.yaml found in: {agent_path}"
+    );
+    let content = local_fs.read_to_string(&manifest_path)?;
+    Ok(match parse_agent_manifest(&content) {
+        Ok(manifest) => manifest_violations(&manifest),
+        Err(e) => vec![ManifestViolation {
+            rule: crate::domain::error::ManifestRule::Parse,
+            field: "agent.yaml".to_string(),
+            message: format!("{e:#}"),
+        }],
+    })
+}
+
+/// Convert the legacy flat definition at `legacy` into a v1 `agent.yaml`
+/// at `out`. The result must pass validation before anything is written.
+/// Returns one warning per legacy field that was dropped.
+///
+/// # Errors
+///
+/// Returns an error if `out` already exists, `legacy` cannot be read or
+/// migrated, or the migrated manifest breaks a validation rule.
+pub fn migrate_legacy_manifest(
+    local_fs: &impl crate::application::ports::LocalFs,
+    legacy: &std::path::Path,
+    out: &std::path::Path,
+) -> Result<Vec<String>> {
+    use crate::domain::agent::migrate;
+
+    anyhow::ensure!(
+        !local_fs.exists(out),
+        "{} already exists; choose another --out",
+        out.display()
+    );
+    let content = local_fs
+        .read_to_string(legacy)
+        .with_context(|| format!("reading {}", legacy.display()))?;
+    let migration = migrate::migrate_legacy_manifest(&content)
+        .with_context(|| format!("migrating {}", legacy.display()))?;
+    crate::domain::agent::validate_full_manifest(&migration.manifest)
+        .context("the migrated manifest needs manual fixes")?;
+    local_fs.write(out, migrate::manifest_yaml(&migration.manifest)?)?;
+    Ok(migration.warnings)
+}
+
+/// Return the workspace's active agent.
+///
+/// # Errors
+///
+/// Returns an error if the state cannot be read or no agent is active.
+pub async fn require_active_agent(state_mgr: &impl WorkspaceStateStore) -> Result<String> {
+    state_mgr
+        .load_async()
+        .await?
+        .and_then(|s| s.active_agent)
+        .ok_or_else(|| anyhow::anyhow!("no active agent. Start one: polis start --agent <name>"))
+}
+
+/// Ensure the workspace VM is running.
+///
+/// # Errors
+///
+/// Returns an error if the VM state cannot be read or the VM is not running.
+pub async fn require_running(provisioner: &impl InstanceInspector) -> Result<()> {
+    anyhow::ensure!(
+        vm::state(provisioner).await? == VmState::Running,
+        "Workspace is not running. Start it first: polis start --agent <name>"
+    );
+    Ok(())
+}
+
 /// Install an agent from a local folder into the VM.
 ///
+/// `port_overrides` remap declared container ports to other host ports in
+/// the generated overlay without editing `agent.yaml`.
+///
 /// Steps:
 /// 1. Validate the agent folder and manifest (domain validation)
 /// 2. Generate artifacts using domain functions
@@ -101,6 +240,7 @@ pub async fn install_agent(
     local_fs: &impl crate::application::ports::LocalFs,
     reporter: &impl ProgressReporter,
     agent_path: &str,
+    port_overrides: &[PortOverride],
 ) -> Result<String> {
     // Step 1: Validate agent folder and get name.
     let folder = std::path::Path::new(agent_path);
@@ -112,8 +252,8 @@ pub async fn install_agent(
     );
     let content = local_fs.read_to_string(&manifest_path)?;
 
-    let manifest: polis_common::agent::AgentManifest =
-        serde_yaml::from_str(&content).context("failed to parse agent.yaml")?;
+    let mut manifest = parse_agent_manifest(&content)?;
+    apply_port_overrides(&mut manifest, port_overrides)?;
     crate::domain::agent::validate::validate_full_manifest(&manifest)?;
     let name = manifest.metadata.name.clone();
 
@@ -131,6 +271,23 @@ pub async fn install_agent(
         "Agent '{name}' already installed. Remove it first: polis agent remove {name}"
     );
 
+    // Step 3b: Reject host ports already claimed by other installed agents.
+    let mut installed: Vec<polis_common::agent::AgentManifest> = Vec::new();
+    for (dir_name, yaml) in scan_agent_manifests(provisioner).await? {
+        let Ok(mut other) = serde_yaml::from_str(&yaml) else {
+            continue;
+        };
+        apply_port_overrides(
+            &mut other,
+            &read_port_overrides(provisioner, &dir_name).await?,
+        )?;
+        installed.push(other);
+    }
+    let env_content = crate::application::services::vm::provision::read_deployed_env(provisioner)
+        .await
+        .unwrap_or_default();
+    crate::domain::agent::validate::validate_port_collisions(&manifest, &installed, &env_content)?;
+
     // Step 4: Generate artifacts via domain functions.
     reporter.step(&format!("generating artifacts for '{name}'..."));
     let agent_folder = std::path::Path::new(agent_path);
@@ -138,7 +295,7 @@ pub async fn install_agent(
         .parent()
         .ok_or_else(|| anyhow::anyhow!("cannot determine parent directory of agent folder"))?;
     let polis_dir = parent_dir.parent().unwrap_or(parent_dir);
-    generate_and_write_artifacts(local_fs, polis_dir, &name)?;
+    generate_and_write_artifacts(local_fs, polis_dir, &name, port_overrides)?;
 
     // Step 5: Transfer agent folder to VM.
     reporter.step(&format!("copying '{name}' to VM..."));
@@ -187,6 +344,7 @@ pub async fn remove_agent(
     let is_active = active.as_deref() == Some(agent_name);
 
     if is_active {
+        vm::require_docker(provisioner).await?;
         reporter.step(&format!("stopping active agent '{agent_name}'..."));
         let base = format!("{VM_ROOT}/docker-compose.yml");
         let overlay = format!("{VM_ROOT}/agents/{agent_name}/.generated/compose.agent.yaml");
@@ -250,16 +408,9 @@ pub async fn update_agent(
     local_fs: &impl crate::application::ports::LocalFs,
     reporter: &impl ProgressReporter,
 ) -> Result<String> {
-    let name = state_mgr
-        .load_async()
-        .await?
-        .and_then(|s| s.active_agent)
-        .ok_or_else(|| anyhow::anyhow!("no active agent. Start one: polis start --agent <name>"))?;
-
-    anyhow::ensure!(
-        vm::state(provisioner).await? == VmState::Running,
-        "Workspace is not running. Start it first: polis start --agent <name>"
-    );
+    let name = require_active_agent(state_mgr).await?;
+    require_running(provisioner).await?;
+    vm::require_docker(provisioner).await?;
 
     reporter.step(&format!("regenerating artifacts for '{name}'..."));
 
@@ -282,7 +433,8 @@ pub async fn update_agent(
     local_fs.create_dir_all(&agent_dir)?;
     local_fs.write(&agent_dir.join("agent.yaml"), stdout_str)?;
 
-    generate_and_write_artifacts(local_fs, tmp.path(), &name)?;
+    let port_overrides = read_port_overrides(provisioner, &name).await?;
+    generate_and_write_artifacts(local_fs, tmp.path(), &name, &port_overrides)?;
 
     // Transfer the regenerated .generated/ folder back into the VM.
     // Remove existing .generated to avoid nested directories from
@@ -341,7 +493,50 @@ pub async fn list_agents(
     provisioner: &impl ShellExecutor,
     state_mgr: &impl WorkspaceStateStore,
 ) -> Result<Vec<AgentInfo>> {
-    // Scan agents/*/agent.yaml inside VM (exclude _template).
+    let active = state_mgr.load_async().await?.and_then(|s| s.active_agent);
+
+    let mut agents = Vec::new();
+    for (dir_name, yaml) in scan_agent_manifests(provisioner).await? {
+        let is_active = active.as_deref() == Some(&dir_name);
+        if let Ok(m) = serde_yaml::from_str::<serde_yaml::Value>(&yaml) {
+            let metadata = m.get("metadata");
+            agents.push(AgentInfo {
+                name: metadata
+                    .and_then(|m| m.get("name"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or(&dir_name)
+                    .to_string(),
+                display_name: metadata
+                    .and_then(|m| m.get("displayName"))
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+                version: metadata
+                    .and_then(|m| m.get("version"))
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+                description: metadata
+                    .and_then(|m| m.get("description"))
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+                active: is_active,
+                labels: metadata
+                    .and_then(|m| m.get("labels"))
+                    .and_then(|v| serde_yaml::from_value(v.clone()).ok())
+                    .unwrap_or_default(),
+            });
+        }
+    }
+
+    Ok(agents)
+}
+
+/// Read every installed agent's `agent.yaml` from the VM as
+/// `(directory name, raw YAML)` pairs, skipping `_template`.
+///
+/// # Errors
+///
+/// This function will return an error if the underlying operations fail.
+async fn scan_agent_manifests(provisioner: &impl ShellExecutor) -> Result<Vec<(String, String)>> {
     let scan = provisioner
         .exec(&[
             "bash",
@@ -361,9 +556,7 @@ pub async fn list_agents(
         .await?;
 
     let output = String::from_utf8_lossy(&scan.stdout);
-    let active = state_mgr.load_async().await?.and_then(|s| s.active_agent);
-
-    let mut agents = Vec::new();
+    let mut manifests = Vec::new();
     let mut current_name: Option<String> = None;
     let mut current_yaml = String::new();
 
@@ -376,26 +569,7 @@ pub async fn list_agents(
             current_yaml.clear();
         } else if line == "===END===" {
             if let Some(dir_name) = current_name.take() {
-                let is_active = active.as_deref() == Some(&dir_name);
-                if let Ok(m) = serde_yaml::from_str::<serde_yaml::Value>(&current_yaml) {
-                    let metadata = m.get("metadata");
-                    agents.push(AgentInfo {
-                        name: metadata
-                            .and_then(|m| m.get("name"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or(&dir_name)
-                            .to_string(),
-                        version: metadata
-                            .and_then(|m| m.get("version"))
-                            .and_then(|v| v.as_str())
-                            .map(String::from),
-                        description: metadata
-                            .and_then(|m| m.get("description"))
-                            .and_then(|v| v.as_str())
-                            .map(String::from),
-                        active: is_active,
-                    });
-                }
+                manifests.push((dir_name, std::mem::take(&mut current_yaml)));
             }
         } else if current_name.is_some() {
             current_yaml.push_str(line);
@@ -403,5 +577,90 @@ pub async fn list_agents(
         }
     }
 
-    Ok(agents)
+    Ok(manifests)
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use std::cell::RefCell;
+    use std::path::{Path, PathBuf};
+
+    use super::*;
+
+    /// Records every `write_agent_artifacts` call instead of touching disk.
+    #[derive(Default)]
+    struct MemoryWriter {
+        writes: RefCell<Vec<(PathBuf, Vec<GeneratedFile>)>>,
+    }
+
+    impl LocalArtifactWriter for MemoryWriter {
+        fn write_agent_artifacts(&self, dir: &Path, files: &[GeneratedFile]) -> Result<()> {
+            self.writes
+                .borrow_mut()
+                .push((dir.to_path_buf(), files.to_vec()));
+            Ok(())
+        }
+    }
+
+    fn manifest() -> polis_common::agent::AgentManifest {
+        crate::domain::agent::test_manifest("alpha", &[(3000, "ALPHA_PORT", 9000)])
+    }
+
+    #[test]
+    fn test_write_artifacts_to_dir_writes_four_artifacts() {
+        use crate::domain::agent::artifacts;
+
+        let writer = MemoryWriter::default();
+        let manifest = manifest();
+        let dir = Path::new("/polis/agents/alpha/.generated");
+        write_artifacts_to_dir(&writer, dir, "alpha", &manifest, "KEY=v\n".into(), &[])
+            .expect("write");
+
+        let writes = writer.writes.into_inner();
+        assert_eq!(writes.len(), 1);
+        let (written_to, files) = &writes[0];
+        assert_eq!(written_to, dir);
+        let names: Vec<&str> = files.iter().map(|f| f.file.as_str()).collect();
+        assert_eq!(
+            names,
+            crate::domain::agent::doctor::generated_artifacts("alpha")
+        );
+
+        let unit = artifacts::systemd_unit(&manifest);
+        assert_eq!(files[0].content, artifacts::compose_overlay(&manifest));
+        assert_eq!(files[1].content, unit);
+        assert_eq!(files[2].content, artifacts::service_hash(&unit));
+        assert_eq!(files[3].content, "KEY=v\n");
+    }
+
+    #[test]
+    fn test_write_artifacts_to_dir_persists_port_overrides() {
+        let writer = MemoryWriter::default();
+        let overrides = [PortOverride {
+            container: 3000,
+            host: 9100,
+        }];
+        let mut manifest = manifest();
+        apply_port_overrides(&mut manifest, &overrides).expect("declared port");
+        write_artifacts_to_dir(
+            &writer,
+            Path::new(".generated"),
+            "alpha",
+            &manifest,
+            String::new(),
+            &overrides,
+        )
+        .expect("write");
+
+        let writes = writer.writes.into_inner();
+        let files = &writes[0].1;
+        assert_eq!(files.len(), 5);
+        assert_eq!(files[4].file, PORT_OVERRIDES_FILE);
+        assert_eq!(
+            parse_port_overrides(&files[4].content).expect("parse"),
+            overrides
+        );
+        assert!(files[0].content.contains("\"9100:3000\""));
+    }
 }
@@ -0,0 +1,119 @@
+//! Application service — per-agent diagnostics (`polis agent doctor`).
+//!
+//! Imports only from `crate::domain` and `crate::application::ports`.
+
+use anyhow::Result;
+
+use crate::application::ports::{InstanceInspector, ShellExecutor};
+use crate::application::services::vm::lifecycle::{self as vm, VmState};
+use crate::domain::agent::artifacts::service_hash_matches;
+use crate::domain::agent::doctor::{
+    AgentCheck, AgentDiagnosis, HEALTH_PROBE_LIMIT, missing_artifacts, parse_health_log,
+};
+use crate::domain::workspace::{CONTAINER_NAME, VM_ROOT};
+
+/// Diagnose one installed agent: generated artifacts, unit-file integrity,
+/// systemd state, and the latest workspace health probes.
+///
+/// # Errors
+///
+/// Returns an error if the name is invalid, the VM is not running, the agent
+/// is not installed, or a VM command cannot be executed.
+pub async fn diagnose_agent(
+    provisioner: &(impl ShellExecutor + InstanceInspector),
+    name: &str,
+) -> Result<AgentDiagnosis> {
+    anyhow::ensure!(
+        crate::domain::agent::validate::is_valid_agent_name(name),
+        "invalid agent name: '{name}'"
+    );
+    anyhow::ensure!(
+        vm::state(provisioner).await? == VmState::Running,
+        "VM is not running. Start it first: polis start"
+    );
+
+    let agent_dir = format!("{VM_ROOT}/agents/{name}");
+    let exists = provisioner.exec(&["test", "-d", &agent_dir]).await?;
+    anyhow::ensure!(exists.status.success(), "Agent '{name}' is not installed.");
+
+    let generated = format!("{agent_dir}/.generated");
+    let mut checks = Vec::new();
+
+    let listing = provisioner.exec(&["ls", "-1", &generated]).await?;
+    let missing = missing_artifacts(name, &String::from_utf8_lossy(&listing.stdout));
+    checks.push(AgentCheck {
+        name: "artifacts",
+        ok: missing.is_empty(),
+        detail: if missing.is_empty() {
+            "generated artifacts present".to_string()
+        } else {
+            format!("missing in .generated/: {}", missing.join(", "))
+        },
+    });
+
+    let unit = provisioner
+        .exec(&["cat", &format!("{generated}/{name}.service")])
+        .await?;
+    let recorded = provisioner
+        .exec(&["cat", &format!("{generated}/{name}.service.sha256")])
+        .await?;
+    let intact = unit.status.success()
+        && recorded.status.success()
+        && service_hash_matches(
+            &String::from_utf8_lossy(&unit.stdout),
+            &String::from_utf8_lossy(&recorded.stdout),
+        );
+    checks.push(AgentCheck {
+        name: "service_integrity",
+        ok: intact,
+        detail: if intact {
+            format!("{name}.service matches {name}.service.sha256")
+        } else {
+            format!("{name}.service does not match {name}.service.sha256 (possible tampering)")
+        },
+    });
+
+    let unit_state = provisioner
+        .exec(&[
+            "docker",
+            "exec",
+            CONTAINER_NAME,
+            "systemctl",
+            "is-active",
+            &format!("{name}.service"),
+        ])
+        .await?;
+    let state = String::from_utf8_lossy(&unit_state.stdout)
+        .trim()
+        .to_string();
+    checks.push(AgentCheck {
+        name: "systemd_unit",
+        ok: state == "active",
+        detail: format!(
+            "{name}.service is {}",
+            if state.is_empty() { "unknown" } else { &state }
+        ),
+    });
+
+    let health = provisioner
+        .exec(&[
+            "docker",
+            "inspect",
+            "--format",
+            "{{json .State.Health}}",
+            CONTAINER_NAME,
+        ])
+        .await?;
+    let health_probes = if health.status.success() {
+        parse_health_log(&String::from_utf8_lossy(&health.stdout), HEALTH_PROBE_LIMIT)
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    Ok(AgentDiagnosis {
+        agent: name.to_string(),
+        checks,
+        health_probes,
+    })
+}
@@ -0,0 +1,87 @@
+//! Application service — agent service logs (`polis agent logs`).
+//!
+//! Imports only from `crate::domain` and `crate::application::ports`.
+
+use anyhow::{Context, Result};
+
+use crate::application::ports::{InstanceInspector, ShellExecutor, WorkspaceStateStore};
+use crate::application::services::agent_crud::{require_active_agent, require_running};
+use crate::application::services::vm::lifecycle::require_docker;
+use crate::domain::workspace::CONTAINER_NAME;
+
+/// Print the journal of the active agent's systemd unit, streaming new
+/// entries when `follow` is set.
+///
+/// Output goes straight to the terminal via `exec_status`, so Ctrl-C while
+/// following stops `journalctl` and returns its exit status.
+///
+/// # Errors
+///
+/// Returns an error if no agent is active, the VM is not running, or the
+/// command cannot be spawned.
+pub async fn show_agent_logs(
+    provisioner: &(impl ShellExecutor + InstanceInspector),
+    state_mgr: &impl WorkspaceStateStore,
+    follow: bool,
+    tail: Option<u32>,
+) -> Result<std::process::ExitStatus> {
+    let name = require_active_agent(state_mgr).await?;
+    require_running(provisioner).await?;
+    require_docker(provisioner).await?;
+
+    let args = journal_args(&name, follow, tail);
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    provisioner
+        .exec_status(&arg_refs)
+        .await
+        .context("failed to read agent logs")
+}
+
+/// `docker exec` arguments that run `journalctl` for agent `name`'s unit.
+fn journal_args(name: &str, follow: bool, tail: Option<u32>) -> Vec<String> {
+    let mut args: Vec<String> = ["docker", "exec", CONTAINER_NAME, "journalctl", "-u"]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    args.push(format!("{name}.service"));
+    args.push("--no-pager".to_string());
+    if let Some(n) = tail {
+        args.push("-n".to_string());
+        args.push(n.to_string());
+    }
+    if follow {
+        args.push("-f".to_string());
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_args_tail_and_follow() {
+        assert_eq!(
+            journal_args("alpha", true, Some(50)),
+            [
+                "docker",
+                "exec",
+                "polis-workspace",
+                "journalctl",
+                "-u",
+                "alpha.service",
+                "--no-pager",
+                "-n",
+                "50",
+                "-f",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_journal_args_defaults_print_whole_journal() {
+        let args = journal_args("alpha", false, None);
+        assert_eq!(args.last().map(String::as_str), Some("--no-pager"));
+        assert!(!args.iter().any(|a| a == "-f" || a == "-n"));
+    }
+}
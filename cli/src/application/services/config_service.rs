@@ -1,7 +1,9 @@
 //! Application service — configuration use-cases.
 
 use crate::application::ports::ConfigStore;
-use crate::domain::config::PolisConfig;
+use crate::domain::config::{
+    PolisConfig, check_round_trip, config_value, set_config_value, validate_config_key,
+};
 use anyhow::Result;
 
 /// Load configuration.
@@ -22,6 +24,32 @@ pub fn save_config(store: &impl ConfigStore, config: &PolisConfig) -> Result<()>
     store.save(config)
 }
 
+/// Read one setting.
+///
+/// # Errors
+///
+/// Returns an error if the key is unknown or the config cannot be loaded.
+pub fn get_value(store: &impl ConfigStore, key: &str) -> Result<serde_json::Value> {
+    validate_config_key(key)?;
+    config_value(&store.load()?, key)
+}
+
+/// Set one setting. The config is saved only if it would load back intact,
+/// so an invalid value never reaches the config file.
+///
+/// # Errors
+///
+/// Returns an error if the key or value is invalid, the updated config would
+/// not load back, or the config cannot be loaded or saved.
+pub fn set_value(store: &impl ConfigStore, key: &str, value: &str) -> Result<()> {
+    validate_config_key(key)?;
+    let mut config = store.load()?;
+    set_config_value(&mut config, key, value)?;
+    check_round_trip(&config)
+        .map_err(|e| anyhow::anyhow!("Refusing to save {key} = {value}: {e:#}"))?;
+    store.save(&config)
+}
+
 const VM_MCP_ADMIN_PASS: &str = "/opt/polis/secrets/mcp-admin-pass.txt";
 
 /// Propagate the security level to the workspace VM.
@@ -73,3 +101,61 @@ pub async fn propagate_security_level(
         _ => Ok(false),
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Config store holding the config in memory and counting saves.
+    #[derive(Default)]
+    struct MemoryStore {
+        config: RefCell<PolisConfig>,
+        saves: RefCell<u32>,
+    }
+
+    impl ConfigStore for MemoryStore {
+        fn load(&self) -> Result<PolisConfig> {
+            Ok(self.config.borrow().clone())
+        }
+        fn save(&self, config: &PolisConfig) -> Result<()> {
+            *self.config.borrow_mut() = config.clone();
+            *self.saves.borrow_mut() += 1;
+            Ok(())
+        }
+        fn path(&self) -> Result<PathBuf> {
+            Ok(PathBuf::from("config.yaml"))
+        }
+    }
+
+    #[test]
+    fn set_value_saves_and_get_value_reads_it_back() {
+        let store = MemoryStore::default();
+        set_value(&store, "security.level", "strict").expect("set");
+        assert_eq!(
+            get_value(&store, "security.level").expect("get"),
+            serde_json::json!("strict")
+        );
+        assert_eq!(*store.saves.borrow(), 1);
+    }
+
+    #[test]
+    fn set_value_never_saves_a_config_that_would_not_load_back() {
+        let store = MemoryStore::default();
+        store.config.borrow_mut().security.level = "permissive".to_string();
+
+        let err = set_value(&store, "update.check", "false").expect_err("invalid level");
+        assert!(
+            err.to_string().starts_with(
+                "Refusing to save update.check = false: Invalid value for security.level"
+            ),
+            "{err}"
+        );
+        assert!(set_value(&store, "update.check", "maybe").is_err());
+        assert!(set_value(&store, "defaults.agent", "x").is_err());
+        assert_eq!(*store.saves.borrow(), 0);
+    }
+}
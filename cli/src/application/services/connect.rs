@@ -86,10 +86,8 @@ pub async fn install_pubkey(mp: &impl ShellExecutor, pubkey: &str) -> Result<()>
 ///
 /// This function will return an error if the underlying operations fail.
 pub async fn write_host_key(ssh: &impl SshConfigurator, raw_key: &str) -> Result<()> {
-    let trimmed = raw_key.trim();
-    anyhow::ensure!(!trimmed.is_empty(), "empty host key");
-    crate::domain::ssh::validate_host_key(trimmed)?;
-    let host_key = format!("workspace {trimmed}");
+    anyhow::ensure!(!raw_key.trim().is_empty(), "empty host key");
+    let host_key = crate::domain::ssh::known_hosts_line(raw_key)?;
     ssh.update_host_key(&host_key).await
 }
 
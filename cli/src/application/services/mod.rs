@@ -5,11 +5,15 @@
 //! `crate::application::ports` — never from `crate::infra`, `crate::commands`,
 //! or `crate::output`.
 
+pub mod agent_archive;
 pub mod agent_crud;
+pub mod agent_doctor;
+pub mod agent_logs;
 pub mod cleanup_service;
 pub mod config_service;
 pub mod connect;
 pub mod security_service;
+pub mod self_test;
 pub mod update;
 pub mod vm;
 pub mod workspace_doctor;
@@ -0,0 +1,386 @@
+//! Application service — `polis self-test`.
+//!
+//! Launches a throwaway VM, runs smoke checks against it, and deletes it
+//! again. `mp` must point at `SELF_TEST_INSTANCE`; the run refuses to
+//! touch an instance named `polis`.
+
+use anyhow::{Context, Result};
+
+use crate::application::ports::{
+    AssetExtractor, InstanceSpec, LocalFs, POLIS_INSTANCE, ProgressReporter, VmProvisioner,
+};
+use crate::application::services::vm::lifecycle::{
+    check_prerequisites, prepare_cloud_init, verify_cloud_init,
+};
+use crate::domain::self_test::{SelfTestPhase, SelfTestReport};
+
+/// Run every self-test phase and report how each went.
+///
+/// After a failed launch or smoke check the remaining smoke checks are
+/// skipped, but teardown still runs once a launch was attempted so no VM
+/// is left behind.
+pub async fn run_self_test(
+    mp: &impl VmProvisioner,
+    assets: &impl AssetExtractor,
+    local_fs: &impl LocalFs,
+    reporter: &impl ProgressReporter,
+    launch_timeout_secs: u64,
+) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+
+    run_phase(&mut report, reporter, SelfTestPhase::Prerequisites, async {
+        anyhow::ensure!(
+            mp.instance_name() != POLIS_INSTANCE,
+            "refusing to self-test the '{POLIS_INSTANCE}' workspace VM"
+        );
+        check_prerequisites(mp).await
+    })
+    .await;
+    if report.has_failure() {
+        report.skip(SelfTestPhase::Launch);
+        for phase in SelfTestPhase::SMOKE {
+            report.skip(phase);
+        }
+        report.skip(SelfTestPhase::Teardown);
+        return report;
+    }
+
+    run_phase(&mut report, reporter, SelfTestPhase::Launch, async {
+        launch(mp, assets, local_fs, launch_timeout_secs).await
+    })
+    .await;
+    for phase in SelfTestPhase::SMOKE {
+        if report.has_failure() {
+            report.skip(phase);
+            continue;
+        }
+        run_phase(&mut report, reporter, phase, smoke_check(mp, phase)).await;
+    }
+
+    run_phase(&mut report, reporter, SelfTestPhase::Teardown, async {
+        teardown(mp).await
+    })
+    .await;
+    report
+}
+
+async fn run_phase(
+    report: &mut SelfTestReport,
+    reporter: &impl ProgressReporter,
+    phase: SelfTestPhase,
+    work: impl Future<Output = Result<()>>,
+) {
+    reporter.begin_stage(&format!("{}...", phase.label()));
+    let result = work.await.map_err(|e| format!("{e:#}"));
+    if result.is_ok() {
+        reporter.complete_stage();
+    } else {
+        reporter.fail_stage();
+    }
+    report.record(phase, result);
+}
+
+/// Launch the self-test VM with the polis cloud-init, removing any
+/// leftover from an interrupted run first.
+async fn launch(
+    mp: &impl VmProvisioner,
+    assets: &impl AssetExtractor,
+    local_fs: &impl LocalFs,
+    launch_timeout_secs: u64,
+) -> Result<()> {
+    if mp.info().await.is_ok_and(|o| o.status.success()) {
+        teardown(mp)
+            .await
+            .context("removing a leftover self-test VM")?;
+    }
+    let (cloud_init, _assets_guard) = prepare_cloud_init(assets, local_fs).await?;
+    let timeout = launch_timeout_secs.to_string();
+    let output = mp
+        .launch(&InstanceSpec {
+            image: "24.04",
+            cpus: "2",
+            memory: "4G",
+            disk: "20G",
+            cloud_init: Some(&cloud_init),
+            timeout: Some(&timeout),
+        })
+        .await?;
+    anyhow::ensure!(
+        output.status.success(),
+        "multipass launch failed: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(())
+}
+
+async fn smoke_check(mp: &impl VmProvisioner, phase: SelfTestPhase) -> Result<()> {
+    match phase {
+        SelfTestPhase::CloudInit => verify_cloud_init(mp).await,
+        SelfTestPhase::Docker => {
+            let output = mp.exec(&["docker", "info"]).await?;
+            anyhow::ensure!(output.status.success(), "docker is not responding");
+            Ok(())
+        }
+        SelfTestPhase::Sysbox => {
+            let output = mp
+                .exec(&["docker", "info", "--format", "{{.Runtimes}}"])
+                .await?;
+            anyhow::ensure!(
+                String::from_utf8_lossy(&output.stdout).contains("sysbox-runc"),
+                "sysbox-runc is not registered with docker"
+            );
+            Ok(())
+        }
+        // Only `SelfTestPhase::SMOKE` phases are passed in.
+        _ => Ok(()),
+    }
+}
+
+async fn teardown(mp: &impl VmProvisioner) -> Result<()> {
+    let deleted = mp.delete().await?;
+    anyhow::ensure!(
+        deleted.status.success(),
+        "multipass delete failed: {}",
+        String::from_utf8_lossy(&deleted.stderr).trim()
+    );
+    let purged = mp.purge().await?;
+    anyhow::ensure!(
+        purged.status.success(),
+        "multipass purge failed: {}",
+        String::from_utf8_lossy(&purged.stderr).trim()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use std::cell::RefCell;
+    use std::process::Output;
+
+    use super::*;
+    use crate::application::ports::{FileTransfer, InstanceInspector, InstanceLifecycle};
+    use crate::application::services::vm::test_support::{
+        exit_status, fail_output, impl_shell_executor_stubs, ok_output,
+    };
+    use crate::domain::self_test::PhaseOutcome;
+
+    /// VM double that records lifecycle calls and fails what it is told to.
+    #[derive(Default)]
+    struct SelfTestVm {
+        /// Instance name; `SELF_TEST_INSTANCE` when `None`.
+        name: Option<&'static str>,
+        launch_fails: bool,
+        no_sysbox: bool,
+        calls: RefCell<Vec<&'static str>>,
+    }
+
+    impl InstanceLifecycle for SelfTestVm {
+        async fn launch(&self, spec: &InstanceSpec<'_>) -> Result<Output> {
+            assert!(
+                spec.cloud_init.is_some(),
+                "launch uses the polis cloud-init"
+            );
+            self.calls.borrow_mut().push("launch");
+            Ok(if self.launch_fails {
+                fail_output()
+            } else {
+                ok_output(b"")
+            })
+        }
+        async fn start(&self) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        async fn stop(&self) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        async fn delete(&self) -> Result<Output> {
+            self.calls.borrow_mut().push("delete");
+            Ok(ok_output(b""))
+        }
+        async fn purge(&self) -> Result<Output> {
+            self.calls.borrow_mut().push("purge");
+            Ok(ok_output(b""))
+        }
+        async fn snapshot(&self, _: &str) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        async fn restore(&self, _: &str) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        async fn list_snapshots(&self) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+    }
+
+    impl InstanceInspector for SelfTestVm {
+        fn instance_name(&self) -> &str {
+            self.name
+                .unwrap_or(crate::application::ports::SELF_TEST_INSTANCE)
+        }
+        async fn info(&self) -> Result<Output> {
+            Ok(fail_output())
+        }
+        async fn version(&self) -> Result<Output> {
+            Ok(ok_output(b"multipass   1.16.0\n"))
+        }
+    }
+
+    impl FileTransfer for SelfTestVm {
+        async fn transfer(&self, _: &str, _: &str) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        async fn transfer_recursive(&self, _: &str, _: &str) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+    }
+
+    impl crate::application::ports::ShellExecutor for SelfTestVm {
+        impl_shell_executor_stubs!(exec_with_stdin, exec_spawn);
+
+        async fn exec(&self, args: &[&str]) -> Result<Output> {
+            if args.contains(&"{{.Runtimes}}") && self.no_sysbox {
+                return Ok(ok_output(b"map[runc:{runc [] <nil>}]"));
+            }
+            Ok(ok_output(b"map[sysbox-runc:{} runc:{}]"))
+        }
+
+        async fn exec_status(&self, _: &[&str]) -> Result<std::process::ExitStatus> {
+            Ok(exit_status(0))
+        }
+    }
+
+    struct Assets;
+
+    impl AssetExtractor for Assets {
+        async fn extract_assets(&self) -> Result<(std::path::PathBuf, Box<dyn std::any::Any>)> {
+            Ok((std::path::PathBuf::from("/tmp/assets"), Box::new(())))
+        }
+        async fn get_asset(&self, _name: &str) -> Result<&'static [u8]> {
+            anyhow::bail!("not expected")
+        }
+    }
+
+    struct Fs;
+
+    impl LocalFs for Fs {
+        fn exists(&self, _: &std::path::Path) -> bool {
+            false
+        }
+        fn create_dir_all(&self, _: &std::path::Path) -> Result<()> {
+            Ok(())
+        }
+        fn remove_dir_all(&self, _: &std::path::Path) -> Result<()> {
+            Ok(())
+        }
+        fn remove_file(&self, _: &std::path::Path) -> Result<()> {
+            Ok(())
+        }
+        fn write(&self, _: &std::path::Path, _: String) -> Result<()> {
+            Ok(())
+        }
+        fn write_bytes(&self, _: &std::path::Path, _: &[u8]) -> Result<()> {
+            Ok(())
+        }
+        fn read_to_string(&self, _: &std::path::Path) -> Result<String> {
+            anyhow::bail!("not expected")
+        }
+        fn set_permissions(&self, _: &std::path::Path, _: u32) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Quiet;
+
+    impl ProgressReporter for Quiet {
+        fn step(&self, _: &str) {}
+        fn success(&self, _: &str) {}
+        fn warn(&self, _: &str) {}
+    }
+
+    async fn run(vm: &SelfTestVm) -> SelfTestReport {
+        run_self_test(vm, &Assets, &Fs, &Quiet, 600).await
+    }
+
+    fn outcomes(report: &SelfTestReport) -> Vec<(SelfTestPhase, bool)> {
+        report
+            .phases
+            .iter()
+            .map(|p| (p.phase, p.outcome == PhaseOutcome::Passed))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_self_test_passes_and_tears_down() {
+        let vm = SelfTestVm::default();
+        let report = run(&vm).await;
+
+        assert!(report.passed(), "{report:?}");
+        assert_eq!(report.phases.len(), 6);
+        assert_eq!(*vm.calls.borrow(), ["launch", "delete", "purge"]);
+    }
+
+    #[tokio::test]
+    async fn test_self_test_failed_launch_skips_smoke_checks_but_tears_down() {
+        let vm = SelfTestVm {
+            launch_fails: true,
+            ..SelfTestVm::default()
+        };
+        let report = run(&vm).await;
+
+        assert!(!report.passed());
+        assert_eq!(
+            outcomes(&report),
+            [
+                (SelfTestPhase::Prerequisites, true),
+                (SelfTestPhase::Launch, false),
+                (SelfTestPhase::CloudInit, false),
+                (SelfTestPhase::Docker, false),
+                (SelfTestPhase::Sysbox, false),
+                (SelfTestPhase::Teardown, true),
+            ]
+        );
+        assert!(
+            report.phases[2..5]
+                .iter()
+                .all(|p| p.outcome == PhaseOutcome::Skipped)
+        );
+        assert_eq!(*vm.calls.borrow(), ["launch", "delete", "purge"]);
+    }
+
+    #[tokio::test]
+    async fn test_self_test_refuses_the_workspace_vm() {
+        let vm = SelfTestVm {
+            name: Some(POLIS_INSTANCE),
+            ..SelfTestVm::default()
+        };
+        let report = run(&vm).await;
+
+        assert!(!report.passed());
+        assert!(vm.calls.borrow().is_empty(), "nothing launched or deleted");
+    }
+
+    #[tokio::test]
+    async fn test_self_test_reports_failing_smoke_check() {
+        let vm = SelfTestVm {
+            no_sysbox: true,
+            ..SelfTestVm::default()
+        };
+        let report = run(&vm).await;
+
+        let sysbox = report
+            .phases
+            .iter()
+            .find(|p| p.phase == SelfTestPhase::Sysbox)
+            .expect("sysbox phase");
+        assert!(
+            matches!(&sysbox.outcome, PhaseOutcome::Failed { detail } if detail.contains("sysbox-runc")),
+            "{sysbox:?}"
+        );
+        assert_eq!(
+            report.phases.last().map(|p| &p.outcome),
+            Some(&PhaseOutcome::Passed),
+            "teardown still runs"
+        );
+    }
+}
@@ -6,14 +6,19 @@
 use anyhow::{Context, Result};
 
 use crate::application::ports::{
-    AssetExtractor, FileHasher, FileTransfer, InstanceInspector, ProgressReporter, ShellExecutor,
+    AssetExtractor, FileHasher, FileTransfer, InstanceInspector, LocalFs, LocalPaths,
+    ProgressReporter, ShellExecutor,
 };
 use crate::application::services::vm::{
-    integrity::{verify_image_digests, write_config_hash},
+    integrity::{compute_config_hash, read_config_hash, verify_image_digests, write_config_hash},
     lifecycle::{self as vm, VmState},
-    provision::transfer_config,
+    provision::{read_deployed_env, transfer_config},
     services::pull_images,
 };
+use crate::domain::update::{
+    ContainerUpdate, ReleaseNotes, UpdateCheckCache, container_updates, newer_release,
+    outdated_services, update_check_due, update_notice,
+};
 
 // ── Public types ──────────────────────────────────────────────────────────────
 
@@ -23,8 +28,9 @@ pub enum UpdateInfo {
     Available {
         /// The new version string (without leading `v`).
         version: String,
-        /// Up to 5 bullet-point release notes.
-        release_notes: Vec<String>,
+        /// Release-note bullets grouped by `### Section` header, up to 5
+        /// per section. Bodies without headers yield one untitled section.
+        release_notes: Vec<(String, Vec<String>)>,
         /// Direct download URL for the platform asset.
         download_url: String,
     },
@@ -62,14 +68,134 @@ pub trait UpdateChecker {
     fn perform_update(&self, version: &str) -> Result<()>;
 }
 
+/// Abstraction over the release listing, enabling test doubles.
+pub trait ReleaseLister {
+    /// List published releases with their parsed notes, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the release list cannot be fetched.
+    fn list_releases(&self) -> Result<Vec<ReleaseNotes>>;
+}
+
+// ── Update notification service ───────────────────────────────────────────────
+
+/// Return a one-line notice when a release newer than `current` exists.
+///
+/// GitHub is only asked when the result cached in
+/// `~/.polis/cache/update-check.json` is older than a day; otherwise the
+/// cached answer is reused. A failed check is cached as "nothing newer" so an
+/// offline machine is not retried on every command. Errors are swallowed —
+/// the notice must never fail the command it follows.
+pub fn cached_update_notice(
+    checker: &impl UpdateChecker,
+    paths: &impl LocalPaths,
+    fs: &impl LocalFs,
+    current: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<String> {
+    update_notice(
+        &cached_update_check(checker, paths, fs, current, now)?,
+        current,
+    )
+}
+
+/// Update availability for `polis status --check-updates`.
+///
+/// The CLI release comes from the same daily cache as the update notice;
+/// with `offline` the cache is used as-is and GitHub is never asked.
+/// Container versions are compared against this CLI from the deployed
+/// `.env`, which is only readable while the VM runs.
+pub async fn status_updates(
+    checker: &impl UpdateChecker,
+    paths: &impl LocalPaths,
+    fs: &impl LocalFs,
+    mp: &(impl InstanceInspector + ShellExecutor),
+    current: &str,
+    offline: bool,
+    now: chrono::DateTime<chrono::Utc>,
+) -> polis_common::types::UpdateStatus {
+    let cache = if offline {
+        read_update_cache(paths, fs)
+    } else {
+        cached_update_check(checker, paths, fs, current, now)
+    };
+    let latest_version = cache
+        .as_ref()
+        .and_then(|c| newer_release(c, current))
+        .map(str::to_owned);
+    let outdated_services = if vm::state(mp).await.ok() == Some(VmState::Running) {
+        read_deployed_env(mp)
+            .await
+            .map(|env| outdated_services(&env, current))
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    polis_common::types::UpdateStatus {
+        updates_available: latest_version.is_some() || !outdated_services.is_empty(),
+        latest_version,
+        outdated_services,
+    }
+}
+
+/// The cached update-check result, if present and readable.
+fn read_update_cache(paths: &impl LocalPaths, fs: &impl LocalFs) -> Option<UpdateCheckCache> {
+    let content = fs.read_to_string(&update_cache_path(paths)?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// `~/.polis/cache/update-check.json`.
+fn update_cache_path(paths: &impl LocalPaths) -> Option<std::path::PathBuf> {
+    Some(
+        paths
+            .polis_dir()
+            .ok()?
+            .join("cache")
+            .join("update-check.json"),
+    )
+}
+
+/// Latest update-check result, refreshed from GitHub when the cache is due.
+fn cached_update_check(
+    checker: &impl UpdateChecker,
+    paths: &impl LocalPaths,
+    fs: &impl LocalFs,
+    current: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<UpdateCheckCache> {
+    let path = update_cache_path(paths)?;
+    let cached = read_update_cache(paths, fs);
+
+    let cache = if update_check_due(cached.as_ref(), now) {
+        let latest_version = match checker.check(current) {
+            Ok(UpdateInfo::Available { version, .. }) => Some(version),
+            Ok(UpdateInfo::UpToDate) | Err(_) => None,
+        };
+        let fresh = UpdateCheckCache {
+            checked_at: now,
+            latest_version,
+        };
+        if let (Some(dir), Ok(content)) = (path.parent(), serde_json::to_string(&fresh)) {
+            let _ = fs
+                .create_dir_all(dir)
+                .and_then(|()| fs.write(&path, content));
+        }
+        fresh
+    } else {
+        cached?
+    };
+    Some(cache)
+}
+
 // ── VM config update service ──────────────────────────────────────────────────
 
 /// Update the VM config when the CLI has been updated to a new version.
 ///
-/// Extracts embedded assets, computes the SHA256 of the new config tarball,
-/// and compares it against the hash stored in the VM. If they differ, stops
-/// services, transfers the new config, pulls images, verifies digests,
-/// restarts services, and writes the new hash.
+/// Computes the config hash of the embedded assets (tarball plus `.env`
+/// template) and compares it against the hash stored in the VM. If they
+/// differ, stops services, transfers the new config, pulls images, verifies
+/// digests, restarts services, and writes the new hash.
 ///
 /// # Errors
 ///
@@ -81,24 +207,18 @@ pub async fn update_vm_config(
     reporter: &impl ProgressReporter,
     assets_dir: &std::path::Path,
     version: &str,
+    registry_prefix: &str,
 ) -> Result<UpdateVmConfigOutcome> {
-    // Compute SHA256 of the new config tarball
-    let new_hash = hasher
-        .sha256_file(&assets_dir.join("polis-setup.config.tar"))
-        .context("computing config tarball hash")?;
-
-    // Read current hash from VM
-    let hash_output = mp
-        .exec(&["cat", "/opt/polis/.config-hash"])
-        .await
-        .context("reading current config hash from VM")?;
-    let current_hash = String::from_utf8_lossy(&hash_output.stdout)
-        .trim()
-        .to_string();
-
-    // If hashes match, config is up to date
-    if new_hash == current_hash {
-        return Ok(UpdateVmConfigOutcome::UpToDate);
+    // Compute the config hash of the new embedded assets
+    let new_hash = compute_config_hash(hasher, assets_dir, version, registry_prefix)?;
+
+    // If the hash stored in the VM matches, config is up to date
+    let current_hash = read_config_hash(mp).await;
+    let deployed_env = read_deployed_env(mp).await.unwrap_or_default();
+    if crate::domain::workspace::config_hash_matches(current_hash.as_deref(), &new_hash) {
+        return Ok(UpdateVmConfigOutcome::UpToDate {
+            containers: container_updates(&deployed_env, None),
+        });
     }
 
     // Hashes differ — perform full config update cycle
@@ -115,7 +235,7 @@ pub async fn update_vm_config(
     .context("stopping services")?;
 
     // Transfer new config
-    transfer_config(mp, assets_dir, version)
+    let env_sha256 = transfer_config(mp, assets_dir, version, registry_prefix)
         .await
         .context("transferring new config")?;
 
@@ -125,7 +245,7 @@ pub async fn update_vm_config(
         .context("pulling Docker images")?;
 
     // Verify image digests
-    verify_image_digests(mp, assets, reporter)
+    verify_image_digests(mp, assets, reporter, registry_prefix)
         .await
         .context("verifying image digests")?;
 
@@ -146,15 +266,39 @@ pub async fn update_vm_config(
         .await
         .context("writing new config hash")?;
 
-    Ok(UpdateVmConfigOutcome::Updated)
+    // Report the tags from the `.env` just written, which may pin services
+    // or use a different registry prefix than the CLI defaults.
+    let new_env = read_deployed_env(mp).await.unwrap_or_default();
+    Ok(UpdateVmConfigOutcome::Updated {
+        env_sha256,
+        containers: container_updates(&deployed_env, Some(&new_env)),
+    })
 }
 
 /// Outcome of the VM config update service.
 pub enum UpdateVmConfigOutcome {
     /// Config was already up to date — no changes made.
-    UpToDate,
+    UpToDate {
+        /// Per-service versions, all unchanged.
+        containers: Vec<ContainerUpdate>,
+    },
     /// Config was updated successfully.
-    Updated,
+    Updated {
+        /// SHA256 of the newly written `.env`, to be recorded in state.
+        env_sha256: String,
+        /// Per-service version changes, from the `.env` read before the update.
+        containers: Vec<ContainerUpdate>,
+    },
+}
+
+impl UpdateVmConfigOutcome {
+    /// Per-service results, whichever way the update went.
+    #[must_use]
+    pub fn into_containers(self) -> Vec<ContainerUpdate> {
+        match self {
+            Self::UpToDate { containers } | Self::Updated { containers, .. } => containers,
+        }
+    }
 }
 
 /// Check whether the VM needs a config update (VM must be running).
@@ -168,3 +312,207 @@ pub enum UpdateVmConfigOutcome {
 pub async fn should_update_vm_config(mp: &impl InstanceInspector) -> Result<bool> {
     Ok(vm::state(mp).await? == VmState::Running)
 }
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    use chrono::{Duration, Utc};
+
+    use super::*;
+    use crate::application::services::vm::test_support::{impl_shell_executor_stubs, ok_output};
+
+    /// Reports `latest` as available and counts how often it was asked.
+    struct CountingChecker {
+        latest: &'static str,
+        calls: Cell<usize>,
+    }
+
+    impl UpdateChecker for CountingChecker {
+        fn check(&self, _current: &str) -> Result<UpdateInfo> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(UpdateInfo::Available {
+                version: self.latest.to_string(),
+                release_notes: vec![],
+                download_url: String::new(),
+            })
+        }
+        fn verify_signature(&self, _url: &str) -> Result<SignatureInfo> {
+            anyhow::bail!("not expected")
+        }
+        fn perform_update(&self, _version: &str) -> Result<()> {
+            anyhow::bail!("not expected")
+        }
+    }
+
+    /// In-memory `~/.polis`.
+    #[derive(Default)]
+    struct MemFs(RefCell<HashMap<PathBuf, String>>);
+
+    impl LocalPaths for MemFs {
+        fn images_dir(&self) -> PathBuf {
+            PathBuf::from("/home/u/.polis/images")
+        }
+        fn polis_dir(&self) -> Result<PathBuf> {
+            Ok(PathBuf::from("/home/u/.polis"))
+        }
+    }
+
+    impl LocalFs for MemFs {
+        fn exists(&self, path: &Path) -> bool {
+            self.0.borrow().contains_key(path)
+        }
+        fn create_dir_all(&self, _path: &Path) -> Result<()> {
+            Ok(())
+        }
+        fn remove_dir_all(&self, _path: &Path) -> Result<()> {
+            Ok(())
+        }
+        fn remove_file(&self, path: &Path) -> Result<()> {
+            self.0.borrow_mut().remove(path);
+            Ok(())
+        }
+        fn write(&self, path: &Path, content: String) -> Result<()> {
+            self.0.borrow_mut().insert(path.to_path_buf(), content);
+            Ok(())
+        }
+        fn write_bytes(&self, path: &Path, content: &[u8]) -> Result<()> {
+            self.write(path, String::from_utf8_lossy(content).into_owned())
+        }
+        fn read_to_string(&self, path: &Path) -> Result<String> {
+            self.0
+                .borrow()
+                .get(path)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("not found: {}", path.display()))
+        }
+        fn set_permissions(&self, _path: &Path, _mode: u32) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_cached_update_notice_checks_at_most_once_per_day() {
+        let checker = CountingChecker {
+            latest: "0.5.0",
+            calls: Cell::new(0),
+        };
+        let fs = MemFs::default();
+        let start = Utc::now();
+
+        let notice = cached_update_notice(&checker, &fs, &fs, "0.4.0", start);
+        assert!(notice.expect("newer release").contains("v0.5.0"));
+        assert!(fs.exists(Path::new("/home/u/.polis/cache/update-check.json")));
+
+        let later = start + Duration::hours(12);
+        assert!(cached_update_notice(&checker, &fs, &fs, "0.4.0", later).is_some());
+        assert_eq!(checker.calls.get(), 1, "cached result should be reused");
+
+        let next_day = start + Duration::hours(25);
+        cached_update_notice(&checker, &fs, &fs, "0.4.0", next_day);
+        assert_eq!(checker.calls.get(), 2, "stale cache should trigger a check");
+    }
+
+    /// VM in a given multipass state whose `.env` pins every service to `tag`.
+    struct DeployedVm {
+        state: &'static str,
+        tag: &'static str,
+    }
+
+    impl InstanceInspector for DeployedVm {
+        async fn info(&self) -> Result<std::process::Output> {
+            let info = format!(r#"{{"info":{{"polis":{{"state":"{}"}}}}}}"#, self.state);
+            Ok(ok_output(info.as_bytes()))
+        }
+        async fn version(&self) -> Result<std::process::Output> {
+            anyhow::bail!("not expected")
+        }
+    }
+
+    impl ShellExecutor for DeployedVm {
+        async fn exec(&self, _: &[&str]) -> Result<std::process::Output> {
+            let env = format!("POLIS_GATE_VERSION={}\n", self.tag);
+            Ok(ok_output(env.as_bytes()))
+        }
+        impl_shell_executor_stubs!(exec_with_stdin, exec_spawn, exec_status);
+    }
+
+    #[tokio::test]
+    async fn test_status_updates_reflects_update_check() {
+        let checker = CountingChecker {
+            latest: "0.5.0",
+            calls: Cell::new(0),
+        };
+        let fs = MemFs::default();
+        let vm = DeployedVm {
+            state: "Running",
+            tag: "v0.4.0",
+        };
+        let status = status_updates(&checker, &fs, &fs, &vm, "0.4.0", false, Utc::now()).await;
+        assert!(status.updates_available);
+        assert_eq!(status.latest_version.as_deref(), Some("0.5.0"));
+        assert!(status.outdated_services.is_empty());
+
+        let status = status_updates(&checker, &fs, &fs, &vm, "0.5.0", false, Utc::now()).await;
+        assert!(status.updates_available, "containers lag behind the CLI");
+        assert_eq!(status.latest_version, None);
+        assert_eq!(status.outdated_services, ["gate"]);
+    }
+
+    #[tokio::test]
+    async fn test_status_updates_up_to_date() {
+        let checker = CountingChecker {
+            latest: "0.4.0",
+            calls: Cell::new(0),
+        };
+        let fs = MemFs::default();
+        let vm = DeployedVm {
+            state: "Stopped",
+            tag: "v0.3.0",
+        };
+        let status = status_updates(&checker, &fs, &fs, &vm, "0.4.0", false, Utc::now()).await;
+        assert!(!status.updates_available);
+        assert!(
+            status.outdated_services.is_empty(),
+            "stopped VM is not read"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_status_updates_offline_uses_cache_only() {
+        let checker = CountingChecker {
+            latest: "0.5.0",
+            calls: Cell::new(0),
+        };
+        let fs = MemFs::default();
+        let vm = DeployedVm {
+            state: "Stopped",
+            tag: "v0.4.0",
+        };
+        let now = Utc::now();
+        let status = status_updates(&checker, &fs, &fs, &vm, "0.4.0", true, now).await;
+        assert!(!status.updates_available);
+        assert_eq!(checker.calls.get(), 0, "offline must not query GitHub");
+
+        cached_update_notice(&checker, &fs, &fs, "0.4.0", now);
+        let status = status_updates(&checker, &fs, &fs, &vm, "0.4.0", true, now).await;
+        assert_eq!(status.latest_version.as_deref(), Some("0.5.0"));
+        assert_eq!(checker.calls.get(), 1);
+    }
+
+    #[test]
+    fn test_cached_update_notice_silent_once_updated() {
+        let checker = CountingChecker {
+            latest: "0.5.0",
+            calls: Cell::new(0),
+        };
+        let fs = MemFs::default();
+        let now = Utc::now();
+        cached_update_notice(&checker, &fs, &fs, "0.4.0", now);
+        assert!(cached_update_notice(&checker, &fs, &fs, "0.5.0", now).is_none());
+        assert_eq!(checker.calls.get(), 1);
+    }
+}
@@ -5,7 +5,6 @@ use std::time::Duration;
 use anyhow::Result;
 
 use crate::application::ports::{ProgressReporter, ShellExecutor};
-use crate::domain::workspace::COMPOSE_PATH;
 
 /// Health status.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -76,54 +75,23 @@ pub async fn wait_ready(
 
 /// Check current health status.
 pub async fn check(mp: &impl ShellExecutor) -> HealthStatus {
-    let Ok(output) = mp
-        .exec(&[
-            "docker",
-            "compose",
-            "-f",
-            COMPOSE_PATH,
-            "ps",
-            "--format",
-            "json",
-            "workspace",
-        ])
-        .await
-    else {
+    let Ok(containers) = mp.container_status(&["workspace"]).await else {
         return HealthStatus::Unknown;
     };
-
-    if !output.status.success() {
-        return HealthStatus::Unknown;
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let Some(line) = stdout.lines().next() else {
+    let Some(container) = containers.first() else {
         return HealthStatus::Unknown;
     };
+    let health = container.health.as_deref().unwrap_or("");
 
-    let container: serde_json::Value = match serde_json::from_str(line) {
-        Ok(v) => v,
-        Err(_) => return HealthStatus::Unknown,
-    };
-
-    let state = container
-        .get("State")
-        .and_then(|s| s.as_str())
-        .unwrap_or("");
-    let health = container
-        .get("Health")
-        .and_then(|s| s.as_str())
-        .unwrap_or("");
-
-    if state == "running" && health == "healthy" {
+    if container.state == "running" && health == "healthy" {
         HealthStatus::Healthy
-    } else if state == "running" {
+    } else if container.state == "running" {
         HealthStatus::Unhealthy {
             reason: format!("health: {health}"),
         }
     } else {
         HealthStatus::Unhealthy {
-            reason: format!("state: {state}"),
+            reason: format!("state: {}", container.state),
         }
     }
 }
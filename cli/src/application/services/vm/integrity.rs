@@ -2,11 +2,40 @@
 //!
 //! Imports only from `crate::domain` and `crate::application::ports`.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
 use anyhow::{Context, Result};
 
-use crate::application::ports::{AssetExtractor, ShellExecutor};
+use crate::application::ports::{AssetExtractor, FileHasher, ShellExecutor, WorkspaceStateStore};
+use crate::application::services::vm::provision::generate_env_content;
+
+/// Compute the config hash for the extracted assets in `assets_dir`.
+///
+/// Covers the config tarball and the `.env` template generated for `version`
+/// and `registry_prefix`, so identical assets always produce the same hash and
+/// a change to either produces a different one. Compare against [`read_config_hash`] with
+/// [`crate::domain::workspace::config_hash_matches`].
+///
+/// # Errors
+///
+/// Returns an error if the tarball cannot be hashed, `version` is not a
+/// valid release tag, or `registry_prefix` is malformed.
+pub fn compute_config_hash(
+    hasher: &(impl FileHasher + ?Sized),
+    assets_dir: &Path,
+    version: &str,
+    registry_prefix: &str,
+) -> Result<String> {
+    let tarball_sha256 = hasher
+        .sha256_file(&assets_dir.join("polis-setup.config.tar"))
+        .context("computing config tarball SHA256")?;
+    let env_template = generate_env_content(version, registry_prefix, &BTreeMap::new())?;
+    Ok(crate::domain::workspace::config_hash(
+        &tarball_sha256,
+        &env_template,
+    ))
+}
 
 /// Write the config hash to `/opt/polis/.config-hash` inside the VM.
 ///
@@ -35,19 +64,53 @@ pub async fn write_config_hash(mp: &impl ShellExecutor, hash: &str) -> Result<()
     Ok(())
 }
 
+/// Read the config hash from `/opt/polis/.config-hash` inside the VM.
+///
+/// Returns `None` if the file is missing, empty, or unreadable — e.g. after a
+/// provisioning attempt that never reached a successful startup.
+pub async fn read_config_hash(mp: &impl ShellExecutor) -> Option<String> {
+    let output = mp.exec(&["cat", "/opt/polis/.config-hash"]).await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let hash = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!hash.is_empty()).then_some(hash)
+}
+
+/// Record the SHA256 of the `.env` just written to the VM in workspace state.
+///
+/// Call after any operation that rewrites `/opt/polis/.env` (provision,
+/// config update, repair) so `polis doctor` does not report the CLI's own
+/// write as tampering. No-op when there is no workspace state yet.
+///
+/// # Errors
+///
+/// Returns an error if the state cannot be loaded or saved.
+pub async fn record_env_hash(state_mgr: &impl WorkspaceStateStore, env_sha256: &str) -> Result<()> {
+    let Some(mut state) = state_mgr.load_async().await? else {
+        return Ok(());
+    };
+    state.env_sha256 = Some(env_sha256.to_owned());
+    state_mgr.save_async(&state).await
+}
+
 /// Mapping from Docker image reference to expected sha256 digest.
 ///
 /// Example entry:
 /// ```json
 /// { "ghcr.io/odralabshq/polis-resolver:v0.4.0": "sha256:abc123..." }
 /// ```
+///
+/// Keys always name the default registry; [`verify_image_digests`] moves them
+/// to the configured registry prefix before inspecting.
 pub type DigestManifest = HashMap<String, String>;
 
 /// Verify that every pulled image's digest matches the embedded release manifest.
 ///
 /// Reads `image-digests.json` from the embedded assets via [`get_asset`],
 /// then for each entry runs `docker inspect` inside the VM and compares
-/// `RepoDigests[0]` against the expected digest.
+/// `RepoDigests[0]` against the expected digest. Images are looked up under
+/// `registry_prefix`, so a mirror must serve the same digests as upstream.
 ///
 /// # Empty manifest
 ///
@@ -63,6 +126,7 @@ pub async fn verify_image_digests(
     mp: &impl ShellExecutor,
     assets: &impl AssetExtractor,
     reporter: &impl crate::application::ports::ProgressReporter,
+    registry_prefix: &str,
 ) -> Result<()> {
     let manifest_bytes = assets.get_asset("image-digests.json").await?;
     let manifest: DigestManifest =
@@ -75,6 +139,7 @@ pub async fn verify_image_digests(
     }
 
     for (image, expected_digest) in &manifest {
+        let image = &crate::domain::config::rebase_image_ref(image, registry_prefix);
         let output = mp
             .exec(&[
                 "docker",
@@ -117,6 +182,7 @@ mod tests {
     use crate::application::services::vm::test_support::{
         fail_output, impl_shell_executor_stubs, ok_output,
     };
+    use crate::domain::config::DEFAULT_REGISTRY_PREFIX;
 
     // ── Mock ─────────────────────────────────────────────────────────────────
 
@@ -167,6 +233,81 @@ mod tests {
         impl_shell_executor_stubs!(exec_with_stdin, exec_spawn, exec_status);
     }
 
+    // ── compute_config_hash tests ─────────────────────────────────────────────
+
+    /// Hashes the file contents, so tests can mutate an asset on disk.
+    struct ContentHasher;
+
+    impl FileHasher for ContentHasher {
+        fn sha256_file(&self, path: &Path) -> Result<String> {
+            let bytes = std::fs::read(path)?;
+            Ok(crate::domain::workspace::env_sha256(
+                &String::from_utf8_lossy(&bytes),
+            ))
+        }
+    }
+
+    fn assets_with_tarball(contents: &[u8]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("polis-setup.config.tar"), contents).expect("write tarball");
+        dir
+    }
+
+    #[test]
+    fn compute_config_hash_identical_assets_match() {
+        let a = assets_with_tarball(b"config v1");
+        let b = assets_with_tarball(b"config v1");
+        assert_eq!(
+            compute_config_hash(&ContentHasher, a.path(), "0.4.0", DEFAULT_REGISTRY_PREFIX)
+                .expect("hash a"),
+            compute_config_hash(&ContentHasher, b.path(), "0.4.0", DEFAULT_REGISTRY_PREFIX)
+                .expect("hash b"),
+        );
+    }
+
+    #[test]
+    fn compute_config_hash_changed_tarball_differs() {
+        let a = assets_with_tarball(b"config v1");
+        let b = assets_with_tarball(b"config v2");
+        assert_ne!(
+            compute_config_hash(&ContentHasher, a.path(), "0.4.0", DEFAULT_REGISTRY_PREFIX)
+                .expect("hash a"),
+            compute_config_hash(&ContentHasher, b.path(), "0.4.0", DEFAULT_REGISTRY_PREFIX)
+                .expect("hash b"),
+        );
+    }
+
+    #[test]
+    fn compute_config_hash_changed_env_template_differs() {
+        let a = assets_with_tarball(b"config v1");
+        assert_ne!(
+            compute_config_hash(&ContentHasher, a.path(), "0.4.0", DEFAULT_REGISTRY_PREFIX)
+                .expect("hash 0.4.0"),
+            compute_config_hash(&ContentHasher, a.path(), "0.4.1", DEFAULT_REGISTRY_PREFIX)
+                .expect("hash 0.4.1"),
+        );
+    }
+
+    #[test]
+    fn compute_config_hash_changed_registry_prefix_differs() {
+        let a = assets_with_tarball(b"config v1");
+        assert_ne!(
+            compute_config_hash(&ContentHasher, a.path(), "0.4.0", DEFAULT_REGISTRY_PREFIX)
+                .expect("hash default"),
+            compute_config_hash(&ContentHasher, a.path(), "0.4.0", "localhost:5000")
+                .expect("hash mirror"),
+        );
+    }
+
+    #[test]
+    fn compute_config_hash_missing_tarball_errors() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        assert!(
+            compute_config_hash(&ContentHasher, dir.path(), "0.4.0", DEFAULT_REGISTRY_PREFIX)
+                .is_err()
+        );
+    }
+
     // ── write_config_hash tests ───────────────────────────────────────────────
 
     struct WriteHashSpy {
@@ -286,7 +427,7 @@ mod tests {
     async fn empty_manifest_skips_verification() {
         let mp = DigestMock::new(vec![]);
         let stub = ManifestStub(b"{}");
-        let result = verify_image_digests(&mp, &stub, &ReporterStub).await;
+        let result = verify_image_digests(&mp, &stub, &ReporterStub, DEFAULT_REGISTRY_PREFIX).await;
         assert!(result.is_ok(), "empty manifest should succeed");
         assert!(mp.calls().is_empty(), "no docker inspect calls");
     }
@@ -302,10 +443,27 @@ mod tests {
         let manifest_bytes: &'static [u8] = manifest_json.leak().as_bytes();
         let stub = ManifestStub(manifest_bytes);
 
-        let result = verify_image_digests(&mp, &stub, &ReporterStub).await;
+        let result = verify_image_digests(&mp, &stub, &ReporterStub, DEFAULT_REGISTRY_PREFIX).await;
         assert!(result.is_ok(), "matching digest should pass: {result:?}");
     }
 
+    #[tokio::test]
+    async fn override_prefix_inspects_mirrored_image() {
+        let digest = "sha256:abc123def456";
+        let mirrored = "localhost:5000/polis-resolver:v0.4.0";
+        let repo_digest = format!("{mirrored}@{digest}");
+        let mp = DigestMock::new(vec![(mirrored, &repo_digest)]);
+
+        let manifest_json =
+            format!("{{\"ghcr.io/odralabshq/polis-resolver:v0.4.0\":\"{digest}\"}}");
+        let manifest_bytes: &'static [u8] = manifest_json.leak().as_bytes();
+        let stub = ManifestStub(manifest_bytes);
+
+        let result = verify_image_digests(&mp, &stub, &ReporterStub, "localhost:5000").await;
+        assert!(result.is_ok(), "mirrored digest should pass: {result:?}");
+        assert_eq!(mp.calls()[0].last().map(String::as_str), Some(mirrored));
+    }
+
     #[tokio::test]
     async fn mismatched_digest_returns_error() {
         let expected = "sha256:expected000";
@@ -318,7 +476,7 @@ mod tests {
         let manifest_bytes: &'static [u8] = manifest_json.leak().as_bytes();
         let stub = ManifestStub(manifest_bytes);
 
-        let err = verify_image_digests(&mp, &stub, &ReporterStub)
+        let err = verify_image_digests(&mp, &stub, &ReporterStub, DEFAULT_REGISTRY_PREFIX)
             .await
             .expect_err("mismatched digest should fail");
 
@@ -350,7 +508,7 @@ mod tests {
         let manifest_bytes: &'static [u8] = manifest_json.leak().as_bytes();
         let stub = ManifestStub(manifest_bytes);
 
-        let err = verify_image_digests(&mp, &stub, &ReporterStub)
+        let err = verify_image_digests(&mp, &stub, &ReporterStub, DEFAULT_REGISTRY_PREFIX)
             .await
             .expect_err("should fail");
         let msg = err.to_string();
@@ -380,7 +538,7 @@ mod tests {
         let manifest_bytes: &'static [u8] = manifest_json.leak().as_bytes();
         let stub = ManifestStub(manifest_bytes);
 
-        let err = verify_image_digests(&FailingMock, &stub, &ReporterStub)
+        let err = verify_image_digests(&FailingMock, &stub, &ReporterStub, DEFAULT_REGISTRY_PREFIX)
             .await
             .expect_err("exec failure should propagate");
         assert!(err.to_string().contains("inspecting image"));
@@ -412,7 +570,7 @@ mod tests {
         let manifest_bytes: &'static [u8] = manifest_json.leak().as_bytes();
         let stub = ManifestStub(manifest_bytes);
 
-        let result = verify_image_digests(&mp, &stub, &ReporterStub).await;
+        let result = verify_image_digests(&mp, &stub, &ReporterStub, DEFAULT_REGISTRY_PREFIX).await;
         assert!(result.is_ok(), "all matching digests should pass");
         assert_eq!(mp.calls().len(), 3, "should inspect all 3 images");
     }
@@ -8,10 +8,17 @@ use crate::application::ports::{
     AssetExtractor, FileTransfer, HostKeyExtractor, InstanceInspector, InstanceLifecycle,
     InstanceSpec, LocalFs, ProgressReporter, ShellExecutor, SshConfigurator, VmProvisioner,
 };
+use crate::domain::instance::InstanceState;
+use crate::domain::workspace::{VM_CPUS, VM_DISK, VM_MEMORY};
 
-const VM_CPUS: &str = "4";
-const VM_MEMORY: &str = "8G";
-const VM_DISK: &str = "40G";
+/// How to recover a workspace whose provisioning went wrong.
+const RECOVERY: &str = "polis delete && polis start";
+
+/// Error for a running VM without Docker (see [`require_docker`]).
+#[must_use]
+pub fn docker_missing() -> String {
+    format!("Docker is not installed in the workspace.\nTo recover, run: {RECOVERY}")
+}
 
 /// VM state as observed from the provisioner.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -33,47 +40,35 @@ pub async fn exists(mp: &impl InstanceInspector) -> bool {
 ///
 /// Returns an error if the multipass output cannot be parsed.
 pub async fn state(mp: &impl InstanceInspector) -> Result<VmState> {
-    let output = match mp.info().await {
-        Ok(o) if o.status.success() => o,
-        _ => return Ok(VmState::NotFound),
-    };
-    let info: serde_json::Value =
-        serde_json::from_slice(&output.stdout).context("parsing multipass info")?;
-    let state_str = info
-        .get("info")
-        .and_then(|i| i.get("polis"))
-        .and_then(|p| p.get("state"))
-        .and_then(|s| s.as_str())
-        .unwrap_or("Unknown");
-    Ok(match state_str {
-        "Running" => VmState::Running,
-        "Starting" => VmState::Starting,
-        _ => VmState::Stopped,
-    })
+    match mp.instance_info().await {
+        Ok(info) => Ok(match info.state {
+            InstanceState::NotFound => VmState::NotFound,
+            InstanceState::Running => VmState::Running,
+            InstanceState::Starting => VmState::Starting,
+            _ => VmState::Stopped,
+        }),
+        // Garbled output is an error; failing to query multipass at all
+        // means there is no VM to act on.
+        Err(e) if e.is::<serde_json::Error>() => Err(e),
+        Err(_) => Ok(VmState::NotFound),
+    }
 }
 
-/// Resolve the primary IPv4 address of the polis VM.
+/// Resolve the primary IPv4 address of the VM.
 ///
-/// Parses `multipass info --format json` output to extract the first IPv4
-/// address from `info.polis.ipv4`.
+/// Uses the first address `multipass info` reports for the instance.
 ///
 /// # Errors
 ///
 /// Returns an error if `info()` fails or no IPv4 address is found.
 pub async fn resolve_vm_ip(mp: &impl InstanceInspector) -> Result<String> {
-    let output = mp.info().await.context("failed to query VM info")?;
-    anyhow::ensure!(output.status.success(), "multipass info failed");
-
-    let info: serde_json::Value =
-        serde_json::from_slice(&output.stdout).context("invalid JSON from multipass info")?;
-
-    info.get("info")
-        .and_then(|i| i.get("polis"))
-        .and_then(|p| p.get("ipv4"))
-        .and_then(|arr| arr.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|v| v.as_str())
-        .map(String::from)
+    let info = mp
+        .instance_info()
+        .await
+        .context("failed to query VM info")?;
+    info.ipv4
+        .into_iter()
+        .next()
         .ok_or_else(|| anyhow::anyhow!("no IPv4 address found for polis VM"))
 }
 
@@ -90,7 +85,6 @@ pub async fn resolve_vm_ip(mp: &impl InstanceInspector) -> Result<String> {
 /// if the command could not be executed.
 pub async fn verify_cloud_init(mp: &impl ShellExecutor) -> Result<()> {
     const LOG: &str = "/var/log/cloud-init-output.log";
-    const RECOVERY: &str = "polis delete && polis start";
 
     let status = mp
         .exec_status(&["cloud-init", "status", "--wait"])
@@ -122,29 +116,45 @@ pub async fn verify_cloud_init(mp: &impl ShellExecutor) -> Result<()> {
     }
 }
 
-/// Create VM using cloud-init provisioning.
+/// Whether `docker` can run inside the VM.
 ///
-/// Extracts the embedded `cloud-init.yaml` to a temporary directory, then
-/// invokes `multipass launch 24.04 --cloud-init <path> --timeout 900`.
-/// After launch completes, verifies that cloud-init succeeded before returning.
+/// # Errors
+///
+/// Returns an error if the command could not be executed at all.
+pub async fn docker_available(mp: &impl ShellExecutor) -> Result<bool> {
+    let output = mp
+        .exec(&["docker", "--version"])
+        .await
+        .context("checking for Docker in the workspace")?;
+    Ok(output.status.success())
+}
+
+/// Preflight for flows that drive `docker` inside the VM: fail with a
+/// recovery hint instead of a bare "command not found" when a partially
+/// failed cloud-init left Docker uninstalled.
 ///
 /// # Errors
 ///
-/// Returns an error if prerequisites are not met, asset extraction fails,
-/// the multipass launch fails, or cloud-init reports a failure.
-pub async fn create(
-    mp: &impl VmProvisioner,
+/// Returns an error if Docker is missing, or the check could not be run.
+pub async fn require_docker(mp: &impl ShellExecutor) -> Result<()> {
+    anyhow::ensure!(docker_available(mp).await?, docker_missing());
+    Ok(())
+}
+
+/// Extract the embedded assets and return the path of `cloud-init.yaml`,
+/// readable by the Multipass daemon, plus a guard that removes the
+/// extracted files when dropped.
+///
+/// # Errors
+///
+/// Returns an error if extraction fails, permissions cannot be set, or the
+/// path is not valid UTF-8.
+pub async fn prepare_cloud_init(
     assets: &impl AssetExtractor,
-    ssh: &impl SshConfigurator,
     local_fs: &impl LocalFs,
-    host_key_extractor: &impl HostKeyExtractor,
-    reporter: &impl ProgressReporter,
-    quiet: bool,
-) -> Result<()> {
-    check_prerequisites(mp).await?;
-
+) -> Result<(String, Box<dyn std::any::Any>)> {
     // Extract embedded assets (cloud-init.yaml, etc.) to a temp dir.
-    let (assets_path, _assets_guard) = assets
+    let (assets_path, assets_guard) = assets
         .extract_assets()
         .await
         .context("extracting embedded assets")?;
@@ -159,10 +169,39 @@ pub async fn create(
         .to_str()
         .context("cloud-init path is not valid UTF-8")?
         .to_string();
+    Ok((cloud_init_str, assets_guard))
+}
+
+/// Create VM using cloud-init provisioning.
+///
+/// Extracts the embedded `cloud-init.yaml` to a temporary directory, then
+/// invokes `multipass launch 24.04 --cloud-init <path> --timeout <launch_timeout_secs>`.
+/// After launch completes, verifies that cloud-init succeeded before returning.
+/// Unless `quiet`, the launch and the cloud-init wait are reported as separate
+/// stages.
+///
+/// # Errors
+///
+/// Returns an error if prerequisites are not met, asset extraction fails,
+/// the multipass launch fails, or cloud-init reports a failure.
+#[allow(clippy::too_many_arguments)]
+pub async fn create(
+    mp: &impl VmProvisioner,
+    assets: &impl AssetExtractor,
+    ssh: &impl SshConfigurator,
+    local_fs: &impl LocalFs,
+    host_key_extractor: &impl HostKeyExtractor,
+    reporter: &impl ProgressReporter,
+    launch_timeout_secs: u64,
+    quiet: bool,
+) -> Result<()> {
+    check_prerequisites(mp).await?;
+    let (cloud_init_str, _assets_guard) = prepare_cloud_init(assets, local_fs).await?;
 
     if !quiet {
-        reporter.begin_stage("preparing workspace...");
+        reporter.begin_stage("launching workspace...");
     }
+    let timeout = launch_timeout_secs.to_string();
     let output = mp
         .launch(&InstanceSpec {
             image: "24.04",
@@ -170,7 +209,7 @@ pub async fn create(
             memory: VM_MEMORY,
             disk: VM_DISK,
             cloud_init: Some(&cloud_init_str),
-            timeout: Some("900"),
+            timeout: Some(&timeout),
         })
         .await
         .context("launching workspace")?;
@@ -180,15 +219,27 @@ pub async fn create(
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("failed to create workspace.\n\nRun 'polis doctor' to diagnose.\n{stderr}");
+        anyhow::bail!(
+            "failed to create workspace.\n\n\
+             Run 'polis doctor' to diagnose. If the launch timed out on a slow \
+             connection, retry with a longer limit:\n  \
+             polis delete && polis start --launch-timeout {}\n{stderr}",
+            launch_timeout_secs.saturating_mul(2)
+        );
     }
 
     // Verify cloud-init completed successfully before proceeding to Phase 2.
+    if !quiet {
+        reporter.begin_stage("waiting for cloud-init...");
+    }
     verify_cloud_init(mp).await?;
+    if !quiet {
+        reporter.complete_stage();
+    }
 
     configure_credentials(mp, local_fs).await;
-    super::services::start_services_with_progress(mp, reporter, quiet).await;
-    pin_host_key(ssh, host_key_extractor).await;
+    super::services::start_services(mp).await;
+    pin_host_key(ssh, host_key_extractor, reporter).await;
     Ok(())
 }
 
@@ -262,10 +313,12 @@ pub async fn restart(
 
 const MULTIPASS_MIN_VERSION: semver::Version = semver::Version::new(1, 16, 0);
 
+/// Fail unless Multipass answers and meets the minimum version.
+///
 /// # Errors
 ///
-/// This function will return an error if the underlying operations fail.
-async fn check_prerequisites(mp: &impl InstanceInspector) -> Result<()> {
+/// Returns an error if Multipass is missing or too old.
+pub async fn check_prerequisites(mp: &impl InstanceInspector) -> Result<()> {
     let output = mp.version().await.map_err(|_| {
         anyhow::anyhow!(
             "Workspace runtime not available.\n\nRun 'polis doctor' to diagnose and fix."
@@ -291,9 +344,19 @@ async fn configure_credentials(mp: &impl FileTransfer, local_fs: &impl LocalFs)
     }
 }
 
-async fn pin_host_key(ssh: &impl SshConfigurator, extractor: &impl HostKeyExtractor) {
-    if let Some(host_key) = extractor.extract_host_key().await {
-        let _ = ssh.update_host_key(&host_key).await;
+/// Pin the workspace host key. Failure is not fatal — `polis connect` pins
+/// it again — but is surfaced so an unpinned key is not silently missed.
+async fn pin_host_key(
+    ssh: &impl SshConfigurator,
+    extractor: &impl HostKeyExtractor,
+    reporter: &impl ProgressReporter,
+) {
+    let pinned = match extractor.extract_host_key().await {
+        Some(host_key) => ssh.update_host_key(&host_key).await.is_ok(),
+        None => false,
+    };
+    if !pinned {
+        reporter.warn("could not pin workspace SSH host key — 'polis connect' will retry");
     }
 }
 
@@ -345,6 +408,12 @@ mod tests {
         assert_eq!(state(&mp).await.expect("state"), VmState::NotFound);
     }
 
+    #[tokio::test]
+    async fn state_errors_on_invalid_info_json() {
+        let mp = MultipassVmInfoStub(ok(b"not json"));
+        assert!(state(&mp).await.is_err());
+    }
+
     #[tokio::test]
     async fn state_running() {
         let mp = MultipassVmInfoStub(ok(br#"{"info":{"polis":{"state":"Running"}}}"#));
@@ -357,6 +426,41 @@ mod tests {
         assert_eq!(state(&mp).await.expect("state"), VmState::Stopped);
     }
 
+    /// Inspector for a VM that is not called `polis`.
+    struct NamedVmInfoStub(&'static str, &'static [u8]);
+
+    impl InstanceInspector for NamedVmInfoStub {
+        fn instance_name(&self) -> &str {
+            self.0
+        }
+        async fn info(&self) -> Result<Output> {
+            Ok(ok(self.1))
+        }
+        async fn version(&self) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+    }
+
+    #[tokio::test]
+    async fn state_reads_the_named_instance() {
+        let mp = NamedVmInfoStub(
+            "polis-selftest",
+            br#"{"info":{"polis-selftest":{"state":"Running","ipv4":["10.0.0.7"]}}}"#,
+        );
+        assert_eq!(state(&mp).await.expect("state"), VmState::Running);
+        assert_eq!(resolve_vm_ip(&mp).await.expect("ip"), "10.0.0.7");
+    }
+
+    #[tokio::test]
+    async fn state_ignores_other_instances() {
+        let mp = NamedVmInfoStub(
+            "polis-selftest",
+            br#"{"info":{"polis":{"state":"Running"}}}"#,
+        );
+        assert_eq!(state(&mp).await.expect("state"), VmState::Stopped);
+        assert!(resolve_vm_ip(&mp).await.is_err());
+    }
+
     #[tokio::test]
     async fn exists_true_when_vm_info_succeeds() {
         let mp = MultipassVmInfoStub(ok(b"{}"));
@@ -413,6 +517,24 @@ mod tests {
         async fn purge(&self) -> Result<Output> {
             anyhow::bail!("not expected")
         }
+        /// # Errors
+        ///
+        /// This function will return an error if the underlying operations fail.
+        async fn snapshot(&self, _: &str) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        /// # Errors
+        ///
+        /// This function will return an error if the underlying operations fail.
+        async fn restore(&self, _: &str) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        /// # Errors
+        ///
+        /// This function will return an error if the underlying operations fail.
+        async fn list_snapshots(&self) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
     }
     impl ShellExecutor for MultipassRestartSpy {
         /// # Errors
@@ -492,4 +614,32 @@ mod tests {
             "expected recovery command in: {msg}"
         );
     }
+
+    /// Answers `docker --version` with a fixed output.
+    struct DockerVersionStub(Output);
+    impl ShellExecutor for DockerVersionStub {
+        async fn exec(&self, args: &[&str]) -> Result<Output> {
+            assert_eq!(args, ["docker", "--version"]);
+            Ok(self.0.clone())
+        }
+        impl_shell_executor_stubs!(exec_with_stdin, exec_spawn, exec_status);
+    }
+
+    #[tokio::test]
+    async fn require_docker_passes_when_docker_runs() {
+        let mp = DockerVersionStub(ok(b"Docker version 27.5.1, build 9f9e405\n"));
+        assert!(require_docker(&mp).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn require_docker_reports_recovery_when_docker_missing() {
+        let mp = DockerVersionStub(Output {
+            status: exit_status(127),
+            stdout: Vec::new(),
+            stderr: b"bash: line 1: docker: command not found\n".to_vec(),
+        });
+        let err = require_docker(&mp).await.expect_err("expected Err");
+        assert_eq!(err.to_string(), docker_missing());
+        assert!(err.to_string().contains("polis delete && polis start"));
+    }
 }
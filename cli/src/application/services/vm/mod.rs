@@ -9,6 +9,7 @@ pub mod integrity;
 pub mod lifecycle;
 pub mod provision;
 pub mod services;
+pub mod snapshot;
 
 #[cfg(test)]
 pub(crate) mod test_support;
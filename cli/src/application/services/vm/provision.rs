@@ -2,6 +2,8 @@
 //!
 //! Imports only from `crate::domain` and `crate::application::ports`.
 
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
 use std::path::Path;
 
 use anyhow::{Context, Result};
@@ -14,9 +16,13 @@ use crate::application::ports::{FileTransfer, ShellExecutor};
 /// 1. Validate tarball entries on the host for path traversal (V-013)
 /// 2. Transfer the tarball into the VM via `multipass transfer`
 /// 3. Extract to `/opt/polis` with `--no-same-owner` (V-013)
-/// 4. Write `.env` with version values via `exec` + `printf '%s'` (V-004)
+/// 4. Write `.env` with the registry prefix and version values via `exec` +
+///    `printf '%s'` (V-004)
 /// 5. Fix execute permissions stripped by Windows tar
 ///
+/// Returns the SHA256 of the written `.env` so the caller can record it in
+/// workspace state for later tamper detection.
+///
 /// # Errors
 ///
 /// Returns an error if the tarball contains path traversal entries, if any
@@ -25,7 +31,8 @@ pub async fn transfer_config(
     mp: &(impl ShellExecutor + FileTransfer),
     assets_dir: &Path,
     version: &str,
-) -> Result<()> {
+    registry_prefix: &str,
+) -> Result<String> {
     let tar_path = assets_dir.join("polis-setup.config.tar");
 
     // 1. Validate tarball entries on the host before transferring (V-013).
@@ -68,7 +75,8 @@ pub async fn transfer_config(
     // Uses printf '%s' which treats the content as a literal string, avoiding
     // shell expansion. Does NOT use exec_with_stdin/tee because Multipass on
     // Windows fails to propagate stdin EOF, causing tee to hang indefinitely.
-    let env_content = generate_env_content(version);
+    // No per-service pins yet: every service follows the CLI tag.
+    let env_content = generate_env_content(version, registry_prefix, &BTreeMap::new())?;
     mp.exec(&[
         "bash",
         "-c",
@@ -96,7 +104,16 @@ pub async fn transfer_config(
     .await
     .context("fixing script permissions and stripping CRLF in VM")?;
 
-    Ok(())
+    Ok(crate::domain::workspace::env_sha256(&env_content))
+}
+
+/// Read the control-plane `.env` from the VM, or `None` if it is unreadable.
+pub async fn read_deployed_env(mp: &impl ShellExecutor) -> Option<String> {
+    let output = mp.exec(&["cat", "/opt/polis/.env"]).await.ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
 }
 
 /// Validate that a tarball contains no path traversal entries.
@@ -135,25 +152,63 @@ pub fn validate_tarball_paths(tar_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// `.env` variables pinning each control-plane service image, in file order.
+pub const SERVICE_VERSION_VARS: [&str; 9] = [
+    "POLIS_RESOLVER_VERSION",
+    "POLIS_CERTGEN_VERSION",
+    "POLIS_GATE_VERSION",
+    "POLIS_SENTINEL_VERSION",
+    "POLIS_SCANNER_VERSION",
+    "POLIS_WORKSPACE_VERSION",
+    "POLIS_HOST_INIT_VERSION",
+    "POLIS_STATE_VERSION",
+    "POLIS_TOOLBOX_VERSION",
+];
+
 /// Generate the `.env` file content from the CLI version string.
 ///
-/// All 9 `POLIS_*_VERSION` variables are set to the same `v{version}` tag —
-/// services are versioned in lockstep with the CLI.
-#[must_use]
-pub fn generate_env_content(version: &str) -> String {
+/// `POLIS_REGISTRY_PREFIX` points `docker-compose.yml` at the registry the
+/// images are pulled from. All 9 `POLIS_*_VERSION` variables default to the
+/// same `v{version}` tag — services are versioned in lockstep with the CLI.
+/// `overrides` maps a variable name to a pinned tag (e.g. a hotfixed single
+/// service); each pinned tag must be `v` followed by a valid semver version.
+///
+/// # Errors
+///
+/// Returns an error if the registry prefix is malformed, or if an override
+/// names an unknown variable or carries a malformed tag.
+pub fn generate_env_content(
+    version: &str,
+    registry_prefix: &str,
+    overrides: &BTreeMap<String, String>,
+) -> Result<String> {
+    crate::domain::config::validate_registry_prefix(registry_prefix)?;
+    for (var, tag) in overrides {
+        anyhow::ensure!(
+            SERVICE_VERSION_VARS.contains(&var.as_str()),
+            "unknown service version variable in overrides: {var}"
+        );
+        validate_version_tag(tag).with_context(|| format!("invalid override for {var}"))?;
+    }
+
     let tag = format!("v{version}");
-    format!(
-        "# Generated by polis CLI v{version}\n\
-         POLIS_RESOLVER_VERSION={tag}\n\
-         POLIS_CERTGEN_VERSION={tag}\n\
-         POLIS_GATE_VERSION={tag}\n\
-         POLIS_SENTINEL_VERSION={tag}\n\
-         POLIS_SCANNER_VERSION={tag}\n\
-         POLIS_WORKSPACE_VERSION={tag}\n\
-         POLIS_HOST_INIT_VERSION={tag}\n\
-         POLIS_STATE_VERSION={tag}\n\
-         POLIS_TOOLBOX_VERSION={tag}\n"
-    )
+    let mut content =
+        format!("# Generated by polis CLI v{version}\nPOLIS_REGISTRY_PREFIX={registry_prefix}\n");
+    for var in SERVICE_VERSION_VARS {
+        let value = overrides.get(var).unwrap_or(&tag);
+        // Writing to a String cannot fail.
+        let _ = writeln!(content, "{var}={value}");
+    }
+    Ok(content)
+}
+
+/// Validate an image tag of the form `v{semver}` (e.g. `v0.4.1`).
+fn validate_version_tag(tag: &str) -> Result<()> {
+    let version = tag
+        .strip_prefix('v')
+        .with_context(|| format!("tag '{tag}' must start with 'v'"))?;
+    semver::Version::parse(version).with_context(|| format!("tag '{tag}' is not valid semver"))?;
+    Ok(())
 }
 
 /// Generate certificates and secrets inside the VM.
@@ -204,6 +259,7 @@ mod tests {
     use super::*;
     use crate::application::ports::{FileTransfer, ShellExecutor};
     use crate::application::services::vm::test_support::{impl_shell_executor_stubs, ok_output};
+    use crate::domain::config::DEFAULT_REGISTRY_PREFIX;
 
     struct TransferConfigSpy {
         transferred: std::cell::RefCell<Vec<(String, String)>>,
@@ -280,7 +336,8 @@ mod tests {
 
     #[test]
     fn generate_env_content_contains_all_9_vars() {
-        let content = generate_env_content("1.2.3");
+        let content = generate_env_content("1.2.3", DEFAULT_REGISTRY_PREFIX, &BTreeMap::new())
+            .expect("env content");
         let expected_vars = [
             "POLIS_RESOLVER_VERSION",
             "POLIS_CERTGEN_VERSION",
@@ -299,7 +356,8 @@ mod tests {
 
     #[test]
     fn generate_env_content_uses_v_prefix() {
-        let content = generate_env_content("1.2.3");
+        let content = generate_env_content("1.2.3", DEFAULT_REGISTRY_PREFIX, &BTreeMap::new())
+            .expect("env content");
         assert!(
             content.contains("POLIS_RESOLVER_VERSION=v1.2.3"),
             "expected v-prefixed version tag"
@@ -312,7 +370,8 @@ mod tests {
 
     #[test]
     fn generate_env_content_all_vars_same_version() {
-        let content = generate_env_content("0.4.0");
+        let content = generate_env_content("0.4.0", DEFAULT_REGISTRY_PREFIX, &BTreeMap::new())
+            .expect("env content");
         let tag = "v0.4.0";
         let count = content.matches(&format!("={tag}")).count();
         assert_eq!(
@@ -323,7 +382,8 @@ mod tests {
 
     #[test]
     fn generate_env_content_valid_env_syntax() {
-        let content = generate_env_content("2.0.0");
+        let content = generate_env_content("2.0.0", DEFAULT_REGISTRY_PREFIX, &BTreeMap::new())
+            .expect("env content");
         for line in content.lines() {
             if line.is_empty() || line.starts_with('#') {
                 continue;
@@ -337,6 +397,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn generate_env_content_override_changes_only_target_var() {
+        let overrides = BTreeMap::from([("POLIS_GATE_VERSION".to_string(), "v0.4.1".to_string())]);
+        let content = generate_env_content("0.4.0", DEFAULT_REGISTRY_PREFIX, &overrides)
+            .expect("env content");
+        assert!(content.contains("POLIS_GATE_VERSION=v0.4.1\n"));
+        assert_eq!(content.matches("=v0.4.0\n").count(), 8);
+        for var in SERVICE_VERSION_VARS
+            .iter()
+            .filter(|v| **v != "POLIS_GATE_VERSION")
+        {
+            assert!(
+                content.contains(&format!("{var}=v0.4.0\n")),
+                "{var} moved off base tag"
+            );
+        }
+    }
+
+    #[test]
+    fn generate_env_content_rejects_unknown_override_var() {
+        let overrides = BTreeMap::from([("POLIS_EVIL_VERSION".to_string(), "v1.0.0".to_string())]);
+        assert!(generate_env_content("0.4.0", DEFAULT_REGISTRY_PREFIX, &overrides).is_err());
+    }
+
+    #[test]
+    fn generate_env_content_rejects_malformed_override_tag() {
+        for tag in ["0.4.1", "v0.4", "latest", "v0.4.1'; rm -rf /", ""] {
+            let overrides = BTreeMap::from([("POLIS_GATE_VERSION".to_string(), tag.to_string())]);
+            assert!(
+                generate_env_content("0.4.0", DEFAULT_REGISTRY_PREFIX, &overrides).is_err(),
+                "tag {tag:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn generate_env_content_writes_registry_prefix() {
+        let content = generate_env_content("0.4.0", DEFAULT_REGISTRY_PREFIX, &BTreeMap::new())
+            .expect("env content");
+        assert!(content.contains("POLIS_REGISTRY_PREFIX=ghcr.io/odralabshq\n"));
+        let content = generate_env_content("0.4.0", "localhost:5000/polis", &BTreeMap::new())
+            .expect("env content");
+        assert!(content.contains("POLIS_REGISTRY_PREFIX=localhost:5000/polis\n"));
+        assert!(generate_env_content("0.4.0", "https://evil", &BTreeMap::new()).is_err());
+    }
+
+    #[test]
+    fn generate_env_content_has_no_version_drift() {
+        let content = generate_env_content("0.4.0", DEFAULT_REGISTRY_PREFIX, &BTreeMap::new())
+            .expect("env content");
+        assert!(crate::domain::health::find_version_drift(&content, "0.4.0").is_empty());
+        assert_eq!(
+            crate::domain::health::find_version_drift(&content, "0.5.0").len(),
+            9
+        );
+    }
+
     #[test]
     fn validate_tarball_paths_accepts_safe_entries() {
         let dir = tempfile::tempdir().expect("tempdir");
@@ -414,7 +531,7 @@ mod tests {
     async fn transfer_config_transfers_tarball_to_vm() {
         let (dir, _tar_path) = make_safe_tarball();
         let mp = TransferConfigSpy::new();
-        transfer_config(&mp, dir.path(), "1.0.0")
+        transfer_config(&mp, dir.path(), "1.0.0", DEFAULT_REGISTRY_PREFIX)
             .await
             .expect("transfer_config");
         let transfers = mp.transferred.borrow();
@@ -430,7 +547,7 @@ mod tests {
     async fn transfer_config_extracts_with_no_same_owner() {
         let (dir, _tar_path) = make_safe_tarball();
         let mp = TransferConfigSpy::new();
-        transfer_config(&mp, dir.path(), "1.0.0")
+        transfer_config(&mp, dir.path(), "1.0.0", DEFAULT_REGISTRY_PREFIX)
             .await
             .expect("transfer_config");
         let calls = mp.exec_calls.borrow();
@@ -453,7 +570,7 @@ mod tests {
     async fn transfer_config_writes_env_via_exec() {
         let (dir, _tar_path) = make_safe_tarball();
         let mp = TransferConfigSpy::new();
-        transfer_config(&mp, dir.path(), "2.3.4")
+        transfer_config(&mp, dir.path(), "2.3.4", DEFAULT_REGISTRY_PREFIX)
             .await
             .expect("transfer_config");
         let calls = mp.exec_calls.borrow();
@@ -471,11 +588,25 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn transfer_config_returns_hash_of_written_env() {
+        let (dir, _tar_path) = make_safe_tarball();
+        let mp = TransferConfigSpy::new();
+        let hash = transfer_config(&mp, dir.path(), "2.3.4", DEFAULT_REGISTRY_PREFIX)
+            .await
+            .expect("transfer_config");
+        let expected = generate_env_content("2.3.4", DEFAULT_REGISTRY_PREFIX, &BTreeMap::new())
+            .expect("env content");
+        assert!(crate::domain::workspace::env_matches_record(
+            &expected, &hash
+        ));
+    }
+
     #[tokio::test]
     async fn transfer_config_fixes_sh_permissions() {
         let (dir, _tar_path) = make_safe_tarball();
         let mp = TransferConfigSpy::new();
-        transfer_config(&mp, dir.path(), "1.0.0")
+        transfer_config(&mp, dir.path(), "1.0.0", DEFAULT_REGISTRY_PREFIX)
             .await
             .expect("transfer_config");
         let calls = mp.exec_calls.borrow();
@@ -509,7 +640,7 @@ mod tests {
             file.write_all(&[0u8; 1024]).expect("write EOF");
         }
         let mp = TransferConfigSpy::new();
-        let result = transfer_config(&mp, dir.path(), "1.0.0").await;
+        let result = transfer_config(&mp, dir.path(), "1.0.0", DEFAULT_REGISTRY_PREFIX).await;
         assert!(result.is_err(), "should reject path traversal tarball");
         assert!(
             mp.transferred.borrow().is_empty(),
@@ -2,53 +2,152 @@
 //!
 //! Imports only from `crate::domain` and `crate::application::ports`.
 
+use std::time::Duration;
+
 use anyhow::{Context, Result};
 
 use crate::application::ports::{ProgressReporter, ShellExecutor};
 
+/// Registry logged into when `POLIS_GHCR_TOKEN` is set.
+const GHCR: &str = "ghcr.io";
+
 /// Pull all Docker images inside the VM via `docker compose pull`.
 ///
 /// Runs `timeout 900 docker compose -f /opt/polis/docker-compose.yml pull`
-/// inside the VM, enforcing a 15-minute limit.
+/// inside the VM, enforcing a 15-minute limit per attempt. Transient
+/// registry failures (a timeout, a reset connection, a failed TLS handshake)
+/// are retried up to three times with a 2s, then 4s backoff; `manifest
+/// unknown` and `unauthorized` fail at once. When `POLIS_GHCR_TOKEN` is
+/// set, the VM logs into `ghcr.io` first (as `POLIS_GHCR_USER`, default
+/// `polis`) so images in a private namespace can be pulled.
 ///
 /// # Errors
 ///
-/// - If the command exits with code 124 (timeout), returns an error suggesting
-///   the user check network connectivity.
+/// - If the last attempt exits with code 124 (timeout), returns an error
+///   suggesting the user check network connectivity.
 /// - If the command fails for any other reason, returns an error with the
 ///   captured stderr for diagnosis.
-pub async fn pull_images(mp: &impl ShellExecutor, _reporter: &impl ProgressReporter) -> Result<()> {
+/// - If `docker login` is rejected.
+pub async fn pull_images(mp: &impl ShellExecutor, reporter: &impl ProgressReporter) -> Result<()> {
+    let token = std::env::var("POLIS_GHCR_TOKEN")
+        .ok()
+        .filter(|t| !t.trim().is_empty());
+    let user = std::env::var("POLIS_GHCR_USER").unwrap_or_else(|_| "polis".to_string());
+    let login = token.as_deref().map(|token| (user.as_str(), token));
+    pull_images_as(mp, reporter, login).await
+}
+
+/// [`pull_images`] with explicit `(user, token)` credentials for `ghcr.io`.
+///
+/// The token is piped to `docker login --password-stdin` so it never
+/// appears in an argv visible to `ps`. The VM logs out again after the
+/// pull, whether or not the pull succeeded.
+///
+/// # Errors
+///
+/// See [`pull_images`].
+pub async fn pull_images_as(
+    mp: &impl ShellExecutor,
+    reporter: &impl ProgressReporter,
+    login: Option<(&str, &str)>,
+) -> Result<()> {
+    let Some((user, token)) = login else {
+        return compose_pull(mp, reporter, PULL_RETRY_BASE_DELAY).await;
+    };
+    reporter.step(&format!("logging in to {GHCR} as {user}"));
     let output = mp
-        .exec(&[
-            "timeout",
-            "900",
-            "docker",
-            "compose",
-            "-f",
-            "/opt/polis/docker-compose.yml",
-            "pull",
-        ])
+        .exec_with_stdin(
+            &["docker", "login", GHCR, "-u", user, "--password-stdin"],
+            token.trim().as_bytes(),
+        )
         .await
-        .context("pulling Docker images from GHCR")?;
-
-    if output.status.success() {
-        return Ok(());
+        .context("running docker login")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(
+            "docker login to {GHCR} failed.\n\
+             {stderr}\n\
+             Check that POLIS_GHCR_TOKEN has the read:packages scope."
+        );
     }
+    let pulled = compose_pull(mp, reporter, PULL_RETRY_BASE_DELAY).await;
+    let _ = mp.exec(&["docker", "logout", GHCR]).await;
+    pulled
+}
+
+/// Attempts made by [`compose_pull`] before a transient failure is final.
+const PULL_ATTEMPTS: u32 = 3;
+
+/// Delay before the first pull retry; doubled for each later one.
+const PULL_RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+
+/// Registry errors that no retry will fix.
+const PERMANENT_PULL_ERRORS: [&str; 2] = ["manifest unknown", "unauthorized"];
+
+/// Registry errors worth another attempt.
+const TRANSIENT_PULL_ERRORS: [&str; 3] = ["connection reset", "timeout", "tls handshake"];
+
+/// Run `docker compose pull`, retrying transient registry failures up to
+/// [`PULL_ATTEMPTS`] times with exponential backoff from `base_delay`.
+async fn compose_pull(
+    mp: &impl ShellExecutor,
+    reporter: &impl ProgressReporter,
+    base_delay: Duration,
+) -> Result<()> {
+    let mut attempt = 1;
+    loop {
+        let output = mp
+            .exec(&[
+                "timeout",
+                "900",
+                "docker",
+                "compose",
+                "-f",
+                "/opt/polis/docker-compose.yml",
+                "pull",
+            ])
+            .await
+            .context("pulling Docker images from GHCR")?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let code = output.status.code();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if attempt < PULL_ATTEMPTS && is_transient_pull_failure(code, &stderr) {
+            tokio::time::sleep(base_delay.saturating_mul(1 << (attempt - 1))).await;
+            attempt += 1;
+            reporter.step(&format!(
+                "image pull failed; retrying ({attempt}/{PULL_ATTEMPTS})..."
+            ));
+            continue;
+        }
+
+        // Exit code 124 means `timeout` killed the process.
+        if code == Some(124) {
+            anyhow::bail!(
+                "Docker image pull timed out after 15 minutes.\n\
+                 Check your network connectivity and retry with: polis start"
+            );
+        }
 
-    // Exit code 124 means `timeout` killed the process.
-    if output.status.code() == Some(124) {
         anyhow::bail!(
-            "Docker image pull timed out after 15 minutes.\n\
+            "failed to pull Docker images.\n\
+             {stderr}\n\
              Check your network connectivity and retry with: polis start"
         );
     }
+}
 
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    anyhow::bail!(
-        "failed to pull Docker images.\n\
-         {stderr}\n\
-         Check your network connectivity and retry with: polis start"
-    );
+/// Whether a failed pull is worth retrying: `timeout` killed it (exit 124)
+/// or the registry dropped the connection, and it was not refused outright.
+fn is_transient_pull_failure(code: Option<i32>, stderr: &str) -> bool {
+    let stderr = stderr.to_ascii_lowercase();
+    if PERMANENT_PULL_ERRORS.iter().any(|e| stderr.contains(e)) {
+        return false;
+    }
+    code == Some(124) || TRANSIENT_PULL_ERRORS.iter().any(|e| stderr.contains(e))
 }
 
 /// Start polis services via systemctl inside the VM.
@@ -71,6 +170,24 @@ pub(super) async fn start_services_with_progress(
     }
 }
 
+/// Pull images, or skip the pull with a warning in offline mode so start
+/// falls back to the images already cached in the VM.
+///
+/// # Errors
+///
+/// Returns an error if the pull itself fails (see [`pull_images`]).
+pub async fn pull_images_unless_offline(
+    mp: &impl ShellExecutor,
+    reporter: &impl ProgressReporter,
+    offline: bool,
+) -> Result<()> {
+    if offline {
+        reporter.warn("offline mode; skipping image pull — using images cached in the workspace");
+        return Ok(());
+    }
+    pull_images(mp, reporter).await
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -126,6 +243,111 @@ mod tests {
         impl_shell_executor_stubs!(exec_with_stdin, exec_spawn, exec_status);
     }
 
+    /// Records every command and the stdin it was given.
+    #[derive(Default)]
+    struct LoginSpy {
+        login_fails: bool,
+        pull_fails: bool,
+        calls: std::cell::RefCell<Vec<(String, Vec<u8>)>>,
+    }
+
+    impl ShellExecutor for LoginSpy {
+        async fn exec(&self, args: &[&str]) -> Result<Output> {
+            self.calls.borrow_mut().push((args.join(" "), Vec::new()));
+            let failed = self.pull_fails && args.contains(&"pull");
+            Ok(Output {
+                status: exit_status(i32::from(failed)),
+                stdout: vec![],
+                stderr: vec![],
+            })
+        }
+        async fn exec_with_stdin(&self, args: &[&str], input: &[u8]) -> Result<Output> {
+            self.calls
+                .borrow_mut()
+                .push((args.join(" "), input.to_vec()));
+            Ok(Output {
+                status: exit_status(i32::from(self.login_fails)),
+                stdout: vec![],
+                stderr: b"unauthorized".to_vec(),
+            })
+        }
+        impl_shell_executor_stubs!(exec_spawn, exec_status);
+    }
+
+    fn commands(spy: &LoginSpy) -> Vec<String> {
+        spy.calls.borrow().iter().map(|(c, _)| c.clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn pull_images_as_logs_in_via_stdin_and_out_after() {
+        let spy = LoginSpy::default();
+        pull_images_as(&spy, &ReporterStub, Some(("ci", "ghp_secret\n")))
+            .await
+            .expect("pull");
+
+        let calls = spy.calls.borrow();
+        assert_eq!(calls[0].0, "docker login ghcr.io -u ci --password-stdin");
+        assert_eq!(calls[0].1, b"ghp_secret");
+        assert!(calls.iter().all(|(argv, _)| !argv.contains("ghp_secret")));
+        assert!(
+            calls[1]
+                .0
+                .ends_with("compose -f /opt/polis/docker-compose.yml pull")
+        );
+        assert_eq!(calls[2].0, "docker logout ghcr.io");
+    }
+
+    #[tokio::test]
+    async fn pull_images_as_logs_out_when_pull_fails() {
+        let spy = LoginSpy {
+            pull_fails: true,
+            ..LoginSpy::default()
+        };
+        let result = pull_images_as(&spy, &ReporterStub, Some(("ci", "t"))).await;
+        assert!(result.is_err());
+        assert_eq!(
+            commands(&spy).last().map(String::as_str),
+            Some("docker logout ghcr.io")
+        );
+    }
+
+    #[tokio::test]
+    async fn pull_images_as_stops_when_login_is_rejected() {
+        let spy = LoginSpy {
+            login_fails: true,
+            ..LoginSpy::default()
+        };
+        let err = pull_images_as(&spy, &ReporterStub, Some(("ci", "t")))
+            .await
+            .expect_err("login rejected");
+        assert!(err.to_string().contains("docker login"), "{err}");
+        assert_eq!(commands(&spy).len(), 1, "no pull after a failed login");
+    }
+
+    #[tokio::test]
+    async fn pull_images_as_without_token_skips_login() {
+        let spy = LoginSpy::default();
+        pull_images_as(&spy, &ReporterStub, None)
+            .await
+            .expect("pull");
+        let calls = commands(&spy);
+        assert_eq!(calls.len(), 1);
+        assert!(calls[0].ends_with("pull"));
+    }
+
+    #[tokio::test]
+    async fn pull_images_unless_offline_skips_network_when_offline() {
+        // A failing pull proves exec is never reached.
+        let mp = PullImagesStub::failure(b"no route to host");
+        let result = pull_images_unless_offline(&mp, &ReporterStub, true).await;
+        assert!(result.is_ok(), "offline pull should be skipped: {result:?}");
+        assert!(
+            pull_images_unless_offline(&mp, &ReporterStub, false)
+                .await
+                .is_err()
+        );
+    }
+
     #[tokio::test]
     async fn pull_images_succeeds_on_exit_code_0() {
         let mp = PullImagesStub::success();
@@ -156,7 +378,7 @@ mod tests {
     #[tokio::test]
     async fn pull_images_timeout_returns_specific_error() {
         let mp = PullImagesStub::timeout();
-        let err = pull_images(&mp, &ReporterStub)
+        let err = compose_pull(&mp, &ReporterStub, Duration::ZERO)
             .await
             .expect_err("expected Err");
         let msg = err.to_string();
@@ -169,7 +391,7 @@ mod tests {
     #[tokio::test]
     async fn pull_images_timeout_suggests_network_check() {
         let mp = PullImagesStub::timeout();
-        let err = pull_images(&mp, &ReporterStub)
+        let err = compose_pull(&mp, &ReporterStub, Duration::ZERO)
             .await
             .expect_err("expected Err");
         let msg = err.to_string();
@@ -178,4 +400,97 @@ mod tests {
             "timeout error must suggest checking network: {msg}"
         );
     }
+
+    /// Fails with `stderr` for the first `failures` pulls, then succeeds.
+    struct FlakyPull {
+        failures: u32,
+        stderr: &'static [u8],
+        pulls: std::cell::Cell<u32>,
+        steps: std::cell::RefCell<Vec<String>>,
+    }
+
+    impl FlakyPull {
+        fn new(failures: u32, stderr: &'static [u8]) -> Self {
+            Self {
+                failures,
+                stderr,
+                pulls: std::cell::Cell::new(0),
+                steps: std::cell::RefCell::default(),
+            }
+        }
+    }
+
+    impl ShellExecutor for FlakyPull {
+        async fn exec(&self, _: &[&str]) -> Result<Output> {
+            let n = self.pulls.get();
+            self.pulls.set(n + 1);
+            let failed = n < self.failures;
+            Ok(Output {
+                status: exit_status(i32::from(failed)),
+                stdout: vec![],
+                stderr: if failed { self.stderr.to_vec() } else { vec![] },
+            })
+        }
+        impl_shell_executor_stubs!(exec_with_stdin, exec_spawn, exec_status);
+    }
+
+    impl ProgressReporter for FlakyPull {
+        fn step(&self, msg: &str) {
+            self.steps.borrow_mut().push(msg.to_string());
+        }
+        fn success(&self, _: &str) {}
+        fn warn(&self, _: &str) {}
+    }
+
+    #[tokio::test]
+    async fn compose_pull_retries_transient_failures() {
+        let mp = FlakyPull::new(2, b"read tcp: connection reset by peer");
+        compose_pull(&mp, &mp, Duration::ZERO)
+            .await
+            .expect("third attempt succeeds");
+        assert_eq!(mp.pulls.get(), 3);
+        assert_eq!(
+            *mp.steps.borrow(),
+            [
+                "image pull failed; retrying (2/3)...",
+                "image pull failed; retrying (3/3)...",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn compose_pull_gives_up_after_three_attempts() {
+        let mp = FlakyPull::new(3, b"net/http: TLS handshake timeout");
+        let err = compose_pull(&mp, &mp, Duration::ZERO)
+            .await
+            .expect_err("every attempt fails");
+        assert_eq!(mp.pulls.get(), 3);
+        assert!(err.to_string().contains("TLS handshake"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn compose_pull_fails_fast_on_permanent_errors() {
+        for stderr in [
+            b"Error response from daemon: manifest unknown".as_slice(),
+            b"Head https://ghcr.io/v2/...: unauthorized: timeout reading token".as_slice(),
+        ] {
+            let mp = FlakyPull::new(3, stderr);
+            let result = compose_pull(&mp, &mp, Duration::ZERO).await;
+            assert!(result.is_err());
+            assert_eq!(mp.pulls.get(), 1, "no retry for permanent errors");
+            assert!(mp.steps.borrow().is_empty());
+        }
+    }
+
+    #[test]
+    fn is_transient_pull_failure_classifies_registry_errors() {
+        assert!(is_transient_pull_failure(Some(124), ""));
+        assert!(is_transient_pull_failure(Some(1), "i/o timeout"));
+        assert!(is_transient_pull_failure(
+            Some(1),
+            "connection reset by peer"
+        ));
+        assert!(!is_transient_pull_failure(Some(1), "connection refused"));
+        assert!(!is_transient_pull_failure(Some(124), "manifest unknown"));
+    }
 }
@@ -0,0 +1,186 @@
+//! VM snapshots: take, restore, and list snapshots of the workspace VM.
+//!
+//! Imports only from `crate::domain` and `crate::application::ports`.
+
+use anyhow::{Context, Result};
+
+use crate::application::ports::{InstanceInspector, InstanceLifecycle};
+use crate::application::services::vm::lifecycle::{self, VmState};
+use crate::domain::instance::parse_snapshot_names;
+
+/// Multipass only snapshots and restores stopped instances.
+async fn ensure_stopped(mp: &impl InstanceInspector) -> Result<()> {
+    match lifecycle::state(mp).await? {
+        VmState::Stopped => Ok(()),
+        VmState::NotFound => anyhow::bail!("Workspace not found. Create it first: polis start"),
+        VmState::Running | VmState::Starting => {
+            anyhow::bail!("Workspace is running. Stop first:\n  polis stop")
+        }
+    }
+}
+
+/// Take snapshot `name` of the stopped workspace VM.
+///
+/// # Errors
+///
+/// Returns an error if the VM is missing or running, or the multipass
+/// snapshot command fails (for example because `name` is taken).
+pub async fn create(mp: &(impl InstanceLifecycle + InstanceInspector), name: &str) -> Result<()> {
+    ensure_stopped(mp).await?;
+    let output = mp.snapshot(name).await.context("taking snapshot")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("failed to take snapshot '{name}': {}", stderr.trim());
+    }
+    Ok(())
+}
+
+/// Roll the stopped workspace VM back to snapshot `name`. The VM's current
+/// state is discarded.
+///
+/// # Errors
+///
+/// Returns an error if the VM is missing or running, or the multipass
+/// restore command fails (for example because there is no such snapshot).
+pub async fn restore(mp: &(impl InstanceLifecycle + InstanceInspector), name: &str) -> Result<()> {
+    ensure_stopped(mp).await?;
+    let output = mp.restore(name).await.context("restoring snapshot")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("failed to restore snapshot '{name}': {}", stderr.trim());
+    }
+    Ok(())
+}
+
+/// Names of the workspace VM's snapshots, sorted.
+///
+/// # Errors
+///
+/// Returns an error if multipass fails or prints invalid JSON.
+pub async fn list(mp: &(impl InstanceLifecycle + InstanceInspector)) -> Result<Vec<String>> {
+    let output = mp.list_snapshots().await.context("listing snapshots")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("failed to list snapshots: {}", stderr.trim());
+    }
+    parse_snapshot_names(&output.stdout, mp.instance_name()).context("parsing multipass list")
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use std::cell::RefCell;
+    use std::process::Output;
+
+    use super::*;
+    use crate::application::ports::InstanceSpec;
+    use crate::application::services::vm::test_support::{fail_output, ok_output};
+
+    /// Lifecycle double that records snapshot calls for a VM in `state`.
+    struct SnapshotSpy {
+        state: &'static str,
+        fail: bool,
+        calls: RefCell<Vec<String>>,
+    }
+
+    impl SnapshotSpy {
+        fn new(state: &'static str) -> Self {
+            Self {
+                state,
+                fail: false,
+                calls: RefCell::new(Vec::new()),
+            }
+        }
+
+        fn record(&self, call: String) -> Output {
+            self.calls.borrow_mut().push(call);
+            if self.fail {
+                Output {
+                    stderr: b"snapshot \"clean\" already exists\n".to_vec(),
+                    ..fail_output()
+                }
+            } else {
+                ok_output(b"")
+            }
+        }
+    }
+
+    impl InstanceLifecycle for SnapshotSpy {
+        async fn launch(&self, _: &InstanceSpec<'_>) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        async fn start(&self) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        async fn stop(&self) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        async fn delete(&self) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        async fn purge(&self) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        async fn snapshot(&self, name: &str) -> Result<Output> {
+            Ok(self.record(format!("snapshot {name}")))
+        }
+        async fn restore(&self, name: &str) -> Result<Output> {
+            Ok(self.record(format!("restore {name}")))
+        }
+        async fn list_snapshots(&self) -> Result<Output> {
+            Ok(ok_output(
+                br#"{"errors":[],"info":{"polis":{"clean":{"comment":"","parent":""}}}}"#,
+            ))
+        }
+    }
+
+    impl InstanceInspector for SnapshotSpy {
+        async fn info(&self) -> Result<Output> {
+            let json = format!(r#"{{"info":{{"polis":{{"state":"{}"}}}}}}"#, self.state);
+            Ok(ok_output(json.as_bytes()))
+        }
+        async fn version(&self) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+    }
+
+    #[tokio::test]
+    async fn create_dispatches_snapshot_with_name() {
+        let mp = SnapshotSpy::new("Stopped");
+        create(&mp, "pre-upgrade").await.expect("create");
+        assert_eq!(*mp.calls.borrow(), ["snapshot pre-upgrade"]);
+    }
+
+    #[tokio::test]
+    async fn restore_dispatches_restore_with_name() {
+        let mp = SnapshotSpy::new("Stopped");
+        restore(&mp, "clean").await.expect("restore");
+        assert_eq!(*mp.calls.borrow(), ["restore clean"]);
+    }
+
+    #[tokio::test]
+    async fn running_vm_is_not_snapshotted_or_restored() {
+        let mp = SnapshotSpy::new("Running");
+        let err = create(&mp, "clean").await.expect_err("running");
+        assert!(err.to_string().contains("polis stop"), "{err}");
+        assert!(restore(&mp, "clean").await.is_err());
+        assert!(mp.calls.borrow().is_empty());
+    }
+
+    #[tokio::test]
+    async fn snapshot_failure_reports_multipass_stderr() {
+        let mut mp = SnapshotSpy::new("Stopped");
+        mp.fail = true;
+        let err = create(&mp, "clean").await.expect_err("fails");
+        assert_eq!(
+            err.to_string(),
+            "failed to take snapshot 'clean': snapshot \"clean\" already exists"
+        );
+    }
+
+    #[tokio::test]
+    async fn list_returns_instance_snapshots() {
+        let mp = SnapshotSpy::new("Stopped");
+        assert_eq!(list(&mp).await.expect("list"), ["clean"]);
+    }
+}
@@ -7,7 +7,7 @@ use anyhow::Result;
 
 use crate::application::ports::{
     CommandRunner, FileTransfer, InstanceInspector, LocalPaths, NetworkProbe, ProgressReporter,
-    ShellExecutor,
+    ShellExecutor, WorkspaceStateStore,
 };
 use crate::domain::health::DoctorChecks;
 use crate::domain::workspace::QUERY_SCRIPT;
@@ -22,6 +22,7 @@ use crate::domain::workspace::QUERY_SCRIPT;
 ///
 /// Returns an error if any health probe fails to execute.
 #[allow(dead_code)] // Public API — not yet called from commands/doctor.rs
+#[allow(clippy::too_many_arguments)]
 pub async fn run_doctor(
     provisioner: &(impl InstanceInspector + ShellExecutor + FileTransfer),
     reporter: &impl ProgressReporter,
@@ -29,12 +30,14 @@ pub async fn run_doctor(
     network_probe: &impl NetworkProbe,
     paths: &impl LocalPaths,
     fs: &impl crate::application::ports::LocalFs,
+    state_mgr: &impl WorkspaceStateStore,
+    version: &str,
 ) -> Result<DoctorChecks> {
     reporter.step("checking prerequisites...");
     let prerequisites = probe_prerequisites(cmd_runner).await?;
 
     reporter.step("checking workspace...");
-    let workspace = probe_workspace(provisioner, cmd_runner, paths, fs).await?;
+    let workspace = probe_workspace(provisioner, cmd_runner, paths, fs, state_mgr, version).await?;
 
     reporter.step("checking network...");
     let network = probe_network(network_probe).await?;
@@ -96,6 +99,8 @@ async fn probe_workspace(
     cmd_runner: &impl CommandRunner,
     paths: &impl LocalPaths,
     fs: &impl crate::application::ports::LocalFs,
+    state_mgr: &impl WorkspaceStateStore,
+    version: &str,
 ) -> Result<crate::domain::health::WorkspaceChecks> {
     let disk_space_gb = probe_disk_space_gb(cmd_runner).await?;
     let image = probe_image_cache(paths, fs);
@@ -106,14 +111,69 @@ async fn probe_workspace(
         .ok()
         == Some(crate::application::services::vm::lifecycle::VmState::Running);
 
+    let deployed_env = if ready {
+        crate::application::services::vm::provision::read_deployed_env(provisioner).await
+    } else {
+        None
+    };
+    let version_drift = deployed_env
+        .as_deref()
+        .map(|env| crate::domain::health::find_version_drift(env, version));
+    let recorded_env_sha256 = state_mgr
+        .load_async()
+        .await
+        .ok()
+        .flatten()
+        .and_then(|s| s.env_sha256);
+    let env_modified = deployed_env
+        .as_deref()
+        .zip(recorded_env_sha256.as_deref())
+        .map(|(env, recorded)| !crate::domain::workspace::env_matches_record(env, recorded));
+    let host_key_pinned = if ready {
+        probe_host_key_pinned(provisioner, paths, fs).await
+    } else {
+        None
+    };
+
     Ok(crate::domain::health::WorkspaceChecks {
         ready,
         disk_space_gb,
         disk_space_ok: disk_space_gb >= 10,
         image,
+        version_drift,
+        env_modified,
+        host_key_pinned,
     })
 }
 
+/// Compare the workspace's SSH host key with `~/.polis/known_hosts`.
+/// `None` if the key cannot be read from the workspace.
+async fn probe_host_key_pinned(
+    provisioner: &impl ShellExecutor,
+    paths: &impl LocalPaths,
+    fs: &impl crate::application::ports::LocalFs,
+) -> Option<bool> {
+    let output = provisioner
+        .exec(&[
+            "docker",
+            "exec",
+            crate::domain::workspace::CONTAINER_NAME,
+            "cat",
+            "/etc/ssh/ssh_host_ed25519_key.pub",
+        ])
+        .await
+        .ok()
+        .filter(|o| o.status.success())?;
+    let known_hosts = paths
+        .polis_dir()
+        .and_then(|dir| fs.read_to_string(&dir.join("known_hosts")))
+        .unwrap_or_default();
+    Some(crate::domain::ssh::host_key_pinned(
+        &known_hosts,
+        &String::from_utf8_lossy(&output.stdout),
+    ))
+}
+
 /// # Errors
 ///
 /// This function will return an error if the underlying operations fail.
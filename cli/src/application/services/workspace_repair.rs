@@ -5,12 +5,70 @@
 
 use anyhow::{Context, Result};
 
-use crate::application::ports::{FileTransfer, InstanceInspector, ProgressReporter, ShellExecutor};
+use crate::application::ports::{
+    FileTransfer, InstanceInspector, ProgressReporter, ShellExecutor, SshConfigurator,
+};
+use crate::application::services::connect::pin_host_key;
 use crate::application::services::vm::provision::{generate_certs_and_secrets, transfer_config};
+use crate::domain::health::{DoctorIssue, Remedy};
 use crate::domain::workspace::COMPOSE_PATH;
 
+/// Apply the automatic remedies of `issues` (`polis doctor --fix`).
+///
+/// Config and service problems share one [`run_repair`] pass, forced to
+/// re-transfer config when the `.env` was tampered with. Expired
+/// certificates alone only regenerate certificates and restart services.
+/// Issues with a [`Remedy::Manual`] remedy are skipped; the caller re-runs
+/// the checks to see what was fixed.
+///
+/// Returns the SHA256 of the `.env` if config was re-transferred.
+///
+/// # Errors
+///
+/// Returns an error if a repair step fails fatally.
+pub async fn apply_fixes(
+    mp: &(impl InstanceInspector + ShellExecutor + FileTransfer),
+    ssh: &impl SshConfigurator,
+    reporter: &impl ProgressReporter,
+    issues: &[DoctorIssue],
+    assets_dir: &std::path::Path,
+    version: &str,
+    registry_prefix: &str,
+) -> Result<Option<String>> {
+    let has = |remedy: Remedy| issues.iter().any(|i| i.remedy == remedy);
+    for issue in issues {
+        if !matches!(issue.remedy, Remedy::Manual(_)) {
+            reporter.step(&format!("fixing: {}", issue.message));
+        }
+    }
+
+    let mut env_sha256 = None;
+    if has(Remedy::ReprovisionConfig) || has(Remedy::RestartServices) {
+        env_sha256 = run_repair(
+            mp,
+            reporter,
+            assets_dir,
+            version,
+            registry_prefix,
+            has(Remedy::ReprovisionConfig),
+        )
+        .await?;
+    } else if has(Remedy::RegenerateCerts) {
+        let certs_regenerated = ensure_certs_valid(mp, reporter).await?;
+        restart_compose_services(mp, reporter, certs_regenerated).await?;
+    }
+    if has(Remedy::RepinHostKey) {
+        reporter.step("Re-pinning workspace SSH host key...");
+        pin_host_key(mp, ssh).await;
+    }
+    Ok(env_sha256)
+}
+
 /// Repair the workspace.
 ///
+/// Returns the SHA256 of the `.env` if config was re-transferred, so the
+/// caller can record it in workspace state.
+///
 /// # Errors
 ///
 /// Returns an error if any repair step fails fatally.
@@ -19,18 +77,26 @@ pub async fn run_repair(
     reporter: &impl ProgressReporter,
     assets_dir: &std::path::Path,
     version: &str,
+    registry_prefix: &str,
     health_checks_failed: bool,
-) -> Result<()> {
+) -> Result<Option<String>> {
+    let mut env_sha256 = None;
     if health_checks_failed {
-        retransfer_config_forced(mp, reporter, assets_dir, version).await?;
+        env_sha256 = Some(
+            retransfer_config_forced(mp, reporter, assets_dir, version, registry_prefix).await?,
+        );
     }
     ensure_docker_running(mp, reporter).await?;
     ensure_sysbox_registered(mp, reporter).await?;
-    ensure_config_present(mp, reporter, assets_dir, version).await?;
+    if let Some(hash) =
+        ensure_config_present(mp, reporter, assets_dir, version, registry_prefix).await?
+    {
+        env_sha256 = Some(hash);
+    }
     let certs_regenerated = ensure_certs_valid(mp, reporter).await?;
     ensure_polis_service_enabled(mp, reporter).await?;
     restart_compose_services(mp, reporter, certs_regenerated).await?;
-    Ok(())
+    Ok(env_sha256)
 }
 
 async fn retransfer_config_forced(
@@ -38,13 +104,14 @@ async fn retransfer_config_forced(
     reporter: &impl ProgressReporter,
     assets_dir: &std::path::Path,
     version: &str,
-) -> Result<()> {
+    registry_prefix: &str,
+) -> Result<String> {
     reporter.step("Re-transferring config (health checks failed, VM state untrusted)...");
-    transfer_config(mp, assets_dir, version)
+    let env_sha256 = transfer_config(mp, assets_dir, version, registry_prefix)
         .await
         .context("re-transferring config to VM")?;
     reporter.success("Config re-transferred");
-    Ok(())
+    Ok(env_sha256)
 }
 
 async fn ensure_docker_running(
@@ -99,7 +166,8 @@ async fn ensure_config_present(
     reporter: &impl ProgressReporter,
     assets_dir: &std::path::Path,
     version: &str,
-) -> Result<()> {
+    registry_prefix: &str,
+) -> Result<Option<String>> {
     reporter.step("Checking /opt/polis config...");
     let config_ok = mp
         .exec(&["test", "-f", "/opt/polis/.env"])
@@ -108,14 +176,14 @@ async fn ensure_config_present(
         .unwrap_or(false);
     if config_ok {
         reporter.step("/opt/polis config present");
-    } else {
-        reporter.step("Re-transferring config...");
-        transfer_config(mp, assets_dir, version)
-            .await
-            .context("re-transferring config to VM")?;
-        reporter.success("Config re-transferred");
+        return Ok(None);
     }
-    Ok(())
+    reporter.step("Re-transferring config...");
+    let env_sha256 = transfer_config(mp, assets_dir, version, registry_prefix)
+        .await
+        .context("re-transferring config to VM")?;
+    reporter.success("Config re-transferred");
+    Ok(Some(env_sha256))
 }
 
 async fn ensure_certs_valid(
@@ -11,6 +11,16 @@ pub struct StartOptions<'a, R: crate::application::ports::ProgressReporter> {
     pub envs: Vec<String>,
     pub assets_dir: &'a std::path::Path,
     pub version: &'a str,
+    /// Registry the control-plane images are pulled from.
+    pub registry_prefix: &'a str,
+    /// Re-run config transfer and cert/secret generation even if the VM's
+    /// config hash matches the embedded assets.
+    pub reprovision: bool,
+    /// Skip the Docker image pull and rely on images already in the VM
+    /// (`--offline`).
+    pub offline: bool,
+    /// Seconds `multipass launch` may take when the VM is created.
+    pub launch_timeout_secs: u64,
 }
 
 use chrono::Utc;
@@ -21,13 +31,15 @@ use crate::application::ports::{
 };
 use crate::application::services::vm::{
     health::wait_ready,
-    integrity::{verify_image_digests, write_config_hash},
+    integrity::{compute_config_hash, read_config_hash, verify_image_digests, write_config_hash},
     lifecycle::{self as vm, VmState},
     provision::{generate_certs_and_secrets, transfer_config},
-    services::pull_images,
+    services::pull_images_unless_offline,
 };
 use crate::domain::workspace::{ACTIVE_OVERLAY_PATH, READY_MARKER_PATH};
-use crate::domain::workspace::{VM_ROOT, WorkspaceState};
+use crate::domain::workspace::{
+    COMPOSE_PATH, StartAction, StartPlan, VM_CPUS, VM_DISK, VM_MEMORY, VM_ROOT, WorkspaceState,
+};
 
 /// Outcome of the `start_workspace` use-case.
 #[derive(Debug)]
@@ -67,61 +79,39 @@ pub async fn start_workspace(
     local_fs: &impl LocalFs,
     opts: StartOptions<'_, impl crate::application::ports::ProgressReporter>,
 ) -> Result<StartOutcome> {
-    let reporter = opts.reporter;
-    let StartOptions {
-        agent,
-        envs,
-        assets_dir,
-        version,
-        ..
-    } = opts;
     crate::domain::workspace::check_architecture()?;
 
     let vm_state = vm::state(provisioner).await?;
 
     match vm_state {
         VmState::Running => {
-            handle_running_vm(provisioner, state_mgr, local_fs, reporter, agent, envs).await
-        }
-        VmState::NotFound => {
-            let onboarding = create_and_start_vm(
+            if opts.reprovision {
+                return Err(reprovision_while_running());
+            }
+            handle_running_vm(
                 provisioner,
                 state_mgr,
-                assets,
-                ssh,
-                hasher,
                 local_fs,
-                StartOptions {
-                    reporter,
-                    agent,
-                    envs,
-                    assets_dir,
-                    version,
-                },
+                opts.reporter,
+                opts.agent,
+                opts.envs,
             )
-            .await?;
+            .await
+        }
+        VmState::NotFound => {
+            let agent = opts.agent;
+            let onboarding =
+                create_and_start_vm(provisioner, state_mgr, assets, ssh, hasher, local_fs, opts)
+                    .await?;
             Ok(StartOutcome::Created {
                 agent: agent.map(str::to_owned),
                 onboarding,
             })
         }
         _ => {
-            let onboarding = restart_vm(
-                provisioner,
-                state_mgr,
-                assets,
-                ssh,
-                local_fs,
-                reporter,
-                agent,
-                envs,
-            )
-            .await?;
-            let msg = agent.map_or_else(
-                || "workspace ready".to_string(),
-                |n| format!("workspace ready with agent: {n}"),
-            );
-            wait_ready(provisioner, reporter, false, &msg).await?;
+            let agent = opts.agent;
+            let onboarding =
+                restart_vm(provisioner, state_mgr, assets, hasher, local_fs, opts).await?;
             Ok(StartOutcome::Restarted {
                 agent: agent.map(str::to_owned),
                 onboarding,
@@ -156,7 +146,8 @@ async fn handle_running_vm(
         && let Some(name) = agent
     {
         reporter.begin_stage(&format!("installing agent '{name}'..."));
-        let onboarding = setup_agent(provisioner, local_fs, name, &envs).await?;
+        vm::require_docker(provisioner).await?;
+        let setup = setup_agent(provisioner, local_fs, name, &envs).await?;
 
         // Update symlink for future reboots, then start via compose directly.
         let overlay = crate::domain::agent::overlay_path(name);
@@ -165,34 +156,105 @@ async fn handle_running_vm(
 
         // Persist state before health wait so the CLI tracks the agent
         // even if health polling times out (e.g. first-time install).
-        let mut state = state_mgr
-            .load_async()
-            .await?
-            .unwrap_or_else(|| WorkspaceState {
-                created_at: Utc::now(),
-                image_sha256: None,
-                image_source: None,
-                active_agent: None,
-            });
+        let current = state_mgr.load_async().await?;
+        let mut state = current.clone().unwrap_or_else(|| WorkspaceState {
+            created_at: Utc::now(),
+            image_sha256: None,
+            image_source: None,
+            active_agent: None,
+            env_sha256: None,
+        });
         state.active_agent = Some(name.to_owned());
-        state_mgr.save_async(&state).await?;
+        anyhow::ensure!(
+            state_mgr.compare_and_set(current.as_ref(), &state).await?,
+            "workspace state changed while switching to agent '{name}'; run polis start again"
+        );
 
         let msg = format!("workspace ready with agent: {name}");
         wait_ready(provisioner, reporter, false, &msg).await?;
+        verify_required_services(provisioner, &setup.required_services).await?;
 
         return Ok(StartOutcome::Restarted {
             agent: Some(name.to_owned()),
-            onboarding,
+            onboarding: setup.onboarding,
         });
     }
 
-    let current_desc = current_agent
-        .as_deref()
-        .map_or_else(|| "no agent".to_string(), |n| format!("agent '{n}'"));
-    let requested_desc = agent.map_or_else(|| "no agent".to_string(), |n| format!("--agent {n}"));
-    anyhow::bail!(
+    Err(agent_mismatch(current_agent.as_deref(), agent))
+}
+
+/// Error for `--reprovision` against a running workspace.
+fn reprovision_while_running() -> anyhow::Error {
+    anyhow::anyhow!("Workspace is running. Stop first:\n  polis stop\n  polis start --reprovision")
+}
+
+/// Error for requesting a different agent than the running workspace has.
+fn agent_mismatch(current: Option<&str>, requested: Option<&str>) -> anyhow::Error {
+    let current_desc = current.map_or_else(|| "no agent".to_string(), |n| format!("agent '{n}'"));
+    let requested_desc =
+        requested.map_or_else(|| "no agent".to_string(), |n| format!("--agent {n}"));
+    anyhow::anyhow!(
         "Workspace is running with {current_desc}. Stop first:\n  polis stop\n  polis start {requested_desc}"
-    );
+    )
+}
+
+/// Work out what `start_workspace` would do, for `polis start --dry-run`.
+///
+/// Only reads the VM state and the local workspace state; nothing is
+/// created, started or transferred.
+///
+/// # Errors
+///
+/// Returns an error if the host is unsupported, the state cannot be read,
+/// or `polis start` would refuse the request.
+pub async fn plan_start(
+    provisioner: &impl crate::application::ports::InstanceInspector,
+    state_mgr: &impl WorkspaceStateStore,
+    agent: Option<&str>,
+    reprovision: bool,
+    offline: bool,
+) -> Result<StartPlan> {
+    crate::domain::workspace::check_architecture()?;
+    let vm_state = vm::state(provisioner).await?;
+    let current_agent = state_mgr.load_async().await?.and_then(|s| s.active_agent);
+    start_plan(
+        vm_state,
+        current_agent.as_deref(),
+        agent,
+        reprovision,
+        offline,
+    )
+}
+
+/// Pure decision behind [`plan_start`], mirroring the branches of
+/// [`start_workspace`] and [`handle_running_vm`].
+fn start_plan(
+    vm_state: VmState,
+    current_agent: Option<&str>,
+    agent: Option<&str>,
+    reprovision: bool,
+    offline: bool,
+) -> Result<StartPlan> {
+    let (action, pull_images, reprovision) = match vm_state {
+        VmState::Running if reprovision => return Err(reprovision_while_running()),
+        VmState::Running if current_agent == agent => (StartAction::AlreadyRunning, false, false),
+        VmState::Running if current_agent.is_none() => (StartAction::AddAgent, false, false),
+        VmState::Running => return Err(agent_mismatch(current_agent, agent)),
+        VmState::NotFound => (StartAction::Create, !offline, true),
+        VmState::Stopped | VmState::Starting => (StartAction::StartExisting, !offline, reprovision),
+    };
+    let mut compose_files = vec![COMPOSE_PATH.to_string()];
+    compose_files.extend(agent.map(crate::domain::agent::overlay_path));
+    Ok(StartPlan {
+        action,
+        agent: agent.map(str::to_owned),
+        cpus: VM_CPUS,
+        memory: VM_MEMORY,
+        disk: VM_DISK,
+        pull_images,
+        reprovision,
+        compose_files,
+    })
 }
 
 /// Full provisioning flow for a new VM.
@@ -211,48 +273,59 @@ async fn create_and_start_vm(
         envs,
         assets_dir,
         version,
+        registry_prefix,
+        offline,
+        launch_timeout_secs,
         ..
     } = opts;
     // Step 1: Compute config hash before transfer.
-    let tar_path = assets_dir.join("polis-setup.config.tar");
-    let config_hash = hasher
-        .sha256_file(&tar_path)
-        .context("computing config tarball SHA256")?;
-
-    reporter.begin_stage("preparing workspace...");
+    let config_hash = compute_config_hash(hasher, assets_dir, version, registry_prefix)?;
 
     // Step 2: Launch VM with cloud-init.
-    vm::create(provisioner, assets, ssh, local_fs, ssh, reporter, true).await?;
+    vm::create(
+        provisioner,
+        assets,
+        ssh,
+        local_fs,
+        ssh,
+        reporter,
+        launch_timeout_secs,
+        false,
+    )
+    .await?;
+    vm::require_docker(provisioner).await?;
 
     // Step 3: Transfer config tarball.
-    reporter.begin_stage("securing workspace...");
-    transfer_config(provisioner, assets_dir, version)
+    reporter.begin_stage("transferring config...");
+    let env_sha256 = transfer_config(provisioner, assets_dir, version, registry_prefix)
         .await
         .context("transferring config to VM")?;
 
     // Step 4: Generate certificates and secrets.
+    reporter.begin_stage("generating certificates...");
     generate_certs_and_secrets(provisioner)
         .await
         .context("generating certificates and secrets")?;
 
     // Step 5: Pull Docker images.
-    reporter.begin_stage("verifying components...");
-    pull_images(provisioner, reporter)
+    reporter.begin_stage("pulling images...");
+    pull_images_unless_offline(provisioner, reporter, offline)
         .await
         .context("pulling Docker images")?;
 
     // Step 6: Verify image digests.
-    verify_image_digests(provisioner, assets, reporter)
+    reporter.begin_stage("verifying images...");
+    verify_image_digests(provisioner, assets, reporter, registry_prefix)
         .await
         .context("verifying image digests")?;
 
     // Step 7: Set up agent if requested.
-    let (overlay, onboarding) = if let Some(name) = agent {
+    let (overlay, setup) = if let Some(name) = agent {
         reporter.begin_stage(&format!("installing agent '{name}'..."));
-        let steps = setup_agent(provisioner, local_fs, name, &envs).await?;
-        (Some(crate::domain::agent::overlay_path(name)), steps)
+        let setup = setup_agent(provisioner, local_fs, name, &envs).await?;
+        (Some(crate::domain::agent::overlay_path(name)), setup)
     } else {
-        (None, vec![])
+        (None, AgentSetup::default())
     };
 
     // Step 8: Set active overlay symlink and start via systemd.
@@ -269,6 +342,7 @@ async fn create_and_start_vm(
         |n| format!("workspace ready with agent: {n}"),
     );
     wait_ready(provisioner, reporter, false, &msg).await?;
+    verify_required_services(provisioner, &setup.required_services).await?;
 
     // Step 10: Write config hash after successful startup.
     write_config_hash(provisioner, &config_hash)
@@ -281,41 +355,83 @@ async fn create_and_start_vm(
         image_sha256: None,
         image_source: None,
         active_agent: agent.map(str::to_owned),
+        env_sha256: Some(env_sha256),
     };
     state_mgr.save_async(&state).await?;
 
-    Ok(onboarding)
+    Ok(setup.onboarding)
 }
 
 /// Restart a stopped VM.
-#[allow(clippy::too_many_arguments)]
+///
+/// Config transfer and cert/secret generation are re-run when the VM's config
+/// hash is missing or stale, or unconditionally with `--reprovision`. Both
+/// steps are safe to repeat: the tarball is re-extracted over `/opt/polis`
+/// and the generation scripts skip certs and secrets that already exist.
 async fn restart_vm(
     provisioner: &impl VmProvisioner,
     state_mgr: &impl WorkspaceStateStore,
-    _assets: &impl AssetExtractor,
-    _ssh: &impl SshConfigurator,
+    assets: &impl AssetExtractor,
+    hasher: &impl FileHasher,
     local_fs: &impl LocalFs,
-    reporter: &impl ProgressReporter,
-    agent: Option<&str>,
-    envs: Vec<String>,
+    opts: StartOptions<'_, impl crate::application::ports::ProgressReporter>,
 ) -> Result<Vec<polis_common::agent::OnboardingStep>> {
+    let reporter = opts.reporter;
+    let StartOptions {
+        agent,
+        envs,
+        assets_dir,
+        version,
+        registry_prefix,
+        reprovision,
+        offline,
+        ..
+    } = opts;
+    let config_hash = compute_config_hash(hasher, assets_dir, version, registry_prefix)?;
+
     // Start the VM (systemd polis.service is gated by .ready which was cleared).
     reporter.begin_stage("starting workspace...");
     vm::start(provisioner).await?;
+    vm::require_docker(provisioner).await?;
     reporter.complete_stage();
 
+    let deployed_hash = read_config_hash(provisioner).await;
+    let env_sha256 = if crate::domain::workspace::needs_reprovision(
+        reprovision,
+        deployed_hash.as_deref(),
+        &config_hash,
+    ) {
+        reporter.begin_stage("transferring config...");
+        let env_sha256 = transfer_config(provisioner, assets_dir, version, registry_prefix)
+            .await
+            .context("transferring config to VM")?;
+        reporter.begin_stage("generating certificates...");
+        generate_certs_and_secrets(provisioner)
+            .await
+            .context("generating certificates and secrets")?;
+        Some(env_sha256)
+    } else {
+        None
+    };
+
     // Pull images BEFORE starting services.
-    reporter.begin_stage("verifying components...");
-    pull_images(provisioner, reporter)
+    reporter.begin_stage("pulling images...");
+    pull_images_unless_offline(provisioner, reporter, offline)
         .await
         .context("pulling Docker images")?;
+    if env_sha256.is_some() {
+        reporter.begin_stage("verifying images...");
+        verify_image_digests(provisioner, assets, reporter, registry_prefix)
+            .await
+            .context("verifying image digests")?;
+    }
 
-    let (overlay, onboarding) = if let Some(name) = agent {
+    let (overlay, setup) = if let Some(name) = agent {
         reporter.begin_stage(&format!("installing agent '{name}'..."));
-        let steps = setup_agent(provisioner, local_fs, name, &envs).await?;
-        (Some(crate::domain::agent::overlay_path(name)), steps)
+        let setup = setup_agent(provisioner, local_fs, name, &envs).await?;
+        (Some(crate::domain::agent::overlay_path(name)), setup)
     } else {
-        (None, vec![])
+        (None, AgentSetup::default())
     };
 
     // Set overlay symlink, then gate-open and start services.
@@ -336,11 +452,39 @@ async fn restart_vm(
             image_sha256: None,
             image_source: None,
             active_agent: None,
+            env_sha256: None,
         });
     state.active_agent = agent.map(str::to_owned);
+    if env_sha256.is_some() {
+        state.env_sha256.clone_from(&env_sha256);
+    }
     state_mgr.save_async(&state).await?;
 
-    Ok(onboarding)
+    let msg = agent.map_or_else(
+        || "workspace ready".to_string(),
+        |n| format!("workspace ready with agent: {n}"),
+    );
+    wait_ready(provisioner, reporter, false, &msg).await?;
+    verify_required_services(provisioner, &setup.required_services).await?;
+
+    // Write config hash only after a successful startup so a failed
+    // re-provision is retried on the next start (Requirement 15.1).
+    if env_sha256.is_some() {
+        write_config_hash(provisioner, &config_hash)
+            .await
+            .context("writing config hash")?;
+    }
+
+    Ok(setup.onboarding)
+}
+
+/// Manifest details `setup_agent` hands back to the start flow.
+#[derive(Default)]
+struct AgentSetup {
+    /// Getting-started steps shown after a successful start.
+    onboarding: Vec<polis_common::agent::OnboardingStep>,
+    /// Control-plane services that must be running (`requirements.services`).
+    required_services: Vec<String>,
 }
 
 /// Validate and generate artifacts for an agent.
@@ -353,7 +497,7 @@ async fn setup_agent<P: VmProvisioner>(
     local_fs: &impl LocalFs,
     agent_name: &str,
     envs: &[String],
-) -> Result<Vec<polis_common::agent::OnboardingStep>> {
+) -> Result<AgentSetup> {
     // Verify agent manifest exists in the VM.
     let manifest_path = format!("{VM_ROOT}/agents/{agent_name}/agent.yaml");
     let check = provisioner
@@ -375,6 +519,8 @@ async fn setup_agent<P: VmProvisioner>(
         String::from_utf8_lossy(&cat_out.stderr)
     );
 
+    verify_deployed_unit(provisioner, agent_name).await?;
+
     // Generate artifacts in a temp dir using pure Rust domain functions.
     let name = agent_name.to_owned();
     let stdout_bytes = cat_out.stdout.clone();
@@ -393,11 +539,17 @@ async fn setup_agent<P: VmProvisioner>(
         .context("creating temp dir for agent artifacts")?;
     let tmp_path = tmp.path().to_path_buf();
 
-    let manifest: polis_common::agent::AgentManifest =
+    let mut manifest: polis_common::agent::AgentManifest =
         serde_yaml::from_slice(&stdout_bytes).context("parsing agent.yaml from VM")?;
+    let port_overrides =
+        crate::application::services::agent_crud::read_port_overrides(provisioner, &name).await?;
+    crate::domain::agent::ports::apply_port_overrides(&mut manifest, &port_overrides)?;
     crate::domain::agent::validate::validate_full_manifest(&manifest)?;
 
-    let onboarding = manifest.spec.onboarding.clone();
+    let setup = AgentSetup {
+        onboarding: manifest.spec.onboarding.clone(),
+        required_services: crate::domain::agent::services::required_services(&manifest),
+    };
 
     let generated_dir = tmp_path.join("agents").join(&name).join(".generated");
 
@@ -414,6 +566,7 @@ async fn setup_agent<P: VmProvisioner>(
         &name,
         &manifest,
         env_content,
+        &port_overrides,
     )?;
 
     // Transfer the generated artifacts back into the VM.
@@ -440,7 +593,72 @@ async fn setup_agent<P: VmProvisioner>(
         String::from_utf8_lossy(&transfer_out.stderr)
     );
 
-    Ok(onboarding)
+    Ok(setup)
+}
+
+/// Refuse to start if the agent's deployed `<name>.service` no longer
+/// matches its recorded `.service.sha256`, i.e. it was edited inside the VM
+/// since polis generated it. A first start (nothing deployed) passes.
+///
+/// # Errors
+///
+/// Returns an error if the unit was modified out-of-band.
+async fn verify_deployed_unit(provisioner: &impl ShellExecutor, agent_name: &str) -> Result<()> {
+    use crate::domain::agent::artifacts::{UnitIntegrity, unit_integrity};
+
+    let generated = format!("{VM_ROOT}/agents/{agent_name}/.generated");
+    let read = |file: String| async move {
+        provisioner
+            .exec(&["cat", &file])
+            .await
+            .ok()
+            .filter(|out| out.status.success())
+            .map(|out| String::from_utf8_lossy(&out.stdout).into_owned())
+    };
+    let unit = read(format!("{generated}/{agent_name}.service")).await;
+    let recorded = read(format!("{generated}/{agent_name}.service.sha256")).await;
+    anyhow::ensure!(
+        unit_integrity(unit.as_deref(), recorded.as_deref()) != UnitIntegrity::Modified,
+        "{agent_name}.service was modified outside polis (does not match {agent_name}.service.sha256); refusing to start.\n\
+         Inspect: polis agent doctor {agent_name}"
+    );
+    Ok(())
+}
+
+/// Fail if any control-plane service the agent depends on is not running.
+///
+/// # Errors
+///
+/// Returns an error naming the stopped dependencies, or if `docker compose
+/// ps` cannot be run.
+async fn verify_required_services(
+    provisioner: &impl ShellExecutor,
+    services: &[String],
+) -> Result<()> {
+    use crate::domain::agent::services::{missing_services, service_check_args};
+
+    if services.is_empty() {
+        return Ok(());
+    }
+    let args = service_check_args(services);
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = provisioner
+        .exec(&arg_refs)
+        .await
+        .context("checking agent service dependencies")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "failed to check agent service dependencies: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let missing = missing_services(services, &String::from_utf8_lossy(&output.stdout));
+    anyhow::ensure!(
+        missing.is_empty(),
+        "agent requires platform services that are not running: {}\n\
+         Diagnose: polis doctor",
+        missing.join(", ")
+    );
+    Ok(())
 }
 
 /// Set or remove the active compose overlay symlink.
@@ -516,3 +734,52 @@ async fn start_compose<P: VmProvisioner>(provisioner: &P, agent_name: Option<&st
     }
     Ok(())
 }
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_plan_missing_vm_creates_and_pulls() {
+        let plan = start_plan(VmState::NotFound, None, Some("alpha"), false, false).expect("plan");
+        assert_eq!(plan.action, StartAction::Create);
+        assert!(plan.pull_images);
+        assert!(plan.reprovision);
+        assert_eq!((plan.cpus, plan.memory, plan.disk), ("4", "8G", "40G"));
+        assert_eq!(
+            plan.compose_files,
+            [
+                "/opt/polis/docker-compose.yml",
+                "/opt/polis/agents/alpha/.generated/compose.agent.yaml",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_start_plan_stopped_vm_honours_offline_and_reprovision() {
+        let plan = start_plan(VmState::Stopped, None, None, true, true).expect("plan");
+        assert_eq!(plan.action, StartAction::StartExisting);
+        assert!(!plan.pull_images);
+        assert!(plan.reprovision);
+        assert_eq!(plan.compose_files, ["/opt/polis/docker-compose.yml"]);
+    }
+
+    #[test]
+    fn test_start_plan_running_vm() {
+        let plan =
+            start_plan(VmState::Running, Some("alpha"), Some("alpha"), false, false).expect("plan");
+        assert_eq!(plan.action, StartAction::AlreadyRunning);
+        assert!(!plan.pull_images);
+        let plan = start_plan(VmState::Running, None, Some("alpha"), false, false).expect("plan");
+        assert_eq!(plan.action, StartAction::AddAgent);
+    }
+
+    #[test]
+    fn test_start_plan_running_vm_rejects_what_start_rejects() {
+        let err = start_plan(VmState::Running, Some("alpha"), Some("beta"), false, false)
+            .expect_err("agent mismatch");
+        assert!(err.to_string().contains("agent 'alpha'"), "{err}");
+        assert!(start_plan(VmState::Running, None, None, true, false).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! Application service — workspace status gathering use-case.
 //!
-//! Imports only from `crate::domain` and `crate::application::ports`.
+//! Imports only from `crate::domain`, `crate::application::ports` and the
+//! VM config-hash reader.
 //! All I/O is routed through injected port traits.
 
 use std::collections::HashMap;
@@ -11,10 +12,16 @@ use polis_common::types::{
 };
 
 use crate::application::ports::{InstanceInspector, ShellExecutor};
-use crate::domain::workspace::QUERY_SCRIPT;
+use crate::application::services::vm::integrity::read_config_hash;
+use crate::application::services::vm::lifecycle::{docker_available, docker_missing};
+use crate::domain::instance::InstanceState;
+use crate::domain::workspace::{QUERY_SCRIPT, config_state};
 
 /// Gather all workspace status information.
 ///
+/// With `expected_config_hash` (the hash `polis start` would deploy), a
+/// running VM also reports whether its recorded config hash matches.
+///
 /// # Errors
 ///
 /// This function is infallible — all errors are swallowed and reflected as
@@ -24,13 +31,18 @@ struct ContainerInfo {
     health: Option<String>,
 }
 
-pub async fn gather_status(mp: &(impl InstanceInspector + ShellExecutor)) -> StatusOutput {
+pub async fn gather_status(
+    mp: &(impl InstanceInspector + ShellExecutor),
+    expected_config_hash: Option<&str>,
+) -> StatusOutput {
     let Some(vm_state) = check_multipass_status(mp).await else {
         return StatusOutput {
             workspace: workspace_unknown(),
             agent: None,
             security: empty_security(),
             events: empty_events(),
+            updates: None,
+            config: None,
         };
     };
 
@@ -43,11 +55,35 @@ pub async fn gather_status(mp: &(impl InstanceInspector + ShellExecutor)) -> Sta
             agent: None,
             security: empty_security(),
             events: empty_events(),
+            updates: None,
+            config: None,
         };
     }
 
     // VM is running, gather detailed status in a single consolidated call
-    let (uptime_seconds, containers) = gather_remote_info(mp).await;
+    let (uptime_seconds, containers) = match gather_remote_info(mp).await {
+        Some(info) => info,
+        // The query script drives docker; without Docker nothing is
+        // starting, so report an error rather than "starting".
+        None if matches!(docker_available(mp).await, Ok(false)) => {
+            return StatusOutput {
+                workspace: workspace_unknown(),
+                agent: None,
+                security: empty_security(),
+                events: empty_events(),
+                updates: None,
+                config: None,
+            };
+        }
+        None => (None, HashMap::new()),
+    };
+    let config = match expected_config_hash {
+        Some(expected) => Some(config_state(
+            read_config_hash(mp).await.as_deref(),
+            expected,
+        )),
+        None => None,
+    };
 
     let workspace_info = containers.get("workspace");
     let is_workspace_running = workspace_info.is_some_and(|i| i.state == "running");
@@ -82,6 +118,8 @@ pub async fn gather_status(mp: &(impl InstanceInspector + ShellExecutor)) -> Sta
                 .is_some_and(|i| i.state == "running"),
         },
         events: empty_events(),
+        updates: None,
+        config,
     }
 }
 
@@ -100,27 +138,23 @@ fn empty_events() -> SecurityEvents {
     }
 }
 
-/// Check multipass VM state.
-async fn check_multipass_status(mp: &impl InstanceInspector) -> Option<WorkspaceState> {
-    let output = mp.info().await.ok()?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("does not exist") || stderr.contains("was not found") {
-            return Some(WorkspaceState::NotFound);
-        }
-        return None;
-    }
-
-    let info: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
-    let state = info.get("info")?.get("polis")?.get("state")?.as_str()?;
+/// The reason to show when a running workspace reports `Error` because
+/// Docker is missing inside the VM; `None` for any other cause.
+pub async fn docker_missing_hint(mp: &(impl InstanceInspector + ShellExecutor)) -> Option<String> {
+    let running = check_multipass_status(mp).await? == WorkspaceState::Running;
+    (running && !docker_available(mp).await.ok()?).then(docker_missing)
+}
 
-    Some(match state {
-        "Running" => WorkspaceState::Running,
-        "Stopped" => WorkspaceState::Stopped,
-        "Starting" => WorkspaceState::Starting,
-        "Stopping" => WorkspaceState::Stopping,
-        _ => WorkspaceState::Error,
+/// Check multipass VM state; `None` when multipass cannot tell.
+async fn check_multipass_status(mp: &impl InstanceInspector) -> Option<WorkspaceState> {
+    let info = mp.instance_info().await.ok()?;
+    Some(match info.state {
+        InstanceState::NotFound => WorkspaceState::NotFound,
+        InstanceState::Running => WorkspaceState::Running,
+        InstanceState::Stopped => WorkspaceState::Stopped,
+        InstanceState::Starting => WorkspaceState::Starting,
+        InstanceState::Stopping => WorkspaceState::Stopping,
+        InstanceState::Unknown => WorkspaceState::Error,
     })
 }
 
@@ -140,22 +174,19 @@ struct ContainerEntry {
     health: Option<String>,
 }
 
-/// Gather uptime and container info in a single remote call.
+/// Gather uptime and container info in a single remote call; `None` when
+/// the query script could not be run.
 async fn gather_remote_info(
     mp: &impl ShellExecutor,
-) -> (Option<u64>, HashMap<String, ContainerInfo>) {
+) -> Option<(Option<u64>, HashMap<String, ContainerInfo>)> {
     let mut containers = HashMap::new();
     let mut uptime = None;
 
     // Call the query script inside the VM to avoid Multipass Windows pipe issues.
     // If this fails, the script may not be deployed in the VM (check config tarball).
-    let output = mp.exec(&[QUERY_SCRIPT, "status"]).await;
-
-    let Ok(o) = output else {
-        return (uptime, containers);
-    };
+    let o = mp.exec(&[QUERY_SCRIPT, "status"]).await.ok()?;
     if !o.status.success() {
-        return (uptime, containers);
+        return None;
     }
 
     // Parse the consolidated JSON response.
@@ -175,7 +206,7 @@ async fn gather_remote_info(
         }
     }
 
-    (uptime, containers)
+    Some((uptime, containers))
 }
 
 /// Return an unknown/error workspace status.
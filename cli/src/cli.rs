@@ -2,6 +2,7 @@
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use std::io::IsTerminal;
 use std::process::ExitCode;
 
 use crate::app::AppContext;
@@ -31,10 +32,28 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub no_color: bool,
 
+    /// Disable spinners and progress bars without suppressing other output
+    #[arg(long, global = true)]
+    pub no_progress: bool,
+
     /// Skip interactive confirmation prompts (also set by `CI` or `POLIS_YES` env vars)
     #[arg(short = 'y', long, global = true)]
     pub yes: bool,
 
+    /// Disable all network calls (also set by `POLIS_OFFLINE=1`)
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Overall limit for each HTTP request, e.g. 120 or 10m [default: 300]
+    #[arg(
+        long,
+        global = true,
+        value_name = "DURATION",
+        env = "POLIS_HTTP_TIMEOUT",
+        value_parser = crate::domain::parse_duration
+    )]
+    pub timeout: Option<std::time::Duration>,
+
     #[command(subcommand)]
     pub command: Command,
 }
@@ -51,7 +70,7 @@ pub enum Command {
     Delete(commands::DeleteArgs),
 
     /// Show workspace status
-    Status,
+    Status(commands::status::StatusArgs),
 
     /// Show connection options
     Connect(commands::connect::ConnectArgs),
@@ -73,9 +92,15 @@ pub enum Command {
     /// Run a command in the workspace
     Exec(commands::exec::ExecArgs),
 
+    /// Show control-plane service logs
+    Logs(commands::logs::LogsArgs),
+
     /// Update Polis
     Update(commands::update::UpdateArgs),
 
+    /// Show release notes without updating
+    Changelog(commands::changelog::ChangelogArgs),
+
     /// Manage agents
     #[command(subcommand)]
     Agent(commands::agent::AgentCommand),
@@ -84,6 +109,9 @@ pub enum Command {
     #[command(subcommand)]
     Security(commands::security::SecurityCommand),
 
+    /// Launch a throwaway VM, smoke-test it, and delete it (requires --yes)
+    SelfTest(commands::self_test::SelfTestArgs),
+
     /// Show version
     Version,
 
@@ -109,7 +137,10 @@ impl Cli {
             no_color,
             quiet,
             json,
+            no_progress,
             yes,
+            offline,
+            timeout,
             command,
         } = self;
         let no_color = no_color || std::env::var("NO_COLOR").is_ok();
@@ -120,23 +151,44 @@ impl Cli {
                 no_color,
                 quiet,
                 json,
+                no_progress,
             },
-            behaviour: crate::app::BehaviourFlags { yes },
+            behaviour: crate::app::BehaviourFlags { yes, offline },
         })?;
 
+        // Commands whose stdout is consumed by other programs never get a notice.
+        let notify = !matches!(
+            command,
+            Command::Update(_)
+                | Command::Exec(_)
+                | Command::Logs(_)
+                | Command::SshProxy
+                | Command::Provision
+                | Command::ExtractHostKey
+        );
+
+        let github = crate::infra::update::GithubUpdateChecker::new(
+            app.offline,
+            crate::infra::http::HttpTimeouts::with_total_secs(timeout.map(|d| d.as_secs())),
+        );
+
         let exit_code = match command {
             Command::Start(args) => commands::start::run(&args, &app).await?,
             Command::Stop => commands::stop::run(&app).await?,
             Command::Delete(args) => commands::delete::run(&args, &app).await?,
-            Command::Status => commands::status::run(&app, &app.provisioner).await?,
+            Command::Status(args) => {
+                commands::status::run(&args, &app, &app.provisioner, &github).await?
+            }
             Command::Connect(args) => commands::connect::run(&app, args).await?,
             Command::Config(cmd) => commands::config::run(&app, cmd, &app.provisioner).await?,
             Command::Update(args) => {
-                commands::update::run(&args, &app, &crate::infra::update::GithubUpdateChecker)
-                    .await?
+                commands::update::run(&args, &app, &app.provisioner, &github).await?
             }
+            Command::Changelog(args) => commands::changelog::run(&args, &app, &github)?,
             Command::Doctor { verbose, fix } => commands::doctor::run(&app, verbose, fix).await?,
             Command::Exec(args) => commands::exec::run(&args, &app.provisioner).await?,
+            Command::Logs(args) => commands::logs::run(&args, &app.provisioner).await?,
+            Command::SelfTest(args) => commands::self_test::run(&args, &app).await?,
             Command::Version => commands::version::run(&app)?,
             Command::Agent(cmd) => commands::agent::run(cmd, &app).await?,
             Command::Security(cmd) => commands::security::run(cmd, &app, &app.provisioner).await?,
@@ -152,6 +204,47 @@ impl Cli {
             }
         };
 
+        if notify {
+            print_update_notice(&app);
+        }
+
         Ok(exit_code)
     }
 }
+
+/// Longest the once-a-day release check behind the update notice may take.
+/// It runs after the command has finished, so it must not hold up the exit
+/// on a slow network; `--timeout` does not apply to it.
+const UPDATE_NOTICE_TIMEOUT_SECS: u64 = 3;
+
+/// Print a one-line notice after the command when a newer release exists.
+///
+/// Skipped for `--json`, `--quiet`, `--offline`, non-terminal stdout,
+/// `update.check: false` in config, or when `POLIS_NO_UPDATE_CHECK` is set.
+/// A failed or timed-out check prints nothing.
+fn print_update_notice(app: &AppContext) {
+    let enabled = std::env::var_os("POLIS_NO_UPDATE_CHECK").is_none()
+        && crate::application::services::config_service::load_config(&app.config_store)
+            .is_ok_and(|config| config.update.check);
+    if !enabled
+        || app.offline
+        || app.is_json()
+        || app.output.quiet
+        || !std::io::stdout().is_terminal()
+    {
+        return;
+    }
+    let github = crate::infra::update::GithubUpdateChecker::new(
+        app.offline,
+        crate::infra::http::HttpTimeouts::with_total_secs(Some(UPDATE_NOTICE_TIMEOUT_SECS)),
+    );
+    if let Some(notice) = crate::application::services::update::cached_update_notice(
+        &github,
+        &app.local_fs,
+        &app.local_fs,
+        env!("CARGO_PKG_VERSION"),
+        chrono::Utc::now(),
+    ) {
+        app.output.info(&notice);
+    }
+}
@@ -1,16 +1,38 @@
 //! `polis agent` — manage AI agents.
 
+use std::process::ExitCode;
+
 use anyhow::Result;
-use clap::Subcommand;
+use clap::{Args, Subcommand};
 
+use super::agent_add::{self, AddArgs};
+use super::agent_archive;
+use super::agent_manifest::{self, MigrateArgs, SchemaArgs, ValidateArgs};
 use crate::app::AppContext;
-use crate::application::services::agent_crud;
+use crate::application::services::agent_archive::AgentSource;
+use crate::application::services::{agent_crud, agent_doctor, agent_logs};
+use crate::domain::agent::labels::{LabelSelector, matches_labels};
+use crate::output::columns::parse_agent_columns;
 
 /// Agent subcommands.
 #[derive(Subcommand)]
 pub enum AgentCommand {
     /// List available agents
-    List,
+    List {
+        #[command(flatten)]
+        format: super::ListFormatArgs,
+        /// Only show agents carrying this label (repeatable; all must match)
+        #[arg(long = "label", value_name = "KEY=VALUE")]
+        labels: Vec<LabelSelector>,
+    },
+    /// Install an agent from a local folder
+    Add(AddArgs),
+    /// Check an agent folder's agent.yaml without installing it
+    Validate(ValidateArgs),
+    /// Print the JSON Schema for agent.yaml (for editor validation)
+    Schema(SchemaArgs),
+    /// Convert a legacy flat agent definition to a polis.dev/v1 agent.yaml
+    Migrate(MigrateArgs),
     /// Create a new agent from an image
     #[clap(hide = true)]
     Create {
@@ -19,6 +41,25 @@ pub enum AgentCommand {
         /// Base image (e.g. mcp/base)
         image: String,
     },
+    /// Diagnose an installed agent
+    Doctor {
+        /// Name of the agent to check
+        name: String,
+    },
+    /// Package an installed agent into a portable .tar.gz
+    Export(agent_archive::ExportArgs),
+    /// Alias for `polis agent add --bundle <file>`
+    #[clap(hide = true)]
+    Import {
+        /// Tarball to import
+        file: std::path::PathBuf,
+    },
+    /// Package an agent folder into a signed bundle
+    Bundle(agent_archive::BundleArgs),
+    /// Verify a signed bundle and install the agent it carries
+    Install(agent_archive::InstallArgs),
+    /// Show the active agent's service logs
+    Logs(LogsArgs),
     /// Remove an agent
     Delete {
         /// Name of the agent to remove
@@ -26,15 +67,38 @@ pub enum AgentCommand {
     },
 }
 
+/// Arguments for `polis agent logs`.
+#[derive(Args)]
+pub struct LogsArgs {
+    /// Keep streaming new log entries until interrupted
+    #[arg(long, short)]
+    pub follow: bool,
+    /// Only show the last N lines
+    #[arg(long, value_name = "N")]
+    pub tail: Option<u32>,
+}
+
 /// Run an agent command.
 ///
 /// # Errors
 ///
 /// This function will return an error if the underlying operations fail.
-pub async fn run(cmd: AgentCommand, app: &AppContext) -> Result<std::process::ExitCode> {
+pub async fn run(cmd: AgentCommand, app: &AppContext) -> Result<ExitCode> {
     match cmd {
-        AgentCommand::List => list_agents(app).await,
+        AgentCommand::List { format, labels } => list_agents(app, &format, &labels).await,
+        AgentCommand::Add(args) => agent_add::run(app, &args).await,
+        AgentCommand::Validate(args) => agent_manifest::validate(app, &args),
+        AgentCommand::Schema(args) => agent_manifest::schema(app, &args),
+        AgentCommand::Migrate(args) => agent_manifest::migrate(app, &args),
         AgentCommand::Create { name, image } => create_agent(app, &name, &image),
+        AgentCommand::Doctor { name } => doctor_agent(app, &name).await,
+        AgentCommand::Export(args) => agent_archive::export(app, &args).await,
+        AgentCommand::Import { file } => {
+            agent_add::add(app, AgentSource::Archive(&file), &[]).await
+        }
+        AgentCommand::Bundle(args) => agent_archive::bundle(app, &args),
+        AgentCommand::Install(args) => agent_archive::install(app, &args).await,
+        AgentCommand::Logs(args) => show_logs(app, &args).await,
         AgentCommand::Delete { name } => delete_agent(app, &name).await,
     }
 }
@@ -42,16 +106,23 @@ pub async fn run(cmd: AgentCommand, app: &AppContext) -> Result<std::process::Ex
 /// # Errors
 ///
 /// This function will return an error if the underlying operations fail.
-async fn list_agents(app: &AppContext) -> Result<std::process::ExitCode> {
-    let agents = agent_crud::list_agents(&app.provisioner, &app.state_mgr).await?;
-    app.renderer().render_agent_list(&agents)?;
-    Ok(std::process::ExitCode::SUCCESS)
+async fn list_agents(
+    app: &AppContext,
+    args: &super::ListFormatArgs,
+    labels: &[LabelSelector],
+) -> Result<ExitCode> {
+    let columns = parse_agent_columns(args.columns.as_deref())?;
+    let mut agents = agent_crud::list_agents(&app.provisioner, &app.state_mgr).await?;
+    agents.retain(|a| matches_labels(&a.labels, labels));
+    app.renderer()
+        .render_agent_list(&agents, app.list_format(args.format), &columns)?;
+    Ok(ExitCode::SUCCESS)
 }
 
 /// # Errors
 ///
 /// This function will return an error if the underlying operations fail.
-fn create_agent(app: &AppContext, name: &str, image: &str) -> Result<std::process::ExitCode> {
+fn create_agent(app: &AppContext, name: &str, image: &str) -> Result<ExitCode> {
     app.output
         .info(&format!("Creating agent {name} from {image}..."));
     anyhow::bail!("create_agent is not implemented yet");
@@ -60,7 +131,31 @@ fn create_agent(app: &AppContext, name: &str, image: &str) -> Result<std::proces
 /// # Errors
 ///
 /// This function will return an error if the underlying operations fail.
-async fn delete_agent(app: &AppContext, name: &str) -> Result<std::process::ExitCode> {
+async fn doctor_agent(app: &AppContext, name: &str) -> Result<ExitCode> {
+    let diagnosis = agent_doctor::diagnose_agent(&app.provisioner, name).await?;
+    app.renderer().render_agent_doctor(&diagnosis)?;
+    Ok(if diagnosis.healthy() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// # Errors
+///
+/// This function will return an error if the underlying operations fail.
+async fn show_logs(app: &AppContext, args: &LogsArgs) -> Result<ExitCode> {
+    let status =
+        agent_logs::show_agent_logs(&app.provisioner, &app.state_mgr, args.follow, args.tail)
+            .await?;
+    let code = status.code().unwrap_or(1);
+    Ok(ExitCode::from(u8::try_from(code).unwrap_or(255)))
+}
+
+/// # Errors
+///
+/// This function will return an error if the underlying operations fail.
+async fn delete_agent(app: &AppContext, name: &str) -> Result<ExitCode> {
     app.output.info(&format!("Deleting agent {name}..."));
     agent_crud::remove_agent(
         &app.provisioner,
@@ -70,7 +165,7 @@ async fn delete_agent(app: &AppContext, name: &str) -> Result<std::process::Exit
     )
     .await?;
     app.output.success(&format!("Agent {name} deleted"));
-    Ok(std::process::ExitCode::SUCCESS)
+    Ok(ExitCode::SUCCESS)
 }
 
 #[cfg(test)]
@@ -84,8 +179,12 @@ mod tests {
                 no_color: true,
                 quiet: true,
                 json: false,
+                no_progress: false,
+            },
+            behaviour: crate::app::BehaviourFlags {
+                yes: true,
+                offline: false,
             },
-            behaviour: crate::app::BehaviourFlags { yes: true },
         })
         .expect("AppContext");
 
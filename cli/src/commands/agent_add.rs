@@ -0,0 +1,73 @@
+//! `polis agent add` — install an agent from a local folder or an exported
+//! archive.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::app::AppContext;
+use crate::application::services::agent_archive::{self, AgentSource};
+use crate::domain::agent::ports::PortOverride;
+
+/// Arguments for `polis agent add`.
+#[derive(Args)]
+pub struct AddArgs {
+    /// Folder containing agent.yaml
+    #[arg(long, required_unless_present = "bundle")]
+    pub path: Option<String>,
+    /// Archive made by `polis agent export` to install instead of a folder
+    #[arg(long, value_name = "FILE.tar.gz", conflicts_with = "path")]
+    pub bundle: Option<PathBuf>,
+    /// Publish a declared container port on another host port
+    /// (repeatable), e.g. --port 3000=9100
+    #[arg(long = "port", value_name = "CONTAINER=HOST")]
+    pub ports: Vec<PortOverride>,
+    /// Print the generated artifacts instead of installing (no VM needed)
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Run `polis agent add`.
+///
+/// # Errors
+///
+/// This function will return an error if the underlying operations fail.
+pub async fn run(app: &AppContext, args: &AddArgs) -> Result<ExitCode> {
+    let source = match (&args.bundle, &args.path) {
+        (Some(bundle), _) => AgentSource::Archive(bundle),
+        // clap requires --path whenever --bundle is absent.
+        (None, path) => AgentSource::Folder(path.as_deref().unwrap_or_default()),
+    };
+    if args.dry_run {
+        let artifacts = agent_archive::preview_add(&app.local_fs, source, &args.ports)?;
+        app.renderer().render_artifact_preview(&artifacts)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    add(app, source, &args.ports).await
+}
+
+/// Install the agent from `source` and tell the user how to start it.
+///
+/// # Errors
+///
+/// This function will return an error if the underlying operations fail.
+pub async fn add(
+    app: &AppContext,
+    source: AgentSource<'_>,
+    ports: &[PortOverride],
+) -> Result<ExitCode> {
+    let name = agent_archive::add_agent(
+        &app.provisioner,
+        &app.state_mgr,
+        &app.local_fs,
+        &app.terminal_reporter(),
+        source,
+        ports,
+    )
+    .await?;
+    app.output
+        .info(&format!("Start it: polis start --agent {name}"));
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,101 @@
+//! `polis agent export` / `bundle` / `install` — move agents
+//! between machines.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::app::AppContext;
+use crate::application::services;
+use crate::infra::agent_bundle::ZipsignBundleSigner;
+
+/// Arguments for `polis agent export`.
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Name of the agent to export
+    pub name: String,
+    /// Archive to write [default: <name>.tar.gz]
+    #[arg(long, short)]
+    pub out: Option<PathBuf>,
+    /// Overwrite the archive if it already exists
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Arguments for `polis agent bundle`.
+#[derive(Args)]
+pub struct BundleArgs {
+    /// Agent folder containing agent.yaml
+    pub dir: PathBuf,
+    /// ed25519 keypair file to sign with (64 bytes, zipsign format)
+    #[arg(long)]
+    pub key: PathBuf,
+    /// Bundle to write [default: <name>.tar.gz]
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+/// Arguments for `polis agent install`.
+#[derive(Args)]
+pub struct InstallArgs {
+    /// Signed bundle made by `polis agent bundle`
+    pub bundle: PathBuf,
+    /// Base64 ed25519 public key to accept signatures from (repeatable)
+    #[arg(long = "trusted-key", value_name = "KEY", required = true)]
+    pub trusted_keys: Vec<String>,
+}
+
+/// # Errors
+///
+/// This function will return an error if the underlying operations fail.
+pub async fn export(app: &AppContext, args: &ExportArgs) -> Result<std::process::ExitCode> {
+    let out = args
+        .out
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("{}.tar.gz", args.name)));
+    services::agent_archive::export_agent(
+        &app.provisioner,
+        &app.local_fs,
+        &app.terminal_reporter(),
+        &args.name,
+        &out,
+        args.force,
+    )
+    .await?;
+    Ok(std::process::ExitCode::SUCCESS)
+}
+
+/// # Errors
+///
+/// This function will return an error if the underlying operations fail.
+pub fn bundle(app: &AppContext, args: &BundleArgs) -> Result<std::process::ExitCode> {
+    services::agent_archive::bundle_agent(
+        &app.local_fs,
+        &ZipsignBundleSigner,
+        &app.terminal_reporter(),
+        &args.dir,
+        &args.key,
+        args.output.as_deref(),
+    )?;
+    Ok(std::process::ExitCode::SUCCESS)
+}
+
+/// # Errors
+///
+/// This function will return an error if the underlying operations fail.
+pub async fn install(app: &AppContext, args: &InstallArgs) -> Result<std::process::ExitCode> {
+    let name = services::agent_archive::install_bundle(
+        &app.provisioner,
+        &app.state_mgr,
+        &app.local_fs,
+        &ZipsignBundleSigner,
+        &app.terminal_reporter(),
+        &args.bundle,
+        &args.trusted_keys,
+    )
+    .await?;
+    app.output
+        .info(&format!("Start it: polis start --agent {name}"));
+    Ok(std::process::ExitCode::SUCCESS)
+}
@@ -0,0 +1,98 @@
+//! `polis agent validate` / `schema` / `migrate` — check `agent.yaml`,
+//! describe it for editors, and convert legacy definitions.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::app::AppContext;
+use crate::application::ports::LocalFs;
+use crate::application::services::agent_crud;
+use crate::domain::agent::schema::agent_manifest_schema;
+use crate::output::json::write_json;
+
+/// Arguments for `polis agent validate`.
+#[derive(Args)]
+pub struct ValidateArgs {
+    /// Folder containing agent.yaml
+    #[arg(long)]
+    pub path: String,
+    /// Explain each problem and show a corrected example
+    #[arg(long)]
+    pub explain: bool,
+}
+
+/// Arguments for `polis agent migrate`.
+#[derive(Args)]
+pub struct MigrateArgs {
+    /// Legacy flat agent definition
+    pub legacy: PathBuf,
+    /// Where to write the polis.dev/v1 manifest
+    #[arg(long, default_value = "agent.yaml")]
+    pub out: PathBuf,
+}
+
+/// Arguments for `polis agent schema`.
+#[derive(Args)]
+pub struct SchemaArgs {
+    /// Write the schema to this file instead of stdout
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+/// Check the `agent.yaml` in `--path`; fails the exit code on any violation.
+///
+/// # Errors
+///
+/// This function will return an error if the underlying operations fail.
+pub fn validate(app: &AppContext, args: &ValidateArgs) -> Result<ExitCode> {
+    let path = &args.path;
+    let violations = agent_crud::lint_agent_manifest(&app.local_fs, path)?;
+    app.renderer()
+        .render_manifest_violations(path, &violations, args.explain)?;
+    Ok(if violations.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Print the `agent.yaml` schema, or write it to `--output`.
+///
+/// # Errors
+///
+/// Returns an error if the schema cannot be written.
+pub fn schema(app: &AppContext, args: &SchemaArgs) -> Result<ExitCode> {
+    let schema = agent_manifest_schema();
+    let Some(path) = &args.output else {
+        app.renderer().render_agent_schema(&schema)?;
+        return Ok(ExitCode::SUCCESS);
+    };
+    let mut doc = Vec::new();
+    write_json(&mut doc, &schema)?;
+    app.local_fs
+        .write_bytes(path, &doc)
+        .with_context(|| format!("writing {}", path.display()))?;
+    app.output
+        .success(&format!("Wrote agent.yaml schema to {}", path.display()));
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Convert a legacy agent definition into a v1 `agent.yaml`.
+///
+/// # Errors
+///
+/// Returns an error if the definition cannot be migrated or written.
+pub fn migrate(app: &AppContext, args: &MigrateArgs) -> Result<ExitCode> {
+    let warnings = agent_crud::migrate_legacy_manifest(&app.local_fs, &args.legacy, &args.out)?;
+    for warning in &warnings {
+        app.output.warn(warning);
+    }
+    app.output.success(&format!(
+        "Wrote {} (check it with: polis agent validate --path <folder>)",
+        args.out.display()
+    ));
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,33 @@
+//! `polis changelog` — show release notes without updating.
+
+use anyhow::Result;
+use clap::Args;
+use std::process::ExitCode;
+
+use crate::app::AppContext;
+use crate::application::services::update::ReleaseLister;
+use crate::domain::update::select_release;
+
+/// Arguments for the changelog command.
+#[derive(Args)]
+pub struct ChangelogArgs {
+    /// Release to show, e.g. `v0.4.0` (default: latest)
+    #[arg(long)]
+    pub version: Option<String>,
+}
+
+/// Run `polis changelog [--version vX]`.
+///
+/// # Errors
+///
+/// Returns an error if the release list cannot be fetched or the requested
+/// version does not exist.
+pub fn run(
+    args: &ChangelogArgs,
+    app: &AppContext,
+    lister: &impl ReleaseLister,
+) -> Result<ExitCode> {
+    let release = select_release(lister.list_releases()?, args.version.as_deref())?;
+    app.renderer().render_changelog(&release)?;
+    Ok(ExitCode::SUCCESS)
+}
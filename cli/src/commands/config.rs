@@ -1,4 +1,4 @@
-//! `polis config` — show and set configuration values.
+//! `polis config` — show, get and set configuration values.
 
 use anyhow::Result;
 use std::process::ExitCode;
@@ -6,7 +6,6 @@ use std::process::ExitCode;
 use crate::app::AppContext;
 use crate::application::ports::{ConfigStore, InstanceInspector, ShellExecutor};
 use crate::application::services::config_service;
-use crate::domain::config::{validate_config_key, validate_config_value};
 
 use clap::Subcommand;
 
@@ -15,6 +14,11 @@ use clap::Subcommand;
 pub enum ConfigCommand {
     /// Show current configuration
     Show,
+    /// Print a single configuration value
+    Get {
+        /// Configuration key
+        key: String,
+    },
     /// Set configuration value
     Set {
         /// Configuration key
@@ -34,6 +38,7 @@ pub async fn run(
 ) -> Result<ExitCode> {
     match cmd {
         ConfigCommand::Show => show_config(app),
+        ConfigCommand::Get { key } => get_config(app, &key),
         ConfigCommand::Set { key, value } => set_config(app, &key, &value).await,
     }
 }
@@ -49,18 +54,16 @@ fn show_config(app: &AppContext) -> Result<ExitCode> {
 
 /// # Errors
 /// This function will return an error if the underlying operations fail.
-async fn set_config(app: &AppContext, key: &str, value: &str) -> Result<ExitCode> {
-    validate_config_key(key)?;
-    validate_config_value(key, value)?;
-
-    let mut config = config_service::load_config(&app.config_store)?;
-
-    match key {
-        "security.level" => config.security.level = value.to_string(),
-        _ => anyhow::bail!("Unknown setting: {key}"),
-    }
+fn get_config(app: &AppContext, key: &str) -> Result<ExitCode> {
+    let value = config_service::get_value(&app.config_store, key)?;
+    app.renderer().render_config_value(&value)?;
+    Ok(ExitCode::SUCCESS)
+}
 
-    config_service::save_config(&app.config_store, &config)?;
+/// # Errors
+/// This function will return an error if the underlying operations fail.
+async fn set_config(app: &AppContext, key: &str, value: &str) -> Result<ExitCode> {
+    config_service::set_value(&app.config_store, key, value)?;
 
     app.output.success(&format!("Set {key} = {value}"));
 
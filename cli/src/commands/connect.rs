@@ -8,7 +8,16 @@ use crate::application::ports::SshConfigurator;
 
 /// Arguments for the connect command.
 #[derive(Args)]
-pub struct ConnectArgs {}
+pub struct ConnectArgs {
+    /// Also add a `Host polis` block to ~/.ssh/config so `ssh polis` works
+    /// directly (the existing config is backed up first)
+    #[arg(long)]
+    pub write_ssh_config: bool,
+    /// Forward a host port to a workspace port over SSH, e.g. 8080:3000
+    /// (repeatable; runs until Ctrl-C)
+    #[arg(long = "forward", value_name = "LOCAL:REMOTE")]
+    pub forwards: Vec<crate::domain::ssh::PortForward>,
+}
 
 /// Run `polis connect`.
 ///
@@ -18,7 +27,7 @@ pub struct ConnectArgs {}
 /// # Errors
 ///
 /// Returns an error if SSH config setup fails or permissions are unsafe.
-pub async fn run(app: &AppContext, _args: ConnectArgs) -> Result<std::process::ExitCode> {
+pub async fn run(app: &AppContext, args: ConnectArgs) -> Result<std::process::ExitCode> {
     let ctx = &app.output;
     let mp = &app.provisioner;
     let already_configured = SshConfigurator::is_configured(&app.ssh).await?;
@@ -52,10 +61,44 @@ pub async fn run(app: &AppContext, _args: ConnectArgs) -> Result<std::process::E
     // Pin the workspace host key so StrictHostKeyChecking can verify it.
     crate::application::services::connect::pin_host_key(mp, &app.ssh).await;
 
-    show_connection_options(ctx, already_configured);
+    if args.write_ssh_config {
+        write_ssh_config(app).await?;
+    }
+
+    show_connection_options(ctx, already_configured, args.write_ssh_config);
+    if !args.forwards.is_empty() {
+        forward_ports(app, &args.forwards).await?;
+    }
     Ok(std::process::ExitCode::SUCCESS)
 }
 
+/// Hold the `--forward` tunnels open until ssh exits or Ctrl-C.
+///
+/// # Errors
+///
+/// Returns an error if ssh cannot be started or exits with an error.
+async fn forward_ports(
+    app: &AppContext,
+    forwards: &[crate::domain::ssh::PortForward],
+) -> Result<()> {
+    app.output.blank();
+    for f in forwards {
+        app.output.step(&format!(
+            "forwarding localhost:{} -> workspace:{}",
+            f.local, f.remote
+        ));
+    }
+    app.output.info("press Ctrl-C to stop");
+    tokio::select! {
+        status = SshConfigurator::forward_ports(&app.ssh, forwards) => {
+            let status = status?;
+            anyhow::ensure!(status.success(), "ssh port forwarding exited with {status}");
+        }
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    Ok(())
+}
+
 /// # Errors
 ///
 /// This function will return an error if the underlying operations fail.
@@ -73,7 +116,48 @@ async fn setup_ssh_config(app: &AppContext) -> Result<()> {
     Ok(())
 }
 
-fn show_connection_options(ctx: &crate::output::OutputContext, already_configured: bool) {
+/// Write the managed `Host polis` block pointing at the current VM address.
+///
+/// # Errors
+///
+/// This function will return an error if the VM address cannot be resolved
+/// or `~/.ssh/config` cannot be updated.
+async fn write_ssh_config(app: &AppContext) -> Result<()> {
+    let vm_ip =
+        crate::application::services::vm::lifecycle::resolve_vm_ip(&app.provisioner).await?;
+    let block = crate::domain::ssh::polis_host_block(&vm_ip, &proxy_command())?;
+    if let Some(backup) = SshConfigurator::write_host_block(&app.ssh, &block).await? {
+        app.output.info(&format!(
+            "previous SSH config saved to {}",
+            backup.display()
+        ));
+    }
+    app.output.success("added Host polis to ~/.ssh/config");
+    Ok(())
+}
+
+/// `ProxyCommand` for the `Host polis` block. Windows OpenSSH requires an
+/// absolute path to the executable.
+#[cfg(not(windows))]
+fn proxy_command() -> String {
+    "polis _ssh-proxy".to_string()
+}
+
+#[cfg(windows)]
+fn proxy_command() -> String {
+    format!(
+        "\"{}\" _ssh-proxy",
+        std::env::current_exe()
+            .unwrap_or_else(|_| std::path::PathBuf::from("polis.exe"))
+            .display()
+    )
+}
+
+fn show_connection_options(
+    ctx: &crate::output::OutputContext,
+    already_configured: bool,
+    host_block_written: bool,
+) {
     if already_configured {
         ctx.success("workspace ready to connect");
     } else {
@@ -81,6 +165,9 @@ fn show_connection_options(ctx: &crate::output::OutputContext, already_configure
     }
     ctx.blank();
     ctx.kv("SSH     ", "ssh workspace");
+    if host_block_written {
+        ctx.kv("        ", "ssh polis");
+    }
     ctx.kv("VS Code ", "code --remote ssh-remote+workspace /workspace");
     ctx.kv(
         "Cursor  ",
@@ -2,8 +2,10 @@ use anyhow::{Context, Result};
 use std::process::ExitCode;
 
 use crate::app::AppContext;
+use crate::application::services::vm::integrity::record_env_hash;
 use crate::application::services::workspace_doctor;
 use crate::application::services::workspace_repair;
+use crate::domain::health::{DoctorIssue, Remedy, doctor_issues};
 
 // ── Entry point ───────────────────────────────────────────────────────────────
 
@@ -19,6 +21,7 @@ pub async fn run(app: &AppContext, verbose: bool, fix: bool) -> Result<ExitCode>
     let ctx = &app.output;
     let mp = &app.provisioner;
     let reporter = app.terminal_reporter();
+    let version = env!("CARGO_PKG_VERSION");
 
     // 1. Diagnose
     let checks = workspace_doctor::run_doctor(
@@ -28,6 +31,8 @@ pub async fn run(app: &AppContext, verbose: bool, fix: bool) -> Result<ExitCode>
         &app.network_probe,
         &app.local_fs,
         &app.local_fs,
+        &app.state_mgr,
+        version,
     )
     .await?;
 
@@ -37,11 +42,28 @@ pub async fn run(app: &AppContext, verbose: bool, fix: bool) -> Result<ExitCode>
     app.renderer().render_doctor(&checks, &issues, verbose)?;
 
     // 3. Optional Repair
-    if fix && !issues.is_empty() {
+    let found = doctor_issues(&checks);
+    let fixable: Vec<&DoctorIssue> = found
+        .iter()
+        .filter(|i| !matches!(i.remedy, Remedy::Manual(_)))
+        .collect();
+    if fix && !fixable.is_empty() {
         let (assets_dir, _guard) = app.assets_dir().context("extracting embedded assets")?;
-        let version = env!("CARGO_PKG_VERSION");
+        let prefix = app.registry_prefix()?;
 
-        workspace_repair::run_repair(mp, &reporter, &assets_dir, version, false).await?;
+        if let Some(env_sha256) = workspace_repair::apply_fixes(
+            mp,
+            &app.ssh,
+            &reporter,
+            &found,
+            &assets_dir,
+            version,
+            &prefix,
+        )
+        .await?
+        {
+            record_env_hash(&app.state_mgr, &env_sha256).await?;
+        }
 
         // Re-probe after repair to confirm success
         if !ctx.quiet {
@@ -54,14 +76,33 @@ pub async fn run(app: &AppContext, verbose: bool, fix: bool) -> Result<ExitCode>
             &app.network_probe,
             &app.local_fs,
             &app.local_fs,
+            &app.state_mgr,
+            version,
         )
         .await?;
         let issues_after = crate::domain::health::collect_issues(&checks_after);
         app.renderer()
             .render_doctor(&checks_after, &issues_after, verbose)?;
-    } else if !issues.is_empty() && !ctx.quiet {
+        for issue in fixable {
+            if issues_after.contains(&issue.message) {
+                ctx.warn(&format!("still failing: {}", issue.message));
+            } else {
+                ctx.success(&format!("fixed: {}", issue.message));
+            }
+        }
+    } else if !fixable.is_empty() && !ctx.quiet {
         ctx.info("Run 'polis doctor --fix' to attempt automated repair.");
     }
+    if fix {
+        for issue in &found {
+            if let Remedy::Manual(hint) = issue.remedy {
+                ctx.warn(&format!(
+                    "manual action required: {} — {hint}",
+                    issue.message
+                ));
+            }
+        }
+    }
 
     Ok(ExitCode::SUCCESS)
 }
@@ -76,8 +117,12 @@ mod tests {
                 no_color: true,
                 quiet: true,
                 json: false,
+                no_progress: false,
+            },
+            behaviour: crate::app::BehaviourFlags {
+                yes: true,
+                offline: false,
             },
-            behaviour: crate::app::BehaviourFlags { yes: true },
         })
         .expect("AppContext");
 
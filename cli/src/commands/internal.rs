@@ -58,6 +58,7 @@ where
 /// Returns an error if the VM IP cannot be resolved or SSH cannot be spawned.
 pub async fn ssh_proxy(mp: &impl crate::application::ports::InstanceInspector) -> Result<ExitCode> {
     let vm_ip = crate::application::services::vm::lifecycle::resolve_vm_ip(mp).await?;
+    let vm_host = crate::domain::ssh::ssh_host(&vm_ip)?;
 
     let identity_key = dirs::home_dir()
         .ok_or_else(|| anyhow::anyhow!("cannot determine home directory"))?
@@ -86,7 +87,7 @@ pub async fn ssh_proxy(mp: &impl crate::application::ports::InstanceInspector) -
             "LogLevel=ERROR",
             "-o",
             "BatchMode=yes",
-            &format!("ubuntu@{vm_ip}"),
+            &format!("ubuntu@{vm_host}"),
             &docker_cmd,
         ])
         .stdin(std::process::Stdio::inherit())
@@ -106,17 +107,31 @@ pub async fn ssh_proxy(mp: &impl crate::application::ports::InstanceInspector) -
 
 /// Extracts the workspace SSH host key and prints it in `known_hosts` format.
 ///
-/// Output: `workspace ssh-ed25519 <key-material>`
+/// Output contract: exactly one line, `workspace ssh-ed25519 <base64>`, with
+/// no key comment. The line is written verbatim by `KnownHostsManager::update`.
 ///
 /// Invoked during provisioning via `polis _extract-host-key`.
 ///
 /// # Errors
 ///
-/// Returns an error if the host key cannot be extracted.
+/// Returns an error if the workspace is unreachable or the host key is invalid.
 #[allow(clippy::large_futures)]
 pub async fn extract_host_key(
     mp: &impl crate::application::ports::ShellExecutor,
 ) -> Result<ExitCode> {
+    let line = read_known_hosts_line(mp).await?;
+    println!("{line}");
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Reads the workspace host public key and formats it as a `known_hosts` line.
+///
+/// # Errors
+///
+/// Returns an error if the workspace is unreachable or the host key is invalid.
+async fn read_known_hosts_line(
+    mp: &impl crate::application::ports::ShellExecutor,
+) -> Result<String> {
     let output = mp
         .exec(&[
             "docker",
@@ -126,15 +141,14 @@ pub async fn extract_host_key(
             "/etc/ssh/ssh_host_ed25519_key.pub",
         ])
         .await
-        .context("failed to run multipass")?;
-    anyhow::ensure!(output.status.success(), "multipass exec failed");
-    let key = String::from_utf8(output.stdout)
-        .context("host key output is not valid UTF-8")?
-        .trim()
-        .to_string();
-    crate::domain::ssh::validate_host_key(&key)?;
-    println!("workspace {key}");
-    Ok(ExitCode::SUCCESS)
+        .context("workspace is not reachable — is it running? (polis status)")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "could not read workspace SSH host key: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    let raw = String::from_utf8(output.stdout).context("host key output is not valid UTF-8")?;
+    crate::domain::ssh::known_hosts_line(&raw)
 }
 
 // ---------------------------------------------------------------------------
@@ -189,6 +203,77 @@ mod tests {
     }
 }
 
+#[cfg(test)]
+mod extract_host_key_tests {
+    use std::process::Output;
+
+    use anyhow::Result;
+
+    use super::read_known_hosts_line;
+    use crate::application::ports::ShellExecutor;
+    use crate::application::services::vm::test_support::{
+        exit_status, impl_shell_executor_stubs, ok_output,
+    };
+    use crate::infra::ssh::KnownHostsManager;
+
+    const PUBKEY: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHostKeyMaterial root@workspace\n";
+
+    struct HostKeyStub(Option<Output>);
+
+    impl ShellExecutor for HostKeyStub {
+        async fn exec(&self, _: &[&str]) -> Result<Output> {
+            self.0
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("instance \"polis\" is not running"))
+        }
+        impl_shell_executor_stubs!(exec_with_stdin, exec_spawn, exec_status);
+    }
+
+    #[tokio::test]
+    async fn test_read_known_hosts_line_prints_host_type_and_key() {
+        let mp = HostKeyStub(Some(ok_output(PUBKEY.as_bytes())));
+        let line = read_known_hosts_line(&mp).await.expect("line");
+        assert_eq!(
+            line,
+            "workspace ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHostKeyMaterial"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_known_hosts_line_round_trips_through_known_hosts_manager() {
+        let mp = HostKeyStub(Some(ok_output(PUBKEY.as_bytes())));
+        let line = read_known_hosts_line(&mp).await.expect("line");
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let path = dir.path().join("known_hosts");
+        KnownHostsManager::with_path(path.clone())
+            .update(&line)
+            .expect("update");
+        let written = std::fs::read_to_string(&path).expect("read known_hosts");
+        assert_eq!(written, line);
+        let fields: Vec<&str> = written.split(' ').collect();
+        assert_eq!(fields.len(), 3, "host, key type, base64: {written:?}");
+    }
+
+    #[tokio::test]
+    async fn test_read_known_hosts_line_unreachable_vm_errors_clearly() {
+        let err = read_known_hosts_line(&HostKeyStub(None))
+            .await
+            .expect_err("unreachable VM");
+        assert!(err.to_string().contains("not reachable"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_read_known_hosts_line_missing_key_errors() {
+        let mp = HostKeyStub(Some(Output {
+            status: exit_status(1),
+            stdout: Vec::new(),
+            stderr: b"No such file or directory".to_vec(),
+        }));
+        let err = read_known_hosts_line(&mp).await.expect_err("missing key");
+        assert!(err.to_string().contains("No such file"), "{err}");
+    }
+}
+
 #[cfg(test)]
 mod preservation_tests {
     use super::bridge_io;
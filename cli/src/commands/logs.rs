@@ -0,0 +1,43 @@
+//! `polis logs` — show control-plane service logs.
+
+use std::process::ExitCode;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::application::ports::ShellExecutor;
+use crate::domain::logs::{LogSince, compose_logs_args};
+
+/// Arguments for the logs command.
+#[derive(Args)]
+pub struct LogsArgs {
+    /// Only show logs of this service, e.g. gate (repeatable)
+    #[arg(long = "service", value_name = "NAME")]
+    pub services: Vec<String>,
+
+    /// Only show the last N lines per service
+    #[arg(long, value_name = "N")]
+    pub tail: Option<u32>,
+
+    /// Only show logs since an RFC 3339 timestamp or a duration ago
+    /// (e.g. 10m, 2h, 1d)
+    #[arg(long, value_name = "TIME")]
+    pub since: Option<LogSince>,
+}
+
+/// Print control-plane logs from `docker compose logs` inside the VM.
+///
+/// # Errors
+///
+/// Returns an error if the command cannot be spawned.
+pub async fn run(args: &LogsArgs, mp: &impl ShellExecutor) -> Result<ExitCode> {
+    let docker_args = compose_logs_args(&args.services, args.tail, args.since.as_ref());
+    let arg_refs: Vec<&str> = docker_args.iter().map(String::as_str).collect();
+    let status = mp
+        .exec_status(&arg_refs)
+        .await
+        .context("failed to read control-plane logs")?;
+
+    let code = status.code().unwrap_or(1);
+    Ok(ExitCode::from(u8::try_from(code).unwrap_or(255)))
+}
@@ -1,13 +1,19 @@
 //! Command implementations
 
 pub mod agent;
+pub mod agent_add;
+pub mod agent_archive;
+pub mod agent_manifest;
+pub mod changelog;
 pub mod config;
 pub mod connect;
 pub mod delete;
 pub mod doctor;
 pub mod exec;
 pub mod internal;
+pub mod logs;
 pub mod security;
+pub mod self_test;
 pub mod start;
 pub mod status;
 pub mod stop;
@@ -27,3 +33,15 @@ pub struct DeleteArgs {
     #[arg(short = 'y', long)]
     pub yes: bool,
 }
+
+/// Output format options shared by list-like commands.
+#[derive(Args, Default)]
+pub struct ListFormatArgs {
+    /// Output format (`--json` takes precedence)
+    #[arg(long, value_enum)]
+    pub format: Option<crate::output::ListFormat>,
+
+    /// Comma-separated columns to show, in order (table and CSV only)
+    #[arg(long, value_name = "COLS")]
+    pub columns: Option<String>,
+}
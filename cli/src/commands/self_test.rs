@@ -0,0 +1,56 @@
+//! `polis self-test` — launch a throwaway VM, smoke-test it, and delete it.
+
+use std::process::ExitCode;
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::app::AppContext;
+use crate::application::ports::SELF_TEST_INSTANCE;
+use crate::application::services::self_test::run_self_test;
+use crate::domain::workspace::VM_LAUNCH_TIMEOUT_SECS;
+use crate::output::OutputContext;
+use crate::output::reporter::TerminalReporter;
+
+/// Arguments for `polis self-test`.
+#[derive(Args)]
+pub struct SelfTestArgs {
+    /// Time to allow the VM to launch, e.g. 1800 or 30m [default: 15m]
+    #[arg(long, value_name = "DURATION", value_parser = crate::domain::parse_duration)]
+    pub launch_timeout: Option<std::time::Duration>,
+}
+
+/// Run `polis self-test`.
+///
+/// # Errors
+///
+/// Returns an error if `--yes` was not given or rendering fails. Phase
+/// failures are reported, not returned.
+pub async fn run(args: &SelfTestArgs, app: &AppContext) -> Result<ExitCode> {
+    anyhow::ensure!(
+        app.non_interactive,
+        "polis self-test launches and deletes a temporary VM ({SELF_TEST_INSTANCE}), \
+         which takes several minutes and a few GB of disk.\n\
+         Re-run with: polis self-test --yes"
+    );
+    let launch_timeout = args
+        .launch_timeout
+        .map_or(VM_LAUNCH_TIMEOUT_SECS, |d| d.as_secs());
+    // Progress lines would corrupt the JSON document on stdout.
+    let silent = OutputContext::new(true, true);
+    let ctx = if app.is_json() { &silent } else { &app.output };
+    let report = run_self_test(
+        &app.self_test_provisioner(),
+        &app.assets,
+        &app.local_fs,
+        &TerminalReporter::new(ctx),
+        launch_timeout,
+    )
+    .await;
+    app.renderer().render_self_test(&report)?;
+    Ok(if report.passed() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
@@ -7,6 +7,7 @@ use std::process::ExitCode;
 use crate::app::AppContext;
 use crate::application::services::workspace_start::{self as service, StartOutcome};
 use crate::output::OutputContext;
+use crate::output::reporter::TerminalReporter;
 use owo_colors::OwoColorize as _;
 
 /// Arguments for the start command.
@@ -19,6 +20,26 @@ pub struct StartArgs {
     /// Environment variables to pass to the agent (e.g. -e KEY=VAL)
     #[arg(short = 'e', long = "env")]
     pub envs: Vec<String>,
+
+    /// Re-run config transfer and certificate/secret generation on a stopped
+    /// workspace, even if its config is up to date. Existing certificates and
+    /// secrets are kept; only missing ones are generated.
+    #[arg(long, visible_alias = "force-config")]
+    pub reprovision: bool,
+
+    /// Print what would be done without creating, starting or changing
+    /// anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Time to allow a new workspace VM to launch, including image pulls
+    /// during cloud-init, e.g. 1800 or 30m [default: 15m]
+    #[arg(long, value_name = "DURATION", value_parser = crate::domain::parse_duration)]
+    pub launch_timeout: Option<std::time::Duration>,
+
+    /// Print how long each provisioning stage took
+    #[arg(long)]
+    pub timings: bool,
 }
 
 /// # Errors
@@ -26,14 +47,29 @@ pub struct StartArgs {
 /// This function will return an error if the underlying operations fail.
 /// Run `polis start`.
 pub async fn run(args: &StartArgs, app: &AppContext) -> Result<ExitCode> {
+    if args.dry_run {
+        let plan = service::plan_start(
+            &app.provisioner,
+            &app.state_mgr,
+            args.agent.as_deref(),
+            args.reprovision,
+            app.offline,
+        )
+        .await?;
+        app.renderer().render_start_plan(&plan)?;
+        return Ok(ExitCode::SUCCESS);
+    }
     let (assets_dir, _assets_guard) = app.assets_dir().context("extracting assets")?;
     let version = env!("CARGO_PKG_VERSION");
-    let reporter = app.terminal_reporter();
+    let registry_prefix = app.registry_prefix()?;
+    // Progress lines would corrupt the JSON document on stdout.
+    let silent = OutputContext::new(true, true);
+    let ctx = if app.is_json() { &silent } else { &app.output };
+    let reporter = TerminalReporter::new(ctx);
     if args.agent.is_some() {
-        app.output
-            .info("Starting workspace. Agent initialization may take several minutes depending on the selected agent.");
+        ctx.info("Starting workspace. Agent initialization may take several minutes depending on the selected agent.");
     } else {
-        app.output.info("Starting workspace.");
+        ctx.info("Starting workspace.");
     }
 
     let opts = crate::application::services::workspace_start::StartOptions {
@@ -42,6 +78,14 @@ pub async fn run(args: &StartArgs, app: &AppContext) -> Result<ExitCode> {
         envs: args.envs.clone(),
         assets_dir: &assets_dir,
         version,
+        registry_prefix: &registry_prefix,
+        reprovision: args.reprovision,
+        offline: app.offline,
+        launch_timeout_secs: args
+            .launch_timeout
+            .map_or(crate::domain::workspace::VM_LAUNCH_TIMEOUT_SECS, |d| {
+                d.as_secs()
+            }),
     };
     let outcome = service::start_workspace(
         &app.provisioner,
@@ -56,12 +100,15 @@ pub async fn run(args: &StartArgs, app: &AppContext) -> Result<ExitCode> {
 
     match outcome {
         StartOutcome::AlreadyRunning { agent, .. } => {
-            print_already_running_message(agent.as_deref(), &app.output);
+            print_already_running_message(agent.as_deref(), ctx);
         }
         StartOutcome::Created { onboarding, .. } | StartOutcome::Restarted { onboarding, .. } => {
-            render_onboarding_steps(&app.output, &onboarding);
+            render_onboarding_steps(ctx, &onboarding);
         }
     }
+    if args.timings {
+        app.renderer().render_start_timings(&reporter.timings())?;
+    }
 
     Ok(ExitCode::SUCCESS)
 }
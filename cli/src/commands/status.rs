@@ -4,10 +4,43 @@
 //! then renders the result via `app.renderer()`.
 
 use anyhow::Result;
+use clap::Args;
 
 use crate::app::AppContext;
 use crate::application::ports::{InstanceInspector, ShellExecutor};
-use crate::application::services::workspace_status::gather_status;
+use crate::application::services::update::{UpdateChecker, status_updates};
+use crate::application::services::vm::integrity::compute_config_hash;
+use crate::application::services::workspace_status::{docker_missing_hint, gather_status};
+
+/// Arguments for the status command.
+#[derive(Args, Default)]
+pub struct StatusArgs {
+    /// Also report whether a newer CLI or newer containers are available
+    /// (uses the daily cached update check; never queries with --offline)
+    #[arg(long)]
+    pub check_updates: bool,
+    /// Keep refreshing until interrupted (one JSON object per line with --json)
+    #[arg(long)]
+    pub watch: bool,
+    /// Time between refreshes with --watch, e.g. 5 or 30s (1s to 1h)
+    #[arg(
+        long,
+        value_name = "DURATION",
+        default_value = "2s",
+        requires = "watch",
+        value_parser = parse_interval
+    )]
+    pub interval: std::time::Duration,
+}
+
+fn parse_interval(s: &str) -> Result<std::time::Duration, String> {
+    use std::time::Duration;
+    crate::domain::duration::parse_duration_within(
+        s,
+        Duration::from_secs(1),
+        Duration::from_secs(3_600),
+    )
+}
 
 /// Run the status command.
 ///
@@ -15,21 +48,94 @@ use crate::application::services::workspace_status::gather_status;
 ///
 /// Returns an error if JSON serialization fails.
 pub async fn run(
+    args: &StatusArgs,
     app: &AppContext,
     mp: &(impl InstanceInspector + ShellExecutor),
+    checker: &impl UpdateChecker,
 ) -> Result<std::process::ExitCode> {
+    let expected = expected_config_hash(app);
+    let expected = expected.as_deref();
+    if args.watch {
+        // Ctrl-C is how a watch ends, so it is a clean exit here.
+        tokio::select! {
+            result = watch(args, app, mp, checker, expected) => result?,
+            _ = tokio::signal::ctrl_c() => {}
+        }
+        return Ok(std::process::ExitCode::SUCCESS);
+    }
+
     let pb = if app.mode == crate::app::OutputMode::Human && app.output.show_progress() {
         Some(crate::output::progress::spinner("gathering status..."))
     } else {
         None
     };
-
-    let output = gather_status(mp).await;
-
+    let output = collect(args, app, mp, checker, expected).await;
     if let Some(pb) = pb {
         pb.finish_and_clear();
     }
 
     app.renderer().render_status(&output)?;
+    if output.workspace.status == polis_common::types::WorkspaceState::Error
+        && app.mode == crate::app::OutputMode::Human
+        && let Some(hint) = docker_missing_hint(mp).await
+    {
+        app.output.warn(&hint);
+    }
     Ok(std::process::ExitCode::SUCCESS)
 }
+
+/// Re-render the status every `--interval`; returns only on error.
+async fn watch(
+    args: &StatusArgs,
+    app: &AppContext,
+    mp: &(impl InstanceInspector + ShellExecutor),
+    checker: &impl UpdateChecker,
+    expected: Option<&str>,
+) -> Result<()> {
+    let mut ticker = tokio::time::interval(args.interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let output = collect(args, app, mp, checker, expected).await;
+        app.renderer().render_status_refresh(&output)?;
+    }
+}
+
+async fn collect(
+    args: &StatusArgs,
+    app: &AppContext,
+    mp: &(impl InstanceInspector + ShellExecutor),
+    checker: &impl UpdateChecker,
+    expected: Option<&str>,
+) -> polis_common::types::StatusOutput {
+    let mut output = gather_status(mp, expected).await;
+    if args.check_updates {
+        output.updates = Some(
+            status_updates(
+                checker,
+                &app.local_fs,
+                &app.local_fs,
+                mp,
+                env!("CARGO_PKG_VERSION"),
+                app.offline,
+                chrono::Utc::now(),
+            )
+            .await,
+        );
+    }
+    output
+}
+
+/// The config hash `polis start` would deploy, or `None` if it cannot be
+/// computed (status then omits the config state).
+fn expected_config_hash(app: &AppContext) -> Option<String> {
+    let (assets_dir, _guard) = app.assets_dir().ok()?;
+    let registry_prefix = app.registry_prefix().ok()?;
+    compute_config_hash(
+        &app.local_fs,
+        &assets_dir,
+        env!("CARGO_PKG_VERSION"),
+        &registry_prefix,
+    )
+    .ok()
+}
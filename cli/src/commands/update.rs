@@ -4,10 +4,15 @@ use anyhow::{Context, Result};
 use clap::Args;
 
 use crate::app::AppContext;
+use crate::application::ports::{FileTransfer, InstanceInspector, ShellExecutor};
 use crate::application::services::update::{
     UpdateChecker, UpdateInfo, UpdateVmConfigOutcome, update_vm_config,
 };
+use crate::application::services::vm::integrity::record_env_hash;
 use crate::application::services::workspace_stop::is_vm_running;
+use crate::domain::update::{ContainerSort, ContainerUpdate, UpdateReport, sort_containers};
+use crate::output::reporter::TerminalReporter;
+use crate::output::{HumanRenderer, OutputContext};
 
 /// Arguments for the update command.
 #[derive(Args)]
@@ -15,6 +20,10 @@ pub struct UpdateArgs {
     /// Check for updates without applying them
     #[arg(long)]
     pub check: bool,
+
+    /// Order of the per-service table: `name` or `status` (updates first)
+    #[arg(long, value_name = "name|status")]
+    pub sort: Option<ContainerSort>,
 }
 
 // Embedded ed25519 public key (base64) for verifying signed CLI release archives.
@@ -27,8 +36,12 @@ pub struct UpdateArgs {
 
 /// Run `polis update [--check]`.
 /// Checks GitHub for a newer release, verifies its signature, prompts the user,
-/// then downloads and replaces the current binary. If the VM is running, also
-/// updates the VM config.
+/// then downloads and replaces the current binary. If the VM behind `mp` is
+/// running, also updates the VM config.
+///
+/// With `--json`, progress output is suppressed and a single
+/// [`UpdateReport`] is printed at the end. Prompts cannot be answered in that
+/// mode, so applying an update requires `--yes`; `--check` does not prompt.
 /// # Errors
 /// Returns an error if the version check, signature verification, download, or
 /// user prompt fails.
@@ -36,21 +49,21 @@ pub struct UpdateArgs {
 pub async fn run(
     args: &UpdateArgs,
     app: &AppContext,
+    mp: &(impl InstanceInspector + ShellExecutor + FileTransfer),
     checker: &impl UpdateChecker,
 ) -> Result<std::process::ExitCode> {
-    let ctx = &app.output;
-    let mp = &app.provisioner;
+    let silent = OutputContext::new(true, true);
+    let ctx = if app.is_json() { &silent } else { &app.output };
     let current = env!("CARGO_PKG_VERSION");
 
-    if !ctx.quiet {
-        ctx.info("Checking for updates...");
-    }
+    ctx.info("Checking for updates...");
 
     let cli_update = checker.check(current)?;
 
-    match &cli_update {
+    let latest = match &cli_update {
         UpdateInfo::UpToDate => {
             ctx.success(&format!("CLI v{current} (latest)"));
+            current.to_string()
         }
         UpdateInfo::Available {
             version,
@@ -58,32 +71,35 @@ pub async fn run(
             ..
         } => {
             ctx.info(&format!("CLI v{current} → v{version} available"));
-            if !release_notes.is_empty() && !ctx.quiet {
-                println!("  Changes in v{version}:");
-                for note in release_notes {
-                    println!("    • {note}");
-                }
-            }
+            HumanRenderer::new(ctx).render_release_notes(version, release_notes);
+            version.clone()
         }
-    }
+    };
+    let mut report = UpdateReport::new(current, &latest);
 
     if args.check {
         ctx.info("Run 'polis update' to apply the update.");
+        app.renderer().render_update(&report)?;
         return Ok(std::process::ExitCode::SUCCESS);
     }
 
+    anyhow::ensure!(
+        !app.is_json() || app.non_interactive,
+        "--json cannot answer the update prompts. Re-run with: polis update --json --yes"
+    );
+
     if matches!(cli_update, UpdateInfo::Available { .. }) {
-        apply_cli_update(app, checker, cli_update)?;
+        report.cli.updated = apply_cli_update(app, ctx, checker, cli_update)?;
     }
 
     // After CLI self-update, update VM config if the VM is running
     if is_vm_running(mp).await? {
-        if !ctx.quiet {
-            ctx.info("Updating VM config...");
-        }
-        update_config(app).await?;
+        ctx.info("Updating VM config...");
+        report.containers = update_config(app, mp, ctx).await?;
+        sort_containers(&mut report.containers, args.sort);
     }
 
+    app.renderer().render_update(&report)?;
     Ok(std::process::ExitCode::SUCCESS)
 }
 
@@ -91,58 +107,57 @@ pub async fn run(
 /// Extracts embedded assets, computes the SHA256 of the new config tarball,
 /// and compares it against the hash stored in the VM. If they differ, stops
 /// services, transfers the new config, pulls images, verifies digests,
-/// restarts services, and writes the new hash.
+/// restarts services, and writes the new hash. Returns per-service results.
 /// # Errors
 /// Returns an error if any step of the update cycle fails.
-pub async fn update_config(app: &AppContext) -> Result<()> {
-    let ctx = &app.output;
+pub async fn update_config(
+    app: &AppContext,
+    mp: &(impl InstanceInspector + ShellExecutor + FileTransfer),
+    ctx: &OutputContext,
+) -> Result<Vec<ContainerUpdate>> {
     let (assets_dir, _guard) = app.assets_dir().context("extracting embedded assets")?;
+    let reporter = TerminalReporter::new(ctx);
 
-    let version = env!("CARGO_PKG_VERSION");
-    let reporter = app.terminal_reporter();
-    let hasher = &crate::infra::fs::LocalFs;
-
-    match update_vm_config(
-        &app.provisioner,
+    let outcome = update_vm_config(
+        mp,
         &app.assets,
-        hasher,
+        &crate::infra::fs::LocalFs,
         &reporter,
         &assets_dir,
-        version,
+        env!("CARGO_PKG_VERSION"),
+        &app.registry_prefix()?,
     )
-    .await?
-    {
-        UpdateVmConfigOutcome::UpToDate => {
-            ctx.success("Config is up to date");
-        }
-        UpdateVmConfigOutcome::Updated => {
+    .await?;
+    match &outcome {
+        UpdateVmConfigOutcome::UpToDate { .. } => ctx.success("Config is up to date"),
+        UpdateVmConfigOutcome::Updated { env_sha256, .. } => {
+            record_env_hash(&app.state_mgr, env_sha256).await?;
             ctx.success("Config updated successfully");
         }
     }
-
-    Ok(())
+    Ok(outcome.into_containers())
 }
 
+/// Verify, confirm, and install an available CLI update. Returns whether the
+/// binary was replaced.
 /// # Errors
 /// This function will return an error if the underlying operations fail.
 fn apply_cli_update(
     app: &AppContext,
+    ctx: &OutputContext,
     checker: &impl UpdateChecker,
     cli_update: UpdateInfo,
-) -> Result<()> {
-    let ctx = &app.output;
+) -> Result<bool> {
     let UpdateInfo::Available {
         version,
         download_url,
         ..
     } = cli_update
     else {
-        return Ok(());
+        return Ok(false);
     };
 
-    if !ctx.quiet {
-        ctx.info("Verifying checksum...");
-    }
+    ctx.info("Verifying checksum...");
     let sig = checker
         .verify_signature(&download_url)
         .context("checksum verification failed")?;
@@ -155,14 +170,12 @@ fn apply_cli_update(
         .context("reading confirmation")?;
 
     if confirmed {
-        if !ctx.quiet {
-            ctx.info("Downloading...");
-        }
+        ctx.info("Downloading...");
         checker.perform_update(&version).context("update failed")?;
         ctx.success(&format!("CLI updated to v{version}"));
         ctx.info("Restart your terminal or run: exec polis");
     }
-    Ok(())
+    Ok(confirmed)
 }
 
 // ── Tests ─────────────────────────────────────────────────────────────────────
@@ -172,44 +185,144 @@ fn apply_cli_update(
 mod tests {
     use super::*;
     use crate::application::services::update::SignatureInfo;
+    use crate::application::services::vm::test_support::{
+        fail_output, impl_shell_executor_stubs, ok_output,
+    };
     use crate::domain::workspace::hex_encode;
 
+    /// VM double: reports running or not, and bails on anything else so a
+    /// test fails loudly if the config update is reached unexpectedly.
+    struct Vm {
+        running: bool,
+    }
+
+    impl Vm {
+        fn stopped() -> Self {
+            Self { running: false }
+        }
+    }
+
+    impl InstanceInspector for Vm {
+        async fn info(&self) -> anyhow::Result<std::process::Output> {
+            Ok(if self.running {
+                ok_output(br#"{"info":{"polis":{"state":"Running"}}}"#)
+            } else {
+                fail_output()
+            })
+        }
+        async fn version(&self) -> anyhow::Result<std::process::Output> {
+            anyhow::bail!("not expected")
+        }
+    }
+
+    impl ShellExecutor for Vm {
+        impl_shell_executor_stubs!(exec, exec_with_stdin, exec_spawn, exec_status);
+    }
+
+    impl FileTransfer for Vm {
+        async fn transfer(&self, _: &str, _: &str) -> anyhow::Result<std::process::Output> {
+            anyhow::bail!("not expected")
+        }
+        async fn transfer_recursive(
+            &self,
+            _: &str,
+            _: &str,
+        ) -> anyhow::Result<std::process::Output> {
+            anyhow::bail!("not expected")
+        }
+    }
+
+    /// Reports `9.9.9` as available and records what `perform_update` installs.
+    #[derive(Default)]
+    struct NewerRelease {
+        installed: std::cell::RefCell<Option<String>>,
+    }
+
+    impl UpdateChecker for NewerRelease {
+        fn check(&self, _current: &str) -> anyhow::Result<UpdateInfo> {
+            Ok(UpdateInfo::Available {
+                version: "9.9.9".to_string(),
+                release_notes: vec![],
+                download_url: "https://example.com/polis.tar.gz".to_string(),
+            })
+        }
+        fn verify_signature(&self, _url: &str) -> anyhow::Result<SignatureInfo> {
+            Ok(SignatureInfo {
+                sha256: "ab".repeat(32),
+            })
+        }
+        fn perform_update(&self, version: &str) -> anyhow::Result<()> {
+            *self.installed.borrow_mut() = Some(version.to_string());
+            Ok(())
+        }
+    }
+
+    struct AlwaysUpToDate;
+    impl UpdateChecker for AlwaysUpToDate {
+        /// # Errors
+        /// This function will return an error if the underlying operations fail.
+        fn check(&self, _current: &str) -> anyhow::Result<UpdateInfo> {
+            Ok(UpdateInfo::UpToDate)
+        }
+        /// # Errors
+        /// This function will return an error if the underlying operations fail.
+        fn verify_signature(&self, _url: &str) -> anyhow::Result<SignatureInfo> {
+            anyhow::bail!("not expected: should not verify when up to date")
+        }
+        /// # Errors
+        /// This function will return an error if the underlying operations fail.
+        fn perform_update(&self, _version: &str) -> anyhow::Result<()> {
+            anyhow::bail!("not expected: should not update when up to date")
+        }
+    }
+
+    fn quiet_app() -> AppContext {
+        crate::app::AppContext::new(&crate::app::AppFlags {
+            output: crate::app::OutputFlags {
+                no_color: true,
+                quiet: true,
+                json: false,
+                no_progress: false,
+            },
+            behaviour: crate::app::BehaviourFlags {
+                yes: true,
+                offline: false,
+            },
+        })
+        .expect("AppContext")
+    }
+
+    fn apply_args() -> UpdateArgs {
+        UpdateArgs {
+            check: false,
+            sort: None,
+        }
+    }
+
     // -----------------------------------------------------------------------
     // run() via UpdateChecker trait mock — unit
     // -----------------------------------------------------------------------
 
     #[tokio::test]
     async fn test_run_up_to_date_returns_ok() {
-        struct AlwaysUpToDate;
-        impl UpdateChecker for AlwaysUpToDate {
-            /// # Errors
-            /// This function will return an error if the underlying operations fail.
-            fn check(&self, _current: &str) -> anyhow::Result<UpdateInfo> {
-                Ok(UpdateInfo::UpToDate)
-            }
-            /// # Errors
-            /// This function will return an error if the underlying operations fail.
-            fn verify_signature(&self, _url: &str) -> anyhow::Result<SignatureInfo> {
-                anyhow::bail!("not expected: should not verify when up to date")
-            }
-            /// # Errors
-            /// This function will return an error if the underlying operations fail.
-            fn perform_update(&self, _version: &str) -> anyhow::Result<()> {
-                anyhow::bail!("not expected: should not update when up to date")
-            }
-        }
-
-        let args = UpdateArgs { check: true };
+        let args = UpdateArgs {
+            check: true,
+            sort: None,
+        };
         let app = crate::app::AppContext::new(&crate::app::AppFlags {
             output: crate::app::OutputFlags {
                 no_color: true,
                 quiet: true,
                 json: false,
+                no_progress: false,
+            },
+            behaviour: crate::app::BehaviourFlags {
+                yes: true,
+                offline: false,
             },
-            behaviour: crate::app::BehaviourFlags { yes: true },
         })
         .expect("AppContext");
-        let result = run(&args, &app, &AlwaysUpToDate).await;
+        let result = run(&args, &app, &Vm::stopped(), &AlwaysUpToDate).await;
         assert!(result.is_ok());
     }
 
@@ -238,17 +351,24 @@ mod tests {
             }
         }
 
-        let args = UpdateArgs { check: false };
+        let args = UpdateArgs {
+            check: false,
+            sort: None,
+        };
         let app = crate::app::AppContext::new(&crate::app::AppFlags {
             output: crate::app::OutputFlags {
                 no_color: true,
                 quiet: true,
                 json: false,
+                no_progress: false,
+            },
+            behaviour: crate::app::BehaviourFlags {
+                yes: true,
+                offline: false,
             },
-            behaviour: crate::app::BehaviourFlags { yes: true },
         })
         .expect("AppContext");
-        let result = run(&args, &app, &BadSignature).await;
+        let result = run(&args, &app, &Vm::stopped(), &BadSignature).await;
         assert!(result.is_err());
         assert!(
             result.unwrap_err().to_string().contains("checksum"),
@@ -256,6 +376,33 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_run_confirmed_update_installs_release() {
+        let checker = NewerRelease::default();
+        let result = run(&apply_args(), &quiet_app(), &Vm::stopped(), &checker).await;
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(checker.installed.borrow().as_deref(), Some("9.9.9"));
+    }
+
+    #[tokio::test]
+    async fn test_run_vm_not_running_skips_config_update() {
+        // The stub bails on every exec, so reaching the config update fails.
+        let result = run(&apply_args(), &quiet_app(), &Vm::stopped(), &AlwaysUpToDate).await;
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[tokio::test]
+    async fn test_run_vm_running_updates_config() {
+        let vm = Vm { running: true };
+        let err = run(&apply_args(), &quiet_app(), &vm, &AlwaysUpToDate)
+            .await
+            .expect_err("config update reaches the VM stub");
+        assert!(
+            format!("{err:#}").contains("stopping services"),
+            "unexpected error: {err:#}"
+        );
+    }
+
     // -----------------------------------------------------------------------
     // hex_encode — unit
     // -----------------------------------------------------------------------
@@ -0,0 +1,192 @@
+//! Agent export archives (`polis agent export` / `import`) — pure, no I/O.
+//!
+//! An archive is a gzipped tar of `agents/<name>/`, including `.generated/`,
+//! rooted at the agent directory name. Import skips `.generated/` and
+//! regenerates it from the manifest.
+
+use std::path::{Component, Path};
+
+use anyhow::Result;
+
+use super::validate::is_valid_agent_name;
+use crate::domain::workspace::VM_ROOT;
+
+/// Directory holding generated artifacts, skipped on import.
+pub const GENERATED_DIR: &str = ".generated";
+
+/// Arguments for writing the archive of agent `name` to stdout inside the VM.
+///
+/// Entries are sorted and carry a fixed mtime and owner so exporting the
+/// same agent twice yields the same bytes.
+#[must_use]
+pub fn export_tar_args(name: &str) -> Vec<String> {
+    [
+        "tar",
+        "-C",
+        &format!("{VM_ROOT}/agents"),
+        "--sort=name",
+        "--mtime=@0",
+        "--owner=0",
+        "--group=0",
+        "--numeric-owner",
+        "-cf",
+        "-",
+        name,
+    ]
+    .map(str::to_string)
+    .to_vec()
+}
+
+/// Reject an archive entry that is absolute or climbs out with `..`.
+///
+/// # Errors
+///
+/// Returns an error naming the offending entry.
+pub fn check_entry_path(entry: &Path) -> Result<()> {
+    for component in entry.components() {
+        match component {
+            Component::RootDir | Component::Prefix(_) => {
+                anyhow::bail!("archive entry is an absolute path: {}", entry.display())
+            }
+            Component::ParentDir => {
+                anyhow::bail!("archive entry contains path traversal: {}", entry.display())
+            }
+            Component::CurDir | Component::Normal(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Whether `entry` lives under `<name>/.generated/`.
+#[must_use]
+pub fn is_generated_entry(entry: &Path) -> bool {
+    let mut parts = entry.components().filter(|c| *c != Component::CurDir);
+    parts.next();
+    matches!(parts.next(), Some(Component::Normal(dir)) if dir == GENERATED_DIR)
+}
+
+/// Determine the agent an archive holds from its entry paths.
+///
+/// Every entry must live under a single top-level directory named like an
+/// agent, and one entry must be `<name>/agent.yaml`. Entries under
+/// `<name>/.generated/` are allowed. Path traversal is checked separately by
+/// [`check_entry_path`].
+///
+/// # Errors
+///
+/// Returns an error describing the first rule the archive breaks.
+pub fn archive_agent_name<P: AsRef<Path>>(entries: &[P]) -> Result<String> {
+    let mut name: Option<String> = None;
+    let mut has_manifest = false;
+    for entry in entries {
+        let entry = entry.as_ref();
+        let mut parts = entry.components().filter(|c| *c != Component::CurDir);
+        let Some(Component::Normal(root)) = parts.next() else {
+            anyhow::bail!(
+                "archive entry '{}' is not inside an agent folder",
+                entry.display()
+            );
+        };
+        let root = root.to_string_lossy();
+        match &name {
+            Some(n) if *n != root => {
+                anyhow::bail!("archive holds more than one agent folder ('{n}', '{root}')")
+            }
+            Some(_) => {}
+            None => name = Some(root.into_owned()),
+        }
+        let rest: Vec<_> = parts.collect();
+        if let [Component::Normal(first)] = rest.as_slice()
+            && *first == "agent.yaml"
+        {
+            has_manifest = true;
+        }
+    }
+
+    let name = name.ok_or_else(|| anyhow::anyhow!("archive is empty"))?;
+    anyhow::ensure!(
+        is_valid_agent_name(&name),
+        "archive folder '{name}' is not a valid agent name"
+    );
+    anyhow::ensure!(has_manifest, "archive has no {name}/agent.yaml");
+    Ok(name)
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_tar_args_are_reproducible() {
+        assert_eq!(
+            export_tar_args("alpha"),
+            [
+                "tar",
+                "-C",
+                "/opt/polis/agents",
+                "--sort=name",
+                "--mtime=@0",
+                "--owner=0",
+                "--group=0",
+                "--numeric-owner",
+                "-cf",
+                "-",
+                "alpha",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_archive_agent_name_accepts_single_agent_folder() {
+        let entries = ["alpha/", "alpha/agent.yaml", "./alpha/scripts/install.sh"];
+        assert_eq!(
+            archive_agent_name(&entries).expect("valid archive"),
+            "alpha"
+        );
+    }
+
+    #[test]
+    fn test_archive_agent_name_rejects_mixed_roots_and_missing_manifest() {
+        let err = archive_agent_name(&["alpha/agent.yaml", "beta/agent.yaml"])
+            .expect_err("two folders")
+            .to_string();
+        assert!(err.contains("more than one"), "{err}");
+        let err = archive_agent_name(&["alpha/install.sh"])
+            .expect_err("no manifest")
+            .to_string();
+        assert!(err.contains("alpha/agent.yaml"), "{err}");
+        assert!(archive_agent_name::<&str>(&[]).is_err());
+    }
+
+    #[test]
+    fn test_archive_agent_name_rejects_bad_names() {
+        assert!(archive_agent_name(&["Alpha/agent.yaml"]).is_err());
+        assert!(archive_agent_name(&["agent.yaml"]).is_err());
+    }
+
+    #[test]
+    fn test_archive_agent_name_allows_generated_files() {
+        let entries = ["alpha/agent.yaml", "alpha/.generated/alpha.service"];
+        assert_eq!(archive_agent_name(&entries).expect("valid"), "alpha");
+        assert!(is_generated_entry(Path::new(
+            "alpha/.generated/alpha.service"
+        )));
+        assert!(is_generated_entry(Path::new("./alpha/.generated")));
+        assert!(!is_generated_entry(Path::new("alpha/scripts/.generated")));
+        assert!(!is_generated_entry(Path::new(".generated/agent.yaml")));
+    }
+
+    #[test]
+    fn test_check_entry_path_rejects_absolute_and_traversal() {
+        assert!(check_entry_path(Path::new("alpha/./scripts/install.sh")).is_ok());
+        let err = check_entry_path(Path::new("/etc/passwd"))
+            .expect_err("absolute")
+            .to_string();
+        assert!(err.contains("absolute"), "{err}");
+        let err = check_entry_path(Path::new("alpha/../../etc/passwd"))
+            .expect_err("traversal")
+            .to_string();
+        assert!(err.contains("path traversal"), "{err}");
+    }
+}
@@ -12,6 +12,8 @@
 use polis_common::agent::AgentManifest;
 use sha2::{Digest, Sha256};
 
+use crate::domain::agent::ports::{PORT_OVERRIDES_FILE, PortOverride, render_port_overrides};
+
 /// Generate `compose.agent.yaml` content — Docker Compose overlay with port
 /// mappings, volumes, healthcheck, and socat proxy sidecars.
 ///
@@ -241,6 +243,88 @@ pub fn service_hash(unit_content: &str) -> String {
     format!("{:x}\n", hasher.finalize())
 }
 
+/// One generated artifact, as previewed by `polis agent add --dry-run`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct GeneratedFile {
+    /// File name under `.generated/`, e.g. `compose.agent.yaml`.
+    pub file: String,
+    /// Full file contents.
+    pub content: String,
+}
+
+/// Every file written to `.generated/` for agent `name`: the compose
+/// overlay, systemd unit, unit hash and `.env`, plus the port-overrides file
+/// when `port_overrides` is non-empty so the next regeneration can re-apply
+/// them. `manifest` must already have the overrides applied.
+#[must_use]
+pub fn generated_files(
+    name: &str,
+    manifest: &AgentManifest,
+    env_content: String,
+    port_overrides: &[PortOverride],
+) -> Vec<GeneratedFile> {
+    let unit = systemd_unit(manifest);
+    let hash = service_hash(&unit);
+    let mut files = vec![
+        GeneratedFile {
+            file: "compose.agent.yaml".to_string(),
+            content: compose_overlay(manifest),
+        },
+        GeneratedFile {
+            file: format!("{name}.service"),
+            content: unit,
+        },
+        GeneratedFile {
+            file: format!("{name}.service.sha256"),
+            content: hash,
+        },
+        GeneratedFile {
+            file: format!("{name}.env"),
+            content: env_content,
+        },
+    ];
+    if !port_overrides.is_empty() {
+        files.push(GeneratedFile {
+            file: PORT_OVERRIDES_FILE.to_string(),
+            content: render_port_overrides(port_overrides),
+        });
+    }
+    files
+}
+
+/// Whether `recorded` (the `.service.sha256` contents) is the hash of
+/// `unit`, computed the same way as [`service_hash`].
+#[must_use]
+pub fn service_hash_matches(unit: &str, recorded: &str) -> bool {
+    let recorded = recorded.trim();
+    !recorded.is_empty() && service_hash(unit).trim() == recorded
+}
+
+/// State of an agent's deployed `<name>.service` relative to its recorded
+/// `<name>.service.sha256`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitIntegrity {
+    /// Neither file exists yet (first start after install).
+    NotDeployed,
+    /// The unit hashes to the recorded value.
+    Intact,
+    /// The unit or its hash was changed, or one of them removed.
+    Modified,
+}
+
+/// Decide [`UnitIntegrity`] from the deployed files' contents, `None`
+/// meaning the file does not exist.
+#[must_use]
+pub fn unit_integrity(unit: Option<&str>, recorded: Option<&str>) -> UnitIntegrity {
+    match (unit, recorded) {
+        (None, None) => UnitIntegrity::NotDeployed,
+        (Some(unit), Some(recorded)) if service_hash_matches(unit, recorded) => {
+            UnitIntegrity::Intact
+        }
+        _ => UnitIntegrity::Modified,
+    }
+}
+
 /// Generate filtered env file content from declared requirements.
 ///
 /// Takes the full `.env` file content and the manifest's requirements,
@@ -273,3 +357,49 @@ pub fn filtered_env(env_content: &str, manifest: &AgentManifest) -> String {
         format!("{}\n", filtered_lines.join("\n"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNIT: &str = "[Unit]\nDescription=alpha\n[Service]\nExecStart=/usr/bin/alpha\n";
+
+    #[test]
+    fn test_service_hash_matches_generated_hash() {
+        assert!(service_hash_matches(UNIT, &service_hash(UNIT)));
+        assert!(service_hash_matches(
+            UNIT,
+            &format!("  {}  ", service_hash(UNIT).trim())
+        ));
+    }
+
+    #[test]
+    fn test_service_hash_mismatch_detects_tampering() {
+        let tampered = UNIT.replace("/usr/bin/alpha", "/tmp/evil");
+        assert!(!service_hash_matches(&tampered, &service_hash(UNIT)));
+        assert!(!service_hash_matches(UNIT, ""));
+        assert!(!service_hash_matches(UNIT, "not-a-hash\n"));
+    }
+
+    #[test]
+    fn test_unit_integrity_intact_when_hash_matches() {
+        let hash = service_hash(UNIT);
+        assert_eq!(
+            unit_integrity(Some(UNIT), Some(&hash)),
+            UnitIntegrity::Intact
+        );
+        assert_eq!(unit_integrity(None, None), UnitIntegrity::NotDeployed);
+    }
+
+    #[test]
+    fn test_unit_integrity_modified_on_mismatch_or_missing_file() {
+        let hash = service_hash(UNIT);
+        let tampered = format!("{UNIT}ExecStartPre=/tmp/evil\n");
+        assert_eq!(
+            unit_integrity(Some(&tampered), Some(&hash)),
+            UnitIntegrity::Modified
+        );
+        assert_eq!(unit_integrity(Some(UNIT), None), UnitIntegrity::Modified);
+        assert_eq!(unit_integrity(None, Some(&hash)), UnitIntegrity::Modified);
+    }
+}
@@ -0,0 +1,133 @@
+//! Per-agent diagnostics for `polis agent doctor` — pure, no I/O.
+//!
+//! The application service gathers raw command output from the VM; the
+//! functions here turn it into pass/fail checks.
+
+use serde::{Deserialize, Serialize};
+
+/// How many of the most recent health-probe results are reported.
+pub const HEALTH_PROBE_LIMIT: usize = 5;
+
+/// A single pass/fail diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AgentCheck {
+    /// Short check identifier, e.g. `service_integrity`.
+    pub name: &'static str,
+    /// Whether the check passed.
+    pub ok: bool,
+    /// Human-readable result.
+    pub detail: String,
+}
+
+/// One entry of the workspace container's Docker health log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthProbe {
+    /// When the probe started (RFC 3339, as reported by Docker).
+    #[serde(rename(deserialize = "Start"))]
+    pub start: String,
+    /// Exit code of the probe command; `0` means healthy.
+    #[serde(rename(deserialize = "ExitCode"))]
+    pub exit_code: i64,
+    /// Captured probe output.
+    #[serde(rename(deserialize = "Output"), default)]
+    pub output: String,
+}
+
+/// Everything `polis agent doctor` reports for one agent.
+#[derive(Debug, Serialize)]
+pub struct AgentDiagnosis {
+    /// Agent name.
+    pub agent: String,
+    /// Checks in the order they ran.
+    pub checks: Vec<AgentCheck>,
+    /// Most recent health-probe results, oldest first.
+    pub health_probes: Vec<HealthProbe>,
+}
+
+impl AgentDiagnosis {
+    /// Whether every check passed.
+    #[must_use]
+    pub fn healthy(&self) -> bool {
+        self.checks.iter().all(|c| c.ok)
+    }
+}
+
+/// Files `polis agent add` generates under `agents/<name>/.generated/`.
+#[must_use]
+pub fn generated_artifacts(name: &str) -> [String; 4] {
+    [
+        "compose.agent.yaml".to_string(),
+        format!("{name}.service"),
+        format!("{name}.service.sha256"),
+        format!("{name}.env"),
+    ]
+}
+
+/// Generated artifacts absent from an `ls -1` listing of `.generated/`.
+#[must_use]
+pub fn missing_artifacts(name: &str, listing: &str) -> Vec<String> {
+    let present: Vec<&str> = listing.lines().map(str::trim).collect();
+    generated_artifacts(name)
+        .into_iter()
+        .filter(|f| !present.contains(&f.as_str()))
+        .collect()
+}
+
+/// Parse `docker inspect --format '{{json .State.Health}}'` output into the
+/// last `limit` probe results. Containers without a healthcheck report
+/// `null`, which yields no probes.
+///
+/// # Errors
+///
+/// Returns an error if the output is not valid health JSON.
+pub fn parse_health_log(json: &str, limit: usize) -> anyhow::Result<Vec<HealthProbe>> {
+    #[derive(Deserialize)]
+    struct Health {
+        #[serde(rename = "Log", default)]
+        log: Vec<HealthProbe>,
+    }
+
+    let health: Option<Health> = serde_json::from_str(json.trim())?;
+    let mut log = health.map(|h| h.log).unwrap_or_default();
+    let skip = log.len().saturating_sub(limit);
+    Ok(log.split_off(skip))
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_artifacts_lists_absent_files() {
+        let listing = "compose.agent.yaml\nalpha.service\nport-overrides\n";
+        assert_eq!(
+            missing_artifacts("alpha", listing),
+            ["alpha.service.sha256", "alpha.env"]
+        );
+        let complete = generated_artifacts("alpha").join("\n");
+        assert!(missing_artifacts("alpha", &complete).is_empty());
+    }
+
+    #[test]
+    fn test_parse_health_log_keeps_most_recent_probes() {
+        let json = r#"{"Status":"healthy","FailingStreak":0,"Log":[
+            {"Start":"t1","End":"t1","ExitCode":1,"Output":"down"},
+            {"Start":"t2","End":"t2","ExitCode":0,"Output":""},
+            {"Start":"t3","End":"t3","ExitCode":0,"Output":"ok"}]}"#;
+        let probes = parse_health_log(json, 2).expect("valid health JSON");
+        assert_eq!(probes.len(), 2);
+        assert_eq!(probes[0].start, "t2");
+        assert_eq!(probes[1].output, "ok");
+    }
+
+    #[test]
+    fn test_parse_health_log_null_yields_no_probes() {
+        assert!(
+            parse_health_log("null\n", HEALTH_PROBE_LIMIT)
+                .expect("null is valid")
+                .is_empty()
+        );
+        assert!(parse_health_log("not json", HEALTH_PROBE_LIMIT).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Agent `metadata.labels` — grammar checks and `--label` selectors.
+//!
+//! Pure functions, no I/O.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Label keys: lowercase alphanumeric with interior `-`, `_`, `.`, 1–63 chars.
+pub static LABEL_KEY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    #[allow(clippy::expect_used)]
+    Regex::new(r"^[a-z0-9]([a-z0-9._-]{0,61}[a-z0-9])?$").expect("valid regex")
+});
+
+/// Label values: alphanumeric with interior `-`, `_`, `.`, 1–63 chars.
+pub static LABEL_VALUE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    #[allow(clippy::expect_used)]
+    Regex::new(r"^[A-Za-z0-9]([A-Za-z0-9._-]{0,61}[A-Za-z0-9])?$").expect("valid regex")
+});
+
+/// Describe every label that breaks the grammar, one message per problem.
+#[must_use]
+pub fn label_errors(labels: &BTreeMap<String, String>) -> Vec<String> {
+    let mut errors = Vec::new();
+    for (key, value) in labels {
+        if !LABEL_KEY_RE.is_match(key) {
+            errors.push(format!(
+                "metadata.labels key '{key}' must be lowercase alphanumeric with '-', '_' or '.' (max 63 chars)"
+            ));
+        }
+        if !LABEL_VALUE_RE.is_match(value) {
+            errors.push(format!(
+                "metadata.labels value '{value}' for '{key}' must be alphanumeric with '-', '_' or '.' (max 63 chars)"
+            ));
+        }
+    }
+    errors
+}
+
+/// A `key=value` selector from `polis agent list --label`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelSelector {
+    pub key: String,
+    pub value: String,
+}
+
+impl FromStr for LabelSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("invalid label '{s}' (expected KEY=VALUE)"))?;
+        if !LABEL_KEY_RE.is_match(key) || !LABEL_VALUE_RE.is_match(value) {
+            return Err(format!("invalid label '{s}' (expected KEY=VALUE)"));
+        }
+        Ok(Self {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+/// Whether `labels` satisfies every selector (an empty list matches all).
+#[must_use]
+pub fn matches_labels(labels: &BTreeMap<String, String>, selectors: &[LabelSelector]) -> bool {
+    selectors
+        .iter()
+        .all(|s| labels.get(&s.key) == Some(&s.value))
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_label_errors_accepts_valid_labels() {
+        assert!(label_errors(&labels(&[("team", "payments"), ("env", "Prod-2")])).is_empty());
+    }
+
+    #[test]
+    fn test_label_errors_reports_bad_keys_and_values() {
+        let errors = label_errors(&labels(&[("Team", "payments"), ("env", "prod env")]));
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("'Team'"));
+        assert!(errors[1].contains("'prod env'"));
+    }
+
+    #[test]
+    fn test_label_selector_parses_key_value() {
+        let selector: LabelSelector = "team=payments".parse().expect("valid selector");
+        assert_eq!(selector.key, "team");
+        assert_eq!(selector.value, "payments");
+        assert!("team".parse::<LabelSelector>().is_err());
+        assert!("team=".parse::<LabelSelector>().is_err());
+    }
+
+    #[test]
+    fn test_matches_labels_requires_every_selector() {
+        let agent = labels(&[("team", "payments"), ("env", "prod")]);
+        let team: LabelSelector = "team=payments".parse().expect("valid");
+        let env_dev: LabelSelector = "env=dev".parse().expect("valid");
+        assert!(matches_labels(&agent, &[]));
+        assert!(matches_labels(&agent, std::slice::from_ref(&team)));
+        assert!(!matches_labels(&agent, &[team, env_dev]));
+        assert!(!matches_labels(
+            &BTreeMap::new(),
+            &["team=payments".parse().expect("valid")]
+        ));
+    }
+}
@@ -0,0 +1,398 @@
+//! Migration of legacy flat agent definitions to `polis.dev/v1` — pure, no
+//! I/O.
+//!
+//! The legacy format predates `apiVersion`: one flat mapping of scalar keys,
+//! for example:
+//!
+//! ```yaml
+//! name: my-agent
+//! display_name: My Agent
+//! version: 1.2.0
+//! command: /usr/bin/node dist/index.js
+//! port: 3000
+//! memory_limit: 4G
+//! env_one_of: ANTHROPIC_API_KEY, OPENAI_API_KEY
+//! ```
+//!
+//! List-valued keys (`env_one_of`, `env_optional`, `services`,
+//! `read_write_paths`) take either a YAML sequence or a comma-separated
+//! string.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Context, Result};
+use polis_common::agent::{
+    AgentHealth, AgentManifest, AgentMetadata, AgentPort, AgentRequirements, AgentResources,
+    AgentRuntime, AgentSecurity, AgentSpec,
+};
+use serde_yaml::Value;
+
+/// Health-check timings used when a legacy definition only names a command.
+const DEFAULT_HEALTH: (&str, &str, u32, &str) = ("30s", "10s", 3, "60s");
+
+/// A migrated manifest plus the legacy keys that had no v1 equivalent.
+#[derive(Debug)]
+pub struct Migration {
+    pub manifest: AgentManifest,
+    /// One message per legacy key that was dropped.
+    pub warnings: Vec<String>,
+}
+
+/// Convert a legacy flat agent definition into a v1 `AgentManifest`.
+///
+/// Missing optional fields get the same defaults as `agents/_template`:
+/// `install.sh`, workdir `/app`, user `polis`, version `0.1.0`. The result
+/// is not validated here; see `validate::manifest_violations`.
+///
+/// # Errors
+///
+/// Returns an error if `content` is not a flat mapping, already looks like a
+/// v1 manifest, or lacks `name` or `command`.
+pub fn migrate_legacy_manifest(content: &str) -> Result<Migration> {
+    let fields: BTreeMap<String, Value> =
+        serde_yaml::from_str(content).context("legacy definition must be a flat YAML mapping")?;
+    anyhow::ensure!(
+        !fields.contains_key("apiVersion"),
+        "already a polis.dev/v1 manifest; nothing to migrate"
+    );
+    let mut legacy = Legacy {
+        fields,
+        warnings: Vec::new(),
+    };
+
+    let name = legacy
+        .string("name")
+        .context("legacy definition has no 'name'")?;
+    let command = legacy
+        .string("command")
+        .context("legacy definition has no 'command'")?;
+    let metadata = AgentMetadata {
+        display_name: legacy
+            .string("display_name")
+            .unwrap_or_else(|| name.clone()),
+        version: legacy
+            .string("version")
+            .unwrap_or_else(|| "0.1.0".to_string()),
+        description: legacy.string("description").unwrap_or_default(),
+        author: legacy.string("author"),
+        license: legacy.string("license"),
+        provider: legacy.string("provider"),
+        capabilities: Vec::new(),
+        labels: BTreeMap::new(),
+        name,
+    };
+
+    let runtime = AgentRuntime {
+        command,
+        workdir: legacy
+            .string("workdir")
+            .unwrap_or_else(|| "/app".to_string()),
+        user: legacy.string("user").unwrap_or_else(|| "polis".to_string()),
+        env_file: legacy.string("env_file"),
+        env: HashMap::new(),
+    };
+
+    let spec = AgentSpec {
+        packaging: "script".to_string(),
+        install: legacy
+            .string("install")
+            .unwrap_or_else(|| "install.sh".to_string()),
+        runtime,
+        init: legacy.string("init"),
+        health: legacy.health(),
+        security: legacy.security(),
+        ports: legacy.ports(&metadata.name),
+        resources: legacy.resources(),
+        requirements: legacy.requirements(),
+        persistence: Vec::new(),
+        capabilities: None,
+        commands: None,
+        onboarding: Vec::new(),
+    };
+
+    let mut warnings = legacy.warnings;
+    warnings.extend(
+        legacy
+            .fields
+            .keys()
+            .map(|key| format!("legacy field '{key}' has no v1 equivalent and was dropped")),
+    );
+    Ok(Migration {
+        manifest: AgentManifest {
+            api_version: "polis.dev/v1".to_string(),
+            kind: "AgentPlugin".to_string(),
+            metadata,
+            spec,
+        },
+        warnings,
+    })
+}
+
+/// Render `manifest` as `agent.yaml`, leaving out unset optional fields and
+/// empty lists rather than writing them as `null` and `[]`.
+///
+/// # Errors
+///
+/// Returns an error if serialization fails.
+pub fn manifest_yaml(manifest: &AgentManifest) -> Result<String> {
+    let mut value = serde_yaml::to_value(manifest).context("serializing agent manifest")?;
+    prune_empty(&mut value);
+    serde_yaml::to_string(&value).context("serializing agent manifest")
+}
+
+fn prune_empty(value: &mut Value) {
+    if let Value::Mapping(map) = value {
+        for v in map.values_mut() {
+            prune_empty(v);
+        }
+        map.retain(|_, v| match v {
+            Value::Null => false,
+            Value::Sequence(s) => !s.is_empty(),
+            Value::Mapping(m) => !m.is_empty(),
+            _ => true,
+        });
+    } else if let Value::Sequence(items) = value {
+        items.iter_mut().for_each(prune_empty);
+    }
+}
+
+/// Legacy fields not yet consumed, and problems met consuming them.
+struct Legacy {
+    fields: BTreeMap<String, Value>,
+    warnings: Vec<String>,
+}
+
+impl Legacy {
+    fn health(&mut self) -> Option<AgentHealth> {
+        let command = self.string("health_command")?;
+        let (interval, timeout, retries, start_period) = DEFAULT_HEALTH;
+        Some(AgentHealth {
+            command,
+            interval: self
+                .string("health_interval")
+                .unwrap_or_else(|| interval.to_string()),
+            timeout: self
+                .string("health_timeout")
+                .unwrap_or_else(|| timeout.to_string()),
+            retries: self.number("health_retries").unwrap_or(retries),
+            start_period: self
+                .string("health_start_period")
+                .unwrap_or_else(|| start_period.to_string()),
+        })
+    }
+
+    /// `port` (and optionally `host_port`) as one published port whose host
+    /// side can be overridden via `<NAME>_HOST_PORT`.
+    fn ports(&mut self, name: &str) -> Vec<AgentPort> {
+        let Some(port) = self.number("port") else {
+            return Vec::new();
+        };
+        vec![AgentPort {
+            container: port,
+            host_env: format!("{}_HOST_PORT", name.to_ascii_uppercase().replace('-', "_")),
+            default: self.number("host_port").unwrap_or(port),
+        }]
+    }
+
+    /// `read_write_paths` under the template's hardened security defaults.
+    fn security(&mut self) -> Option<AgentSecurity> {
+        let read_write_paths = self.list("read_write_paths");
+        (!read_write_paths.is_empty()).then(|| AgentSecurity {
+            protect_system: "strict".to_string(),
+            protect_home: "read-only".to_string(),
+            read_write_paths,
+            no_new_privileges: true,
+            private_tmp: true,
+            memory_max: None,
+            cpu_quota: None,
+        })
+    }
+
+    fn resources(&mut self) -> Option<AgentResources> {
+        let memory_limit = self.string("memory_limit")?;
+        Some(AgentResources {
+            memory_reservation: self
+                .string("memory_reservation")
+                .unwrap_or_else(|| memory_limit.clone()),
+            memory_limit,
+        })
+    }
+
+    fn requirements(&mut self) -> Option<AgentRequirements> {
+        let requirements = AgentRequirements {
+            env_one_of: self.list("env_one_of"),
+            env_optional: self.list("env_optional"),
+            services: self.list("services"),
+        };
+        let empty = requirements.env_one_of.is_empty()
+            && requirements.env_optional.is_empty()
+            && requirements.services.is_empty();
+        (!empty).then_some(requirements)
+    }
+
+    /// Take `key` as a string; numbers and booleans are stringified.
+    fn string(&mut self, key: &str) -> Option<String> {
+        match self.fields.remove(key)? {
+            Value::String(s) => Some(s),
+            Value::Number(n) => Some(n.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            Value::Null => None,
+            _ => {
+                self.warnings.push(format!(
+                    "legacy field '{key}' is not a scalar and was dropped"
+                ));
+                None
+            }
+        }
+    }
+
+    /// Take `key` as a number that fits `T`.
+    fn number<T: TryFrom<u64>>(&mut self, key: &str) -> Option<T> {
+        let raw = self.string(key)?;
+        let parsed = raw
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .and_then(|n| T::try_from(n).ok());
+        if parsed.is_none() {
+            self.warnings.push(format!(
+                "legacy field '{key}' ('{raw}') is not a valid number and was dropped"
+            ));
+        }
+        parsed
+    }
+
+    /// Take `key` as a list: a YAML sequence or a comma-separated string.
+    fn list(&mut self, key: &str) -> Vec<String> {
+        let items: Vec<String> = match self.fields.get(key) {
+            Some(Value::Sequence(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+            Some(_) => self
+                .string(key)
+                .map(|s| s.split(',').map(|i| i.trim().to_string()).collect())
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+        self.fields.remove(key);
+        items.into_iter().filter(|i| !i.is_empty()).collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::validate::{manifest_violations, parse_agent_manifest};
+
+    const LEGACY: &str = r"
+name: claude-dev
+display_name: Claude Dev
+version: 1.2.0
+description: Claude coding assistant
+author: anthropic
+command: /usr/bin/node dist/index.js --port 3000
+workdir: /opt/claude
+init: scripts/init.sh
+port: 3000
+host_port: 9100
+health_command: curl -sf http://127.0.0.1:3000/health
+health_retries: 5
+memory_limit: 4G
+env_one_of: ANTHROPIC_API_KEY, OPENAI_API_KEY
+services: [scanner]
+read_write_paths:
+  - /home/polis/.claude
+theme: dark
+autostart: true
+";
+
+    #[test]
+    fn test_migrate_legacy_maps_fields_and_applies_defaults() {
+        let Migration { manifest, warnings } = migrate_legacy_manifest(LEGACY).expect("migrates");
+
+        assert_eq!(manifest.api_version, "polis.dev/v1");
+        assert_eq!(manifest.metadata.name, "claude-dev");
+        assert_eq!(manifest.metadata.display_name, "Claude Dev");
+        assert_eq!(manifest.metadata.version, "1.2.0");
+        assert_eq!(manifest.spec.packaging, "script");
+        assert_eq!(manifest.spec.install, "install.sh");
+        assert_eq!(manifest.spec.runtime.workdir, "/opt/claude");
+        assert_eq!(manifest.spec.runtime.user, "polis");
+        assert_eq!(manifest.spec.init.as_deref(), Some("scripts/init.sh"));
+
+        let port = &manifest.spec.ports[0];
+        assert_eq!(
+            (port.container, port.default, port.host_env.as_str()),
+            (3000, 9100, "CLAUDE_DEV_HOST_PORT")
+        );
+        let health = manifest.spec.health.as_ref().expect("health");
+        assert_eq!((health.retries, health.interval.as_str()), (5, "30s"));
+        let resources = manifest.spec.resources.as_ref().expect("resources");
+        assert_eq!(resources.memory_reservation, "4G");
+        let requirements = manifest.spec.requirements.as_ref().expect("requirements");
+        assert_eq!(
+            requirements.env_one_of,
+            ["ANTHROPIC_API_KEY", "OPENAI_API_KEY"]
+        );
+        assert_eq!(requirements.services, ["scanner"]);
+        let security = manifest.spec.security.as_ref().expect("security");
+        assert_eq!(security.read_write_paths, ["/home/polis/.claude"]);
+
+        assert!(manifest_violations(&manifest).is_empty());
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert!(warnings[0].contains("'autostart'"), "{warnings:?}");
+        assert!(warnings[1].contains("'theme'"), "{warnings:?}");
+    }
+
+    #[test]
+    fn test_manifest_yaml_round_trips_without_nulls() {
+        let migration = migrate_legacy_manifest(LEGACY).expect("migrates");
+        let yaml = manifest_yaml(&migration.manifest).expect("yaml");
+
+        assert!(!yaml.contains("null"), "{yaml}");
+        assert!(!yaml.contains("[]"), "{yaml}");
+        let reparsed = parse_agent_manifest(&yaml).expect("reparses");
+        assert_eq!(reparsed.metadata.name, "claude-dev");
+        assert_eq!(reparsed.spec.ports[0].default, 9100);
+    }
+
+    #[test]
+    fn test_migrate_minimal_legacy_uses_template_defaults() {
+        let migration =
+            migrate_legacy_manifest("name: tiny\ncommand: /bin/tiny\n").expect("migrates");
+        let m = &migration.manifest;
+        assert_eq!(m.metadata.display_name, "tiny");
+        assert_eq!(m.metadata.version, "0.1.0");
+        assert_eq!(m.spec.runtime.workdir, "/app");
+        assert!(m.spec.health.is_none());
+        assert!(m.spec.requirements.is_none());
+        assert!(m.spec.ports.is_empty());
+        assert!(migration.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_rejects_v1_and_incomplete_input() {
+        let v1 = "apiVersion: polis.dev/v1\nkind: AgentPlugin\n";
+        let err = migrate_legacy_manifest(v1).expect_err("already v1");
+        assert!(err.to_string().contains("nothing to migrate"), "{err}");
+
+        let err = migrate_legacy_manifest("name: x\n").expect_err("no command");
+        assert!(err.to_string().contains("'command'"), "{err}");
+
+        assert!(migrate_legacy_manifest("- a\n- b\n").is_err());
+    }
+
+    #[test]
+    fn test_migrate_warns_about_bad_numbers() {
+        let migration =
+            migrate_legacy_manifest("name: x\ncommand: /bin/x\nport: http\n").expect("migrates");
+        assert!(migration.manifest.spec.ports.is_empty());
+        assert!(
+            migration.warnings[0].contains("'port'"),
+            "{:?}",
+            migration.warnings
+        );
+    }
+}
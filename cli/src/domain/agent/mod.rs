@@ -3,23 +3,88 @@
 //! This module has zero imports from `crate::infra`, `crate::commands`,
 //! `crate::application`, `tokio`, `std::fs`, `std::process`, or `std::net`.
 
+pub mod archive;
 pub mod artifacts;
+pub mod doctor;
+pub mod labels;
+pub mod migrate;
+pub mod ports;
+pub mod schema;
+pub mod services;
 pub mod validate;
 
 #[allow(unused_imports)]
 pub use artifacts::{compose_overlay, filtered_env, service_hash, systemd_unit};
 #[allow(unused_imports)]
 pub use validate::{
-    AGENT_NAME_RE, ALLOWED_RW_PREFIXES, PLATFORM_PORTS, SHELL_METACHAR_RE, is_valid_agent_name,
-    validate_full_manifest,
+    AGENT_NAME_RE, ALLOWED_RW_PREFIXES, PLATFORM_PORTS, SHELL_METACHAR_RE, effective_host_port,
+    is_valid_agent_name, validate_full_manifest, validate_port_collisions,
 };
+/// Minimal valid manifest for agent `name` publishing `ports`, given as
+/// `(container, hostEnv, default)` triples. Shared by the agent tests.
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+pub(crate) fn test_manifest(
+    name: &str,
+    ports: &[(u16, &str, u16)],
+) -> polis_common::agent::AgentManifest {
+    use std::fmt::Write as _;
+
+    let mut ports_yaml = String::new();
+    for (container, host_env, default) in ports {
+        let _ = write!(
+            ports_yaml,
+            "\n    - container: {container}\n      hostEnv: \"{host_env}\"\n      default: {default}"
+        );
+    }
+    if ports_yaml.is_empty() {
+        ports_yaml.push_str(" []");
+    }
+    let yaml = format!(
+        "apiVersion: polis.dev/v1
+kind: AgentPlugin
+metadata:
+  name: {name}
+  displayName: {name}
+  version: 0.1.0
+  description: test agent
+spec:
+  packaging: script
+  install: install.sh
+  runtime:
+    command: /usr/bin/agent
+    workdir: /home/polis
+    user: polis
+  ports:{ports_yaml}
+"
+    );
+    serde_yaml::from_str(&yaml).expect("valid manifest")
+}
+
 /// Information about an installed agent.
+///
+/// This is the per-agent schema of `polis agent list --json`; optional
+/// fields serialize as `null` rather than being omitted.
 #[derive(Debug, serde::Serialize)]
 pub struct AgentInfo {
+    /// Agent folder name, as passed to other `polis agent` commands.
     pub name: String,
+    /// `metadata.displayName` from `agent.yaml`.
+    pub display_name: Option<String>,
+    /// `metadata.version` from `agent.yaml`.
     pub version: Option<String>,
+    /// `metadata.description` from `agent.yaml`.
     pub description: Option<String>,
+    /// Whether this is the workspace's active agent.
     pub active: bool,
+    /// `metadata.labels` from `agent.yaml`.
+    pub labels: std::collections::BTreeMap<String, String>,
+}
+
+/// Top-level document of `polis agent list --json`.
+#[derive(Debug, serde::Serialize)]
+pub struct AgentListOutput<'a> {
+    pub agents: &'a [AgentInfo],
 }
 
 /// Returns the path to an agent's compose overlay file inside the VM.
@@ -0,0 +1,187 @@
+//! Host-port overrides for agent socat proxies — pure, no I/O.
+//!
+//! `polis agent add --port <container>=<host>` remaps a declared port
+//! without touching `agent.yaml`. The overrides are persisted next to the
+//! generated artifacts so later regenerations (`polis start`, `polis agent
+//! update`) keep them.
+
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+use anyhow::Result;
+use polis_common::agent::AgentManifest;
+
+use crate::domain::error::AgentError;
+
+/// File under `agents/<name>/.generated/` holding the overrides, one
+/// `container=host` pair per line.
+pub const PORT_OVERRIDES_FILE: &str = "port-overrides";
+
+/// A `container=host` remapping for one declared agent port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortOverride {
+    /// Container port as declared in `spec.ports[].container`.
+    pub container: u16,
+    /// Host port the socat proxy should publish instead.
+    pub host: u16,
+}
+
+impl FromStr for PortOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (container, host) = s
+            .split_once('=')
+            .ok_or_else(|| format!("invalid port override '{s}' (expected CONTAINER=HOST)"))?;
+        let parse = |v: &str| {
+            v.trim()
+                .parse::<u16>()
+                .ok()
+                .filter(|p| *p != 0)
+                .ok_or_else(|| format!("invalid port '{}' in override '{s}'", v.trim()))
+        };
+        Ok(Self {
+            container: parse(container)?,
+            host: parse(host)?,
+        })
+    }
+}
+
+/// Parse the contents of a [`PORT_OVERRIDES_FILE`]. Blank lines and `#`
+/// comments are skipped.
+///
+/// # Errors
+///
+/// Returns an error if any line is not a valid `container=host` pair.
+pub fn parse_port_overrides(content: &str) -> Result<Vec<PortOverride>> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.parse().map_err(anyhow::Error::msg))
+        .collect()
+}
+
+/// Render overrides in the [`PORT_OVERRIDES_FILE`] format.
+#[must_use]
+pub fn render_port_overrides(overrides: &[PortOverride]) -> String {
+    let mut content = String::new();
+    for o in overrides {
+        let _ = writeln!(content, "{}={}", o.container, o.host);
+    }
+    content
+}
+
+/// Apply overrides to `manifest` in place. The overridden port becomes a
+/// fixed mapping: `default` is set to the new host port and `hostEnv` is
+/// cleared so the `.env` cannot move it again.
+///
+/// The result should still be checked with `validate_full_manifest` and
+/// `validate_port_collisions`, which reject reserved and claimed ports.
+///
+/// # Errors
+///
+/// Returns an error if an override names a container port the manifest
+/// does not declare, or the same container port twice.
+pub fn apply_port_overrides(
+    manifest: &mut AgentManifest,
+    overrides: &[PortOverride],
+) -> Result<()> {
+    let mut errors: Vec<String> = Vec::new();
+    for (i, o) in overrides.iter().enumerate() {
+        if overrides[..i]
+            .iter()
+            .any(|prev| prev.container == o.container)
+        {
+            errors.push(format!("Port {} is overridden more than once", o.container));
+            continue;
+        }
+        match manifest
+            .spec
+            .ports
+            .iter_mut()
+            .find(|p| p.container == o.container)
+        {
+            Some(port) => {
+                port.default = o.host;
+                port.host_env.clear();
+            }
+            None => errors.push(format!(
+                "Agent '{}' does not declare container port {}",
+                manifest.metadata.name, o.container
+            )),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AgentError::ValidationFailed(errors.join("\n")).into())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::artifacts::compose_overlay;
+    use crate::domain::agent::test_manifest;
+    use crate::domain::agent::validate::validate_full_manifest;
+
+    fn manifest() -> AgentManifest {
+        test_manifest("alpha", &[(3000, "ALPHA_PORT", 9000)])
+    }
+
+    fn over(container: u16, host: u16) -> PortOverride {
+        PortOverride { container, host }
+    }
+
+    #[test]
+    fn test_port_override_parses_container_equals_host() {
+        assert_eq!("3000=9100".parse(), Ok(over(3000, 9100)));
+        assert!("3000".parse::<PortOverride>().is_err());
+        assert!("3000=0".parse::<PortOverride>().is_err());
+        assert!("3000=70000".parse::<PortOverride>().is_err());
+    }
+
+    #[test]
+    fn test_apply_port_overrides_changes_socat_host_port() {
+        let mut m = manifest();
+        assert!(compose_overlay(&m).contains("\"${ALPHA_PORT:-9000}:3000\""));
+        apply_port_overrides(&mut m, &[over(3000, 9100)]).expect("declared port");
+        let overlay = compose_overlay(&m);
+        assert!(overlay.contains("\"9100:3000\""), "{overlay}");
+        assert!(!overlay.contains("ALPHA_PORT"), "{overlay}");
+    }
+
+    #[test]
+    fn test_apply_port_overrides_rejects_undeclared_port() {
+        let err = apply_port_overrides(&mut manifest(), &[over(4000, 9100)])
+            .expect_err("4000 is not declared")
+            .to_string();
+        assert!(err.contains("container port 4000"), "{err}");
+    }
+
+    #[test]
+    fn test_apply_port_overrides_rejects_duplicates() {
+        let err = apply_port_overrides(&mut manifest(), &[over(3000, 9100), over(3000, 9200)])
+            .expect_err("3000 twice");
+        assert!(err.to_string().contains("more than once"));
+    }
+
+    #[test]
+    fn test_override_onto_reserved_port_fails_validation() {
+        let mut m = manifest();
+        apply_port_overrides(&mut m, &[over(3000, 8080)]).expect("declared port");
+        assert!(validate_full_manifest(&m).is_err());
+    }
+
+    #[test]
+    fn test_port_overrides_file_round_trips() {
+        let overrides = vec![over(3000, 9100), over(3001, 9101)];
+        let content = render_port_overrides(&overrides);
+        assert_eq!(content, "3000=9100\n3001=9101\n");
+        let parsed = parse_port_overrides(&format!("# comment\n\n{content}")).expect("valid file");
+        assert_eq!(parsed, overrides);
+    }
+}
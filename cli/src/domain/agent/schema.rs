@@ -0,0 +1,275 @@
+//! JSON Schema for `agent.yaml` — pure, no I/O.
+//!
+//! Mirrors the `AgentManifest` types in `polis-common` and, where JSON
+//! Schema can express them, the rules in [`super::validate`]. Patterns and
+//! enums are built from the same constants the validator uses so the two
+//! cannot drift apart.
+
+use serde_json::{Value, json};
+
+use super::labels::{LABEL_KEY_RE, LABEL_VALUE_RE};
+use super::services::PLATFORM_SERVICES;
+use super::validate::{
+    AGENT_NAME_RE, ALLOWED_RW_PREFIXES, PERSISTENCE_NAME_RE, PLATFORM_PORTS, SHELL_METACHAR_RE,
+};
+
+/// `$id` of the emitted schema.
+pub const SCHEMA_ID: &str = "https://polis.dev/schemas/agent.v1.json";
+
+/// JSON Schema (draft-07, the newest draft most editors support) describing
+/// a valid `agent.yaml`.
+///
+/// Rules that need more than one document to check — host-port collisions
+/// between installed agents — are left to `polis agent add`.
+#[must_use]
+pub fn agent_manifest_schema() -> Value {
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "$id": SCHEMA_ID,
+        "title": "Polis agent manifest (agent.yaml)",
+        "type": "object",
+        "required": ["apiVersion", "kind", "metadata", "spec"],
+        "properties": {
+            "apiVersion": { "const": "polis.dev/v1" },
+            "kind": { "const": "AgentPlugin" },
+            "metadata": metadata_schema(),
+            "spec": spec_schema(),
+        },
+    })
+}
+
+fn metadata_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["name", "displayName", "version", "description"],
+        "properties": {
+            "name": {
+                "type": "string",
+                "pattern": AGENT_NAME_RE.as_str(),
+                "description": "Lowercase alphanumeric with interior hyphens, at most 63 characters",
+            },
+            "displayName": { "type": "string" },
+            "version": { "type": "string" },
+            "description": { "type": "string" },
+            "author": { "type": "string" },
+            "license": { "type": "string" },
+            "provider": {
+                "type": "string",
+                "description": "LLM provider; derived from spec.requirements.envOneOf when absent",
+            },
+            "capabilities": string_array(),
+            "labels": {
+                "type": "object",
+                "propertyNames": { "pattern": LABEL_KEY_RE.as_str() },
+                "additionalProperties": { "type": "string", "pattern": LABEL_VALUE_RE.as_str() },
+            },
+        },
+    })
+}
+
+fn spec_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["packaging", "install", "runtime"],
+        "properties": {
+            "packaging": { "enum": ["script"] },
+            "install": relative_path("Install script, relative to the agent folder"),
+            "init": relative_path("Init script, relative to the agent folder"),
+            "runtime": runtime_schema(),
+            "health": {
+                "type": "object",
+                "required": ["command", "interval", "timeout", "retries", "startPeriod"],
+                "properties": {
+                    "command": { "type": "string" },
+                    "interval": { "type": "string" },
+                    "timeout": { "type": "string" },
+                    "retries": { "type": "integer", "minimum": 0 },
+                    "startPeriod": { "type": "string" },
+                },
+            },
+            "security": security_schema(),
+            "ports": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["container", "hostEnv", "default"],
+                    "properties": {
+                        "container": port(),
+                        "hostEnv": { "type": "string" },
+                        "default": {
+                            "allOf": [port(), { "not": { "enum": PLATFORM_PORTS } }],
+                            "description": "Host port; must not be a platform-reserved port",
+                        },
+                    },
+                },
+            },
+            "resources": {
+                "type": "object",
+                "required": ["memoryLimit", "memoryReservation"],
+                "properties": {
+                    "memoryLimit": { "type": "string" },
+                    "memoryReservation": { "type": "string" },
+                },
+            },
+            "requirements": {
+                "type": "object",
+                "properties": {
+                    "envOneOf": string_array(),
+                    "envOptional": string_array(),
+                    "services": {
+                        "type": "array",
+                        "items": { "enum": PLATFORM_SERVICES },
+                    },
+                },
+            },
+            "persistence": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["name", "containerPath"],
+                    "properties": {
+                        "name": { "type": "string", "pattern": PERSISTENCE_NAME_RE.as_str() },
+                        "containerPath": {
+                            "type": "string",
+                            "pattern": "^/",
+                            "not": { "pattern": r"(^|/)\.\.(/|$)" },
+                        },
+                    },
+                },
+            },
+            "capabilities": {
+                "type": "object",
+                "required": ["network", "mcp", "dockerInDocker"],
+                "properties": {
+                    "network": { "type": "boolean" },
+                    "filesystem": string_array(),
+                    "mcp": { "type": "boolean" },
+                    "dockerInDocker": { "type": "boolean" },
+                },
+            },
+            "commands": relative_path("Agent command script, relative to the agent folder"),
+            "onboarding": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["title", "command"],
+                    "properties": {
+                        "title": { "type": "string" },
+                        "command": { "type": "string" },
+                    },
+                },
+            },
+        },
+    })
+}
+
+fn runtime_schema() -> Value {
+    // `SHELL_METACHAR_RE` is a single character class; negate it.
+    let metachars = SHELL_METACHAR_RE.as_str();
+    let class = &metachars[1..metachars.len() - 1];
+    json!({
+        "type": "object",
+        "required": ["command", "workdir", "user"],
+        "properties": {
+            "command": {
+                "type": "string",
+                "pattern": format!("^/[^{class}]*$"),
+                "description": "Absolute path plus arguments, without shell metacharacters",
+            },
+            "workdir": { "type": "string" },
+            "user": { "type": "string", "not": { "const": "root" } },
+            "envFile": { "type": "string" },
+            "env": { "type": "object", "additionalProperties": { "type": "string" } },
+        },
+    })
+}
+
+fn security_schema() -> Value {
+    let prefixes: Vec<String> = ALLOWED_RW_PREFIXES
+        .iter()
+        .map(|p| regex::escape(p))
+        .collect();
+    json!({
+        "type": "object",
+        "required": ["protectSystem", "protectHome", "noNewPrivileges", "privateTmp"],
+        "properties": {
+            "protectSystem": { "type": "string" },
+            "protectHome": { "type": "string" },
+            "readWritePaths": {
+                "type": "array",
+                "items": {
+                    "type": "string",
+                    "pattern": format!("^({})", prefixes.join("|")),
+                },
+            },
+            "noNewPrivileges": { "type": "boolean" },
+            "privateTmp": { "type": "boolean" },
+            "memoryMax": { "type": "string" },
+            "cpuQuota": { "type": "string" },
+        },
+    })
+}
+
+fn relative_path(description: &str) -> Value {
+    json!({
+        "type": "string",
+        "not": { "pattern": r"\.\." },
+        "description": description,
+    })
+}
+
+fn port() -> Value {
+    json!({ "type": "integer", "minimum": 1, "maximum": 65535 })
+}
+
+fn string_array() -> Value {
+    json!({ "type": "array", "items": { "type": "string" } })
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_is_valid_json_with_key_constraints() {
+        let text = serde_json::to_string(&agent_manifest_schema()).expect("serialize");
+        let schema: Value = serde_json::from_str(&text).expect("valid JSON");
+
+        assert_eq!(schema["$schema"], "http://json-schema.org/draft-07/schema#");
+        assert_eq!(
+            schema["properties"]["metadata"]["properties"]["name"]["pattern"],
+            AGENT_NAME_RE.as_str()
+        );
+        assert_eq!(
+            schema["properties"]["spec"]["properties"]["packaging"]["enum"],
+            json!(["script"])
+        );
+        let spec = &schema["properties"]["spec"]["properties"];
+        assert_eq!(
+            spec["ports"]["items"]["properties"]["default"]["allOf"][1]["not"]["enum"],
+            json!(PLATFORM_PORTS)
+        );
+        assert_eq!(
+            spec["security"]["properties"]["readWritePaths"]["items"]["pattern"],
+            r"^(/home/polis/|/tmp/|/var/lib/|/var/log/)"
+        );
+    }
+
+    #[test]
+    fn test_schema_patterns_agree_with_validator() {
+        let schema = agent_manifest_schema();
+        let command = schema["properties"]["spec"]["properties"]["runtime"]["properties"]
+            ["command"]["pattern"]
+            .as_str()
+            .expect("command pattern");
+        let re = regex::Regex::new(command).expect("command pattern compiles");
+
+        assert!(re.is_match("/usr/bin/node dist/index.js --port 18789"));
+        assert!(!re.is_match("node dist/index.js"), "must be absolute");
+        for bad in ["/bin/sh -c 'a; b'", "/bin/echo $HOME", "/bin/cat a|b"] {
+            assert!(!re.is_match(bad), "{bad}");
+            assert!(SHELL_METACHAR_RE.is_match(bad));
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Control-plane service dependencies declared by agents — pure, no I/O.
+//!
+//! An agent lists the services it needs in `spec.requirements.services`;
+//! `polis start --agent` checks them with `docker compose ps` before
+//! reporting success.
+
+use polis_common::agent::AgentManifest;
+
+use crate::domain::workspace::VM_ROOT;
+
+/// Long-running control-plane services an agent may depend on, keyed by
+/// their `docker-compose.yml` service names.
+pub const PLATFORM_SERVICES: &[&str] = &[
+    "resolver", "gate", "sentinel", "scanner", "state", "toolbox",
+];
+
+/// Services listed in the manifest's `spec.requirements.services`.
+#[must_use]
+pub fn required_services(manifest: &AgentManifest) -> Vec<String> {
+    manifest
+        .spec
+        .requirements
+        .as_ref()
+        .map(|r| r.services.clone())
+        .unwrap_or_default()
+}
+
+/// Arguments for listing which of `services` are running, one service name
+/// per output line.
+#[must_use]
+pub fn service_check_args(services: &[String]) -> Vec<String> {
+    let mut args: Vec<String> = [
+        "docker",
+        "compose",
+        "-f",
+        &format!("{VM_ROOT}/docker-compose.yml"),
+        "ps",
+        "--services",
+        "--status",
+        "running",
+    ]
+    .iter()
+    .map(ToString::to_string)
+    .collect();
+    args.extend(services.iter().cloned());
+    args
+}
+
+/// Required services absent from the `docker compose ps --services` output,
+/// in declaration order.
+#[must_use]
+pub fn missing_services<'a>(required: &'a [String], running_output: &str) -> Vec<&'a str> {
+    let running: Vec<&str> = running_output.lines().map(str::trim).collect();
+    required
+        .iter()
+        .map(String::as_str)
+        .filter(|svc| !running.contains(svc))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_service_check_args_lists_running_required_services() {
+        let args = service_check_args(&names(&["scanner", "sentinel"]));
+        assert_eq!(
+            args,
+            [
+                "docker",
+                "compose",
+                "-f",
+                "/opt/polis/docker-compose.yml",
+                "ps",
+                "--services",
+                "--status",
+                "running",
+                "scanner",
+                "sentinel",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_services_reports_stopped_dependencies() {
+        let required = names(&["scanner", "sentinel", "gate"]);
+        assert_eq!(
+            missing_services(&required, "sentinel\n"),
+            ["scanner", "gate"]
+        );
+        assert!(missing_services(&required, "gate\nscanner\nsentinel\n").is_empty());
+    }
+}
@@ -4,12 +4,12 @@
 //! data out. Zero imports from `tokio`, `std::fs`, `crate::infra`,
 //! `crate::commands`, or `crate::application`.
 
-use anyhow::Result;
-use polis_common::agent::AgentManifest;
+use anyhow::{Context, Result};
+use polis_common::agent::{AgentManifest, AgentPort};
 use regex::Regex;
 use std::sync::LazyLock;
 
-use crate::domain::error::AgentError;
+use crate::domain::error::{AgentError, ManifestRule};
 
 /// Same rule enforced by `generate-agent.sh`; checked here before any
 /// path interpolation to prevent path-traversal (CWE-22).
@@ -25,12 +25,51 @@ pub static SHELL_METACHAR_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"[;|&`$()\\<>!#~*\[\]{}]").expect("valid regex")
 });
 
+/// Docker volume name suffix for `spec.persistence[].name`; the overlay
+/// names the volume `polis-agent-<agent>-<name>`.
+pub static PERSISTENCE_NAME_RE: LazyLock<Regex> = LazyLock::new(|| {
+    #[allow(clippy::expect_used)]
+    Regex::new(r"^[a-z0-9][a-z0-9_-]*$").expect("valid regex")
+});
+
 /// Platform-reserved ports that agents must not use.
 pub const PLATFORM_PORTS: &[u16] = &[53, 1344, 6379, 8080, 18080];
 
 /// Allowed prefixes for readWritePaths (same as generate-agent.sh).
 pub const ALLOWED_RW_PREFIXES: &[&str] = &["/home/polis/", "/tmp/", "/var/lib/", "/var/log/"];
 
+/// A single manifest rule violation, located by its YAML field path.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ManifestViolation {
+    /// The rule that was broken.
+    pub rule: ManifestRule,
+    /// Field path, e.g. `spec.runtime.command` or `spec.ports[0].default`.
+    pub field: String,
+    /// What is wrong with the field.
+    pub message: String,
+}
+
+impl ManifestViolation {
+    fn new(rule: ManifestRule, field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            rule,
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Parse `agent.yaml` content into an `AgentManifest`.
+///
+/// Pure function — no I/O, no async.
+///
+/// # Errors
+///
+/// Returns an error if the content is not a valid agent manifest.
+pub fn parse_agent_manifest(content: &str) -> Result<AgentManifest> {
+    serde_yaml::from_str(content).context("failed to parse agent.yaml")
+}
+
 /// Validate a parsed `AgentManifest` against the same rules as
 /// `generate-agent.sh`. Returns `Ok(())` or an error listing all violations.
 ///
@@ -46,6 +85,10 @@ pub const ALLOWED_RW_PREFIXES: &[&str] = &["/home/polis/", "/tmp/", "/var/lib/",
 /// 9. `spec.init` has no ".." (path traversal)
 ///    10+. Port conflicts with `PLATFORM_PORTS`
 ///    N+. `readWritePaths` prefix validation against `ALLOWED_RW_PREFIXES`
+///    N+. `requirements.services` entries are known platform services
+///    N+. `metadata.labels` keys and values follow the label grammar
+///    N+. `persistence[].name` is a valid volume name and
+///    `persistence[].containerPath` is absolute without ".."
 ///
 /// Pure function — no I/O, no async.
 ///
@@ -53,90 +96,262 @@ pub const ALLOWED_RW_PREFIXES: &[&str] = &["/home/polis/", "/tmp/", "/var/lib/",
 ///
 /// Returns an error listing all validation violations if any check fails.
 pub fn validate_full_manifest(manifest: &AgentManifest) -> Result<()> {
-    let mut errors: Vec<String> = Vec::new();
+    let errors: Vec<String> = manifest_violations(manifest)
+        .into_iter()
+        .map(|v| v.message)
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AgentError::ValidationFailed(errors.join("\n")).into())
+    }
+}
+
+/// Every rule in [`validate_full_manifest`] that `manifest` breaks, each
+/// tagged with the offending field.
+///
+/// Pure function — no I/O, no async.
+#[must_use]
+pub fn manifest_violations(manifest: &AgentManifest) -> Vec<ManifestViolation> {
+    let mut errors: Vec<ManifestViolation> = Vec::new();
 
     validate_metadata(manifest, &mut errors);
     validate_runtime(manifest, &mut errors);
     validate_paths(manifest, &mut errors);
     validate_ports(manifest, &mut errors);
     validate_security(manifest, &mut errors);
+    validate_services(manifest, &mut errors);
+    validate_persistence(manifest, &mut errors);
 
-    if errors.is_empty() {
-        Ok(())
-    } else {
-        Err(AgentError::ValidationFailed(errors.join("\n")).into())
-    }
+    errors
 }
 
-fn validate_metadata(manifest: &AgentManifest, errors: &mut Vec<String>) {
+fn validate_metadata(manifest: &AgentManifest, errors: &mut Vec<ManifestViolation>) {
+    errors.extend(
+        crate::domain::agent::labels::label_errors(&manifest.metadata.labels)
+            .into_iter()
+            .map(|msg| ManifestViolation::new(ManifestRule::Label, "metadata.labels", msg)),
+    );
     if manifest.api_version != "polis.dev/v1" {
-        errors.push("Unsupported apiVersion. Expected polis.dev/v1".to_string());
+        errors.push(ManifestViolation::new(
+            ManifestRule::ApiVersion,
+            "apiVersion",
+            "Unsupported apiVersion. Expected polis.dev/v1",
+        ));
     }
     if manifest.kind != "AgentPlugin" {
-        errors.push("Unsupported kind. Expected AgentPlugin".to_string());
+        errors.push(ManifestViolation::new(
+            ManifestRule::Kind,
+            "kind",
+            "Unsupported kind. Expected AgentPlugin",
+        ));
     }
     if !AGENT_NAME_RE.is_match(&manifest.metadata.name) {
-        errors.push(format!(
-            "metadata.name '{}' must be lowercase alphanumeric with hyphens",
-            manifest.metadata.name
+        errors.push(ManifestViolation::new(
+            ManifestRule::Name,
+            "metadata.name",
+            format!(
+                "metadata.name '{}' must be lowercase alphanumeric with hyphens",
+                manifest.metadata.name
+            ),
         ));
     }
     if manifest.spec.packaging != "script" {
-        errors.push("Only 'script' packaging is supported".to_string());
+        errors.push(ManifestViolation::new(
+            ManifestRule::Packaging,
+            "spec.packaging",
+            "Only 'script' packaging is supported",
+        ));
     }
 }
 
-fn validate_runtime(manifest: &AgentManifest, errors: &mut Vec<String>) {
+fn validate_runtime(manifest: &AgentManifest, errors: &mut Vec<ManifestViolation>) {
     let cmd = &manifest.spec.runtime.command;
     if !cmd.starts_with('/') {
-        errors.push("runtime.command must start with /".to_string());
+        errors.push(ManifestViolation::new(
+            ManifestRule::CommandAbsolute,
+            "spec.runtime.command",
+            "runtime.command must start with /",
+        ));
     }
     if SHELL_METACHAR_RE.is_match(cmd) {
-        errors.push("runtime.command contains shell metacharacters".to_string());
+        errors.push(ManifestViolation::new(
+            ManifestRule::CommandMetacharacters,
+            "spec.runtime.command",
+            "runtime.command contains shell metacharacters",
+        ));
     }
     if manifest.spec.runtime.user == "root" {
-        errors.push("Agents must run as unprivileged user (not root)".to_string());
+        errors.push(ManifestViolation::new(
+            ManifestRule::UnprivilegedUser,
+            "spec.runtime.user",
+            "Agents must run as unprivileged user (not root)",
+        ));
     }
 }
 
-fn validate_paths(manifest: &AgentManifest, errors: &mut Vec<String>) {
+fn validate_paths(manifest: &AgentManifest, errors: &mut Vec<ManifestViolation>) {
     if manifest.spec.install.contains("..") {
-        errors.push("spec.install path escapes agent directory".to_string());
+        errors.push(ManifestViolation::new(
+            ManifestRule::PathEscape,
+            "spec.install",
+            "spec.install path escapes agent directory",
+        ));
     }
     if let Some(init) = &manifest.spec.init
         && init.contains("..")
     {
-        errors.push("spec.init path escapes agent directory".to_string());
+        errors.push(ManifestViolation::new(
+            ManifestRule::PathEscape,
+            "spec.init",
+            "spec.init path escapes agent directory",
+        ));
     }
 }
 
-fn validate_ports(manifest: &AgentManifest, errors: &mut Vec<String>) {
-    for port_spec in &manifest.spec.ports {
+fn validate_ports(manifest: &AgentManifest, errors: &mut Vec<ManifestViolation>) {
+    for (i, port_spec) in manifest.spec.ports.iter().enumerate() {
         if PLATFORM_PORTS.contains(&port_spec.default) {
-            errors.push(format!(
-                "Port {} conflicts with platform service",
-                port_spec.default
+            errors.push(ManifestViolation::new(
+                ManifestRule::ReservedPort,
+                format!("spec.ports[{i}].default"),
+                format!("Port {} conflicts with platform service", port_spec.default),
             ));
         }
     }
 }
 
-fn validate_security(manifest: &AgentManifest, errors: &mut Vec<String>) {
+fn validate_security(manifest: &AgentManifest, errors: &mut Vec<ManifestViolation>) {
     if let Some(security) = &manifest.spec.security {
-        for path in &security.read_write_paths {
+        for (i, path) in security.read_write_paths.iter().enumerate() {
             let allowed = ALLOWED_RW_PREFIXES
                 .iter()
                 .any(|prefix| path.starts_with(prefix));
             if !allowed {
-                errors.push(format!(
-                    "readWritePaths entry '{path}' is outside allowed prefixes: {}",
-                    ALLOWED_RW_PREFIXES.join(", ")
+                errors.push(ManifestViolation::new(
+                    ManifestRule::ReadWritePath,
+                    format!("spec.security.readWritePaths[{i}]"),
+                    format!(
+                        "readWritePaths entry '{path}' is outside allowed prefixes: {}",
+                        ALLOWED_RW_PREFIXES.join(", ")
+                    ),
                 ));
             }
         }
     }
 }
 
+fn validate_services(manifest: &AgentManifest, errors: &mut Vec<ManifestViolation>) {
+    use crate::domain::agent::services::PLATFORM_SERVICES;
+
+    let services = crate::domain::agent::services::required_services(manifest);
+    for (i, service) in services.iter().enumerate() {
+        if !PLATFORM_SERVICES.contains(&service.as_str()) {
+            errors.push(ManifestViolation::new(
+                ManifestRule::PlatformService,
+                format!("spec.requirements.services[{i}]"),
+                format!(
+                    "requirements.services entry '{service}' is not a platform service: {}",
+                    PLATFORM_SERVICES.join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+fn validate_persistence(manifest: &AgentManifest, errors: &mut Vec<ManifestViolation>) {
+    for (i, volume) in manifest.spec.persistence.iter().enumerate() {
+        if !PERSISTENCE_NAME_RE.is_match(&volume.name) {
+            errors.push(ManifestViolation::new(
+                ManifestRule::PersistenceName,
+                format!("spec.persistence[{i}].name"),
+                format!(
+                    "persistence name '{}' must start with a lowercase letter or digit and \
+contain only lowercase letters, digits, '_' and '-'",
+                    volume.name
+                ),
+            ));
+        }
+        let path = std::path::Path::new(&volume.container_path);
+        let escapes = path
+            .components()
+            .any(|c| c == std::path::Component::ParentDir);
+        if !volume.container_path.starts_with('/') || escapes {
+            errors.push(ManifestViolation::new(
+                ManifestRule::PersistencePath,
+                format!("spec.persistence[{i}].containerPath"),
+                format!(
+                    "persistence containerPath '{}' must be absolute and must not contain '..'",
+                    volume.container_path
+                ),
+            ));
+        }
+    }
+}
+
+/// Host port the socat proxy for `port` binds: the value of its `hostEnv`
+/// variable in the control-plane `.env` when set to a valid port, otherwise
+/// the manifest default — the same `${VAR:-default}` the compose overlay
+/// expands.
+///
+/// Pure function — no I/O, no async.
+#[must_use]
+pub fn effective_host_port(port: &AgentPort, env_content: &str) -> u16 {
+    if port.host_env.is_empty() {
+        return port.default;
+    }
+    env_content
+        .lines()
+        .filter_map(|line| line.trim().split_once('='))
+        .filter(|(key, _)| key.trim() == port.host_env)
+        .filter_map(|(_, value)| value.trim().trim_matches('"').parse::<u16>().ok())
+        .next_back()
+        .unwrap_or(port.default)
+}
+
+/// Reject `manifest` if any of its effective host ports is already claimed
+/// by another installed agent. Entries in `installed` with the same name as
+/// `manifest` are ignored.
+///
+/// Pure function — no I/O, no async.
+///
+/// # Errors
+///
+/// Returns an error naming every conflicting port and agent.
+pub fn validate_port_collisions(
+    manifest: &AgentManifest,
+    installed: &[AgentManifest],
+    env_content: &str,
+) -> Result<()> {
+    let mut errors: Vec<String> = Vec::new();
+    for port in &manifest.spec.ports {
+        let host_port = effective_host_port(port, env_content);
+        let owners = installed
+            .iter()
+            .filter(|other| other.metadata.name != manifest.metadata.name)
+            .filter(|other| {
+                other
+                    .spec
+                    .ports
+                    .iter()
+                    .any(|p| effective_host_port(p, env_content) == host_port)
+            });
+        for other in owners {
+            errors.push(format!(
+                "Port {host_port} is already used by agent '{}'",
+                other.metadata.name
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AgentError::ValidationFailed(errors.join("\n")).into())
+    }
+}
+
 /// Returns `true` if `name` is a valid agent name.
 ///
 /// Valid names match `^[a-z0-9]([a-z0-9-]{0,61}[a-z0-9])?$` — lowercase
@@ -147,3 +362,148 @@ fn validate_security(manifest: &AgentManifest, errors: &mut Vec<String>) {
 pub fn is_valid_agent_name(name: &str) -> bool {
     AGENT_NAME_RE.is_match(name)
 }
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::test_manifest as manifest;
+
+    #[test]
+    fn test_effective_host_port_uses_env_override() {
+        let m = manifest("alpha", &[(3000, "ALPHA_PORT", 9000)]);
+        let p = &m.spec.ports[0];
+        assert_eq!(effective_host_port(p, ""), 9000);
+        assert_eq!(effective_host_port(p, "ALPHA_PORT=9100\n"), 9100);
+        assert_eq!(effective_host_port(p, "ALPHA_PORT=abc\n"), 9000);
+    }
+
+    #[test]
+    fn test_validate_port_collisions_names_conflicting_agent() {
+        let new = manifest("alpha", &[(3000, "ALPHA_PORT", 9000)]);
+        let installed = [
+            manifest("beta", &[(4000, "BETA_PORT", 9001)]),
+            manifest("gamma", &[(5000, "", 9000)]),
+        ];
+        let err = validate_port_collisions(&new, &installed, "")
+            .expect_err("port 9000 collides with gamma")
+            .to_string();
+        assert!(
+            err.contains("Port 9000 is already used by agent 'gamma'"),
+            "{err}"
+        );
+        assert!(!err.contains("beta"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_port_collisions_respects_env_overrides() {
+        let new = manifest("alpha", &[(3000, "ALPHA_PORT", 9000)]);
+        let installed = [manifest("beta", &[(4000, "BETA_PORT", 9000)])];
+        assert!(validate_port_collisions(&new, &installed, "").is_err());
+        assert!(validate_port_collisions(&new, &installed, "BETA_PORT=9200\n").is_ok());
+        let err = validate_port_collisions(&new, &installed, "ALPHA_PORT=9200\nBETA_PORT=9200\n")
+            .expect_err("both moved to 9200");
+        assert!(err.to_string().contains("Port 9200"));
+    }
+
+    #[test]
+    fn test_validate_full_manifest_rejects_unknown_service_dependency() {
+        let with_services = |services: &[&str]| {
+            let mut m = manifest("alpha", &[]);
+            m.spec.requirements = Some(polis_common::agent::AgentRequirements {
+                env_one_of: vec![],
+                env_optional: vec![],
+                services: services.iter().map(ToString::to_string).collect(),
+            });
+            m
+        };
+        assert!(validate_full_manifest(&with_services(&["scanner", "sentinel"])).is_ok());
+        let err = validate_full_manifest(&with_services(&["scanner", "mailer"]))
+            .expect_err("mailer is unknown")
+            .to_string();
+        assert!(err.contains("'mailer'"), "{err}");
+        assert!(!err.contains("'scanner'"), "{err}");
+    }
+
+    #[test]
+    fn test_manifest_violations_reports_every_field() {
+        let mut m = manifest("alpha", &[(3000, "", 8080)]);
+        m.spec.runtime.command = "agent; rm -rf /".to_string();
+        m.spec.runtime.user = "root".to_string();
+        let violations = manifest_violations(&m);
+        let fields: Vec<&str> = violations.iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            [
+                "spec.runtime.command",
+                "spec.runtime.command",
+                "spec.runtime.user",
+                "spec.ports[0].default",
+            ]
+        );
+        let rules: Vec<ManifestRule> = violations.iter().map(|v| v.rule).collect();
+        assert_eq!(
+            rules,
+            [
+                ManifestRule::CommandAbsolute,
+                ManifestRule::CommandMetacharacters,
+                ManifestRule::UnprivilegedUser,
+                ManifestRule::ReservedPort,
+            ]
+        );
+        let err = validate_full_manifest(&m).expect_err("invalid").to_string();
+        for v in &violations {
+            assert!(err.contains(&v.message), "{err}");
+        }
+        assert!(manifest_violations(&manifest("alpha", &[])).is_empty());
+    }
+
+    #[test]
+    fn test_manifest_violations_checks_persistence_volumes() {
+        let with_volumes = |volumes: &[(&str, &str)]| {
+            let mut m = manifest("alpha", &[]);
+            m.spec.persistence = volumes
+                .iter()
+                .map(|(name, path)| polis_common::agent::AgentPersistence {
+                    name: (*name).to_string(),
+                    container_path: (*path).to_string(),
+                })
+                .collect();
+            m
+        };
+        assert!(manifest_violations(&with_volumes(&[("data", "/home/polis/.data")])).is_empty());
+        assert!(manifest_violations(&with_volumes(&[("cache_2", "/var/cache")])).is_empty());
+
+        let violations = manifest_violations(&with_volumes(&[
+            ("my volume!", "/home/polis/data"),
+            ("-data", "relative/path"),
+            ("logs", "/home/polis/../../etc"),
+        ]));
+        let fields: Vec<&str> = violations.iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            [
+                "spec.persistence[0].name",
+                "spec.persistence[1].name",
+                "spec.persistence[1].containerPath",
+                "spec.persistence[2].containerPath",
+            ]
+        );
+        assert!(violations[0].message.contains("'my volume!'"));
+    }
+
+    #[test]
+    fn test_parse_agent_manifest_rejects_malformed_yaml() {
+        let err = parse_agent_manifest("apiVersion: [")
+            .expect_err("malformed")
+            .to_string();
+        assert!(err.contains("agent.yaml"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_port_collisions_ignores_same_agent() {
+        let new = manifest("alpha", &[(3000, "", 9000)]);
+        let installed = [manifest("alpha", &[(3000, "", 9000)])];
+        assert!(validate_port_collisions(&new, &installed, "").is_ok());
+    }
+}
@@ -2,15 +2,20 @@
 //!
 //! Pure functions only — no I/O, no async, no filesystem access.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
 use crate::domain::error::ConfigError;
 
 // ── Constants ────────────────────────────────────────────────────────────────
 
-pub const VALID_CONFIG_KEYS: &[&str] = &["security.level"];
+pub const VALID_CONFIG_KEYS: &[&str] = &["security.level", "update.check", "registry.prefix"];
 pub const VALID_SECURITY_LEVELS: &[&str] = &["relaxed", "balanced", "strict"];
+pub const VALID_BOOL_VALUES: &[&str] = &["true", "false"];
+/// Registry and namespace the control-plane images are published under.
+pub const DEFAULT_REGISTRY_PREFIX: &str = "ghcr.io/odralabshq";
+/// Environment variable that overrides `registry.prefix`.
+pub const REGISTRY_PREFIX_ENV: &str = "POLIS_REGISTRY_PREFIX";
 
 // ── Config schema ────────────────────────────────────────────────────────────
 
@@ -21,6 +26,12 @@ pub struct PolisConfig {
     /// Security settings.
     #[serde(default)]
     pub security: SecurityConfig,
+    /// Update notification settings.
+    #[serde(default)]
+    pub update: UpdateConfig,
+    /// Container registry settings.
+    #[serde(default)]
+    pub registry: RegistryConfig,
 }
 
 /// Security configuration.
@@ -43,6 +54,37 @@ fn default_security_level() -> String {
     "balanced".to_string()
 }
 
+/// Update notification configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateConfig {
+    /// Check for new releases (at most once a day) and print a notice after
+    /// commands. Defaults to `true`.
+    #[serde(default = "default_update_check")]
+    pub check: bool,
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            check: default_update_check(),
+        }
+    }
+}
+
+fn default_update_check() -> bool {
+    true
+}
+
+/// Container registry configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RegistryConfig {
+    /// Registry path the control-plane images are pulled from, e.g. a
+    /// mirror such as `registry.example.com/polis`. `None` means
+    /// [`DEFAULT_REGISTRY_PREFIX`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+}
+
 // ── Validators ───────────────────────────────────────────────────────────────
 
 /// Validates a configuration key against the whitelist.
@@ -52,15 +94,18 @@ fn default_security_level() -> String {
 /// Returns an error if the key is not in the allowed list.
 pub fn validate_config_key(key: &str) -> Result<()> {
     if !VALID_CONFIG_KEYS.contains(&key) {
-        return Err(ConfigError::UnknownKey {
-            key: key.to_string(),
-            valid: VALID_CONFIG_KEYS.join(", "),
-        }
-        .into());
+        return Err(unknown_key(key).into());
     }
     Ok(())
 }
 
+fn unknown_key(key: &str) -> ConfigError {
+    ConfigError::UnknownKey {
+        key: key.to_string(),
+        valid: VALID_CONFIG_KEYS.join(", "),
+    }
+}
+
 /// Validates a configuration value for the given key.
 ///
 /// # Errors
@@ -75,9 +120,157 @@ pub fn validate_config_value(key: &str, value: &str) -> Result<()> {
         }
         .into());
     }
+    if key == "update.check" && !VALID_BOOL_VALUES.contains(&value) {
+        return Err(ConfigError::InvalidValue {
+            key: key.to_string(),
+            value: value.to_string(),
+            valid: VALID_BOOL_VALUES.join(", "),
+        }
+        .into());
+    }
+    if key == "registry.prefix" {
+        validate_registry_prefix(value)?;
+    }
+    Ok(())
+}
+
+// ── Get / set ────────────────────────────────────────────────────────────────
+
+/// The value of `key` in `config`. An unset `registry.prefix` reads as
+/// [`DEFAULT_REGISTRY_PREFIX`], as in `polis config show`.
+///
+/// # Errors
+///
+/// Returns an error if the key is not in the allowed list.
+pub fn config_value(config: &PolisConfig, key: &str) -> Result<serde_json::Value> {
+    Ok(match key {
+        "security.level" => config.security.level.clone().into(),
+        "update.check" => config.update.check.into(),
+        "registry.prefix" => config
+            .registry
+            .prefix
+            .as_deref()
+            .unwrap_or(DEFAULT_REGISTRY_PREFIX)
+            .into(),
+        _ => return Err(unknown_key(key).into()),
+    })
+}
+
+/// Validates `value` and stores it under `key` in `config`.
+///
+/// # Errors
+///
+/// Returns an error if the key is unknown or the value is not valid for it.
+pub fn set_config_value(config: &mut PolisConfig, key: &str, value: &str) -> Result<()> {
+    validate_config_key(key)?;
+    validate_config_value(key, value)?;
+    match key {
+        "security.level" => config.security.level = value.to_string(),
+        "update.check" => config.update.check = value == "true",
+        "registry.prefix" => config.registry.prefix = Some(value.to_string()),
+        _ => return Err(unknown_key(key).into()),
+    }
+    Ok(())
+}
+
+/// Checks that `config` would survive a save and reload: it serializes to
+/// YAML that parses back into the same settings, and every setting read
+/// back is valid.
+///
+/// # Errors
+///
+/// Returns an error describing the first setting that would not load back.
+pub fn check_round_trip(config: &PolisConfig) -> Result<()> {
+    let yaml = serde_yaml::to_string(config).context("cannot serialize config")?;
+    let back: PolisConfig =
+        serde_yaml::from_str(&yaml).context("config would not parse when loaded")?;
+    let reloaded = serde_yaml::to_string(&back).context("cannot serialize config")?;
+    anyhow::ensure!(reloaded == yaml, "config would change when loaded");
+    validate_config_value("security.level", &back.security.level)?;
+    if let Some(prefix) = &back.registry.prefix {
+        validate_registry_prefix(prefix)?;
+    }
     Ok(())
 }
 
+// ── Registry ─────────────────────────────────────────────────────────────────
+
+/// Validates a registry prefix of the form `host[:port][/path...]`.
+///
+/// The host is a lowercase DNS name (or `localhost`) with an optional numeric
+/// port; each path component is lowercase alphanumeric, optionally joined by
+/// `.`, `_`, or `-`. Schemes, tags, digests, and trailing slashes are
+/// rejected because the prefix is joined with `/{image}:{tag}`.
+///
+/// # Errors
+///
+/// Returns an error if the prefix is not a well-formed registry path.
+pub fn validate_registry_prefix(prefix: &str) -> Result<()> {
+    let invalid = || ConfigError::InvalidValue {
+        key: "registry.prefix".to_string(),
+        value: prefix.to_string(),
+        valid: format!("a registry path such as {DEFAULT_REGISTRY_PREFIX} or localhost:5000/polis"),
+    };
+    let mut parts = prefix.split('/');
+    let host_port = parts.next().unwrap_or_default();
+    let (host, port) = match host_port.split_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (host_port, None),
+    };
+    let host_ok = !host.is_empty()
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        });
+    let port_ok = port.is_none_or(|p| !p.is_empty() && p.parse::<u16>().is_ok());
+    if host_ok && port_ok && parts.all(is_path_component) {
+        Ok(())
+    } else {
+        Err(invalid().into())
+    }
+}
+
+/// One `/`-separated component of an image repository path.
+fn is_path_component(component: &str) -> bool {
+    let bytes = component.as_bytes();
+    let alnum = |b: &u8| b.is_ascii_lowercase() || b.is_ascii_digit();
+    bytes.first().is_some_and(alnum)
+        && bytes.last().is_some_and(alnum)
+        && bytes
+            .iter()
+            .all(|b| alnum(b) || matches!(b, b'.' | b'_' | b'-'))
+}
+
+/// Pick the effective registry prefix: `POLIS_REGISTRY_PREFIX` when set and
+/// non-empty, then `registry.prefix` from the config file, then
+/// [`DEFAULT_REGISTRY_PREFIX`].
+///
+/// # Errors
+///
+/// Returns an error if the chosen prefix is not a well-formed registry path.
+pub fn resolve_registry_prefix(env: Option<&str>, config: &RegistryConfig) -> Result<String> {
+    let prefix = env
+        .filter(|v| !v.is_empty())
+        .or(config.prefix.as_deref())
+        .unwrap_or(DEFAULT_REGISTRY_PREFIX);
+    validate_registry_prefix(prefix)?;
+    Ok(prefix.to_string())
+}
+
+/// Move an image reference published under [`DEFAULT_REGISTRY_PREFIX`] to
+/// `prefix`. References under any other registry are returned unchanged.
+#[must_use]
+pub fn rebase_image_ref(reference: &str, prefix: &str) -> String {
+    reference
+        .strip_prefix(DEFAULT_REGISTRY_PREFIX)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .map_or_else(|| reference.to_string(), |rest| format!("{prefix}/{rest}"))
+}
+
 // ── Unit tests ───────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -91,6 +284,7 @@ mod tests {
     fn test_polis_config_default_security_level_is_balanced() {
         let cfg = PolisConfig::default();
         assert_eq!(cfg.security.level, "balanced");
+        assert!(cfg.update.check);
     }
 
     #[test]
@@ -182,4 +376,167 @@ mod tests {
         assert!(err.contains("balanced"), "got: {err}");
         assert!(err.contains("strict"), "got: {err}");
     }
+
+    #[test]
+    fn test_validate_config_value_update_check_accepts_bools_only() {
+        assert!(validate_config_value("update.check", "false").is_ok());
+        assert!(validate_config_value("update.check", "true").is_ok());
+        assert!(validate_config_value("update.check", "no").is_err());
+    }
+
+    // ── get / set ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_config_value_reads_each_key() {
+        let mut cfg = PolisConfig::default();
+        assert_eq!(
+            config_value(&cfg, "security.level").unwrap(),
+            serde_json::json!("balanced")
+        );
+        assert_eq!(
+            config_value(&cfg, "update.check").unwrap(),
+            serde_json::json!(true)
+        );
+        assert_eq!(
+            config_value(&cfg, "registry.prefix").unwrap(),
+            serde_json::json!(DEFAULT_REGISTRY_PREFIX)
+        );
+        cfg.registry.prefix = Some("localhost:5000/polis".to_string());
+        assert_eq!(
+            config_value(&cfg, "registry.prefix").unwrap(),
+            serde_json::json!("localhost:5000/polis")
+        );
+    }
+
+    #[test]
+    fn test_config_value_unknown_key_lists_valid_keys() {
+        let err = config_value(&PolisConfig::default(), "defaults.agent")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unknown setting"), "got: {err}");
+        assert!(err.contains("registry.prefix"), "got: {err}");
+    }
+
+    #[test]
+    fn test_set_config_value_round_trips_through_get() {
+        let mut cfg = PolisConfig::default();
+        for (key, value, expected) in [
+            ("security.level", "strict", serde_json::json!("strict")),
+            ("update.check", "false", serde_json::json!(false)),
+            (
+                "registry.prefix",
+                "localhost:5000",
+                serde_json::json!("localhost:5000"),
+            ),
+        ] {
+            set_config_value(&mut cfg, key, value).unwrap();
+            assert_eq!(config_value(&cfg, key).unwrap(), expected, "{key}");
+        }
+        assert!(check_round_trip(&cfg).is_ok());
+    }
+
+    #[test]
+    fn test_set_config_value_rejects_invalid_value_unchanged() {
+        let mut cfg = PolisConfig::default();
+        assert!(set_config_value(&mut cfg, "security.level", "permissive").is_err());
+        assert!(set_config_value(&mut cfg, "nope", "true").is_err());
+        assert_eq!(cfg.security.level, "balanced");
+    }
+
+    #[test]
+    fn test_check_round_trip_rejects_invalid_loaded_settings() {
+        let mut cfg = PolisConfig::default();
+        cfg.security.level = "permissive".to_string();
+        let err = check_round_trip(&cfg).unwrap_err().to_string();
+        assert!(err.contains("security.level"), "got: {err}");
+
+        let mut cfg = PolisConfig::default();
+        cfg.registry.prefix = Some("https://ghcr.io".to_string());
+        assert!(check_round_trip(&cfg).is_err());
+    }
+
+    // ── registry prefix ──────────────────────────────────────────────────────
+
+    #[test]
+    fn test_validate_registry_prefix_accepts_registry_paths() {
+        for prefix in [
+            DEFAULT_REGISTRY_PREFIX,
+            "localhost:5000",
+            "localhost:5000/polis",
+            "registry.example.com/mirrors/odra-labs",
+            "10.0.0.5:443/polis_images",
+        ] {
+            assert!(validate_registry_prefix(prefix).is_ok(), "{prefix}");
+        }
+    }
+
+    #[test]
+    fn test_validate_registry_prefix_rejects_malformed_paths() {
+        for prefix in [
+            "",
+            "https://ghcr.io/odralabshq",
+            "ghcr.io/odralabshq/",
+            "ghcr.io//odralabshq",
+            "GHCR.io/odralabshq",
+            "ghcr.io/OdraLabsHQ",
+            "ghcr.io/odralabshq:v1",
+            "ghcr.io/odralabshq@sha256:abc",
+            "localhost:port/polis",
+            "-bad.io/polis",
+            "ghcr.io/odralabshq polis",
+        ] {
+            assert!(validate_registry_prefix(prefix).is_err(), "{prefix}");
+        }
+    }
+
+    #[test]
+    fn test_validate_config_value_registry_prefix() {
+        assert!(validate_config_value("registry.prefix", "localhost:5000/polis").is_ok());
+        let err = validate_config_value("registry.prefix", "https://ghcr.io")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("registry.prefix"), "got: {err}");
+    }
+
+    #[test]
+    fn test_resolve_registry_prefix_defaults_to_ghcr() {
+        let prefix = resolve_registry_prefix(None, &RegistryConfig::default()).expect("default");
+        assert_eq!(prefix, DEFAULT_REGISTRY_PREFIX);
+    }
+
+    #[test]
+    fn test_resolve_registry_prefix_env_overrides_config() {
+        let config = RegistryConfig {
+            prefix: Some("mirror.example.com/polis".to_string()),
+        };
+        assert_eq!(
+            resolve_registry_prefix(None, &config).expect("config"),
+            "mirror.example.com/polis"
+        );
+        assert_eq!(
+            resolve_registry_prefix(Some("localhost:5000"), &config).expect("env"),
+            "localhost:5000"
+        );
+        assert_eq!(
+            resolve_registry_prefix(Some(""), &config).expect("empty env"),
+            "mirror.example.com/polis"
+        );
+        assert!(resolve_registry_prefix(Some("ghcr.io/x/"), &config).is_err());
+    }
+
+    #[test]
+    fn test_rebase_image_ref_only_moves_default_registry() {
+        assert_eq!(
+            rebase_image_ref("ghcr.io/odralabshq/polis-gate:v1.0", "localhost:5000"),
+            "localhost:5000/polis-gate:v1.0"
+        );
+        assert_eq!(
+            rebase_image_ref("docker.io/library/redis:7", "localhost:5000"),
+            "docker.io/library/redis:7"
+        );
+        assert_eq!(
+            rebase_image_ref("ghcr.io/odralabshqx/polis:v1", "localhost:5000"),
+            "ghcr.io/odralabshqx/polis:v1"
+        );
+    }
 }
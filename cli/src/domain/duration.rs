@@ -0,0 +1,175 @@
+//! Duration values for CLI flags (`--timeout`, `--launch-timeout`,
+//! `polis logs --since`) — pure, no I/O.
+
+use std::time::Duration;
+
+const SECS_PER_UNIT: [(char, u64); 4] = [('d', 86_400), ('h', 3_600), ('m', 60), ('s', 1)];
+
+/// Parse a duration such as `300`, `90s`, `10m`, `2h`, `1d` or `1h30m`.
+///
+/// A bare number is seconds. Suffixed amounts may be combined, largest unit
+/// first and each unit at most once (`1d12h`, `1h30m`). Zero, negative,
+/// fractional and overflowing values are rejected, as is whitespace inside
+/// the value. The error is a message suitable for clap's `value_parser`.
+///
+/// # Errors
+///
+/// Returns an error message if `s` is not a valid, positive duration.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let invalid =
+        || format!("invalid duration '{s}': use seconds (300) or 90s, 10m, 2h, 1d, 1h30m");
+    if s.is_empty() {
+        return Err(invalid());
+    }
+    if s.starts_with('-') {
+        return Err(format!("invalid duration '{s}': must not be negative"));
+    }
+    if s.bytes().all(|b| b.is_ascii_digit()) {
+        let secs: u64 = s.parse().map_err(|_| invalid())?;
+        return positive(secs, s);
+    }
+
+    let mut total: u64 = 0;
+    let mut rest = s;
+    let mut units = SECS_PER_UNIT.iter();
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .filter(|&i| i > 0)
+            .ok_or_else(invalid)?;
+        let (amount, tail) = rest.split_at(split);
+        let unit = tail.chars().next().ok_or_else(invalid)?;
+        // Units must appear in descending order, each at most once.
+        let &(_, per_unit) = units.find(|(u, _)| *u == unit).ok_or_else(invalid)?;
+        let secs = amount
+            .parse::<u64>()
+            .ok()
+            .and_then(|n| n.checked_mul(per_unit))
+            .and_then(|n| n.checked_add(total))
+            .ok_or_else(|| format!("invalid duration '{s}': too large"))?;
+        total = secs;
+        rest = &tail[unit.len_utf8()..];
+    }
+    positive(total, s)
+}
+
+/// [`parse_duration`], also requiring the result to be between `min` and
+/// `max` inclusive.
+///
+/// # Errors
+///
+/// Returns an error message if `s` is not a valid duration or is out of range.
+pub fn parse_duration_within(s: &str, min: Duration, max: Duration) -> Result<Duration, String> {
+    let d = parse_duration(s)?;
+    if d < min || d > max {
+        return Err(format!(
+            "invalid duration '{}': must be between {} and {}",
+            s.trim(),
+            format_duration(min),
+            format_duration(max)
+        ));
+    }
+    Ok(d)
+}
+
+/// Format `d` the way Docker and Go print durations: `1h30m`, `24h`, `45s`.
+#[must_use]
+pub fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    let (h, m, s) = (secs / 3_600, secs % 3_600 / 60, secs % 60);
+    let mut out = String::new();
+    for (n, unit) in [(h, 'h'), (m, 'm'), (s, 's')] {
+        if n > 0 {
+            out.push_str(&n.to_string());
+            out.push(unit);
+        }
+    }
+    if out.is_empty() {
+        out.push_str("0s");
+    }
+    out
+}
+
+fn positive(secs: u64, s: &str) -> Result<Duration, String> {
+    if secs == 0 {
+        return Err(format!("invalid duration '{s}': must be greater than zero"));
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn secs(s: &str) -> u64 {
+        parse_duration(s).expect(s).as_secs()
+    }
+
+    #[test]
+    fn test_parse_duration_bare_seconds_and_units() {
+        assert_eq!(secs("300"), 300);
+        assert_eq!(secs("90s"), 90);
+        assert_eq!(secs("10m"), 600);
+        assert_eq!(secs("2h"), 7_200);
+        assert_eq!(secs("1d"), 86_400);
+        assert_eq!(secs(" 5m "), 300);
+    }
+
+    #[test]
+    fn test_parse_duration_accepts_compound_largest_unit_first() {
+        assert_eq!(secs("1h30m"), 5_400);
+        assert_eq!(secs("1d12h"), 129_600);
+        assert_eq!(secs("2m5s"), 125);
+        for bad in ["30m1h", "1h1h", "5s5"] {
+            assert!(parse_duration(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_zero_and_negative() {
+        for zero in ["0", "0s", "0h0m"] {
+            let err = parse_duration(zero).expect_err(zero);
+            assert!(err.contains("greater than zero"), "{zero}: {err}");
+        }
+        let err = parse_duration("-5m").expect_err("negative");
+        assert!(err.contains("negative"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_duration_within_enforces_bounds() {
+        let (min, max) = (Duration::from_secs(1), Duration::from_secs(3_600));
+        assert_eq!(
+            parse_duration_within("30s", min, max),
+            Ok(Duration::from_secs(30))
+        );
+        assert_eq!(parse_duration_within("1h", min, max), Ok(max));
+        let err = parse_duration_within("1h1s", min, max).expect_err("too long");
+        assert!(err.contains("between 1s and 1h"), "{err}");
+        assert!(parse_duration_within("0", min, max).is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_overflow() {
+        let err = parse_duration("999999999999999d").expect_err("overflow");
+        assert!(err.contains("too large"), "{err}");
+        assert!(parse_duration("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_garbage() {
+        for bad in ["", "m", "10w", "10 m", "1.5h", "h1", "1h 30m", "ten"] {
+            let err = parse_duration(bad).expect_err(bad);
+            assert!(err.contains("invalid duration"), "{bad}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_format_duration_round_trips() {
+        for s in ["45s", "10m", "1h30m", "24h", "1m30s"] {
+            assert_eq!(format_duration(parse_duration(s).expect(s)), s);
+        }
+        assert_eq!(format_duration(Duration::from_secs(86_400)), "24h");
+    }
+}
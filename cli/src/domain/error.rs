@@ -5,8 +5,13 @@
 //! All error types implement `thiserror::Error` and convert to `anyhow::Error`
 //! via the `?` operator.
 
+use std::borrow::Cow;
+
 use thiserror::Error;
 
+use crate::domain::agent::services::PLATFORM_SERVICES;
+use crate::domain::agent::validate::{ALLOWED_RW_PREFIXES, PLATFORM_PORTS};
+
 // ── Workspace errors ──────────────────────────────────────────────────────────
 
 /// Errors related to workspace lifecycle and identity.
@@ -48,6 +53,172 @@ pub enum AgentError {
     ValidationFailed(String),
 }
 
+/// Declares [`ManifestRule`] and [`ManifestRule::ALL`] from one variant
+/// list, so a new rule cannot be left out of `ALL`.
+macro_rules! manifest_rules {
+    ($($(#[$doc:meta])* $rule:ident,)+) => {
+        /// A rule `agent.yaml` must follow. Every manifest violation names the
+        /// rule it broke so `polis agent validate --explain` can say why the
+        /// rule exists and show a corrected example.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+        #[serde(rename_all = "snake_case")]
+        pub enum ManifestRule {
+            $($(#[$doc])* $rule,)+
+        }
+
+        impl ManifestRule {
+            /// Every rule, in the order the validator checks them.
+            pub const ALL: &'static [Self] = &[$(Self::$rule),+];
+        }
+    };
+}
+
+manifest_rules! {
+    /// `agent.yaml` is not valid YAML or is missing required fields.
+    Parse,
+    ApiVersion,
+    Kind,
+    Name,
+    Packaging,
+    CommandAbsolute,
+    CommandMetacharacters,
+    UnprivilegedUser,
+    PathEscape,
+    ReservedPort,
+    ReadWritePath,
+    PlatformService,
+    Label,
+    PersistenceName,
+    PersistencePath,
+}
+
+/// `["a", "b", "c"]` → `a, b and c`.
+fn and_list(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.clone(),
+        [init @ .., last] => format!("{} and {last}", init.join(", ")),
+    }
+}
+
+impl ManifestRule {
+    /// Why the rule exists, in a sentence or two.
+    #[must_use]
+    pub fn explanation(self) -> Cow<'static, str> {
+        let text = match self {
+            Self::Parse => {
+                "agent.yaml must be valid YAML with apiVersion, kind, metadata (name, \
+                 displayName, version, description) and spec (packaging, install, runtime)."
+            }
+            Self::ApiVersion => {
+                "apiVersion pins the manifest format; polis only understands polis.dev/v1."
+            }
+            Self::Kind => "kind tells polis what the manifest describes; agents are AgentPlugin.",
+            Self::Name => {
+                "metadata.name becomes part of file paths, systemd units and Docker names, so it \
+                 is limited to lowercase letters, digits and interior hyphens (at most 63)."
+            }
+            Self::Packaging => {
+                "Agents are installed by running their install script; 'script' is the only \
+                 packaging polis supports."
+            }
+            Self::CommandAbsolute => {
+                "runtime.command is executed directly by systemd, which needs an absolute path \
+                 to the program rather than a name looked up on PATH."
+            }
+            Self::CommandMetacharacters => {
+                "runtime.command is not run through a shell, and metacharacters could smuggle \
+                 extra commands into the unit. Put pipelines or redirections in a script and \
+                 point runtime.command at it."
+            }
+            Self::UnprivilegedUser => {
+                "Agents run untrusted, model-driven code; running them as root would undo the \
+                 workspace's isolation. Use the unprivileged polis user."
+            }
+            Self::PathEscape => {
+                "spec.install and spec.init are resolved inside the agent folder; '..' could make \
+                 polis run a file from outside it."
+            }
+            Self::ReservedPort => {
+                let ports: Vec<String> = PLATFORM_PORTS.iter().map(u16::to_string).collect();
+                return format!(
+                    "The workspace's own services (DNS, ICAP, Valkey, the proxy) already listen \
+                     on ports {}; an agent port there would shadow them.",
+                    and_list(&ports)
+                )
+                .into();
+            }
+            Self::ReadWritePath => {
+                let prefixes: Vec<String> = ALLOWED_RW_PREFIXES
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                return format!(
+                    "The agent's filesystem is read-only apart from readWritePaths, which are \
+                     limited to {} so an agent cannot make system directories writable.",
+                    and_list(&prefixes)
+                )
+                .into();
+            }
+            Self::PlatformService => {
+                let services: Vec<String> =
+                    PLATFORM_SERVICES.iter().map(ToString::to_string).collect();
+                return format!(
+                    "requirements.services names control-plane services that must be running \
+                     before the agent is reported ready; only {} exist.",
+                    and_list(&services)
+                )
+                .into();
+            }
+            Self::Label => {
+                "Labels are matched by 'polis agent list --label', so keys are lowercase and both \
+                 keys and values are 1-63 alphanumeric characters with interior '-', '_' or '.'."
+            }
+            Self::PersistenceName => {
+                "Each persistence entry becomes a Docker volume named \
+                 polis-agent-<agent>-<name>, so the name must be a valid volume name suffix."
+            }
+            Self::PersistencePath => {
+                "containerPath is where the volume is mounted in the workspace; it must be \
+                 absolute and must not climb out with '..'."
+            }
+        };
+        Cow::Borrowed(text)
+    }
+
+    /// A corrected `agent.yaml` fragment that follows the rule.
+    #[must_use]
+    pub fn example(self) -> &'static str {
+        match self {
+            Self::Parse => {
+                "apiVersion: polis.dev/v1\nkind: AgentPlugin\nmetadata:\n  name: my-agent\n  \
+                 displayName: \"My Agent\"\n  version: \"0.1.0\"\n  description: \"...\"\n\
+                 spec:\n  packaging: script\n  install: install.sh\n  runtime:\n    \
+                 command: /usr/bin/my-agent\n    workdir: /app\n    user: polis"
+            }
+            Self::ApiVersion => "apiVersion: polis.dev/v1",
+            Self::Kind => "kind: AgentPlugin",
+            Self::Name => "metadata:\n  name: my-agent",
+            Self::Packaging => "spec:\n  packaging: script\n  install: install.sh",
+            Self::CommandAbsolute => "runtime:\n  command: /usr/bin/node dist/index.js",
+            Self::CommandMetacharacters => "runtime:\n  command: /app/start.sh --port 3000",
+            Self::UnprivilegedUser => "runtime:\n  user: polis",
+            Self::PathEscape => "spec:\n  install: scripts/install.sh",
+            Self::ReservedPort => {
+                "ports:\n  - container: 3000\n    hostEnv: MY_AGENT_PORT\n    default: 3000"
+            }
+            Self::ReadWritePath => {
+                "security:\n  readWritePaths:\n    - /home/polis/.my-agent/\n    - /tmp/my-agent/"
+            }
+            Self::PlatformService => "requirements:\n  services:\n    - scanner",
+            Self::Label => "metadata:\n  labels:\n    team: payments",
+            Self::PersistenceName | Self::PersistencePath => {
+                "persistence:\n  - name: data\n    containerPath: /home/polis/.my-agent"
+            }
+        }
+    }
+}
+
 // ── Config errors ─────────────────────────────────────────────────────────────
 
 /// Errors related to configuration key/value validation.
@@ -63,3 +234,33 @@ pub enum ConfigError {
         valid: String,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explanations_list_current_platform_constants() {
+        let ports = ManifestRule::ReservedPort.explanation();
+        assert!(ports.contains("53, 1344, 6379, 8080 and 18080"), "{ports}");
+        for service in PLATFORM_SERVICES {
+            let text = ManifestRule::PlatformService.explanation();
+            assert!(text.contains(service), "{text}");
+        }
+        for prefix in ALLOWED_RW_PREFIXES {
+            let text = ManifestRule::ReadWritePath.explanation();
+            assert!(text.contains(prefix), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_every_manifest_rule_has_explanation_and_example() {
+        for rule in ManifestRule::ALL {
+            assert!(!rule.explanation().trim().is_empty(), "{rule:?}");
+            assert!(
+                serde_yaml::from_str::<serde_yaml::Value>(rule.example()).is_ok(),
+                "{rule:?} example must be valid YAML"
+            );
+        }
+    }
+}
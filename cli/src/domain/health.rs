@@ -43,6 +43,26 @@ pub struct WorkspaceChecks {
     pub disk_space_ok: bool,
     /// Image cache status.
     pub image: ImageCheckResult,
+    /// Service versions in the VM `.env` that differ from the CLI version.
+    /// `None` if the `.env` could not be read (e.g. VM not running).
+    pub version_drift: Option<Vec<VersionDrift>>,
+    /// Whether the VM `.env` no longer matches the hash recorded at
+    /// provisioning. `None` if there is no record or the `.env` is unreadable.
+    pub env_modified: Option<bool>,
+    /// Whether `~/.polis/known_hosts` pins the workspace's current SSH host
+    /// key. `None` if the key could not be read (e.g. VM not running).
+    pub host_key_pinned: Option<bool>,
+}
+
+/// A `POLIS_*_VERSION` entry in the VM `.env` that differs from the CLI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VersionDrift {
+    /// Variable name, e.g. `POLIS_GATE_VERSION`.
+    pub var: String,
+    /// Tag currently deployed in the VM, e.g. `v0.3.0`.
+    pub deployed: String,
+    /// Tag matching the running CLI, e.g. `v0.4.0`.
+    pub expected: String,
 }
 
 /// Result of image health checks.
@@ -81,6 +101,40 @@ pub struct SecurityChecks {
     pub certificates_expire_days: i64,
 }
 
+/// How `polis doctor --fix` deals with an issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Remedy {
+    /// Re-transfer config, then run the workspace repair.
+    ReprovisionConfig,
+    /// Run the workspace repair (Docker, sysbox, services).
+    RestartServices,
+    /// Regenerate certificates and secrets, then restart services.
+    RegenerateCerts,
+    /// Re-pin the workspace SSH host key in `~/.polis/known_hosts`.
+    RepinHostKey,
+    /// Needs the user; the text says what to do.
+    Manual(&'static str),
+}
+
+/// A failing check and how to remedy it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoctorIssue {
+    /// Human-readable description.
+    pub message: String,
+    /// What `--fix` will do about it.
+    pub remedy: Remedy,
+}
+
+/// Remedy for an issue inside the VM, which can only be applied while the
+/// workspace runs.
+fn in_vm(checks: &DoctorChecks, remedy: Remedy) -> Remedy {
+    if checks.workspace.ready {
+        remedy
+    } else {
+        Remedy::Manual("start the workspace first: polis start")
+    }
+}
+
 // ── Pure functions ────────────────────────────────────────────────────────────
 
 /// Collect actionable issues from check results.
@@ -90,41 +144,111 @@ pub struct SecurityChecks {
 /// included in the returned issues list.
 #[must_use]
 pub fn collect_issues(checks: &DoctorChecks) -> Vec<String> {
+    doctor_issues(checks)
+        .into_iter()
+        .map(|issue| issue.message)
+        .collect()
+}
+
+/// The issues of [`collect_issues`], each with the remedy `--fix` applies.
+#[must_use]
+pub fn doctor_issues(checks: &DoctorChecks) -> Vec<DoctorIssue> {
     let mut issues = Vec::new();
+    let mut push = |message: String, remedy: Remedy| issues.push(DoctorIssue { message, remedy });
     if !checks.prerequisites.multipass_found {
-        issues.push("multipass is not installed".to_string());
+        push(
+            "multipass is not installed".to_string(),
+            Remedy::Manual("install Multipass ≥ 1.16.0: https://multipass.run/install"),
+        );
     } else if !checks.prerequisites.multipass_version_ok {
         let ver = checks
             .prerequisites
             .multipass_version
             .as_deref()
             .unwrap_or("unknown");
-        issues.push(format!("Multipass {ver} is too old (need ≥ 1.16.0)"));
+        push(
+            format!("Multipass {ver} is too old (need ≥ 1.16.0)"),
+            Remedy::Manual("upgrade Multipass: https://multipass.run/install"),
+        );
     }
     if !checks.workspace.disk_space_ok {
-        issues.push(format!(
-            "Low disk space ({} GB available, need 10 GB)",
-            checks.workspace.disk_space_gb,
-        ));
+        push(
+            format!(
+                "Low disk space ({} GB available, need 10 GB)",
+                checks.workspace.disk_space_gb,
+            ),
+            Remedy::Manual("free up disk space"),
+        );
+    }
+    if let Some(drift) = checks.workspace.version_drift.as_deref()
+        && !drift.is_empty()
+    {
+        push(
+            format!(
+                "{} service version(s) differ from CLI — run 'polis update'",
+                drift.len()
+            ),
+            Remedy::Manual("run: polis update"),
+        );
+    }
+    if checks.workspace.env_modified == Some(true) {
+        push(
+            "VM .env was modified outside polis (hash mismatch)".to_string(),
+            in_vm(checks, Remedy::ReprovisionConfig),
+        );
+    }
+    if checks.workspace.host_key_pinned == Some(false) {
+        push(
+            "Workspace SSH host key is not pinned in ~/.polis/known_hosts".to_string(),
+            in_vm(checks, Remedy::RepinHostKey),
+        );
     }
     if !checks.network.dns {
-        issues.push("DNS resolution failed".to_string());
+        push(
+            "DNS resolution failed".to_string(),
+            Remedy::Manual("check the host's network and DNS settings"),
+        );
     }
     if !checks.security.traffic_inspection {
-        issues.push("Traffic inspection not responding".to_string());
+        push(
+            "Traffic inspection not responding".to_string(),
+            in_vm(checks, Remedy::RestartServices),
+        );
     }
     if !checks.security.malware_db_current {
-        issues.push(format!(
-            "Malware scanner database stale (updated: {}h ago)",
-            checks.security.malware_db_age_hours
-        ));
+        push(
+            format!(
+                "Malware scanner database stale (updated: {}h ago)",
+                checks.security.malware_db_age_hours
+            ),
+            Remedy::Manual("the scanner refreshes it when online; check network access"),
+        );
     }
     if checks.security.certificates_expire_days <= 0 {
-        issues.push("Certificates expired".to_string());
+        push(
+            "Certificates expired".to_string(),
+            in_vm(checks, Remedy::RegenerateCerts),
+        );
     }
     issues
 }
 
+/// Compare the `POLIS_*_VERSION` entries of a `.env` file against the CLI
+/// version. Returns one [`VersionDrift`] per entry whose tag is not
+/// `v{cli_version}`; blank lines, comments, and other variables are ignored.
+#[must_use]
+pub fn find_version_drift(env_content: &str, cli_version: &str) -> Vec<VersionDrift> {
+    let expected = format!("v{cli_version}");
+    crate::domain::update::service_versions(env_content)
+        .filter(|(_, tag)| *tag != expected)
+        .map(|(var, tag)| VersionDrift {
+            var: var.to_string(),
+            deployed: tag.to_string(),
+            expected: expected.clone(),
+        })
+        .collect()
+}
+
 // ── Unit tests ────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -143,6 +267,9 @@ mod tests {
                 disk_space_gb: 50,
                 disk_space_ok: true,
                 image: ImageCheckResult::default(),
+                version_drift: Some(Vec::new()),
+                env_modified: Some(false),
+                host_key_pinned: Some(true),
             },
             network: NetworkChecks {
                 internet: true,
@@ -164,6 +291,38 @@ mod tests {
         assert!(collect_issues(&all_healthy()).is_empty());
     }
 
+    #[test]
+    fn test_doctor_issues_assigns_remedies() {
+        let mut checks = all_healthy();
+        checks.prerequisites.multipass_found = false;
+        checks.workspace.host_key_pinned = Some(false);
+        checks.security.certificates_expire_days = 0;
+        checks.security.traffic_inspection = false;
+        let remedies: Vec<Remedy> = doctor_issues(&checks)
+            .into_iter()
+            .map(|i| i.remedy)
+            .collect();
+        assert!(matches!(remedies[0], Remedy::Manual(hint) if hint.contains("multipass.run")));
+        assert_eq!(
+            remedies[1..],
+            [
+                Remedy::RepinHostKey,
+                Remedy::RestartServices,
+                Remedy::RegenerateCerts
+            ]
+        );
+    }
+
+    #[test]
+    fn test_doctor_issues_vm_remedies_need_running_workspace() {
+        let mut checks = all_healthy();
+        checks.workspace.ready = false;
+        checks.security.certificates_expire_days = 0;
+        let issues = doctor_issues(&checks);
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(issues[0].remedy, Remedy::Manual(hint) if hint.contains("polis start")));
+    }
+
     #[test]
     fn test_collect_issues_low_disk_returns_disk_issue() {
         let mut checks = all_healthy();
@@ -240,6 +399,64 @@ mod tests {
         assert!(issues[0].contains("too old"));
     }
 
+    #[test]
+    fn test_collect_issues_version_drift_returns_update_hint() {
+        let mut checks = all_healthy();
+        checks.workspace.version_drift =
+            Some(find_version_drift("POLIS_GATE_VERSION=v0.3.0\n", "0.4.0"));
+        let issues = collect_issues(&checks);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("polis update"));
+    }
+
+    #[test]
+    fn test_collect_issues_env_modified_returns_issue() {
+        let mut checks = all_healthy();
+        checks.workspace.env_modified = Some(true);
+        let issues = collect_issues(&checks);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("modified outside polis"));
+    }
+
+    #[test]
+    fn test_collect_issues_unchecked_versions_not_an_issue() {
+        let mut checks = all_healthy();
+        checks.workspace.version_drift = None;
+        assert!(collect_issues(&checks).is_empty());
+    }
+
+    #[test]
+    fn test_find_version_drift_matching_env_is_empty() {
+        let env = "# Generated by polis CLI v0.4.0\n\
+                   POLIS_GATE_VERSION=v0.4.0\n\
+                   POLIS_TOOLBOX_VERSION=v0.4.0\n";
+        assert!(find_version_drift(env, "0.4.0").is_empty());
+    }
+
+    #[test]
+    fn test_find_version_drift_reports_mismatched_vars() {
+        let env = "# Generated by polis CLI v0.3.0\n\
+                   POLIS_GATE_VERSION=v0.3.0\n\
+                   POLIS_SENTINEL_VERSION=v0.4.0\n\
+                   POLIS_TOOLBOX_VERSION=v0.3.1\n\
+                   OTHER_VAR=v0.1.0\n";
+        assert_eq!(
+            find_version_drift(env, "0.4.0"),
+            vec![
+                VersionDrift {
+                    var: "POLIS_GATE_VERSION".to_string(),
+                    deployed: "v0.3.0".to_string(),
+                    expected: "v0.4.0".to_string(),
+                },
+                VersionDrift {
+                    var: "POLIS_TOOLBOX_VERSION".to_string(),
+                    deployed: "v0.3.1".to_string(),
+                    expected: "v0.4.0".to_string(),
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_image_check_result_default_is_not_cached() {
         let result = ImageCheckResult::default();
@@ -0,0 +1,314 @@
+//! Typed views of `multipass info`, `multipass list --snapshots` and
+//! `docker compose ps` output — pure, no I/O.
+//!
+//! The inspector and executor ports parse command output here once, so
+//! services consume structured data instead of re-reading raw JSON.
+
+use serde::Deserialize;
+
+use crate::domain::workspace::COMPOSE_PATH;
+
+/// Instance state as reported by `multipass info`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceState {
+    /// Multipass has no instance with this name.
+    NotFound,
+    Starting,
+    Running,
+    Stopping,
+    Stopped,
+    /// Any other state (`Suspended`, `Deleted`, `Unknown`, …), or no state
+    /// reported for the instance.
+    Unknown,
+}
+
+impl InstanceState {
+    fn from_multipass(state: &str) -> Self {
+        match state {
+            "Starting" => Self::Starting,
+            "Running" => Self::Running,
+            "Stopping" => Self::Stopping,
+            "Stopped" => Self::Stopped,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// The parts of `multipass info --format json` polis uses for one instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceInfo {
+    pub state: InstanceState,
+    /// IPv4 addresses, primary first.
+    pub ipv4: Vec<String>,
+}
+
+impl InstanceInfo {
+    /// Info for an instance multipass does not know about.
+    #[must_use]
+    pub fn not_found() -> Self {
+        Self {
+            state: InstanceState::NotFound,
+            ipv4: Vec::new(),
+        }
+    }
+}
+
+/// Whether `multipass info` stderr says the instance does not exist, as
+/// opposed to multipass itself failing.
+#[must_use]
+pub fn is_missing_instance(stderr: &str) -> bool {
+    stderr.contains("does not exist") || stderr.contains("was not found")
+}
+
+#[derive(Deserialize)]
+struct MultipassInfo {
+    #[serde(default)]
+    info: std::collections::HashMap<String, MultipassInstance>,
+}
+
+#[derive(Deserialize)]
+struct MultipassInstance {
+    state: Option<String>,
+    #[serde(default)]
+    ipv4: Vec<String>,
+}
+
+/// Parse `multipass info --format json` output for `instance`.
+///
+/// An instance missing from the output has `Unknown` state and no addresses.
+///
+/// # Errors
+///
+/// Returns an error if `stdout` is not the expected JSON shape.
+pub fn parse_instance_info(stdout: &[u8], instance: &str) -> serde_json::Result<InstanceInfo> {
+    let mut info: MultipassInfo = serde_json::from_slice(stdout)?;
+    let entry = info.info.remove(instance);
+    Ok(InstanceInfo {
+        state: entry
+            .as_ref()
+            .and_then(|e| e.state.as_deref())
+            .map_or(InstanceState::Unknown, InstanceState::from_multipass),
+        ipv4: entry.map(|e| e.ipv4).unwrap_or_default(),
+    })
+}
+
+#[derive(Deserialize)]
+struct MultipassSnapshots {
+    /// Instance name → snapshot name → snapshot details.
+    #[serde(default)]
+    info: std::collections::HashMap<String, serde_json::Map<String, serde_json::Value>>,
+}
+
+/// Parse `multipass list --snapshots --format json` output into the names of
+/// `instance`'s snapshots, sorted. An instance without snapshots is absent
+/// from the output and yields an empty list.
+///
+/// # Errors
+///
+/// Returns an error if `stdout` is not the expected JSON shape.
+pub fn parse_snapshot_names(stdout: &[u8], instance: &str) -> serde_json::Result<Vec<String>> {
+    let mut list: MultipassSnapshots = serde_json::from_slice(stdout)?;
+    let mut names: Vec<String> = list
+        .info
+        .remove(instance)
+        .map(|snapshots| snapshots.into_iter().map(|(name, _)| name).collect())
+        .unwrap_or_default();
+    names.sort();
+    Ok(names)
+}
+
+/// One container from `docker compose ps --format json`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ContainerStatus {
+    #[serde(rename = "Service", default)]
+    pub service: String,
+    /// Docker state, e.g. `running` or `exited`.
+    #[serde(rename = "State")]
+    pub state: String,
+    /// Health check status (`healthy`, `starting`, …); empty or absent when
+    /// the container has no health check.
+    #[serde(rename = "Health", default)]
+    pub health: Option<String>,
+}
+
+/// Arguments for `docker compose ps --format json`, limited to `services`
+/// (all services when empty).
+#[must_use]
+pub fn compose_ps_args(services: &[&str]) -> Vec<String> {
+    [
+        "docker",
+        "compose",
+        "-f",
+        COMPOSE_PATH,
+        "ps",
+        "--format",
+        "json",
+    ]
+    .iter()
+    .chain(services)
+    .map(ToString::to_string)
+    .collect()
+}
+
+/// Parse `docker compose ps --format json` output.
+///
+/// Compose v2.21 and later print one JSON object per line; earlier
+/// releases print a single JSON array. Both are accepted.
+///
+/// # Errors
+///
+/// Returns an error if any entry is not a valid container object.
+pub fn parse_compose_ps(stdout: &[u8]) -> serde_json::Result<Vec<ContainerStatus>> {
+    let text = String::from_utf8_lossy(stdout);
+    let text = text.trim();
+    if text.starts_with('[') {
+        return serde_json::from_str(text);
+    }
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(serde_json::from_str)
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    const MULTIPASS_INFO: &[u8] = br#"{
+        "errors": [],
+        "info": {
+            "polis": {
+                "cpu_count": "2",
+                "disks": {"sda1": {"total": "42107658240", "used": "6285152256"}},
+                "image_release": "24.04 LTS",
+                "ipv4": ["10.95.143.12", "172.17.0.1"],
+                "memory": {"total": 8323899392, "used": 1474592768},
+                "release": "Ubuntu 24.04.1 LTS",
+                "state": "Running"
+            },
+            "polis-selftest": {"ipv4": [], "state": "Stopped"}
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_instance_info_reads_named_instance() {
+        let info = parse_instance_info(MULTIPASS_INFO, "polis").expect("parse");
+        assert_eq!(info.state, InstanceState::Running);
+        assert_eq!(info.ipv4, ["10.95.143.12", "172.17.0.1"]);
+
+        let info = parse_instance_info(MULTIPASS_INFO, "polis-selftest").expect("parse");
+        assert_eq!(info.state, InstanceState::Stopped);
+        assert!(info.ipv4.is_empty());
+    }
+
+    #[test]
+    fn test_parse_instance_info_missing_or_unusual_state_is_unknown() {
+        let info = parse_instance_info(MULTIPASS_INFO, "other").expect("parse");
+        assert_eq!(info.state, InstanceState::Unknown);
+        let info = parse_instance_info(br#"{"info":{"polis":{"state":"Suspended"}}}"#, "polis")
+            .expect("parse");
+        assert_eq!(info.state, InstanceState::Unknown);
+        assert!(parse_instance_info(b"{}", "polis").is_ok());
+    }
+
+    #[test]
+    fn test_parse_instance_info_rejects_invalid_json() {
+        assert!(parse_instance_info(b"info failed", "polis").is_err());
+        assert!(parse_instance_info(br#"{"info":{"polis":{"ipv4":"x"}}}"#, "polis").is_err());
+    }
+
+    #[test]
+    fn test_is_missing_instance() {
+        assert!(is_missing_instance(
+            "info failed: instance \"polis\" does not exist\n"
+        ));
+        assert!(is_missing_instance("The instance polis was not found"));
+        assert!(!is_missing_instance(
+            "cannot connect to the multipass socket"
+        ));
+    }
+
+    #[test]
+    fn test_parse_snapshot_names_lists_instance_snapshots() {
+        let stdout = br#"{
+            "errors": [],
+            "info": {
+                "polis": {
+                    "pre-upgrade": {"comment": "", "parent": ""},
+                    "clean": {"comment": "fresh install", "parent": ""}
+                },
+                "polis-selftest": {"snapshot1": {"comment": "", "parent": ""}}
+            }
+        }"#;
+        assert_eq!(
+            parse_snapshot_names(stdout, "polis").expect("parse"),
+            ["clean", "pre-upgrade"]
+        );
+        assert!(
+            parse_snapshot_names(stdout, "other")
+                .expect("parse")
+                .is_empty()
+        );
+        assert!(
+            parse_snapshot_names(br#"{"errors":[],"info":{}}"#, "polis")
+                .expect("parse")
+                .is_empty()
+        );
+        assert!(parse_snapshot_names(b"list failed", "polis").is_err());
+    }
+
+    #[test]
+    fn test_compose_ps_args_appends_services() {
+        assert_eq!(
+            compose_ps_args(&["workspace"]),
+            [
+                "docker",
+                "compose",
+                "-f",
+                COMPOSE_PATH,
+                "ps",
+                "--format",
+                "json",
+                "workspace"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_compose_ps_line_delimited() {
+        let stdout = br#"{"ID":"3f1c","Name":"polis-workspace","Service":"workspace","State":"running","Health":"healthy","ExitCode":0}
+{"ID":"9a2e","Name":"polis-gate","Service":"gate","State":"exited","Health":"","ExitCode":1}
+"#;
+        let containers = parse_compose_ps(stdout).expect("parse");
+        assert_eq!(
+            containers,
+            [
+                ContainerStatus {
+                    service: "workspace".to_string(),
+                    state: "running".to_string(),
+                    health: Some("healthy".to_string()),
+                },
+                ContainerStatus {
+                    service: "gate".to_string(),
+                    state: "exited".to_string(),
+                    health: Some(String::new()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_compose_ps_array_and_empty() {
+        let stdout = br#"[{"Service":"state","State":"running"}]"#;
+        let containers = parse_compose_ps(stdout).expect("parse");
+        assert_eq!(containers.len(), 1);
+        assert_eq!(containers[0].service, "state");
+        assert_eq!(containers[0].health, None);
+
+        assert!(parse_compose_ps(b"\n").expect("parse").is_empty());
+        assert!(parse_compose_ps(b"[]").expect("parse").is_empty());
+        assert!(parse_compose_ps(b"not json").is_err());
+    }
+}
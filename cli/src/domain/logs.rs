@@ -0,0 +1,143 @@
+//! Control-plane log queries (`polis logs`) — pure, no I/O.
+
+use std::str::FromStr;
+
+use crate::domain::duration::{format_duration, parse_duration};
+use crate::domain::workspace::COMPOSE_PATH;
+
+/// Lower time bound for `polis logs --since`, passed to
+/// `docker compose logs --since`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogSince {
+    /// Relative to now, normalised to a Docker duration such as `10m` or
+    /// `1h30m`. Days (`2d`) are converted to hours, which Docker understands.
+    Relative(String),
+    /// An RFC 3339 timestamp, passed through unchanged.
+    Timestamp(String),
+}
+
+impl LogSince {
+    /// Value for `docker compose logs --since`.
+    #[must_use]
+    pub fn as_arg(&self) -> &str {
+        match self {
+            Self::Relative(s) | Self::Timestamp(s) => s,
+        }
+    }
+}
+
+impl FromStr for LogSince {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if chrono::DateTime::parse_from_rfc3339(s).is_ok() {
+            return Ok(Self::Timestamp(s.to_string()));
+        }
+        let invalid = || {
+            format!(
+                "invalid time '{s}': use an RFC 3339 timestamp \
+                 (2024-05-01T12:00:00Z) or a duration like 30s, 10m, 2h, 1d, 1h30m"
+            )
+        };
+        // Docker reads a bare number as a Unix timestamp, so insist on a unit.
+        if s.ends_with(|c: char| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let duration = parse_duration(s).map_err(|_| invalid())?;
+        Ok(Self::Relative(format_duration(duration)))
+    }
+}
+
+/// Arguments for `docker compose logs` over the control-plane compose file.
+/// No services means all of them.
+#[must_use]
+pub fn compose_logs_args(
+    services: &[String],
+    tail: Option<u32>,
+    since: Option<&LogSince>,
+) -> Vec<String> {
+    let mut args: Vec<String> = ["docker", "compose", "-f", COMPOSE_PATH, "logs"]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    if let Some(n) = tail {
+        args.push("--tail".to_string());
+        args.push(n.to_string());
+    }
+    if let Some(since) = since {
+        args.push("--since".to_string());
+        args.push(since.as_arg().to_string());
+    }
+    args.extend(services.iter().cloned());
+    args
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_since_parses_relative_durations() {
+        let parse = |s: &str| s.parse::<LogSince>().expect("valid");
+        assert_eq!(parse("10m"), LogSince::Relative("10m".to_string()));
+        assert_eq!(parse("2h"), LogSince::Relative("2h".to_string()));
+        assert_eq!(parse("45s"), LogSince::Relative("45s".to_string()));
+        assert_eq!(parse("1d"), LogSince::Relative("24h".to_string()));
+        assert_eq!(parse("1h30m"), LogSince::Relative("1h30m".to_string()));
+    }
+
+    #[test]
+    fn test_log_since_accepts_rfc3339() {
+        assert_eq!(
+            "2024-05-01T12:00:00Z".parse::<LogSince>(),
+            Ok(LogSince::Timestamp("2024-05-01T12:00:00Z".to_string()))
+        );
+        assert!("2024-05-01T12:00:00+02:00".parse::<LogSince>().is_ok());
+    }
+
+    #[test]
+    fn test_log_since_rejects_garbage() {
+        for bad in [
+            "",
+            "m",
+            "10",
+            "0m",
+            "10w",
+            "-5m",
+            "10 m",
+            "2024-05-01",
+            "1.5h",
+        ] {
+            let err = bad.parse::<LogSince>().expect_err(bad);
+            assert!(err.contains("invalid time"), "{bad}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_compose_logs_args_combines_options() {
+        let since = LogSince::Relative("10m".to_string());
+        assert_eq!(
+            compose_logs_args(&["gate".to_string()], Some(50), Some(&since)),
+            [
+                "docker",
+                "compose",
+                "-f",
+                "/opt/polis/docker-compose.yml",
+                "logs",
+                "--tail",
+                "50",
+                "--since",
+                "10m",
+                "gate",
+            ]
+        );
+        assert_eq!(
+            compose_logs_args(&[], None, None)
+                .last()
+                .map(String::as_str),
+            Some("logs")
+        );
+    }
+}
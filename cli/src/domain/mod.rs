@@ -6,12 +6,18 @@
 
 pub mod agent;
 pub mod config;
+pub mod duration;
 pub mod error;
 pub mod health;
+pub mod instance;
+pub mod logs;
+pub mod self_test;
+pub mod update;
 pub mod workspace;
 
 #[allow(unused_imports)]
 pub use config::{PolisConfig, SecurityConfig, validate_config_key, validate_config_value};
+pub use duration::parse_duration;
 #[allow(unused_imports)]
 pub use error::{AgentError, ConfigError, WorkspaceError};
 #[allow(unused_imports)]
@@ -0,0 +1,141 @@
+//! `polis self-test` phases and their results — pure, no I/O.
+
+use serde::Serialize;
+
+/// One step of `polis self-test`, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTestPhase {
+    /// Multipass is installed and recent enough.
+    Prerequisites,
+    /// The throwaway VM launches with the polis cloud-init.
+    Launch,
+    /// Cloud-init finished without errors.
+    CloudInit,
+    /// Docker answers inside the VM.
+    Docker,
+    /// The Sysbox runtime is registered with Docker.
+    Sysbox,
+    /// The throwaway VM is deleted and purged.
+    Teardown,
+}
+
+impl SelfTestPhase {
+    /// Phases that check the launched VM; skipped once one of them fails.
+    pub const SMOKE: [Self; 3] = [Self::CloudInit, Self::Docker, Self::Sysbox];
+
+    /// Human-readable name.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Prerequisites => "prerequisites",
+            Self::Launch => "launch VM",
+            Self::CloudInit => "cloud-init",
+            Self::Docker => "docker",
+            Self::Sysbox => "sysbox runtime",
+            Self::Teardown => "teardown",
+        }
+    }
+}
+
+/// How a phase went.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum PhaseOutcome {
+    Passed,
+    Failed {
+        /// Why the phase failed.
+        detail: String,
+    },
+    /// Not run because an earlier phase failed.
+    Skipped,
+}
+
+/// Result of one phase.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PhaseResult {
+    pub phase: SelfTestPhase,
+    #[serde(flatten)]
+    pub outcome: PhaseOutcome,
+}
+
+/// Results of every phase of one `polis self-test` run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SelfTestReport {
+    pub phases: Vec<PhaseResult>,
+}
+
+impl SelfTestReport {
+    /// Record the result of running `phase`.
+    pub fn record(&mut self, phase: SelfTestPhase, result: Result<(), String>) {
+        let outcome = match result {
+            Ok(()) => PhaseOutcome::Passed,
+            Err(detail) => PhaseOutcome::Failed { detail },
+        };
+        self.phases.push(PhaseResult { phase, outcome });
+    }
+
+    /// Record `phase` as not run.
+    pub fn skip(&mut self, phase: SelfTestPhase) {
+        self.phases.push(PhaseResult {
+            phase,
+            outcome: PhaseOutcome::Skipped,
+        });
+    }
+
+    /// Whether any phase recorded so far failed.
+    #[must_use]
+    pub fn has_failure(&self) -> bool {
+        self.phases
+            .iter()
+            .any(|p| matches!(p.outcome, PhaseOutcome::Failed { .. }))
+    }
+
+    /// Whether the run passed: at least one phase ran and every phase passed.
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        !self.phases.is_empty()
+            && self
+                .phases
+                .iter()
+                .all(|p| p.outcome == PhaseOutcome::Passed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_passes_only_when_every_phase_passed() {
+        let mut report = SelfTestReport::default();
+        assert!(!report.passed(), "an empty report proves nothing");
+
+        report.record(SelfTestPhase::Prerequisites, Ok(()));
+        report.record(SelfTestPhase::Launch, Ok(()));
+        assert!(report.passed());
+        assert!(!report.has_failure());
+
+        report.skip(SelfTestPhase::Docker);
+        assert!(!report.passed(), "a skipped phase is not a pass");
+        assert!(!report.has_failure());
+
+        report.record(SelfTestPhase::Teardown, Err("busy".to_string()));
+        assert!(report.has_failure());
+    }
+
+    #[test]
+    fn test_phase_result_serializes_flat_status() {
+        let mut report = SelfTestReport::default();
+        report.record(SelfTestPhase::CloudInit, Err("degraded".to_string()));
+        report.skip(SelfTestPhase::Sysbox);
+        let json = serde_json::to_value(&report).unwrap_or_default();
+        assert_eq!(
+            json,
+            serde_json::json!({"phases": [
+                {"phase": "cloud_init", "status": "failed", "detail": "degraded"},
+                {"phase": "sysbox", "status": "skipped"},
+            ]})
+        );
+    }
+}
@@ -15,3 +15,342 @@ pub fn validate_host_key(key: &str) -> Result<()> {
     anyhow::ensure!(!material.trim().is_empty(), "host key has no key material");
     Ok(())
 }
+
+/// Host alias under which the workspace key is pinned in `~/.polis/known_hosts`.
+pub const KNOWN_HOSTS_ALIAS: &str = "workspace";
+
+/// Builds the `known_hosts` line for a raw public key file.
+///
+/// Output is exactly `workspace ssh-ed25519 <base64>`: surrounding whitespace
+/// and any trailing comment (e.g. `root@host`) are dropped.
+///
+/// # Errors
+///
+/// Returns an error if the key is not ed25519 or its material is not base64.
+pub fn known_hosts_line(raw_pubkey: &str) -> Result<String> {
+    let mut fields = raw_pubkey.split_whitespace();
+    let key_type = fields.next().unwrap_or_default();
+    let material = fields.next().unwrap_or_default();
+    validate_host_key(&format!("{key_type} {material}"))?;
+    anyhow::ensure!(
+        material
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'=')),
+        "host key material is not base64"
+    );
+    Ok(format!("{KNOWN_HOSTS_ALIAS} {key_type} {material}"))
+}
+
+/// Whether `known_hosts` content already pins `raw_pubkey` under the
+/// workspace alias.
+#[must_use]
+pub fn host_key_pinned(known_hosts: &str, raw_pubkey: &str) -> bool {
+    known_hosts_line(raw_pubkey).is_ok_and(|line| known_hosts.lines().any(|l| l.trim() == line))
+}
+
+/// First line of the `Host polis` block managed in `~/.ssh/config`.
+pub const MANAGED_BLOCK_BEGIN: &str =
+    "# >>> polis (managed by `polis connect --write-ssh-config`) >>>";
+
+/// Last line of the `Host polis` block managed in `~/.ssh/config`.
+pub const MANAGED_BLOCK_END: &str = "# <<< polis <<<";
+
+/// Builds the managed `Host polis` block for `~/.ssh/config`.
+///
+/// `HostName` records the VM address; the connection itself still goes
+/// through `proxy_command` (`polis _ssh-proxy`), and the host key is checked
+/// against the pinned `~/.polis/known_hosts` entry via `HostKeyAlias`.
+///
+/// # Errors
+///
+/// Returns an error if `vm_ip` is not an IP address literal.
+pub fn polis_host_block(vm_ip: &str, proxy_command: &str) -> Result<String> {
+    let vm_ip = ssh_host(vm_ip)?;
+    Ok(format!(
+        "{MANAGED_BLOCK_BEGIN}
+Host polis
+    HostName {vm_ip}
+    User polis
+    ProxyCommand {proxy_command}
+    HostKeyAlias {KNOWN_HOSTS_ALIAS}
+    StrictHostKeyChecking yes
+    UserKnownHostsFile ~/.polis/known_hosts
+    IdentityFile ~/.polis/id_ed25519
+    IdentitiesOnly yes
+    ForwardAgent no
+{MANAGED_BLOCK_END}
+"
+    ))
+}
+
+/// Normalizes a VM address for use as an SSH host (`HostName`, `user@host`).
+///
+/// OpenSSH expects IPv6 literals without brackets, so `[fd00::5]` becomes
+/// `fd00::5`; IPv4 addresses pass through unchanged.
+///
+/// # Errors
+///
+/// Returns an error if `addr` is not an IP address literal.
+pub fn ssh_host(addr: &str) -> Result<String> {
+    let bare = addr
+        .strip_prefix('[')
+        .and_then(|a| a.strip_suffix(']'))
+        .unwrap_or(addr);
+    bare.parse::<std::net::IpAddr>()
+        .map(|ip| ip.to_string())
+        .map_err(|_| anyhow::anyhow!("invalid VM address: {addr:?}"))
+}
+
+/// Replaces the managed polis block in `config` with `block`, or appends
+/// `block` if none is present. Applying the same block twice is a no-op.
+#[must_use]
+pub fn replace_managed_block(config: &str, block: &str) -> String {
+    let lines: Vec<&str> = config.lines().collect();
+    let begin = lines.iter().position(|l| l.trim() == MANAGED_BLOCK_BEGIN);
+    let end = begin.and_then(|b| {
+        lines[b..]
+            .iter()
+            .position(|l| l.trim() == MANAGED_BLOCK_END)
+            .map(|e| b + e)
+    });
+
+    let mut out = String::new();
+    if let (Some(begin), Some(end)) = (begin, end) {
+        for line in &lines[..begin] {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str(block);
+        for line in &lines[end + 1..] {
+            out.push_str(line);
+            out.push('\n');
+        }
+    } else {
+        out.push_str(config);
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+        if !out.is_empty() && !out.ends_with("\n\n") {
+            out.push('\n');
+        }
+        out.push_str(block);
+    }
+    out
+}
+
+/// A `polis connect --forward LOCAL:REMOTE` port forward from a host port to
+/// a port inside the workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortForward {
+    /// Port on the host's loopback interface.
+    pub local: u16,
+    /// Port inside the workspace.
+    pub remote: u16,
+}
+
+impl std::str::FromStr for PortForward {
+    type Err = String;
+
+    /// Parses `LOCAL:REMOTE`. Only digits and one `:` are accepted, so
+    /// nothing but two port numbers ever reaches the ssh command line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid forward '{s}' (expected LOCAL:REMOTE, e.g. 8080:3000)");
+        let (local, remote) = s.split_once(':').ok_or_else(invalid)?;
+        let port = |p: &str| {
+            if p.is_empty() || !p.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            p.parse::<u16>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| format!("invalid forward '{s}': ports must be 1-65535"))
+        };
+        Ok(Self {
+            local: port(local)?,
+            remote: port(remote)?,
+        })
+    }
+}
+
+impl std::fmt::Display for PortForward {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.local, self.remote)
+    }
+}
+
+/// `ssh` arguments for `forwards`: one `-L LOCAL:localhost:REMOTE` pair
+/// each, so the remote end is resolved inside the workspace.
+#[must_use]
+pub fn forward_args(forwards: &[PortForward]) -> Vec<String> {
+    forwards
+        .iter()
+        .flat_map(|f| {
+            [
+                "-L".to_string(),
+                format!("{}:localhost:{}", f.local, f.remote),
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MATERIAL: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIBase64KeyMaterial+/=";
+
+    #[test]
+    fn test_host_key_pinned_matches_current_key_only() {
+        let raw = format!("ssh-ed25519 {MATERIAL} root@workspace\n");
+        let pinned = format!("workspace ssh-ed25519 {MATERIAL}\n");
+        assert!(host_key_pinned(&pinned, &raw));
+        assert!(!host_key_pinned("workspace ssh-ed25519 AAAAold\n", &raw));
+        assert!(!host_key_pinned("", &raw));
+    }
+
+    #[test]
+    fn test_known_hosts_line_formats_host_type_and_key() {
+        let line = known_hosts_line(&format!("ssh-ed25519 {MATERIAL}\n"))
+            .expect("valid key should format");
+        assert_eq!(line, format!("workspace ssh-ed25519 {MATERIAL}"));
+    }
+
+    #[test]
+    fn test_known_hosts_line_drops_comment() {
+        let line = known_hosts_line(&format!("  ssh-ed25519 {MATERIAL} root@workspace \n"))
+            .expect("valid key should format");
+        assert_eq!(line, format!("workspace ssh-ed25519 {MATERIAL}"));
+    }
+
+    #[test]
+    fn test_known_hosts_line_rejects_non_ed25519() {
+        assert!(known_hosts_line(&format!("ssh-rsa {MATERIAL}")).is_err());
+    }
+
+    #[test]
+    fn test_known_hosts_line_rejects_missing_or_invalid_material() {
+        assert!(known_hosts_line("ssh-ed25519").is_err());
+        assert!(known_hosts_line("ssh-ed25519 not;base64").is_err());
+        assert!(known_hosts_line("").is_err());
+    }
+
+    #[test]
+    fn test_polis_host_block_contains_ip_user_and_known_hosts() {
+        let block = polis_host_block("192.168.64.5", "polis _ssh-proxy").expect("block");
+        assert!(block.starts_with(MANAGED_BLOCK_BEGIN));
+        assert!(block.ends_with(&format!("{MANAGED_BLOCK_END}\n")));
+        assert!(block.contains("Host polis\n"));
+        assert!(block.contains("HostName 192.168.64.5\n"));
+        assert!(block.contains("User polis\n"));
+        assert!(block.contains("HostKeyAlias workspace\n"));
+        assert!(block.contains("UserKnownHostsFile ~/.polis/known_hosts\n"));
+    }
+
+    #[test]
+    fn test_polis_host_block_rejects_non_ip_hostname() {
+        assert!(polis_host_block("evil\n    ProxyCommand sh", "polis _ssh-proxy").is_err());
+        assert!(polis_host_block("", "polis _ssh-proxy").is_err());
+    }
+
+    #[test]
+    fn test_ssh_host_accepts_ipv4_and_ipv6() {
+        assert_eq!(ssh_host("192.168.64.5").expect("v4"), "192.168.64.5");
+        assert_eq!(ssh_host("fd00::5").expect("v6"), "fd00::5");
+        assert_eq!(ssh_host("[fd00::5]").expect("bracketed v6"), "fd00::5");
+        assert!(ssh_host("[fd00::5").is_err());
+        assert!(ssh_host("workspace").is_err());
+    }
+
+    #[test]
+    fn test_polis_host_block_unbrackets_ipv6() {
+        let block = polis_host_block("[::1]", "polis _ssh-proxy").expect("block");
+        assert!(block.contains("HostName ::1\n"));
+    }
+
+    #[test]
+    fn test_replace_managed_block_appends_to_existing_config() {
+        let block = polis_host_block("10.0.0.2", "polis _ssh-proxy").expect("block");
+        let config = "Host *\n    ServerAliveInterval 60";
+        let out = replace_managed_block(config, &block);
+        assert_eq!(
+            out,
+            format!("Host *\n    ServerAliveInterval 60\n\n{block}")
+        );
+    }
+
+    #[test]
+    fn test_replace_managed_block_is_idempotent() {
+        let block = polis_host_block("10.0.0.2", "polis _ssh-proxy").expect("block");
+        let once = replace_managed_block("Host *\n    ServerAliveInterval 60\n", &block);
+        assert_eq!(replace_managed_block(&once, &block), once);
+    }
+
+    #[test]
+    fn test_replace_managed_block_replaces_prior_block_in_place() {
+        let old = polis_host_block("10.0.0.2", "polis _ssh-proxy").expect("old");
+        let new = polis_host_block("10.0.0.3", "polis _ssh-proxy").expect("new");
+        let config = format!("Host a\n    User a\n\n{old}\nHost b\n    User b\n");
+        let out = replace_managed_block(&config, &new);
+        assert_eq!(
+            out,
+            format!("Host a\n    User a\n\n{new}\nHost b\n    User b\n")
+        );
+        assert!(!out.contains("10.0.0.2"));
+    }
+
+    #[test]
+    fn test_replace_managed_block_into_empty_config() {
+        let block = polis_host_block("10.0.0.2", "polis _ssh-proxy").expect("block");
+        assert_eq!(replace_managed_block("", &block), block);
+    }
+
+    #[test]
+    fn test_port_forward_parses_local_and_remote() {
+        let f: PortForward = "8080:3000".parse().expect("valid");
+        assert_eq!(
+            f,
+            PortForward {
+                local: 8080,
+                remote: 3000
+            }
+        );
+        assert_eq!(f.to_string(), "8080:3000");
+        assert!("1:65535".parse::<PortForward>().is_ok());
+    }
+
+    #[test]
+    fn test_port_forward_rejects_bad_specs() {
+        for bad in [
+            "8080",
+            "8080:",
+            ":3000",
+            "0:3000",
+            "8080:65536",
+            "80:80:80",
+            "8080:3000;id",
+            "8080:$(id)",
+            " 8080:3000",
+            "-1:3000",
+            "localhost:3000",
+        ] {
+            assert!(bad.parse::<PortForward>().is_err(), "{bad}");
+        }
+        let err = "8080:70000"
+            .parse::<PortForward>()
+            .expect_err("out of range");
+        assert!(err.contains("1-65535"), "{err}");
+    }
+
+    #[test]
+    fn test_forward_args_emits_one_flag_per_forward() {
+        let forwards: Vec<PortForward> = ["8080:3000", "9000:9000"]
+            .iter()
+            .map(|s| s.parse().expect("valid"))
+            .collect();
+        assert_eq!(
+            forward_args(&forwards),
+            ["-L", "8080:localhost:3000", "-L", "9000:localhost:9000"]
+        );
+        assert!(forward_args(&[]).is_empty());
+    }
+}
@@ -0,0 +1,485 @@
+//! Update report types and pure assembly functions.
+//!
+//! This module is intentionally free of I/O, async, and external layer imports.
+//! All functions take data in and return data out.
+
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable summary of a `polis update` run (`--json`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpdateReport {
+    /// CLI self-update result.
+    pub cli: CliUpdate,
+    /// Per-service result of the VM config update. Empty when the VM is not
+    /// running or only a check was performed.
+    pub containers: Vec<ContainerUpdate>,
+}
+
+impl UpdateReport {
+    /// Report for a run that has not updated anything yet.
+    #[must_use]
+    pub fn new(from: &str, to: &str) -> Self {
+        Self {
+            cli: CliUpdate {
+                from: from.to_string(),
+                to: to.to_string(),
+                updated: false,
+            },
+            containers: Vec::new(),
+        }
+    }
+}
+
+/// CLI self-update result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CliUpdate {
+    /// Version that was running, e.g. `0.4.0`.
+    pub from: String,
+    /// Latest available version (equal to `from` when up to date).
+    pub to: String,
+    /// Whether the binary was replaced.
+    pub updated: bool,
+}
+
+/// Version change of one control-plane service.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContainerUpdate {
+    /// Service name derived from its version variable, e.g. `gate`.
+    pub name: String,
+    /// Tag deployed before the update.
+    pub from: String,
+    /// Tag deployed after the update.
+    pub to: String,
+    /// What happened to the service.
+    pub result: ContainerResult,
+}
+
+/// Outcome for a single service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerResult {
+    /// The service moved to a new tag.
+    Updated,
+    /// The service already ran the target tag.
+    Unchanged,
+}
+
+/// Iterate the `POLIS_*_VERSION` entries of a `.env` file as `(var, tag)`.
+/// Blank lines, comments, and other variables are skipped.
+pub fn service_versions(env_content: &str) -> impl Iterator<Item = (&str, &str)> {
+    env_content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .filter(|(var, _)| var.starts_with("POLIS_") && var.ends_with("_VERSION"))
+}
+
+/// Service name for a version variable: `POLIS_STATE_INIT_VERSION` → `state-init`.
+#[must_use]
+pub fn service_name(var: &str) -> String {
+    var.trim_start_matches("POLIS_")
+        .trim_end_matches("_VERSION")
+        .to_ascii_lowercase()
+        .replace('_', "-")
+}
+
+/// Build per-service results from the `.env` deployed before a config update
+/// and the `.env` written by it.
+///
+/// When `new_env` is `None` (no update) every service is reported as
+/// unchanged at its current tag. Otherwise each moves to its tag in
+/// `new_env`, or stays at its current tag if `new_env` does not list it.
+#[must_use]
+pub fn container_updates(deployed_env: &str, new_env: Option<&str>) -> Vec<ContainerUpdate> {
+    let targets: Vec<(&str, &str)> = new_env
+        .map(|env| service_versions(env).collect())
+        .unwrap_or_default();
+    service_versions(deployed_env)
+        .map(|(var, from)| {
+            let to = targets
+                .iter()
+                .find(|(target, _)| *target == var)
+                .map_or(from, |(_, tag)| *tag);
+            ContainerUpdate {
+                name: service_name(var),
+                from: from.to_string(),
+                to: to.to_string(),
+                result: if from == to {
+                    ContainerResult::Unchanged
+                } else {
+                    ContainerResult::Updated
+                },
+            }
+        })
+        .collect()
+}
+
+/// Order of the per-service rows in an update report (`--sort`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerSort {
+    /// Alphabetical by service name.
+    Name,
+    /// Updated services first, then unchanged ones; ties by name.
+    Status,
+}
+
+impl FromStr for ContainerSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(Self::Name),
+            "status" => Ok(Self::Status),
+            other => Err(format!("unknown sort '{other}' (expected name or status)")),
+        }
+    }
+}
+
+/// Status ordering: services that moved to a new tag sort before unchanged
+/// ones, then by name.
+#[must_use]
+pub fn compare_by_status(a: &ContainerUpdate, b: &ContainerUpdate) -> Ordering {
+    let rank = |c: &ContainerUpdate| match c.result {
+        ContainerResult::Updated => 0,
+        ContainerResult::Unchanged => 1,
+    };
+    rank(a).cmp(&rank(b)).then_with(|| a.name.cmp(&b.name))
+}
+
+/// Sort report rows in place. `None` keeps the `.env` order produced by
+/// [`container_updates`], which is deterministic.
+pub fn sort_containers(containers: &mut [ContainerUpdate], sort: Option<ContainerSort>) {
+    match sort {
+        None => {}
+        Some(ContainerSort::Name) => containers.sort_by(|a, b| a.name.cmp(&b.name)),
+        Some(ContainerSort::Status) => containers.sort_by(compare_by_status),
+    }
+}
+
+// ── Changelog ─────────────────────────────────────────────────────────────────
+
+/// Parsed notes of one published release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseNotes {
+    /// Release version without leading `v`, e.g. `0.4.0`.
+    pub version: String,
+    /// Bullets grouped by section title; an empty title means no header.
+    pub sections: Vec<(String, Vec<String>)>,
+}
+
+/// Pick a release from a newest-first list: the latest when `version` is
+/// `None`, otherwise the one matching `version` (with or without `v`).
+///
+/// # Errors
+///
+/// Returns an error listing the available versions when none matches, or
+/// when the list is empty.
+pub fn select_release(
+    releases: Vec<ReleaseNotes>,
+    version: Option<&str>,
+) -> anyhow::Result<ReleaseNotes> {
+    let Some(wanted) = version.map(|v| v.trim().trim_start_matches('v')) else {
+        return releases
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("no releases published yet"));
+    };
+    let available = releases
+        .iter()
+        .map(|r| format!("v{}", r.version))
+        .collect::<Vec<_>>()
+        .join(", ");
+    releases
+        .into_iter()
+        .find(|r| r.version == wanted)
+        .ok_or_else(|| {
+            anyhow::anyhow!("release v{wanted} not found\n\nAvailable releases: {available}")
+        })
+}
+
+// ── Update notification ───────────────────────────────────────────────────────
+
+/// Minimum time between two background update checks.
+pub const UPDATE_CHECK_INTERVAL: Duration = Duration::hours(24);
+
+/// Result of the last background update check, cached in
+/// `~/.polis/cache/update-check.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateCheckCache {
+    /// When GitHub was last asked for the latest release.
+    pub checked_at: DateTime<Utc>,
+    /// Newest released version seen at that time (without leading `v`).
+    /// `None` if the CLI was already up to date or the check failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_version: Option<String>,
+}
+
+/// Whether the cached result is too old to use and GitHub should be asked
+/// again. A timestamp in the future (clock change) also counts as stale.
+#[must_use]
+pub fn update_check_due(cache: Option<&UpdateCheckCache>, now: DateTime<Utc>) -> bool {
+    cache.is_none_or(|c| {
+        let age = now.signed_duration_since(c.checked_at);
+        age < Duration::zero() || age >= UPDATE_CHECK_INTERVAL
+    })
+}
+
+/// Release in `cache` that is newer than `current`, if any.
+#[must_use]
+pub fn newer_release<'a>(cache: &'a UpdateCheckCache, current: &str) -> Option<&'a str> {
+    let latest = cache.latest_version.as_deref()?;
+    let newer = semver::Version::parse(latest).ok()? > semver::Version::parse(current).ok()?;
+    newer.then_some(latest)
+}
+
+/// Services in a deployed `.env` whose tag differs from `v{cli_version}`,
+/// i.e. that `polis update` would move.
+#[must_use]
+pub fn outdated_services(deployed_env: &str, cli_version: &str) -> Vec<String> {
+    let target = format!("v{cli_version}");
+    service_versions(deployed_env)
+        .filter(|(_, tag)| *tag != target)
+        .map(|(var, _)| service_name(var))
+        .collect()
+}
+
+/// One-line notice for a cached release newer than `current`, if any.
+#[must_use]
+pub fn update_notice(cache: &UpdateCheckCache, current: &str) -> Option<String> {
+    newer_release(cache, current)
+        .map(|latest| format!("polis v{latest} available — run 'polis update'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEPLOYED: &str = "# Generated by polis CLI v0.3.0\n\
+                            POLIS_GATE_VERSION=v0.3.0\n\
+                            POLIS_STATE_INIT_VERSION=v0.4.0\n\
+                            OTHER=1\n";
+
+    const WRITTEN: &str = "# Generated by polis CLI v0.4.0\n\
+                           POLIS_REGISTRY_PREFIX=localhost:5000/polis\n\
+                           POLIS_GATE_VERSION=v0.4.0\n\
+                           POLIS_STATE_INIT_VERSION=v0.4.0\n";
+
+    #[test]
+    fn test_outdated_services_lists_services_behind_cli() {
+        assert_eq!(outdated_services(DEPLOYED, "0.4.0"), ["gate"]);
+        assert!(outdated_services(DEPLOYED, "0.3.0").contains(&"state-init".to_string()));
+    }
+
+    #[test]
+    fn test_service_name_strips_prefix_and_suffix() {
+        assert_eq!(service_name("POLIS_GATE_VERSION"), "gate");
+        assert_eq!(service_name("POLIS_STATE_INIT_VERSION"), "state-init");
+    }
+
+    #[test]
+    fn test_container_updates_after_config_update() {
+        assert_eq!(
+            container_updates(DEPLOYED, Some(WRITTEN)),
+            vec![
+                ContainerUpdate {
+                    name: "gate".to_string(),
+                    from: "v0.3.0".to_string(),
+                    to: "v0.4.0".to_string(),
+                    result: ContainerResult::Updated,
+                },
+                ContainerUpdate {
+                    name: "state-init".to_string(),
+                    from: "v0.4.0".to_string(),
+                    to: "v0.4.0".to_string(),
+                    result: ContainerResult::Unchanged,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_container_updates_reads_targets_from_written_env() {
+        let pinned = "POLIS_GATE_VERSION=v0.3.1\nPOLIS_STATE_INIT_VERSION=v0.4.0\n";
+        let updates = container_updates(DEPLOYED, Some(pinned));
+        assert_eq!(updates[0].to, "v0.3.1");
+        assert_eq!(updates[0].result, ContainerResult::Updated);
+        assert_eq!(updates[1].result, ContainerResult::Unchanged);
+
+        let updates = container_updates(DEPLOYED, Some("POLIS_STATE_INIT_VERSION=v0.5.0\n"));
+        assert_eq!(
+            updates[0].to, "v0.3.0",
+            "services missing from the new env keep their tag"
+        );
+        assert_eq!(updates[1].to, "v0.5.0");
+    }
+
+    #[test]
+    fn test_container_updates_config_up_to_date_reports_unchanged() {
+        let updates = container_updates(DEPLOYED, None);
+        assert_eq!(updates.len(), 2);
+        assert!(
+            updates
+                .iter()
+                .all(|c| c.result == ContainerResult::Unchanged && c.from == c.to)
+        );
+    }
+
+    #[test]
+    fn test_update_report_json_shape() {
+        let mut report = UpdateReport::new("0.3.0", "0.4.0");
+        report.cli.updated = true;
+        report.containers = container_updates("POLIS_GATE_VERSION=v0.3.0\n", Some(WRITTEN));
+        assert_eq!(
+            serde_json::to_value(&report).expect("serialize"),
+            serde_json::json!({
+                "cli": { "from": "0.3.0", "to": "0.4.0", "updated": true },
+                "containers": [
+                    { "name": "gate", "from": "v0.3.0", "to": "v0.4.0", "result": "updated" }
+                ]
+            })
+        );
+    }
+
+    fn container(name: &str, result: ContainerResult) -> ContainerUpdate {
+        ContainerUpdate {
+            name: name.to_string(),
+            from: "v0.3.0".to_string(),
+            to: "v0.4.0".to_string(),
+            result,
+        }
+    }
+
+    fn names(containers: &[ContainerUpdate]) -> Vec<&str> {
+        containers.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_compare_by_status_places_updates_first() {
+        let updated = container("toolbox", ContainerResult::Updated);
+        let unchanged = container("gate", ContainerResult::Unchanged);
+        assert_eq!(compare_by_status(&updated, &unchanged), Ordering::Less);
+        assert_eq!(compare_by_status(&unchanged, &updated), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_sort_containers_by_status_groups_updates_then_names() {
+        let mut containers = vec![
+            container("resolver", ContainerResult::Unchanged),
+            container("toolbox", ContainerResult::Updated),
+            container("gate", ContainerResult::Unchanged),
+            container("certgen", ContainerResult::Updated),
+        ];
+        sort_containers(&mut containers, Some(ContainerSort::Status));
+        assert_eq!(
+            names(&containers),
+            ["certgen", "toolbox", "gate", "resolver"]
+        );
+    }
+
+    #[test]
+    fn test_sort_containers_default_keeps_env_order() {
+        let mut containers = container_updates(DEPLOYED, Some(WRITTEN));
+        sort_containers(&mut containers, None);
+        assert_eq!(names(&containers), ["gate", "state-init"]);
+        sort_containers(&mut containers, Some(ContainerSort::Name));
+        assert_eq!(names(&containers), ["gate", "state-init"]);
+    }
+
+    #[test]
+    fn test_container_sort_parses_known_values() {
+        assert_eq!("name".parse(), Ok(ContainerSort::Name));
+        assert_eq!("status".parse(), Ok(ContainerSort::Status));
+        assert!("age".parse::<ContainerSort>().is_err());
+    }
+
+    fn cache_at(checked_at: DateTime<Utc>, latest: Option<&str>) -> UpdateCheckCache {
+        UpdateCheckCache {
+            checked_at,
+            latest_version: latest.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_update_check_due_without_cache() {
+        assert!(update_check_due(None, Utc::now()));
+    }
+
+    #[test]
+    fn test_update_check_due_at_most_once_per_day() {
+        let now = Utc::now();
+        let fresh = cache_at(now - Duration::hours(23), None);
+        let stale = cache_at(now - Duration::hours(24), None);
+        assert!(!update_check_due(Some(&fresh), now));
+        assert!(update_check_due(Some(&stale), now));
+    }
+
+    #[test]
+    fn test_update_check_due_future_timestamp_is_stale() {
+        let now = Utc::now();
+        let future = cache_at(now + Duration::hours(1), None);
+        assert!(update_check_due(Some(&future), now));
+    }
+
+    #[test]
+    fn test_update_notice_only_for_newer_version() {
+        let cache = cache_at(Utc::now(), Some("0.5.0"));
+        let notice = update_notice(&cache, "0.4.0").expect("newer version");
+        assert!(notice.contains("v0.5.0"), "{notice}");
+        assert!(notice.contains("polis update"), "{notice}");
+        assert!(update_notice(&cache, "0.5.0").is_none());
+        assert!(update_notice(&cache_at(Utc::now(), None), "0.4.0").is_none());
+    }
+
+    fn release(version: &str) -> ReleaseNotes {
+        ReleaseNotes {
+            version: version.to_string(),
+            sections: vec![(String::new(), vec![format!("notes for {version}")])],
+        }
+    }
+
+    #[test]
+    fn test_select_release_defaults_to_latest() {
+        let releases = vec![release("0.4.0"), release("0.3.0")];
+        assert_eq!(
+            select_release(releases, None).expect("latest").version,
+            "0.4.0"
+        );
+    }
+
+    #[test]
+    fn test_select_release_matches_with_or_without_v() {
+        let releases = vec![release("0.4.0"), release("0.3.0")];
+        assert_eq!(
+            select_release(releases.clone(), Some("v0.3.0"))
+                .expect("v-prefixed")
+                .version,
+            "0.3.0"
+        );
+        assert_eq!(
+            select_release(releases, Some("0.3.0"))
+                .expect("bare")
+                .version,
+            "0.3.0"
+        );
+    }
+
+    #[test]
+    fn test_select_release_unknown_version_lists_available() {
+        let releases = vec![release("0.4.0"), release("0.3.0")];
+        let msg = select_release(releases, Some("v0.9.9"))
+            .expect_err("missing")
+            .to_string();
+        assert!(msg.contains("v0.9.9 not found"), "{msg}");
+        assert!(msg.contains("v0.4.0, v0.3.0"), "{msg}");
+    }
+
+    #[test]
+    fn test_select_release_empty_list_errors() {
+        assert!(select_release(Vec::new(), None).is_err());
+    }
+}
@@ -6,12 +6,13 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 /// Workspace state persisted to `~/.polis/state.json`.
 ///
 /// The `created_at` field accepts the legacy `started_at` name for backward
 /// compatibility with older state files.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WorkspaceState {
     /// When workspace was created (accepts legacy `"started_at"` field).
     #[serde(alias = "started_at")]
@@ -25,6 +26,10 @@ pub struct WorkspaceState {
     /// Currently active agent name, or None for control-plane-only.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub active_agent: Option<String>,
+    /// SHA256 of the control-plane `.env` last written by the CLI.
+    /// Lets `polis doctor` detect out-of-band edits to version pins.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_sha256: Option<String>,
 }
 
 /// Check that the host architecture is amd64.
@@ -65,11 +70,146 @@ pub const ACTIVE_OVERLAY_PATH: &str = "/opt/polis/compose.active.yaml";
 /// CLI removes this before controlled restarts.
 pub const READY_MARKER_PATH: &str = "/opt/polis/.ready";
 
+/// vCPUs given to a newly created workspace VM.
+pub const VM_CPUS: &str = "4";
+
+/// Memory given to a newly created workspace VM.
+pub const VM_MEMORY: &str = "8G";
+
+/// Disk size of a newly created workspace VM.
+pub const VM_DISK: &str = "40G";
+
+/// Seconds `multipass launch` may take, including cloud-init, unless
+/// `polis start --launch-timeout` says otherwise.
+pub const VM_LAUNCH_TIMEOUT_SECS: u64 = 900;
+
+/// What `polis start` will do with the VM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartAction {
+    /// Launch and provision a new VM.
+    Create,
+    /// Boot the existing stopped VM.
+    StartExisting,
+    /// Install the requested agent into the running, agent-less workspace.
+    AddAgent,
+    /// Nothing to do; the workspace already runs the requested agent.
+    AlreadyRunning,
+}
+
+/// Provisioning plan printed by `polis start --dry-run`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StartPlan {
+    pub action: StartAction,
+    /// Agent that will be active afterwards, if any.
+    pub agent: Option<String>,
+    pub cpus: &'static str,
+    pub memory: &'static str,
+    pub disk: &'static str,
+    /// Whether Docker images will be pulled (false with `--offline`).
+    pub pull_images: bool,
+    /// Whether config is re-transferred unconditionally. Without
+    /// `--reprovision` a stopped VM is only re-provisioned if its config hash
+    /// is stale, which cannot be checked before it boots.
+    pub reprovision: bool,
+    /// Compose files the workspace will run with, inside the VM.
+    pub compose_files: Vec<String>,
+}
+
+/// Wall-clock time spent in one progress stage, for `polis start --timings`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StageTiming {
+    /// Stage message without its trailing `...`, e.g. `securing workspace`.
+    pub stage: String,
+    pub duration_ms: u64,
+    /// Whether the stage completed rather than failed.
+    pub ok: bool,
+}
+
+impl StageTiming {
+    /// Record a finished stage as reported to `ProgressReporter::begin_stage`.
+    #[must_use]
+    pub fn new(message: &str, elapsed: std::time::Duration, ok: bool) -> Self {
+        Self {
+            stage: message.trim_end_matches("...").to_string(),
+            duration_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+            ok,
+        }
+    }
+}
+
+/// Total time across `timings`, in milliseconds.
+#[must_use]
+pub fn total_duration_ms(timings: &[StageTiming]) -> u64 {
+    timings
+        .iter()
+        .fold(0, |sum, t| sum.saturating_add(t.duration_ms))
+}
+
 /// Path to the guest query script inside the VM.
 /// Used by status and doctor services to gather system info via a single exec call,
 /// avoiding Multipass Windows pipe/buffer issues with piped commands.
 pub const QUERY_SCRIPT: &str = "/opt/polis/scripts/polis-query.sh";
 
+/// Compute the SHA256 hex digest of `.env` content, as stored in
+/// [`WorkspaceState::env_sha256`].
+#[must_use]
+pub fn env_sha256(content: &str) -> String {
+    hex_encode(&Sha256::digest(content.as_bytes()))
+}
+
+/// Whether the `.env` read back from the VM matches the recorded hash.
+#[must_use]
+pub fn env_matches_record(vm_env: &str, recorded_sha256: &str) -> bool {
+    env_sha256(vm_env) == recorded_sha256
+}
+
+/// Combine the config tarball digest and the generated `.env` template into
+/// the hash stored at `/opt/polis/.config-hash`.
+///
+/// Including the `.env` template means a CLI version bump is detected even
+/// when the tarball itself is byte-identical.
+#[must_use]
+pub fn config_hash(tarball_sha256: &str, env_template: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(tarball_sha256.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(env_template.as_bytes());
+    hex_encode(&hasher.finalize())
+}
+
+/// Whether the config hash read from the VM matches the embedded assets.
+/// A missing hash never matches.
+#[must_use]
+pub fn config_hash_matches(deployed_hash: Option<&str>, expected_hash: &str) -> bool {
+    deployed_hash == Some(expected_hash)
+}
+
+/// Classify the config hash read from the VM against the embedded assets.
+#[must_use]
+pub fn config_state(
+    deployed_hash: Option<&str>,
+    expected_hash: &str,
+) -> polis_common::types::ConfigState {
+    use polis_common::types::ConfigState;
+    match deployed_hash {
+        None => ConfigState::Missing,
+        Some(_) if config_hash_matches(deployed_hash, expected_hash) => ConfigState::Current,
+        Some(_) => ConfigState::Stale,
+    }
+}
+
+/// Whether `polis start` must re-run config transfer and cert/secret
+/// generation before starting an existing VM.
+///
+/// Normally that only happens when the config hash recorded in the VM is
+/// missing (a provisioning attempt that never finished) or differs from the
+/// embedded assets. `force` (`--reprovision`) bypasses the comparison.
+#[must_use]
+pub fn needs_reprovision(force: bool, deployed_hash: Option<&str>, expected_hash: &str) -> bool {
+    force || !config_hash_matches(deployed_hash, expected_hash)
+}
+
 /// Encode bytes as lowercase hex string.
 ///
 /// Pure utility used by update signature verification and image digest computation.
@@ -86,6 +226,8 @@ pub fn hex_encode(bytes: &[u8]) -> String {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use super::*;
 
     #[test]
@@ -105,6 +247,86 @@ mod tests {
         assert_eq!(hex_encode(&[0xde, 0xad, 0xbe, 0xef]), "deadbeef");
     }
 
+    #[test]
+    fn test_env_matches_record_same_content() {
+        let env = "# Generated by polis CLI v0.4.0\nPOLIS_GATE_VERSION=v0.4.0\n";
+        assert!(env_matches_record(env, &env_sha256(env)));
+    }
+
+    #[test]
+    fn test_env_matches_record_detects_edit() {
+        let env = "# Generated by polis CLI v0.4.0\nPOLIS_GATE_VERSION=v0.4.0\n";
+        let edited = "# Generated by polis CLI v0.4.0\nPOLIS_GATE_VERSION=v0.3.9\n";
+        assert!(!env_matches_record(edited, &env_sha256(env)));
+    }
+
+    #[test]
+    fn test_env_sha256_is_hex_sha256() {
+        assert_eq!(
+            env_sha256(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_config_hash_is_deterministic() {
+        let env = "POLIS_GATE_VERSION=v0.4.0\n";
+        assert_eq!(config_hash("abc123", env), config_hash("abc123", env));
+    }
+
+    #[test]
+    fn test_config_hash_changes_with_either_input() {
+        let env = "POLIS_GATE_VERSION=v0.4.0\n";
+        let base = config_hash("abc123", env);
+        assert_ne!(config_hash("abc124", env), base);
+        assert_ne!(config_hash("abc123", "POLIS_GATE_VERSION=v0.4.1\n"), base);
+    }
+
+    #[test]
+    fn test_config_hash_matches_requires_deployed_hash() {
+        assert!(config_hash_matches(Some("abc123"), "abc123"));
+        assert!(!config_hash_matches(Some("abc124"), "abc123"));
+        assert!(!config_hash_matches(None, "abc123"));
+    }
+
+    #[test]
+    fn test_stage_timing_trims_ellipsis_and_sums() {
+        let timings = [
+            StageTiming::new("securing workspace...", Duration::from_millis(1500), true),
+            StageTiming::new("starting services", Duration::from_secs(2), false),
+        ];
+        assert_eq!(timings[0].stage, "securing workspace");
+        assert_eq!(timings[0].duration_ms, 1500);
+        assert_eq!(timings[1].stage, "starting services");
+        assert!(!timings[1].ok);
+        assert_eq!(total_duration_ms(&timings), 3500);
+        assert_eq!(total_duration_ms(&[]), 0);
+    }
+
+    #[test]
+    fn test_config_state_classifies_deployed_hash() {
+        use polis_common::types::ConfigState;
+        assert_eq!(config_state(Some("abc123"), "abc123"), ConfigState::Current);
+        assert_eq!(config_state(Some("abc124"), "abc123"), ConfigState::Stale);
+        assert_eq!(config_state(None, "abc123"), ConfigState::Missing);
+    }
+
+    #[test]
+    fn test_needs_reprovision_matching_hash_skips() {
+        assert!(!needs_reprovision(false, Some("abc123"), "abc123"));
+    }
+
+    #[test]
+    fn test_needs_reprovision_force_bypasses_matching_hash() {
+        assert!(needs_reprovision(true, Some("abc123"), "abc123"));
+    }
+
+    #[test]
+    fn test_needs_reprovision_stale_or_missing_hash() {
+        assert!(needs_reprovision(false, Some("old"), "abc123"));
+        assert!(needs_reprovision(false, None, "abc123"));
+    }
+
     #[test]
     fn check_architecture_passes_on_non_arm64() {
         if std::env::consts::ARCH == "aarch64" {
@@ -0,0 +1,145 @@
+//! Agent bundle signing — implements `BundleSigner` with zipsign.
+//!
+//! A bundle is a gzipped agent tar with a zipsign ed25519 signature block
+//! appended, the same layout as release tarballs. Bundles are signed with
+//! their own context so a release signature never verifies as a bundle.
+
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::application::ports::BundleSigner;
+use crate::infra::update::build_verifying_key;
+
+/// zipsign context string for agent bundles.
+const BUNDLE_CONTEXT: &[u8] = b"polis-agent-bundle";
+
+/// Production `BundleSigner` backed by `zipsign-api`.
+pub struct ZipsignBundleSigner;
+
+impl BundleSigner for ZipsignBundleSigner {
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    fn sign(&self, archive: &[u8], key_path: &Path) -> Result<Vec<u8>> {
+        let key_file = std::fs::File::open(key_path)
+            .with_context(|| format!("opening signing key {}", key_path.display()))?;
+        let keys = zipsign_api::sign::read_signing_keys([Ok(key_file)])
+            .with_context(|| format!("reading signing key {}", key_path.display()))?;
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(archive).context("compressing bundle")?;
+        let compressed = gz.finish().context("compressing bundle")?;
+
+        let mut signed = Cursor::new(Vec::new());
+        zipsign_api::sign::copy_and_sign_tar(
+            &mut Cursor::new(compressed),
+            &mut signed,
+            &keys,
+            Some(BUNDLE_CONTEXT),
+        )
+        .context("signing bundle")?;
+        Ok(signed.into_inner())
+    }
+
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    fn open(&self, bundle: &[u8], trusted_keys: &[String]) -> Result<Vec<u8>> {
+        anyhow::ensure!(!trusted_keys.is_empty(), "no trusted keys given");
+        let mut keys = Vec::new();
+        for key in trusted_keys {
+            keys.extend(
+                build_verifying_key(key).with_context(|| format!("invalid trusted key '{key}'"))?,
+            );
+        }
+        zipsign_api::verify::verify_tar(&mut Cursor::new(bundle), &keys, Some(BUNDLE_CONTEXT))
+            .map_err(|e| anyhow::anyhow!("bundle signature verification failed: {e}"))?;
+
+        let mut unsigned = Cursor::new(Vec::new());
+        zipsign_api::unsign::copy_and_unsign_tar(&mut Cursor::new(bundle), &mut unsigned)
+            .context("stripping bundle signature")?;
+        let mut archive = Vec::new();
+        flate2::read::GzDecoder::new(Cursor::new(unsigned.into_inner()))
+            .read_to_end(&mut archive)
+            .context("decompressing bundle")?;
+        Ok(archive)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+    use base64::Engine as _;
+
+    fn key_file(dir: &Path, seed: u8) -> (std::path::PathBuf, String) {
+        let key = zipsign_api::SigningKey::from_bytes(&[seed; 32]);
+        let path = dir.join(format!("key-{seed}"));
+        std::fs::write(&path, key.to_keypair_bytes()).expect("write key");
+        let public =
+            base64::engine::general_purpose::STANDARD.encode(key.verifying_key().to_bytes());
+        (path, public)
+    }
+
+    fn agent_tar() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let data = b"apiVersion: polis.dev/v1\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "alpha/agent.yaml", &data[..])
+            .expect("append");
+        builder.into_inner().expect("tar")
+    }
+
+    #[test]
+    fn test_bundle_sign_verify_extract_round_trip() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (key_path, public) = key_file(dir.path(), 7);
+        let archive = agent_tar();
+        let bundle = ZipsignBundleSigner.sign(&archive, &key_path).expect("sign");
+        let opened = ZipsignBundleSigner
+            .open(&bundle, &[public])
+            .expect("trusted signature");
+        assert_eq!(opened, archive);
+    }
+
+    #[test]
+    fn test_bundle_rejects_tampered_content() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (key_path, public) = key_file(dir.path(), 7);
+        let mut bundle = ZipsignBundleSigner
+            .sign(&agent_tar(), &key_path)
+            .expect("sign");
+        // The signature block trails the gzip payload; flip the payload's
+        // last byte so the tampering lands in signed content.
+        let mut payload = Cursor::new(Vec::new());
+        zipsign_api::unsign::copy_and_unsign_tar(&mut Cursor::new(&bundle), &mut payload)
+            .expect("unsign");
+        bundle[payload.into_inner().len() - 1] ^= 0xff;
+        let err = ZipsignBundleSigner
+            .open(&bundle, &[public])
+            .expect_err("tampered");
+        let msg = err.to_string();
+        assert!(
+            msg.contains("bundle signature verification failed")
+                && msg.contains("no matching key/signature pair found"),
+            "{msg}"
+        );
+    }
+
+    #[test]
+    fn test_bundle_rejects_untrusted_key() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (key_path, _) = key_file(dir.path(), 7);
+        let (_, other_public) = key_file(dir.path(), 9);
+        let bundle = ZipsignBundleSigner
+            .sign(&agent_tar(), &key_path)
+            .expect("sign");
+        assert!(ZipsignBundleSigner.open(&bundle, &[other_public]).is_err());
+    }
+}
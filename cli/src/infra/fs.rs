@@ -1,47 +1,18 @@
-//! Filesystem infrastructure — implements `LocalArtifactWriter` and raw file ops.
+//! Filesystem infrastructure — implements `LocalFs` and raw file ops.
 
-use std::collections::HashMap;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use sha2::{Digest, Sha256};
 
-use crate::application::ports::LocalArtifactWriter;
 use crate::domain::workspace::hex_encode;
 
-/// Writes agent artifact files to the local filesystem under `.generated/`.
-/// Production filesystem implementation of `LocalArtifactWriter`.
+/// Production filesystem implementation of `LocalFs` (and, through it,
+/// `LocalArtifactWriter`).
 #[allow(dead_code)] // Not yet wired from command handlers
 pub struct LocalFs;
 
-impl LocalArtifactWriter for LocalFs {
-    /// # Errors
-    ///
-    /// This function will return an error if the underlying operations fail.
-    async fn write_agent_artifacts(
-        &self,
-        agent_name: &str,
-        files: HashMap<String, String>,
-    ) -> Result<PathBuf> {
-        let dir = PathBuf::from("agents").join(agent_name).join(".generated");
-        let dir_clone = dir.clone();
-        tokio::task::spawn_blocking(move || {
-            std::fs::create_dir_all(&dir_clone)
-                .with_context(|| format!("creating artifact dir {}", dir_clone.display()))?;
-            for (filename, content) in &files {
-                let path = dir_clone.join(filename);
-                std::fs::write(&path, content)
-                    .with_context(|| format!("writing artifact {}", path.display()))?;
-            }
-            Ok::<PathBuf, anyhow::Error>(dir_clone)
-        })
-        .await
-        .context("spawn_blocking for write_agent_artifacts")??;
-        Ok(dir)
-    }
-}
-
 impl crate::application::ports::FileHasher for LocalFs {
     /// # Errors
     ///
@@ -101,6 +72,13 @@ impl crate::application::ports::LocalFs for LocalFs {
         std::fs::write(path, content).with_context(|| format!("writing file {}", path.display()))
     }
 
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    fn write_bytes(&self, path: &Path, content: &[u8]) -> Result<()> {
+        std::fs::write(path, content).with_context(|| format!("writing file {}", path.display()))
+    }
+
     /// # Errors
     ///
     /// This function will return an error if the underlying operations fail.
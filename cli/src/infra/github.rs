@@ -0,0 +1,113 @@
+//! GitHub API request configuration shared by every GitHub call.
+//!
+//! The release list, `resolve_latest_image_url`, and the `self_update`
+//! installer all read their base URL, User-Agent, and token from here so
+//! authenticated rate limits apply consistently.
+
+/// GitHub releases API URL.
+pub const GITHUB_RELEASES_URL: &str =
+    "https://api.github.com/repos/OdraLabsHQ/polis/releases?per_page=10";
+
+/// Resolved GitHub request settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GithubConfig {
+    /// Releases endpoint (`POLIS_GITHUB_API_URL` override).
+    pub releases_url: String,
+    /// `User-Agent` header (`POLIS_USER_AGENT` override).
+    pub user_agent: String,
+    /// Bearer token for authenticated rate limits (`GITHUB_TOKEN`).
+    pub token: Option<String>,
+}
+
+impl GithubConfig {
+    /// Read settings from the process environment.
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Build settings from an environment lookup. Empty values count as unset.
+    #[must_use]
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| lookup(name).filter(|v| !v.trim().is_empty());
+        Self {
+            releases_url: var("POLIS_GITHUB_API_URL")
+                .unwrap_or_else(|| GITHUB_RELEASES_URL.to_string()),
+            user_agent: var("POLIS_USER_AGENT")
+                .unwrap_or_else(|| format!("polis-cli/{}", env!("CARGO_PKG_VERSION"))),
+            token: var("GITHUB_TOKEN"),
+        }
+    }
+
+    /// Headers for a GitHub API request. `Authorization` is only present
+    /// when a token is configured.
+    #[must_use]
+    pub fn api_headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = vec![
+            ("Accept", "application/vnd.github+json".to_string()),
+            ("User-Agent", self.user_agent.clone()),
+        ];
+        if let Some(token) = &self.token {
+            headers.push(("Authorization", format!("Bearer {token}")));
+        }
+        headers
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn config(vars: &[(&str, &str)]) -> GithubConfig {
+        GithubConfig::from_lookup(|name| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| (*v).to_string())
+        })
+    }
+
+    fn header<'a>(headers: &'a [(&'static str, String)], name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn test_api_headers_include_token_when_set() {
+        let headers = config(&[("GITHUB_TOKEN", "ghp_example")]).api_headers();
+        assert_eq!(
+            header(&headers, "Authorization"),
+            Some("Bearer ghp_example")
+        );
+    }
+
+    #[test]
+    fn test_api_headers_omit_authorization_without_token() {
+        let headers = config(&[("GITHUB_TOKEN", "  ")]).api_headers();
+        assert_eq!(header(&headers, "Authorization"), None);
+        assert!(
+            header(&headers, "User-Agent")
+                .expect("user agent")
+                .starts_with("polis-cli/")
+        );
+    }
+
+    #[test]
+    fn test_from_lookup_applies_overrides() {
+        let cfg = config(&[
+            (
+                "POLIS_GITHUB_API_URL",
+                "https://ghe.example.com/api/v3/repos/o/p/releases",
+            ),
+            ("POLIS_USER_AGENT", "acme-polis"),
+        ]);
+        assert_eq!(
+            cfg.releases_url,
+            "https://ghe.example.com/api/v3/repos/o/p/releases"
+        );
+        assert_eq!(cfg.user_agent, "acme-polis");
+        assert_eq!(cfg.token, None);
+    }
+}
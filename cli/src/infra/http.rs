@@ -0,0 +1,380 @@
+//! HTTP infrastructure — implements `HttpClient` using `ureq`.
+//!
+//! Every outbound HTTP request builds its agent through
+//! [`UreqHttpClient::agent_for`], so corporate proxies configured via
+//! `POLIS_PROXY` / `HTTPS_PROXY`, the `NO_PROXY` exclusions, and the
+//! connect/overall timeouts apply uniformly.
+
+use std::io::Read;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use crate::application::ports::HttpClient;
+
+/// Proxy settings read from the standard environment variables.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// Proxy URL, from the first of `POLIS_PROXY`, `HTTPS_PROXY`,
+    /// `https_proxy`, `ALL_PROXY`, `all_proxy` that is set.
+    pub proxy: Option<String>,
+    /// Lowercased `NO_PROXY` / `no_proxy` entries that bypass the proxy.
+    pub no_proxy: Vec<String>,
+}
+
+impl ProxyConfig {
+    /// Read settings from the process environment.
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Build settings from an environment lookup. Empty values count as unset.
+    #[must_use]
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| lookup(name).filter(|v| !v.trim().is_empty());
+        let proxy = [
+            "POLIS_PROXY",
+            "HTTPS_PROXY",
+            "https_proxy",
+            "ALL_PROXY",
+            "all_proxy",
+        ]
+        .into_iter()
+        .find_map(var)
+        .map(|v| v.trim().to_string());
+        let no_proxy = var("NO_PROXY")
+            .or_else(|| var("no_proxy"))
+            .unwrap_or_default()
+            .split(',')
+            .map(|entry| entry.trim().to_ascii_lowercase())
+            .filter(|entry| !entry.is_empty())
+            .collect();
+        Self { proxy, no_proxy }
+    }
+
+    /// Proxy to use for `url`, or `None` for a direct connection because no
+    /// proxy is configured or the host matches a `NO_PROXY` entry.
+    ///
+    /// `NO_PROXY` entries match the host exactly or any subdomain of it
+    /// (`github.com` and `.github.com` both cover `api.github.com`); a port
+    /// suffix on an entry is ignored, and `*` disables the proxy entirely.
+    #[must_use]
+    pub fn proxy_for(&self, url: &str) -> Option<&str> {
+        let proxy = self.proxy.as_deref()?;
+        let host = url_host(url).to_ascii_lowercase();
+        let bypass = self.no_proxy.iter().any(|entry| {
+            if entry == "*" {
+                return true;
+            }
+            let entry = entry.trim_start_matches("*.").trim_start_matches('.');
+            let entry = entry.rsplit_once(':').map_or(entry, |(name, _)| name);
+            host == entry || host.ends_with(&format!(".{entry}"))
+        });
+        (!bypass).then_some(proxy)
+    }
+}
+
+/// Host part of `url`, without scheme, credentials, port, or path.
+fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    if let Some(bracketed) = host_port.strip_prefix('[') {
+        return bracketed.split(']').next().unwrap_or_default();
+    }
+    host_port
+        .split_once(':')
+        .map_or(host_port, |(host, _)| host)
+}
+
+/// Default limit for establishing a connection.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default limit for a whole request, including reading the body.
+pub const DEFAULT_TOTAL_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Connect and overall deadlines applied to every request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpTimeouts {
+    /// Limit for the TCP/TLS connection to be established.
+    pub connect: Duration,
+    /// Limit for the entire request, from connect to the last body byte.
+    pub total: Duration,
+}
+
+impl Default for HttpTimeouts {
+    fn default() -> Self {
+        Self {
+            connect: DEFAULT_CONNECT_TIMEOUT,
+            total: DEFAULT_TOTAL_TIMEOUT,
+        }
+    }
+}
+
+impl HttpTimeouts {
+    /// Defaults, with the overall limit replaced by `total_secs` when given
+    /// (`--timeout`). The connect limit never exceeds the overall one.
+    #[must_use]
+    pub fn with_total_secs(total_secs: Option<u64>) -> Self {
+        let Some(secs) = total_secs else {
+            return Self::default();
+        };
+        let total = Duration::from_secs(secs);
+        Self {
+            connect: DEFAULT_CONNECT_TIMEOUT.min(total),
+            total,
+        }
+    }
+}
+
+/// Production HTTP client backed by `ureq`.
+#[derive(Debug, Clone, Default)]
+pub struct UreqHttpClient {
+    /// Proxy applied to every request.
+    pub proxy: ProxyConfig,
+    /// Deadlines applied to every request.
+    pub timeouts: HttpTimeouts,
+}
+
+impl UreqHttpClient {
+    /// Client using the proxy settings from the process environment.
+    #[must_use]
+    pub fn new(timeouts: HttpTimeouts) -> Self {
+        Self {
+            proxy: ProxyConfig::from_env(),
+            timeouts,
+        }
+    }
+
+    /// `ureq` agent for a request to `url`, routed through the proxy unless
+    /// the host is excluded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configured proxy URL is malformed.
+    pub fn agent_for(&self, url: &str) -> Result<ureq::Agent> {
+        let mut builder = ureq::AgentBuilder::new()
+            .timeout_connect(self.timeouts.connect)
+            .timeout(self.timeouts.total);
+        if let Some(proxy) = self.proxy.proxy_for(url) {
+            // The URL may carry credentials, so it is not echoed back.
+            let proxy = ureq::Proxy::new(proxy).context(
+                "invalid proxy URL in POLIS_PROXY/HTTPS_PROXY (expected http://host:port)",
+            )?;
+            builder = builder.proxy(proxy);
+        }
+        Ok(builder.build())
+    }
+
+    /// Error for a request that hit `limit`.
+    fn timed_out(limit: Duration) -> anyhow::Error {
+        anyhow::anyhow!(
+            "download timed out after {}s; check connectivity or raise --timeout",
+            limit.as_secs()
+        )
+    }
+}
+
+/// Whether an I/O error means a deadline passed.
+fn is_timeout(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+    )
+}
+
+impl HttpClient for UreqHttpClient {
+    /// # Errors
+    ///
+    /// Returns a "download timed out" error when the connect or overall
+    /// deadline passes, and the underlying error for any other failure.
+    fn get(&self, url: &str, headers: &[(&str, String)], max_bytes: u64) -> Result<Vec<u8>> {
+        let request = headers
+            .iter()
+            .fold(self.agent_for(url)?.get(url), |req, (name, value)| {
+                req.set(name, value)
+            });
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Transport(transport))
+                if std::error::Error::source(&transport)
+                    .and_then(|e| e.downcast_ref::<std::io::Error>())
+                    .is_some_and(is_timeout) =>
+            {
+                let limit = if transport.kind() == ureq::ErrorKind::ConnectionFailed {
+                    self.timeouts.connect
+                } else {
+                    self.timeouts.total
+                };
+                return Err(Self::timed_out(limit));
+            }
+            Err(e) => return Err(e).with_context(|| format!("GET {url}")),
+        };
+        let mut body = Vec::new();
+        match response
+            .into_reader()
+            .take(max_bytes)
+            .read_to_end(&mut body)
+        {
+            Ok(_) => Ok(body),
+            Err(e) if is_timeout(&e) => Err(Self::timed_out(self.timeouts.total)),
+            Err(e) => Err(e).with_context(|| format!("reading response from {url}")),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn config(vars: &[(&str, &str)]) -> ProxyConfig {
+        ProxyConfig::from_lookup(|name| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| (*v).to_string())
+        })
+    }
+
+    const RELEASES: &str = "https://api.github.com/repos/OdraLabsHQ/polis/releases";
+
+    #[test]
+    fn test_from_lookup_without_proxy_connects_directly() {
+        let cfg = config(&[("NO_PROXY", "github.com")]);
+        assert_eq!(cfg.proxy, None);
+        assert_eq!(cfg.proxy_for(RELEASES), None);
+    }
+
+    #[test]
+    fn test_from_lookup_polis_proxy_takes_precedence() {
+        let cfg = config(&[
+            ("HTTPS_PROXY", "http://corp:3128"),
+            ("POLIS_PROXY", "http://polis-only:8080"),
+        ]);
+        assert_eq!(cfg.proxy_for(RELEASES), Some("http://polis-only:8080"));
+    }
+
+    #[test]
+    fn test_from_lookup_reads_standard_vars_in_order() {
+        assert_eq!(
+            config(&[
+                ("https_proxy", "http://lower:1"),
+                ("ALL_PROXY", "http://all:2")
+            ])
+            .proxy,
+            Some("http://lower:1".to_string())
+        );
+        assert_eq!(
+            config(&[("HTTPS_PROXY", " "), ("all_proxy", "http://all:2")]).proxy,
+            Some("http://all:2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_no_proxy_bypasses_matching_hosts() {
+        let cfg = config(&[
+            ("HTTPS_PROXY", "http://corp:3128"),
+            ("no_proxy", "localhost, .GitHub.com,ghcr.io:443"),
+        ]);
+        assert_eq!(
+            cfg.no_proxy,
+            vec!["localhost", ".github.com", "ghcr.io:443"]
+        );
+        assert_eq!(cfg.proxy_for(RELEASES), None);
+        assert_eq!(cfg.proxy_for("https://github.com/OdraLabsHQ/polis"), None);
+        assert_eq!(cfg.proxy_for("https://ghcr.io/v2/"), None);
+        assert_eq!(
+            cfg.proxy_for("https://objects.githubusercontent.com/asset"),
+            Some("http://corp:3128")
+        );
+        assert_eq!(
+            cfg.proxy_for("https://notgithub.com/"),
+            Some("http://corp:3128")
+        );
+    }
+
+    #[test]
+    fn test_no_proxy_wildcard_disables_proxy() {
+        let cfg = config(&[("HTTPS_PROXY", "http://corp:3128"), ("NO_PROXY", "*")]);
+        assert_eq!(cfg.proxy_for(RELEASES), None);
+    }
+
+    #[test]
+    fn test_url_host_strips_credentials_port_and_path() {
+        assert_eq!(
+            url_host("https://user:pw@api.github.com:443/x?y"),
+            "api.github.com"
+        );
+        assert_eq!(url_host("http://[::1]:8080/"), "::1");
+        assert_eq!(url_host("ghcr.io"), "ghcr.io");
+    }
+
+    fn client(proxy: ProxyConfig, timeouts: HttpTimeouts) -> UreqHttpClient {
+        UreqHttpClient { proxy, timeouts }
+    }
+
+    #[test]
+    fn test_agent_for_rejects_malformed_proxy() {
+        let cfg = config(&[("POLIS_PROXY", "ftp://corp:3128")]);
+        assert!(
+            client(cfg, HttpTimeouts::default())
+                .agent_for(RELEASES)
+                .is_err()
+        );
+        let cfg = config(&[
+            ("POLIS_PROXY", "ftp://corp:3128"),
+            ("NO_PROXY", "github.com"),
+        ]);
+        assert!(
+            client(cfg, HttpTimeouts::default())
+                .agent_for(RELEASES)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_timeouts_default_and_override() {
+        assert_eq!(
+            HttpTimeouts::with_total_secs(None),
+            HttpTimeouts {
+                connect: Duration::from_secs(30),
+                total: Duration::from_secs(300),
+            }
+        );
+        let short = HttpTimeouts::with_total_secs(Some(10));
+        assert_eq!(short.total, Duration::from_secs(10));
+        assert_eq!(short.connect, Duration::from_secs(10));
+        let long = HttpTimeouts::with_total_secs(Some(900));
+        assert_eq!(long.connect, DEFAULT_CONNECT_TIMEOUT);
+    }
+
+    #[test]
+    fn test_get_from_server_that_never_responds_times_out() {
+        // Accepts the connection, then never writes a byte.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let url = format!(
+            "http://{}/asset.tar.gz",
+            listener.local_addr().expect("addr")
+        );
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        let server = std::thread::spawn(move || {
+            let (_stream, _) = listener.accept().expect("accept");
+            let _ = done_rx.recv();
+        });
+
+        let timeouts = HttpTimeouts::with_total_secs(Some(1));
+        let err = client(ProxyConfig::default(), timeouts)
+            .get(&url, &[], 1024)
+            .expect_err("stalled server must time out");
+        let msg = err.to_string();
+        assert!(
+            msg.starts_with("download timed out after 1s; check connectivity"),
+            "{msg}"
+        );
+        drop(done_tx);
+        server.join().expect("server thread");
+    }
+}
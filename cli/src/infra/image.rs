@@ -9,9 +9,7 @@ pub struct ResolvedRelease {
     pub tag: String,
 }
 
-/// GitHub releases API URL.
-pub const GITHUB_RELEASES_URL: &str =
-    "https://api.github.com/repos/OdraLabsHQ/polis/releases?per_page=10";
+pub use crate::infra::github::GITHUB_RELEASES_URL;
 
 /// Resolve the latest release tag from GitHub releases.
 ///
@@ -19,20 +17,20 @@ pub const GITHUB_RELEASES_URL: &str =
 ///
 /// # Errors
 ///
-/// Returns an error if the network is unavailable or no release is found.
-pub fn resolve_latest_image_url() -> Result<ResolvedRelease> {
-    let url =
-        std::env::var("POLIS_GITHUB_API_URL").unwrap_or_else(|_| GITHUB_RELEASES_URL.to_string());
-    let token = std::env::var("GITHUB_TOKEN").unwrap_or_default();
-
-    let req = ureq::get(&url)
-        .set("Accept", "application/vnd.github+json")
-        .set("User-Agent", "polis-cli");
-    let req = if token.is_empty() {
-        req
-    } else {
-        req.set("Authorization", &format!("Bearer {token}"))
-    };
+/// Returns an error if `offline` is set, the network is unavailable, or no
+/// release is found.
+pub fn resolve_latest_image_url(offline: bool) -> Result<ResolvedRelease> {
+    crate::infra::network::ensure_online(offline, "release lookup")?;
+    let github = crate::infra::github::GithubConfig::from_env();
+    let agent =
+        crate::infra::http::UreqHttpClient::new(crate::infra::http::HttpTimeouts::default())
+            .agent_for(&github.releases_url)?;
+    let req = github
+        .api_headers()
+        .iter()
+        .fold(agent.get(&github.releases_url), |req, (name, value)| {
+            req.set(name, value)
+        });
 
     let body: serde_json::Value = match req.call() {
         Ok(resp) => serde_json::from_str(&resp.into_string().context("reading response")?)
@@ -6,14 +6,19 @@
 //! Imports from `crate::domain` and `crate::application::ports` are allowed.
 //! Imports from `crate::commands` or `crate::output` are forbidden.
 
+pub mod agent_bundle;
 pub mod assets;
 pub mod command_runner;
 pub mod config;
 pub mod fs;
+pub mod github;
+pub mod http;
 pub mod image;
 pub mod network;
 pub mod provisioner;
+pub mod retry;
 pub mod ssh;
 pub mod state;
+pub mod trace;
 
 pub mod update;
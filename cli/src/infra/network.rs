@@ -4,6 +4,17 @@ use anyhow::Result;
 
 use crate::application::ports::NetworkProbe;
 
+/// Fail fast with a clear message instead of touching the network when
+/// `--offline` / `POLIS_OFFLINE` is active.
+///
+/// # Errors
+///
+/// Returns `offline mode; skipping {what}` when `offline` is `true`.
+pub fn ensure_online(offline: bool, what: &str) -> Result<()> {
+    anyhow::ensure!(!offline, "offline mode; skipping {what}");
+    Ok(())
+}
+
 /// Production implementation that performs real network checks.
 #[allow(dead_code)] // Not yet wired from command handlers
 pub struct TokioNetworkProbe;
@@ -14,6 +14,7 @@ use crate::application::ports::{
     FileTransfer, InstanceInspector, InstanceLifecycle, InstanceSpec, POLIS_INSTANCE, ShellExecutor,
 };
 use crate::infra::command_runner::{DEFAULT_CMD_TIMEOUT, DEFAULT_EXEC_TIMEOUT, TokioCommandRunner};
+use crate::infra::trace::TracingRunner;
 
 /// Infrastructure adapter that routes all multipass CLI calls through a `CommandRunner`.
 ///
@@ -22,6 +23,7 @@ use crate::infra::command_runner::{DEFAULT_CMD_TIMEOUT, DEFAULT_EXEC_TIMEOUT, To
 pub struct MultipassProvisioner<R: CommandRunner> {
     cmd_runner: R,
     exec_runner: R,
+    instance: &'static str,
 }
 
 impl<R: CommandRunner> MultipassProvisioner<R> {
@@ -31,9 +33,21 @@ impl<R: CommandRunner> MultipassProvisioner<R> {
         Self {
             cmd_runner,
             exec_runner,
+            instance: POLIS_INSTANCE,
         }
     }
 
+    /// Point every call at `instance` instead of the user's `polis` VM.
+    ///
+    /// For any other instance, `purge` runs `multipass delete --purge
+    /// <instance>` rather than a global `multipass purge`, so it never
+    /// touches other deleted instances.
+    #[must_use]
+    pub fn with_instance(mut self, instance: &'static str) -> Self {
+        self.instance = instance;
+        self
+    }
+
     /// Create a `TimeoutView` that overrides the command timeout for the
     /// duration of the returned view's lifetime.
     ///
@@ -50,13 +64,15 @@ impl<R: CommandRunner> MultipassProvisioner<R> {
     }
 }
 
-impl MultipassProvisioner<TokioCommandRunner> {
-    /// Convenience constructor for production use.
+impl MultipassProvisioner<TracingRunner<TokioCommandRunner>> {
+    /// Convenience constructor for production use. Calls are traced to
+    /// `POLIS_TRACE_FILE` when it is set.
     #[must_use]
     pub fn default_runner() -> Self {
         Self {
-            cmd_runner: TokioCommandRunner::new(DEFAULT_CMD_TIMEOUT),
-            exec_runner: TokioCommandRunner::new(DEFAULT_EXEC_TIMEOUT),
+            cmd_runner: TracingRunner::from_env(TokioCommandRunner::new(DEFAULT_CMD_TIMEOUT)),
+            exec_runner: TracingRunner::from_env(TokioCommandRunner::new(DEFAULT_EXEC_TIMEOUT)),
+            instance: POLIS_INSTANCE,
         }
     }
 }
@@ -71,7 +87,7 @@ impl<R: CommandRunner> InstanceLifecycle for MultipassProvisioner<R> {
             "launch",
             spec.image,
             "--name",
-            POLIS_INSTANCE,
+            self.instance,
             "--cpus",
             spec.cpus,
             "--memory",
@@ -103,7 +119,7 @@ impl<R: CommandRunner> InstanceLifecycle for MultipassProvisioner<R> {
         self.cmd_runner
             .run_with_timeout(
                 "multipass",
-                &["start", POLIS_INSTANCE],
+                &["start", self.instance],
                 Duration::from_secs(180),
             )
             .await
@@ -115,7 +131,7 @@ impl<R: CommandRunner> InstanceLifecycle for MultipassProvisioner<R> {
     /// This function will return an error if the underlying operations fail.
     async fn stop(&self) -> Result<Output> {
         self.cmd_runner
-            .run("multipass", &["stop", POLIS_INSTANCE])
+            .run("multipass", &["stop", self.instance])
             .await
             .context("failed to run multipass stop")
     }
@@ -125,7 +141,7 @@ impl<R: CommandRunner> InstanceLifecycle for MultipassProvisioner<R> {
     /// This function will return an error if the underlying operations fail.
     async fn delete(&self) -> Result<Output> {
         self.cmd_runner
-            .run("multipass", &["delete", POLIS_INSTANCE])
+            .run("multipass", &["delete", self.instance])
             .await
             .context("failed to run multipass delete")
     }
@@ -134,20 +150,71 @@ impl<R: CommandRunner> InstanceLifecycle for MultipassProvisioner<R> {
     ///
     /// This function will return an error if the underlying operations fail.
     async fn purge(&self) -> Result<Output> {
+        let args: &[&str] = if self.instance == POLIS_INSTANCE {
+            &["purge"]
+        } else {
+            &["delete", "--purge", self.instance]
+        };
         self.cmd_runner
-            .run("multipass", &["purge"])
+            .run("multipass", args)
             .await
             .context("failed to run multipass purge")
     }
+
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    async fn snapshot(&self, name: &str) -> Result<Output> {
+        // Snapshotting copies the instance disk, which can take a while.
+        self.cmd_runner
+            .run_with_timeout(
+                "multipass",
+                &["snapshot", "--name", name, self.instance],
+                Duration::from_secs(600),
+            )
+            .await
+            .context("failed to run multipass snapshot")
+    }
+
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    async fn restore(&self, name: &str) -> Result<Output> {
+        let target = format!("{}.{name}", self.instance);
+        // `--destructive` skips the interactive prompt to snapshot the
+        // current state first.
+        self.cmd_runner
+            .run_with_timeout(
+                "multipass",
+                &["restore", "--destructive", &target],
+                Duration::from_secs(600),
+            )
+            .await
+            .context("failed to run multipass restore")
+    }
+
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    async fn list_snapshots(&self) -> Result<Output> {
+        self.cmd_runner
+            .run("multipass", &["list", "--snapshots", "--format", "json"])
+            .await
+            .context("failed to run multipass list")
+    }
 }
 
 impl<R: CommandRunner> InstanceInspector for MultipassProvisioner<R> {
+    fn instance_name(&self) -> &str {
+        self.instance
+    }
+
     /// # Errors
     ///
     /// This function will return an error if the underlying operations fail.
     async fn info(&self) -> Result<Output> {
         self.cmd_runner
-            .run("multipass", &["info", POLIS_INSTANCE, "--format", "json"])
+            .run("multipass", &["info", self.instance, "--format", "json"])
             .await
             .context("failed to run multipass info")
     }
@@ -168,7 +235,7 @@ impl<R: CommandRunner> FileTransfer for MultipassProvisioner<R> {
     ///
     /// This function will return an error if the underlying operations fail.
     async fn transfer(&self, local: &str, remote: &str) -> Result<Output> {
-        let dest = format!("{POLIS_INSTANCE}:{remote}");
+        let dest = format!("{}:{remote}", self.instance);
         self.cmd_runner
             .run("multipass", &["transfer", local, &dest])
             .await
@@ -179,7 +246,7 @@ impl<R: CommandRunner> FileTransfer for MultipassProvisioner<R> {
     ///
     /// This function will return an error if the underlying operations fail.
     async fn transfer_recursive(&self, local: &str, remote: &str) -> Result<Output> {
-        let dest = format!("{POLIS_INSTANCE}:{remote}");
+        let dest = format!("{}:{remote}", self.instance);
         self.cmd_runner
             .run("multipass", &["transfer", "--recursive", local, &dest])
             .await
@@ -192,7 +259,7 @@ impl<R: CommandRunner> ShellExecutor for MultipassProvisioner<R> {
     ///
     /// This function will return an error if the underlying operations fail.
     async fn exec(&self, args: &[&str]) -> Result<Output> {
-        let mut full = vec!["exec", POLIS_INSTANCE, "--"];
+        let mut full = vec!["exec", self.instance, "--"];
         full.extend_from_slice(args);
         self.exec_runner
             .run("multipass", &full)
@@ -204,7 +271,7 @@ impl<R: CommandRunner> ShellExecutor for MultipassProvisioner<R> {
     ///
     /// This function will return an error if the underlying operations fail.
     async fn exec_with_stdin(&self, args: &[&str], input: &[u8]) -> Result<Output> {
-        let mut full = vec!["exec", POLIS_INSTANCE, "--"];
+        let mut full = vec!["exec", self.instance, "--"];
         full.extend_from_slice(args);
         self.exec_runner
             .run_with_stdin("multipass", &full, input)
@@ -216,7 +283,7 @@ impl<R: CommandRunner> ShellExecutor for MultipassProvisioner<R> {
     ///
     /// This function will return an error if the underlying operations fail.
     fn exec_spawn(&self, args: &[&str]) -> Result<tokio::process::Child> {
-        let mut full = vec!["exec", POLIS_INSTANCE, "--"];
+        let mut full = vec!["exec", self.instance, "--"];
         full.extend_from_slice(args);
         self.cmd_runner
             .spawn("multipass", &full)
@@ -227,7 +294,7 @@ impl<R: CommandRunner> ShellExecutor for MultipassProvisioner<R> {
     ///
     /// This function will return an error if the underlying operations fail.
     async fn exec_status(&self, args: &[&str]) -> Result<std::process::ExitStatus> {
-        let mut full = vec!["exec", POLIS_INSTANCE, "--"];
+        let mut full = vec!["exec", self.instance, "--"];
         full.extend_from_slice(args);
         self.cmd_runner
             .run_status("multipass", &full)
@@ -250,6 +317,10 @@ pub struct TimeoutView<'a, R: CommandRunner> {
 }
 
 impl<R: CommandRunner> InstanceInspector for TimeoutView<'_, R> {
+    fn instance_name(&self) -> &str {
+        self.provisioner.instance
+    }
+
     /// # Errors
     ///
     /// This function will return an error if the underlying operations fail.
@@ -258,7 +329,7 @@ impl<R: CommandRunner> InstanceInspector for TimeoutView<'_, R> {
             .cmd_runner
             .run_with_timeout(
                 "multipass",
-                &["info", POLIS_INSTANCE, "--format", "json"],
+                &["info", self.provisioner.instance, "--format", "json"],
                 self.timeout,
             )
             .await
@@ -282,7 +353,7 @@ impl<R: CommandRunner> ShellExecutor for TimeoutView<'_, R> {
     ///
     /// This function will return an error if the underlying operations fail.
     async fn exec(&self, args: &[&str]) -> Result<Output> {
-        let mut full = vec!["exec", POLIS_INSTANCE, "--"];
+        let mut full = vec!["exec", self.provisioner.instance, "--"];
         full.extend_from_slice(args);
         self.provisioner
             .exec_runner
@@ -295,7 +366,7 @@ impl<R: CommandRunner> ShellExecutor for TimeoutView<'_, R> {
     ///
     /// This function will return an error if the underlying operations fail.
     async fn exec_with_stdin(&self, args: &[&str], input: &[u8]) -> Result<Output> {
-        let mut full = vec!["exec", POLIS_INSTANCE, "--"];
+        let mut full = vec!["exec", self.provisioner.instance, "--"];
         full.extend_from_slice(args);
         self.provisioner
             .exec_runner
@@ -308,7 +379,7 @@ impl<R: CommandRunner> ShellExecutor for TimeoutView<'_, R> {
     ///
     /// This function will return an error if the underlying operations fail.
     fn exec_spawn(&self, args: &[&str]) -> Result<tokio::process::Child> {
-        let mut full = vec!["exec", POLIS_INSTANCE, "--"];
+        let mut full = vec!["exec", self.provisioner.instance, "--"];
         full.extend_from_slice(args);
         self.provisioner
             .cmd_runner
@@ -320,7 +391,7 @@ impl<R: CommandRunner> ShellExecutor for TimeoutView<'_, R> {
     ///
     /// This function will return an error if the underlying operations fail.
     async fn exec_status(&self, args: &[&str]) -> Result<std::process::ExitStatus> {
-        let mut full = vec!["exec", POLIS_INSTANCE, "--"];
+        let mut full = vec!["exec", self.provisioner.instance, "--"];
         full.extend_from_slice(args);
         self.provisioner
             .cmd_runner
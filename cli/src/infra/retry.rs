@@ -0,0 +1,270 @@
+//! Retry decorator for the `CommandRunner` port.
+//!
+//! `RetryingCommandRunner<R>` re-runs a command according to a
+//! [`RetryPolicy`]: how many attempts, the backoff between them, and which
+//! outcomes are worth another try. Call sites opt in by wrapping their
+//! runner — idempotent calls such as file transfers or probes can retry,
+//! while destructive operations keep using the bare runner.
+
+use std::future::Future;
+use std::process::{ExitStatus, Output};
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::application::ports::CommandRunner;
+
+/// What a single attempt produced, as seen by a [`RetryPolicy`] predicate.
+pub enum Attempt<'a> {
+    /// The command could not be run, or was killed on timeout.
+    Error(&'a anyhow::Error),
+    /// The command ran to completion. `stdout` is empty for `run_status`.
+    Exited {
+        status: ExitStatus,
+        stdout: &'a [u8],
+    },
+}
+
+/// When and how often a [`RetryingCommandRunner`] re-runs a command.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first (at least one is always made).
+    pub attempts: u32,
+    /// Delay before the first retry; doubled for each later one, plus jitter.
+    pub base_delay: Duration,
+    /// Whether an attempt's outcome warrants another try.
+    pub retryable: fn(&Attempt<'_>) -> bool,
+}
+
+impl RetryPolicy {
+    /// Retry any failure — spawn errors, timeouts and non-zero exits.
+    #[must_use]
+    pub fn new(attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            attempts,
+            base_delay,
+            retryable: is_failure,
+        }
+    }
+
+    /// Retry only the outcomes `retryable` accepts.
+    #[must_use]
+    pub fn retry_if(mut self, retryable: fn(&Attempt<'_>) -> bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+}
+
+/// Default predicate: anything other than a zero exit is retryable.
+fn is_failure(attempt: &Attempt<'_>) -> bool {
+    !matches!(attempt, Attempt::Exited { status, .. } if status.success())
+}
+
+fn output_attempt(result: &Result<Output>) -> Attempt<'_> {
+    match result {
+        Ok(output) => Attempt::Exited {
+            status: output.status,
+            stdout: &output.stdout,
+        },
+        Err(e) => Attempt::Error(e),
+    }
+}
+
+fn status_attempt(result: &Result<ExitStatus>) -> Attempt<'_> {
+    match result {
+        Ok(status) => Attempt::Exited {
+            status: *status,
+            stdout: &[],
+        },
+        Err(e) => Attempt::Error(e),
+    }
+}
+
+/// `CommandRunner` decorator that retries calls according to a [`RetryPolicy`].
+///
+/// The last attempt's result is returned as-is, so callers still see the
+/// final exit status or error once retries are exhausted. `spawn` is
+/// forwarded without retries.
+pub struct RetryingCommandRunner<R: CommandRunner> {
+    inner: R,
+    policy: RetryPolicy,
+}
+
+impl<R: CommandRunner> RetryingCommandRunner<R> {
+    pub fn new(inner: R, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    async fn retry<T, F, Fut>(
+        &self,
+        mut call: F,
+        attempt_of: fn(&Result<T>) -> Attempt<'_>,
+    ) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            let result = call().await;
+            if attempt >= self.policy.attempts || !(self.policy.retryable)(&attempt_of(&result)) {
+                return result;
+            }
+            tokio::time::sleep(backoff_with_jitter(self.policy.base_delay, attempt)).await;
+            attempt += 1;
+        }
+    }
+}
+
+impl<R: CommandRunner> CommandRunner for RetryingCommandRunner<R> {
+    async fn run(&self, program: &str, args: &[&str]) -> Result<Output> {
+        self.retry(|| self.inner.run(program, args), output_attempt)
+            .await
+    }
+
+    async fn run_with_timeout(
+        &self,
+        program: &str,
+        args: &[&str],
+        timeout: Duration,
+    ) -> Result<Output> {
+        self.retry(
+            || self.inner.run_with_timeout(program, args, timeout),
+            output_attempt,
+        )
+        .await
+    }
+
+    async fn run_with_stdin(&self, program: &str, args: &[&str], stdin: &[u8]) -> Result<Output> {
+        self.retry(
+            || self.inner.run_with_stdin(program, args, stdin),
+            output_attempt,
+        )
+        .await
+    }
+
+    fn spawn(&self, program: &str, args: &[&str]) -> Result<tokio::process::Child> {
+        self.inner.spawn(program, args)
+    }
+
+    async fn run_status(&self, program: &str, args: &[&str]) -> Result<ExitStatus> {
+        self.retry(|| self.inner.run_status(program, args), status_attempt)
+            .await
+    }
+}
+
+/// Delay before retry `attempt` (1-based): `base * 2^(attempt - 1)` plus up to
+/// 50% random jitter so concurrent callers do not retry in lockstep.
+fn backoff_with_jitter(base: Duration, attempt: u32) -> Duration {
+    use std::hash::{BuildHasher as _, Hasher as _};
+
+    let delay = base.saturating_mul(1 << attempt.saturating_sub(1).min(6));
+    let max_jitter = u64::try_from(delay.as_millis() / 2).unwrap_or(u64::MAX);
+    let random = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    delay + Duration::from_millis(random % (max_jitter + 1))
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::application::services::vm::test_support::{exit_status, fail_output, ok_output};
+
+    /// Fails (exit 1) `failures` times, then succeeds; `missing` never spawns.
+    struct FlakyRunner {
+        failures: u32,
+        calls: Cell<u32>,
+    }
+
+    impl FlakyRunner {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures,
+                calls: Cell::new(0),
+            }
+        }
+    }
+
+    impl CommandRunner for FlakyRunner {
+        async fn run(&self, program: &str, _: &[&str]) -> Result<Output> {
+            let n = self.calls.get();
+            self.calls.set(n + 1);
+            anyhow::ensure!(program != "missing", "failed to spawn {program}");
+            if n < self.failures {
+                Ok(fail_output())
+            } else {
+                Ok(ok_output(b"done\n"))
+            }
+        }
+        async fn run_with_timeout(&self, p: &str, a: &[&str], _: Duration) -> Result<Output> {
+            self.run(p, a).await
+        }
+        async fn run_with_stdin(&self, p: &str, a: &[&str], _: &[u8]) -> Result<Output> {
+            self.run(p, a).await
+        }
+        fn spawn(&self, _: &str, _: &[&str]) -> Result<tokio::process::Child> {
+            anyhow::bail!("not expected")
+        }
+        async fn run_status(&self, p: &str, a: &[&str]) -> Result<ExitStatus> {
+            self.run(p, a).await.map(|o| o.status)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_failures_until_success() {
+        let inner = FlakyRunner::new(2);
+        let runner = RetryingCommandRunner::new(&inner, RetryPolicy::new(5, Duration::ZERO));
+
+        let output = runner.run("multipass", &["transfer"]).await.expect("run");
+        assert!(output.status.success());
+        assert_eq!(inner.calls.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_stops_after_policy_attempts() {
+        let inner = FlakyRunner::new(u32::MAX);
+        let runner = RetryingCommandRunner::new(&inner, RetryPolicy::new(3, Duration::ZERO));
+
+        let status = runner
+            .run_status("multipass", &["transfer"])
+            .await
+            .expect("run_status");
+        assert_eq!(status, exit_status(1), "last attempt's status is returned");
+        assert_eq!(inner.calls.get(), 3);
+
+        assert!(runner.run("missing", &[]).await.is_err());
+        assert_eq!(inner.calls.get(), 6, "spawn errors are retried by default");
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_outcome_is_returned_immediately() {
+        let inner = FlakyRunner::new(u32::MAX);
+        let policy = RetryPolicy::new(5, Duration::ZERO)
+            .retry_if(|attempt| matches!(attempt, Attempt::Error(_)));
+        let runner = RetryingCommandRunner::new(&inner, policy);
+
+        let output = runner.run("multipass", &["delete"]).await.expect("run");
+        assert_eq!(output.status, exit_status(1));
+        assert_eq!(inner.calls.get(), 1);
+
+        assert!(runner.run("missing", &[]).await.is_err());
+        assert_eq!(inner.calls.get(), 6);
+    }
+
+    #[test]
+    fn test_backoff_with_jitter_grows_within_bounds() {
+        let base = Duration::from_millis(100);
+        for attempt in 1..=4 {
+            let exp = base * (1 << (attempt - 1));
+            let delay = backoff_with_jitter(base, attempt);
+            assert!(
+                delay >= exp && delay <= exp + exp / 2,
+                "{attempt}: {delay:?}"
+            );
+        }
+    }
+}
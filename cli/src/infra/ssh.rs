@@ -93,6 +93,46 @@ impl KnownHostsManager {
     }
 }
 
+/// Writes `block` into the SSH config at `path`; see
+/// [`SshConfigManager::write_managed_block`].
+fn write_managed_block(path: &std::path::Path, block: &str) -> Result<Option<PathBuf>> {
+    let existing = match std::fs::symlink_metadata(path) {
+        Ok(meta) => {
+            anyhow::ensure!(
+                meta.file_type().is_file(),
+                "{} is not a regular file; refusing to modify it",
+                path.display()
+            );
+            Some(
+                std::fs::read_to_string(path)
+                    .with_context(|| format!("read {}", path.display()))?,
+            )
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e).with_context(|| format!("stat {}", path.display())),
+    };
+
+    let backup = if let Some(content) = &existing {
+        let backup = path.with_file_name("config.polis-backup");
+        std::fs::write(&backup, content).with_context(|| format!("write {}", backup.display()))?;
+        set_permissions(&backup, 0o600)?;
+        Some(backup)
+    } else {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("create dir {}", parent.display()))?;
+            set_permissions(parent, 0o700)?;
+        }
+        None
+    };
+
+    let updated =
+        crate::domain::ssh::replace_managed_block(existing.as_deref().unwrap_or_default(), block);
+    std::fs::write(path, updated).with_context(|| format!("write {}", path.display()))?;
+    set_permissions(path, 0o600)?;
+    Ok(backup)
+}
+
 /// # Errors
 /// This function will return an error if the underlying operations fail.
 #[cfg(unix)]
@@ -443,6 +483,16 @@ Host workspace
         Ok(())
     }
 
+    /// Writes the managed `Host polis` block into `~/.ssh/config`, replacing
+    /// any prior block. The existing config is first copied to
+    /// `config.polis-backup`; refuses to follow a symlink or overwrite a
+    /// non-file. Returns the backup path, or `None` if there was no config.
+    /// # Errors
+    /// Returns an error if the path is not a regular file or any I/O fails.
+    pub fn write_managed_block(&self, block: &str) -> Result<Option<PathBuf>> {
+        write_managed_block(&self.user_config_path, block)
+    }
+
     /// Creates `~/.polis/sockets/` with permissions 700.
     /// No-op on Windows (`ControlMaster` not supported).
     /// # Errors
@@ -801,6 +851,137 @@ mod ssh_config_manager_tests {
             );
         }
     }
+
+    // -----------------------------------------------------------------------
+    // write_managed_block
+    // -----------------------------------------------------------------------
+
+    const BLOCK: &str = "# >>> polis (managed by `polis connect --write-ssh-config`) >>>\n\
+Host polis\n    HostName 10.0.0.2\n# <<< polis <<<\n";
+
+    #[test]
+    fn test_write_managed_block_backs_up_and_is_idempotent() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let mgr = manager_in(&dir);
+        let config = dir.path().join("ssh").join("config");
+        std::fs::create_dir_all(dir.path().join("ssh")).expect("mkdir");
+        std::fs::write(&config, "Host *\n    ServerAliveInterval 60\n").expect("write config");
+
+        let backup = mgr
+            .write_managed_block(BLOCK)
+            .expect("first write")
+            .expect("backup of existing config");
+        assert_eq!(
+            std::fs::read_to_string(&backup).expect("read backup"),
+            "Host *\n    ServerAliveInterval 60\n"
+        );
+        let first = std::fs::read_to_string(&config).expect("read config");
+        assert!(first.contains("Host polis"));
+
+        mgr.write_managed_block(BLOCK).expect("second write");
+        assert_eq!(std::fs::read_to_string(&config).expect("reread"), first);
+    }
+
+    #[test]
+    fn test_write_managed_block_creates_missing_config() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let mgr = manager_in(&dir);
+        let backup = mgr.write_managed_block(BLOCK).expect("write");
+        assert!(backup.is_none());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("ssh").join("config")).expect("read"),
+            BLOCK
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_managed_block_refuses_symlinked_config() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let mgr = manager_in(&dir);
+        let target = dir.path().join("elsewhere");
+        std::fs::write(&target, "").expect("write target");
+        std::fs::create_dir_all(dir.path().join("ssh")).expect("mkdir");
+        std::os::unix::fs::symlink(&target, dir.path().join("ssh").join("config"))
+            .expect("symlink");
+        assert!(mgr.write_managed_block(BLOCK).is_err());
+        assert_eq!(std::fs::read_to_string(&target).expect("read target"), "");
+    }
+}
+
+#[cfg(test)]
+mod host_key_extraction_tests {
+    use std::cell::Cell;
+    use std::process::Output;
+    use std::time::Duration;
+
+    use anyhow::Result;
+
+    use super::extract_host_key_with_retry;
+    use crate::application::ports::CommandRunner;
+    use crate::application::services::vm::test_support::{fail_output, ok_output};
+
+    /// Fails the first `failures` runs, then prints a host key.
+    struct FlakyRunner {
+        failures: u32,
+        calls: Cell<u32>,
+    }
+
+    impl FlakyRunner {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures,
+                calls: Cell::new(0),
+            }
+        }
+    }
+
+    impl CommandRunner for FlakyRunner {
+        async fn run(&self, program: &str, args: &[&str]) -> Result<Output> {
+            assert_eq!(program, "polis");
+            assert_eq!(args, ["_extract-host-key"]);
+            let n = self.calls.get();
+            self.calls.set(n + 1);
+            if n < self.failures {
+                Ok(fail_output())
+            } else {
+                Ok(ok_output(
+                    b"workspace ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIG\n",
+                ))
+            }
+        }
+        async fn run_with_timeout(&self, _: &str, _: &[&str], _: Duration) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        async fn run_with_stdin(&self, _: &str, _: &[&str], _: &[u8]) -> Result<Output> {
+            anyhow::bail!("not expected")
+        }
+        fn spawn(&self, _: &str, _: &[&str]) -> Result<tokio::process::Child> {
+            anyhow::bail!("not expected")
+        }
+        async fn run_status(&self, _: &str, _: &[&str]) -> Result<std::process::ExitStatus> {
+            anyhow::bail!("not expected")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_host_key_with_retry_succeeds_after_transient_failures() {
+        let runner = FlakyRunner::new(2);
+        let key = extract_host_key_with_retry(&runner, "polis", 5, Duration::ZERO).await;
+        assert_eq!(
+            key.as_deref(),
+            Some("workspace ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIG")
+        );
+        assert_eq!(runner.calls.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_extract_host_key_with_retry_gives_up_after_all_attempts() {
+        let runner = FlakyRunner::new(u32::MAX);
+        let key = extract_host_key_with_retry(&runner, "polis", 3, Duration::ZERO).await;
+        assert!(key.is_none());
+        assert_eq!(runner.calls.get(), 3);
+    }
 }
 
 impl crate::application::ports::SshConfigurator for SshConfigManager {
@@ -881,22 +1062,86 @@ impl crate::application::ports::SshConfigurator for SshConfigManager {
         .map_err(|e| anyhow::anyhow!("spawn_blocking panicked: {e}"))??;
         Ok(())
     }
+
+    /// # Errors
+    /// This function will return an error if the underlying operations fail.
+    async fn write_host_block(&self, block: &str) -> Result<Option<PathBuf>> {
+        let user_config_path = self.user_config_path.clone();
+        let block = block.to_owned();
+        tokio::task::spawn_blocking(move || write_managed_block(&user_config_path, &block))
+            .await
+            .map_err(|e| anyhow::anyhow!("spawn_blocking panicked: {e}"))?
+    }
+
+    /// # Errors
+    /// This function will return an error if ssh cannot be started.
+    async fn forward_ports(
+        &self,
+        forwards: &[crate::domain::ssh::PortForward],
+    ) -> Result<std::process::ExitStatus> {
+        tokio::process::Command::new("ssh")
+            .arg("-N")
+            .args(crate::domain::ssh::forward_args(forwards))
+            .arg("workspace")
+            .kill_on_drop(true)
+            .status()
+            .await
+            .context("failed to run ssh")
+    }
 }
 
+/// Attempts made to extract the host key before giving up.
+const HOST_KEY_ATTEMPTS: u32 = 5;
+
+/// Base delay for the jittered exponential backoff between attempts.
+const HOST_KEY_BASE_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
 impl crate::application::ports::HostKeyExtractor for SshConfigManager {
     async fn extract_host_key(&self) -> Option<String> {
         let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("polis"));
-        let output = tokio::process::Command::new(exe)
-            .args(["_extract-host-key"])
-            .output()
-            .await
-            .ok()?;
-        if output.status.success() {
-            String::from_utf8(output.stdout)
-                .ok()
-                .map(|s| s.trim().to_owned())
-        } else {
-            None
-        }
+        let runner = crate::infra::command_runner::TokioCommandRunner::new(
+            std::time::Duration::from_secs(30),
+        );
+        extract_host_key_with_retry(
+            &runner,
+            &exe.to_string_lossy(),
+            HOST_KEY_ATTEMPTS,
+            HOST_KEY_BASE_DELAY,
+        )
+        .await
+    }
+}
+
+/// Runs `<exe> _extract-host-key` via `runner`, retrying with jittered
+/// exponential backoff while the workspace SSH daemon comes up.
+///
+/// Returns the trimmed `known_hosts` line from the first successful attempt,
+/// or `None` once all `attempts` have failed.
+pub async fn extract_host_key_with_retry(
+    runner: &impl crate::application::ports::CommandRunner,
+    exe: &str,
+    attempts: u32,
+    base_delay: std::time::Duration,
+) -> Option<String> {
+    use crate::application::ports::CommandRunner as _;
+    use crate::infra::retry::{Attempt, RetryPolicy, RetryingCommandRunner};
+
+    let policy = RetryPolicy::new(attempts, base_delay).retry_if(|attempt| {
+        !matches!(attempt, Attempt::Exited { status, stdout }
+            if status.success() && host_key_line(stdout).is_some())
+    });
+    let output = RetryingCommandRunner::new(runner, policy)
+        .run(exe, &["_extract-host-key"])
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
     }
+    host_key_line(&output.stdout)
+}
+
+/// The trimmed `known_hosts` line printed by `_extract-host-key`, if any.
+fn host_key_line(stdout: &[u8]) -> Option<String> {
+    let line = std::str::from_utf8(stdout).ok()?.trim();
+    (!line.is_empty()).then(|| line.to_owned())
 }
@@ -2,6 +2,8 @@
 //!
 //! `StateManager` provides async load/save using `tokio::task::spawn_blocking`
 //! with atomic write (temp file + rename) to prevent state corruption.
+//! Writes hold an exclusive lock on a sibling `state.json.lock` file so that
+//! `compare_and_set` is atomic with respect to other polis processes.
 
 use std::path::PathBuf;
 
@@ -73,10 +75,49 @@ impl StateManager {
     ///
     /// This function will return an error if the underlying operations fail.
     fn save_sync(&self, state: &WorkspaceState) -> Result<()> {
+        self.with_lock(|| self.write_unlocked(state))
+    }
+
+    /// Synchronous compare-and-set — used by `compare_and_set` via `spawn_blocking`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state cannot be locked, read, or written.
+    fn compare_and_set_sync(
+        &self,
+        expected: Option<&WorkspaceState>,
+        new: &WorkspaceState,
+    ) -> Result<bool> {
+        self.with_lock(|| {
+            if self.load_sync()?.as_ref() != expected {
+                return Ok(false);
+            }
+            self.write_unlocked(new)?;
+            Ok(true)
+        })
+    }
+
+    /// Run `f` while holding an exclusive lock on the state lock file.
+    /// The lock is released when the file handle is dropped.
+    fn with_lock<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
         if let Some(parent) = self.path.parent() {
             std::fs::create_dir_all(parent)
                 .with_context(|| format!("creating directory {}", parent.display()))?;
         }
+        let lock_path = self.path.with_extension("json.lock");
+        let lock = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("opening lock file {}", lock_path.display()))?;
+        lock.lock()
+            .with_context(|| format!("locking {}", lock_path.display()))?;
+        f()
+    }
+
+    /// Write `state` with temp file + rename; the caller holds the lock.
+    fn write_unlocked(&self, state: &WorkspaceState) -> Result<()> {
         let content = serde_json::to_string_pretty(state).context("serializing state")?;
 
         // Atomic write via temp file then rename (REL-001)
@@ -103,11 +144,16 @@ impl StateManager {
     ///
     /// Returns an error if the file exists but cannot be removed.
     pub fn clear(&self) -> Result<()> {
-        if self.path.exists() {
-            std::fs::remove_file(&self.path)
-                .with_context(|| format!("removing state file {}", self.path.display()))?;
+        if !self.path.exists() {
+            return Ok(());
         }
-        Ok(())
+        self.with_lock(|| {
+            if self.path.exists() {
+                std::fs::remove_file(&self.path)
+                    .with_context(|| format!("removing state file {}", self.path.display()))?;
+            }
+            Ok(())
+        })
     }
 }
 
@@ -151,4 +197,77 @@ impl WorkspaceStateStore for StateManager {
         .await
         .context("state clear task panicked")?
     }
+
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    async fn compare_and_set(
+        &self,
+        expected: Option<&WorkspaceState>,
+        new: &WorkspaceState,
+    ) -> Result<bool> {
+        let path = self.path.clone();
+        let expected = expected.cloned();
+        let new = new.clone();
+        tokio::task::spawn_blocking(move || {
+            let mgr = StateManager::with_path(path);
+            mgr.compare_and_set_sync(expected.as_ref(), &new)
+        })
+        .await
+        .context("state compare-and-set task panicked")?
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn state(agent: Option<&str>) -> WorkspaceState {
+        WorkspaceState {
+            created_at: Utc::now(),
+            image_sha256: None,
+            image_source: None,
+            active_agent: agent.map(str::to_owned),
+            env_sha256: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compare_and_set_succeeds_when_expected_matches() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mgr = StateManager::with_path(dir.path().join("state.json"));
+
+        let first = state(None);
+        assert!(mgr.compare_and_set(None, &first).await.expect("cas"));
+        let second = state(Some("openclaw"));
+        assert!(
+            mgr.compare_and_set(Some(&first), &second)
+                .await
+                .expect("cas")
+        );
+        assert_eq!(mgr.load_async().await.expect("load"), Some(second));
+    }
+
+    #[tokio::test]
+    async fn test_compare_and_set_fails_when_state_changed() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mgr = StateManager::with_path(dir.path().join("state.json"));
+        let stale = state(None);
+        let current = state(Some("openclaw"));
+        mgr.save_async(&current).await.expect("save");
+
+        let swapped = mgr
+            .compare_and_set(Some(&stale), &state(Some("other")))
+            .await
+            .expect("cas");
+        assert!(!swapped);
+        assert!(
+            !mgr.compare_and_set(None, &stale).await.expect("cas"),
+            "a stored state must not be overwritten when none was expected"
+        );
+        assert_eq!(mgr.load_async().await.expect("load"), Some(current));
+    }
 }
@@ -0,0 +1,202 @@
+//! Opt-in performance trace for external commands.
+//!
+//! `TracingRunner<R>` wraps another `CommandRunner` and, when
+//! `POLIS_TRACE_FILE` is set, appends one JSON line per call:
+//!
+//! ```text
+//! {"cmd":"multipass","args":["info","polis"],"duration_ms":412,"exit":0}
+//! ```
+//!
+//! `exit` is `null` when the command could not be run or was killed by a
+//! signal or timeout. Trace writes are best-effort and never fail the call.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{ExitStatus, Output};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::application::ports::CommandRunner;
+
+/// Environment variable naming the trace file.
+pub const TRACE_FILE_ENV: &str = "POLIS_TRACE_FILE";
+
+/// `CommandRunner` decorator that records each call's duration and exit code.
+pub struct TracingRunner<R: CommandRunner> {
+    inner: R,
+    trace_file: Option<PathBuf>,
+}
+
+impl<R: CommandRunner> TracingRunner<R> {
+    /// Wrap `inner`, tracing to `trace_file` (no tracing when `None`).
+    pub fn new(inner: R, trace_file: Option<PathBuf>) -> Self {
+        Self { inner, trace_file }
+    }
+
+    /// Wrap `inner`, tracing to `POLIS_TRACE_FILE` when it is set and non-empty.
+    pub fn from_env(inner: R) -> Self {
+        let trace_file = std::env::var_os(TRACE_FILE_ENV)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from);
+        Self::new(inner, trace_file)
+    }
+
+    fn record(&self, program: &str, args: &[&str], started: Instant, exit: Option<i32>) {
+        let Some(path) = &self.trace_file else {
+            return;
+        };
+        let line = trace_line(program, args, started.elapsed(), exit);
+        if let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+        {
+            let _ = writeln!(file, "{line}");
+        }
+    }
+
+    fn record_output(
+        &self,
+        program: &str,
+        args: &[&str],
+        started: Instant,
+        result: &Result<Output>,
+    ) {
+        let exit = result.as_ref().ok().and_then(|o| o.status.code());
+        self.record(program, args, started, exit);
+    }
+}
+
+/// Render one trace entry as compact JSON.
+fn trace_line(program: &str, args: &[&str], elapsed: Duration, exit: Option<i32>) -> String {
+    serde_json::json!({
+        "cmd": program,
+        "args": args,
+        "duration_ms": u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+        "exit": exit,
+    })
+    .to_string()
+}
+
+impl<R: CommandRunner> CommandRunner for TracingRunner<R> {
+    async fn run(&self, program: &str, args: &[&str]) -> Result<Output> {
+        let started = Instant::now();
+        let result = self.inner.run(program, args).await;
+        self.record_output(program, args, started, &result);
+        result
+    }
+
+    async fn run_with_timeout(
+        &self,
+        program: &str,
+        args: &[&str],
+        timeout: Duration,
+    ) -> Result<Output> {
+        let started = Instant::now();
+        let result = self.inner.run_with_timeout(program, args, timeout).await;
+        self.record_output(program, args, started, &result);
+        result
+    }
+
+    async fn run_with_stdin(&self, program: &str, args: &[&str], stdin: &[u8]) -> Result<Output> {
+        let started = Instant::now();
+        let result = self.inner.run_with_stdin(program, args, stdin).await;
+        self.record_output(program, args, started, &result);
+        result
+    }
+
+    /// Spawned children outlive the call, so they are not traced.
+    fn spawn(&self, program: &str, args: &[&str]) -> Result<tokio::process::Child> {
+        self.inner.spawn(program, args)
+    }
+
+    async fn run_status(&self, program: &str, args: &[&str]) -> Result<ExitStatus> {
+        let started = Instant::now();
+        let result = self.inner.run_status(program, args).await;
+        let exit = result.as_ref().ok().and_then(ExitStatus::code);
+        self.record(program, args, started, exit);
+        result
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::application::services::vm::test_support::exit_status;
+
+    /// Sleeps briefly, then exits 3 (or fails to run for `"missing"`).
+    struct SlowRunner;
+
+    impl CommandRunner for SlowRunner {
+        async fn run(&self, program: &str, _: &[&str]) -> Result<Output> {
+            std::thread::sleep(Duration::from_millis(20));
+            anyhow::ensure!(program != "missing", "failed to spawn {program}");
+            Ok(Output {
+                status: exit_status(3),
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+            })
+        }
+        async fn run_with_timeout(&self, p: &str, a: &[&str], _: Duration) -> Result<Output> {
+            self.run(p, a).await
+        }
+        async fn run_with_stdin(&self, p: &str, a: &[&str], _: &[u8]) -> Result<Output> {
+            self.run(p, a).await
+        }
+        fn spawn(&self, _: &str, _: &[&str]) -> Result<tokio::process::Child> {
+            anyhow::bail!("not expected")
+        }
+        async fn run_status(&self, p: &str, a: &[&str]) -> Result<ExitStatus> {
+            self.run(p, a).await.map(|o| o.status)
+        }
+    }
+
+    fn read_trace(path: &std::path::Path) -> Vec<serde_json::Value> {
+        std::fs::read_to_string(path)
+            .expect("read trace")
+            .lines()
+            .map(|l| serde_json::from_str(l).expect("trace line is JSON"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_tracing_runner_records_duration_and_exit() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("trace.jsonl");
+        let runner = TracingRunner::new(SlowRunner, Some(path.clone()));
+
+        let output = runner
+            .run("multipass", &["info", "polis"])
+            .await
+            .expect("run");
+        assert_eq!(output.status.code(), Some(3));
+        let status = runner
+            .run_status("multipass", &["stop", "polis"])
+            .await
+            .expect("run_status");
+        assert_eq!(status.code(), Some(3));
+        assert!(runner.run("missing", &[]).await.is_err());
+
+        let lines = read_trace(&path);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["cmd"], "multipass");
+        assert_eq!(lines[0]["args"], serde_json::json!(["info", "polis"]));
+        assert_eq!(lines[0]["exit"], 3);
+        assert!(lines[0]["duration_ms"].as_u64().expect("duration_ms") >= 20);
+        assert_eq!(lines[1]["args"], serde_json::json!(["stop", "polis"]));
+        assert_eq!(lines[1]["exit"], 3);
+        assert_eq!(lines[2]["cmd"], "missing");
+        assert!(lines[2]["exit"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_tracing_runner_without_file_writes_nothing() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("trace.jsonl");
+        let runner = TracingRunner::new(SlowRunner, None);
+        runner.run("multipass", &["list"]).await.expect("run");
+        assert!(!path.exists());
+    }
+}
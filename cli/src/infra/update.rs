@@ -1,35 +1,94 @@
 //! Update infrastructure — implements `UpdateChecker` using GitHub releases.
 
 use anyhow::{Context, Result};
+use serde::Deserialize;
 use sha2::{Digest, Sha256};
-use std::io::{Cursor, Read};
+use std::io::Cursor;
 
-use crate::application::services::update::{SignatureInfo, UpdateChecker, UpdateInfo};
+use crate::application::ports::HttpClient;
+use crate::application::services::update::{
+    ReleaseLister, SignatureInfo, UpdateChecker, UpdateInfo,
+};
+use crate::domain::update::ReleaseNotes;
+use crate::infra::github::GithubConfig;
+use crate::infra::http::{HttpTimeouts, UreqHttpClient};
+use crate::infra::network::ensure_online;
 
 /// The base64-encoded ed25519 public key used to verify release signatures.
 pub const POLIS_PUBLIC_KEY_B64: &str = "jI42dOaR/5mN1T0hH+QeWc+L0aH9BwG1L7Yd/4O5QeQ=";
 
+/// Largest release asset `verify_signature` will download.
+const MAX_ASSET_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Largest release-list or checksum response read.
+const MAX_METADATA_BYTES: u64 = 1024 * 1024;
+
 /// Uses GitHub releases API to check and apply updates.
-pub struct GithubUpdateChecker;
+pub struct GithubUpdateChecker<H = UreqHttpClient> {
+    /// Refuse every request with an "offline mode" error.
+    pub offline: bool,
+    /// Client for the releases API, asset, and checksum downloads.
+    pub http: H,
+    /// Base URL, User-Agent, and token for GitHub API requests.
+    pub github: GithubConfig,
+}
+
+impl GithubUpdateChecker {
+    /// Checker backed by the production `ureq` client, honouring the proxy
+    /// environment variables and the given request deadlines.
+    #[must_use]
+    pub fn new(offline: bool, timeouts: HttpTimeouts) -> Self {
+        Self {
+            offline,
+            http: UreqHttpClient::new(timeouts),
+            github: GithubConfig::from_env(),
+        }
+    }
+}
 
-impl UpdateChecker for GithubUpdateChecker {
+/// Subset of a GitHub release object used by the CLI.
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+/// Subset of a GitHub release asset object.
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+impl GithubRelease {
+    fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    fn notes(&self) -> Vec<(String, Vec<String>)> {
+        self.body
+            .as_deref()
+            .map(parse_release_notes)
+            .unwrap_or_default()
+    }
+}
+
+impl<H: HttpClient> UpdateChecker for GithubUpdateChecker<H> {
     /// # Errors
     ///
     /// This function will return an error if the underlying operations fail.
     fn check(&self, current: &str) -> Result<UpdateInfo> {
-        let releases = self_update::backends::github::ReleaseList::configure()
-            .repo_owner("OdraLabsHQ")
-            .repo_name("polis")
-            .build()
-            .context("failed to configure update check")?
-            .fetch()
-            .context("failed to check for updates")?;
+        ensure_online(self.offline, "update check")?;
+        let releases = fetch_releases(&self.http, &self.github)?;
 
         let Some(latest) = releases.first() else {
             return Ok(UpdateInfo::UpToDate);
         };
 
-        let latest_version = latest.version.trim_start_matches('v');
+        let latest_version = latest.version();
         let latest_ver = semver::Version::parse(latest_version)
             .with_context(|| format!("invalid release version: {latest_version}"))?;
         let current_ver = semver::Version::parse(current)
@@ -39,23 +98,17 @@ impl UpdateChecker for GithubUpdateChecker {
             return Ok(UpdateInfo::UpToDate);
         }
 
-        let release_notes = latest
-            .body
-            .as_deref()
-            .map(parse_release_notes)
-            .unwrap_or_default();
-
         let asset_name = get_asset_name()?;
         let download_url = latest
             .assets
             .iter()
             .find(|a| a.name == asset_name)
-            .map(|a| a.download_url.clone())
+            .map(|a| a.browser_download_url.clone())
             .ok_or_else(|| anyhow::anyhow!("no release asset for this platform ({asset_name})"))?;
 
         Ok(UpdateInfo::Available {
             version: latest_version.to_string(),
-            release_notes,
+            release_notes: latest.notes(),
             download_url,
         })
     }
@@ -64,67 +117,30 @@ impl UpdateChecker for GithubUpdateChecker {
     ///
     /// This function will return an error if the underlying operations fail.
     fn verify_signature(&self, download_url: &str) -> Result<SignatureInfo> {
-        let response = ureq::get(download_url)
-            .call()
-            .context("failed to download release asset")?;
-
-        let mut data = Vec::new();
-        response
-            .into_reader()
-            .take(100 * 1024 * 1024)
-            .read_to_end(&mut data)
-            .context("failed to read release asset")?;
-
-        let hash = Sha256::digest(&data);
-        let actual_sha256 = crate::domain::workspace::hex_encode(&hash);
-
-        let checksum_url = format!("{download_url}.sha256");
-        let checksum_response = ureq::get(&checksum_url)
-            .call()
-            .context("failed to download checksum file")?;
-
-        let checksum_content = checksum_response
-            .into_string()
-            .context("failed to read checksum file")?;
-
-        let expected_sha256 = checksum_content
-            .split_whitespace()
-            .next()
-            .ok_or_else(|| anyhow::anyhow!("invalid checksum file format"))?;
-
-        anyhow::ensure!(
-            actual_sha256 == expected_sha256,
-            "checksum mismatch: expected {expected_sha256}, got {actual_sha256}"
-        );
-
-        let public_key_bytes =
-            base64_decode(POLIS_PUBLIC_KEY_B64).context("decoding embedded public key")?;
-        let key_array: [u8; 32] = public_key_bytes
-            .try_into()
-            .map_err(|_| anyhow::anyhow!("public key must be 32 bytes"))?;
-        let keys = zipsign_api::verify::collect_keys([Ok(key_array)])
-            .map_err(|e| anyhow::anyhow!("invalid public key: {e}"))?;
-
-        let mut cursor = Cursor::new(&data);
-        zipsign_api::verify::verify_tar(&mut cursor, &keys, Some(b""))
-            .map_err(|e| anyhow::anyhow!("signature verification failed: {e}"))?;
-
-        Ok(SignatureInfo {
-            sha256: actual_sha256,
-        })
+        ensure_online(self.offline, "release download")?;
+        verify_release_asset(&self.http, &self.github, download_url, POLIS_PUBLIC_KEY_B64)
     }
 
+    /// The download goes through `self_update`'s own HTTP client, which reads
+    /// `HTTPS_PROXY` / `NO_PROXY` itself but not `POLIS_PROXY`.
+    ///
     /// # Errors
     ///
     /// This function will return an error if the underlying operations fail.
     fn perform_update(&self, version: &str) -> Result<()> {
-        let status = self_update::backends::github::Update::configure()
+        ensure_online(self.offline, "release download")?;
+        let mut builder = self_update::backends::github::Update::configure();
+        builder
             .repo_owner("OdraLabsHQ")
             .repo_name("polis")
             .bin_name("polis")
             .show_download_progress(true)
             .current_version(env!("CARGO_PKG_VERSION"))
-            .target_version_tag(&format!("v{version}"))
+            .target_version_tag(&format!("v{version}"));
+        if let Some(token) = &self.github.token {
+            builder.auth_token(token);
+        }
+        let status = builder
             .build()
             .context("failed to configure update")?
             .update()
@@ -135,6 +151,97 @@ impl UpdateChecker for GithubUpdateChecker {
     }
 }
 
+impl<H: HttpClient> ReleaseLister for GithubUpdateChecker<H> {
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    fn list_releases(&self) -> Result<Vec<ReleaseNotes>> {
+        ensure_online(self.offline, "release notes fetch")?;
+        Ok(fetch_releases(&self.http, &self.github)?
+            .iter()
+            .map(|release| ReleaseNotes {
+                version: release.version().to_string(),
+                sections: release.notes(),
+            })
+            .collect())
+    }
+}
+
+/// Fetch the GitHub release list, newest first.
+fn fetch_releases(http: &impl HttpClient, github: &GithubConfig) -> Result<Vec<GithubRelease>> {
+    let body = http
+        .get(
+            &github.releases_url,
+            &github.api_headers(),
+            MAX_METADATA_BYTES,
+        )
+        .context("failed to check for updates")?;
+    serde_json::from_slice(&body).context("failed to parse release list")
+}
+
+/// Download a release asset and its `.sha256`, then check the checksum and
+/// the embedded zipsign ed25519 signature against `public_key_b64`.
+///
+/// # Errors
+///
+/// Returns an error if either download fails, the checksum differs, or the
+/// signature does not verify.
+fn verify_release_asset(
+    http: &impl HttpClient,
+    github: &GithubConfig,
+    download_url: &str,
+    public_key_b64: &str,
+) -> Result<SignatureInfo> {
+    // Downloads may redirect to a CDN, so the API token is not sent here.
+    let headers = [("User-Agent", github.user_agent.clone())];
+    let data = http
+        .get(download_url, &headers, MAX_ASSET_BYTES)
+        .context("failed to download release asset")?;
+
+    let hash = Sha256::digest(&data);
+    let actual_sha256 = crate::domain::workspace::hex_encode(&hash);
+
+    let checksum_url = format!("{download_url}.sha256");
+    let checksum_content = http
+        .get(&checksum_url, &headers, MAX_METADATA_BYTES)
+        .context("failed to download checksum file")?;
+    let checksum_content = String::from_utf8_lossy(&checksum_content);
+
+    let expected_sha256 = checksum_content
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("invalid checksum file format"))?;
+
+    anyhow::ensure!(
+        actual_sha256 == expected_sha256,
+        "checksum mismatch: expected {expected_sha256}, got {actual_sha256}"
+    );
+
+    let keys = build_verifying_key(public_key_b64).context("decoding embedded public key")?;
+
+    let mut cursor = Cursor::new(&data);
+    zipsign_api::verify::verify_tar(&mut cursor, &keys, Some(b""))
+        .map_err(|e| anyhow::anyhow!("signature verification failed: {e}"))?;
+
+    Ok(SignatureInfo {
+        sha256: actual_sha256,
+    })
+}
+
+/// Decode a base64 ed25519 public key into the key set `verify_tar` takes.
+///
+/// # Errors
+///
+/// Returns an error if the input is not base64 or not a valid 32-byte key.
+pub(crate) fn build_verifying_key(public_key_b64: &str) -> Result<Vec<zipsign_api::VerifyingKey>> {
+    let public_key_bytes = base64_decode(public_key_b64.trim())?;
+    let key_array: [u8; 32] = public_key_bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("public key must be 32 bytes"))?;
+    zipsign_api::verify::collect_keys([Ok(key_array)])
+        .map_err(|e| anyhow::anyhow!("invalid public key: {e}"))
+}
+
 pub(crate) fn get_asset_name() -> Result<String> {
     let os = std::env::consts::OS;
     let arch = std::env::consts::ARCH;
@@ -149,17 +256,27 @@ pub(crate) fn get_asset_name() -> Result<String> {
     Ok(name.to_string())
 }
 
-pub(crate) fn parse_release_notes(body: &str) -> Vec<String> {
-    body.lines()
-        .filter(|l| l.starts_with("- ") || l.starts_with("* "))
-        .map(|l| {
-            l.strip_prefix("- ")
-                .or_else(|| l.strip_prefix("* "))
-                .unwrap_or(l)
-                .to_string()
-        })
-        .take(5)
-        .collect()
+/// Maximum bullets kept per release-notes section.
+const MAX_NOTES_PER_SECTION: usize = 5;
+
+/// Group `- ` / `* ` bullets under the `### Section` header they follow.
+/// Bullets before the first header land in an untitled section; sections
+/// without bullets are dropped.
+pub(crate) fn parse_release_notes(body: &str) -> Vec<(String, Vec<String>)> {
+    let mut sections: Vec<(String, Vec<String>)> = vec![(String::new(), Vec::new())];
+    for line in body.lines() {
+        let line = line.trim_end();
+        if let Some(title) = line.strip_prefix("### ") {
+            sections.push((title.trim().to_string(), Vec::new()));
+        } else if let Some(note) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* "))
+            && let Some((_, notes)) = sections.last_mut()
+            && notes.len() < MAX_NOTES_PER_SECTION
+        {
+            notes.push(note.to_string());
+        }
+    }
+    sections.retain(|(_, notes)| !notes.is_empty());
+    sections
 }
 
 pub(crate) fn base64_decode(input: &str) -> Result<Vec<u8>> {
@@ -192,34 +309,347 @@ pub(crate) fn base64_decode(input: &str) -> Result<Vec<u8>> {
 #[cfg(test)]
 #[allow(clippy::expect_used, clippy::unwrap_used, clippy::wildcard_imports)]
 mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    use base64::Engine;
+
     use super::*;
     use crate::domain::workspace::hex_encode;
+    use crate::infra::github::GITHUB_RELEASES_URL;
+
+    /// Serves canned bodies by exact URL (anything else is a 404) and
+    /// records the headers sent with each request.
+    #[derive(Default)]
+    struct MockHttp {
+        bodies: HashMap<String, Vec<u8>>,
+        requests: RefCell<Vec<(String, Vec<(String, String)>)>>,
+    }
+
+    impl MockHttp {
+        fn with(mut self, url: &str, body: impl Into<Vec<u8>>) -> Self {
+            self.bodies.insert(url.to_string(), body.into());
+            self
+        }
+
+        fn header_sent(&self, url: &str, name: &str) -> Option<String> {
+            self.requests
+                .borrow()
+                .iter()
+                .find(|(u, _)| u == url)
+                .and_then(|(_, headers)| headers.iter().find(|(k, _)| k == name))
+                .map(|(_, v)| v.clone())
+        }
+    }
+
+    impl HttpClient for MockHttp {
+        fn get(&self, url: &str, headers: &[(&str, String)], max_bytes: u64) -> Result<Vec<u8>> {
+            self.requests.borrow_mut().push((
+                url.to_string(),
+                headers
+                    .iter()
+                    .map(|(k, v)| ((*k).to_string(), v.clone()))
+                    .collect(),
+            ));
+            let body = self
+                .bodies
+                .get(url)
+                .ok_or_else(|| anyhow::anyhow!("GET {url}: HTTP 404"))?;
+            let len = usize::try_from(max_bytes).map_or(body.len(), |max| body.len().min(max));
+            Ok(body[..len].to_vec())
+        }
+    }
+
+    const ASSET_URL: &str =
+        "https://github.com/OdraLabsHQ/polis/releases/download/v9.9.9/polis.tar.gz";
+
+    fn signing_key(seed: u8) -> zipsign_api::SigningKey {
+        zipsign_api::SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn public_key_b64(key: &zipsign_api::SigningKey) -> String {
+        base64::engine::general_purpose::STANDARD.encode(key.verifying_key().to_bytes())
+    }
+
+    /// A `.tar.gz` signed the way the release workflow does with `zipsign`.
+    fn signed_tarball(key: &zipsign_api::SigningKey) -> Vec<u8> {
+        let mut archive = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        let payload = b"#!/bin/sh\necho polis\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(payload.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        archive
+            .append_data(&mut header, "polis", &payload[..])
+            .expect("append");
+        let unsigned = archive.into_inner().expect("tar").finish().expect("gzip");
+
+        let mut signed = Cursor::new(Vec::new());
+        zipsign_api::sign::copy_and_sign_tar(
+            &mut Cursor::new(unsigned),
+            &mut signed,
+            std::slice::from_ref(key),
+            Some(b""),
+        )
+        .expect("sign");
+        signed.into_inner()
+    }
+
+    /// Mock serving `tarball` and its matching `.sha256` file.
+    fn release_asset(tarball: Vec<u8>) -> MockHttp {
+        let mut checksum = hex_encode(&Sha256::digest(&tarball));
+        checksum.push_str("  polis.tar.gz\n");
+        MockHttp::default()
+            .with(ASSET_URL, tarball)
+            .with(&format!("{ASSET_URL}.sha256"), checksum)
+    }
+
+    fn release_list_json(tag: &str) -> String {
+        let asset = get_asset_name().expect("supported platform");
+        serde_json::json!([{
+            "tag_name": tag,
+            "body": "### Fixes\n- Handle IPv6 Valkey URLs\n",
+            "assets": [
+                { "name": "polis-other.tar.gz", "browser_download_url": "https://example.com/other" },
+                { "name": asset, "browser_download_url": ASSET_URL }
+            ]
+        }])
+        .to_string()
+    }
+
+    /// GitHub settings as if no environment overrides were set.
+    fn github() -> GithubConfig {
+        GithubConfig::from_lookup(|_| None)
+    }
+
+    fn checker(http: MockHttp) -> GithubUpdateChecker<MockHttp> {
+        GithubUpdateChecker {
+            offline: false,
+            http,
+            github: github(),
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // release list via HttpClient — unit
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_check_newer_release_picks_platform_asset() {
+        let http = MockHttp::default().with(GITHUB_RELEASES_URL, release_list_json("v9.9.9"));
+        let UpdateInfo::Available {
+            version,
+            release_notes,
+            download_url,
+        } = checker(http).check("0.4.0").expect("check")
+        else {
+            panic!("expected an update");
+        };
+        assert_eq!(version, "9.9.9");
+        assert_eq!(download_url, ASSET_URL);
+        assert_eq!(
+            release_notes,
+            vec![(
+                "Fixes".to_string(),
+                vec!["Handle IPv6 Valkey URLs".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_check_same_version_is_up_to_date() {
+        let http = MockHttp::default().with(GITHUB_RELEASES_URL, release_list_json("v0.4.0"));
+        assert!(matches!(
+            checker(http).check("0.4.0").expect("check"),
+            UpdateInfo::UpToDate
+        ));
+    }
+
+    #[test]
+    fn test_list_releases_strips_v_prefix() {
+        let http = MockHttp::default().with(GITHUB_RELEASES_URL, release_list_json("v9.9.9"));
+        let releases = checker(http).list_releases().expect("list");
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].version, "9.9.9");
+    }
+
+    #[test]
+    fn test_github_token_sent_to_api_but_not_to_downloads() {
+        let key = signing_key(7);
+        let http = release_asset(signed_tarball(&key))
+            .with(GITHUB_RELEASES_URL, release_list_json("v9.9.9"));
+        let mut checker = checker(http);
+        checker.github = GithubConfig::from_lookup(|name| {
+            (name == "GITHUB_TOKEN").then(|| "ghp_example".to_string())
+        });
+
+        checker.check("0.4.0").expect("check");
+        verify_release_asset(
+            &checker.http,
+            &checker.github,
+            ASSET_URL,
+            &public_key_b64(&key),
+        )
+        .expect("verify");
+
+        assert_eq!(
+            checker
+                .http
+                .header_sent(GITHUB_RELEASES_URL, "Authorization")
+                .as_deref(),
+            Some("Bearer ghp_example")
+        );
+        assert_eq!(checker.http.header_sent(ASSET_URL, "Authorization"), None);
+        assert!(checker.http.header_sent(ASSET_URL, "User-Agent").is_some());
+    }
+
+    #[test]
+    fn test_check_unreachable_api_errors() {
+        let err = checker(MockHttp::default())
+            .check("0.4.0")
+            .map(|_| ())
+            .expect_err("no release list");
+        assert!(
+            err.to_string().contains("failed to check for updates"),
+            "{err}"
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // verify_release_asset — signed tarball via HttpClient
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_verify_release_asset_accepts_signed_tarball() {
+        let key = signing_key(7);
+        let tarball = signed_tarball(&key);
+        let expected = hex_encode(&Sha256::digest(&tarball));
+        let info = verify_release_asset(
+            &release_asset(tarball),
+            &github(),
+            ASSET_URL,
+            &public_key_b64(&key),
+        )
+        .expect("valid signature");
+        assert_eq!(info.sha256, expected);
+    }
+
+    #[test]
+    fn test_verify_release_asset_rejects_checksum_mismatch() {
+        let key = signing_key(7);
+        let http = release_asset(signed_tarball(&key)).with(
+            &format!("{ASSET_URL}.sha256"),
+            format!("{}  polis.tar.gz\n", "0".repeat(64)),
+        );
+        let err = verify_release_asset(&http, &github(), ASSET_URL, &public_key_b64(&key))
+            .map(|_| ())
+            .expect_err("checksum mismatch");
+        assert!(err.to_string().contains("checksum mismatch"), "{err}");
+    }
+
+    #[test]
+    fn test_verify_release_asset_missing_checksum_file_errors() {
+        let key = signing_key(7);
+        let http = MockHttp::default().with(ASSET_URL, signed_tarball(&key));
+        let err = verify_release_asset(&http, &github(), ASSET_URL, &public_key_b64(&key))
+            .map(|_| ())
+            .expect_err("no .sha256");
+        assert!(err.to_string().contains("checksum file"), "{err}");
+    }
+
+    #[test]
+    fn test_verify_release_asset_rejects_foreign_signature() {
+        let http = release_asset(signed_tarball(&signing_key(7)));
+        let err = verify_release_asset(
+            &http,
+            &github(),
+            ASSET_URL,
+            &public_key_b64(&signing_key(8)),
+        )
+        .map(|_| ())
+        .expect_err("signed by a different key");
+        assert!(
+            err.to_string().contains("signature verification failed"),
+            "{err}"
+        );
+    }
 
     // -----------------------------------------------------------------------
     // parse_release_notes — unit
     // -----------------------------------------------------------------------
 
+    /// Flatten sections for assertions that don't care about grouping.
+    fn flat(sections: Vec<(String, Vec<String>)>) -> Vec<String> {
+        sections.into_iter().flat_map(|(_, notes)| notes).collect()
+    }
+
     #[test]
     fn test_parse_release_notes_dash_bullets_extracts_items() {
         let body = "- Improved credential detection\n- Faster workspace startup\n- Bug fixes";
         let notes = parse_release_notes(body);
         assert_eq!(
             notes,
-            vec![
-                "Improved credential detection",
-                "Faster workspace startup",
-                "Bug fixes"
-            ]
+            vec![(
+                String::new(),
+                vec![
+                    "Improved credential detection".to_string(),
+                    "Faster workspace startup".to_string(),
+                    "Bug fixes".to_string()
+                ]
+            )]
         );
     }
 
     #[test]
     fn test_parse_release_notes_star_bullets_extracts_items() {
         let body = "* item one\n* item two";
-        let notes = parse_release_notes(body);
+        let notes = flat(parse_release_notes(body));
         assert_eq!(notes, vec!["item one", "item two"]);
     }
 
+    #[test]
+    fn test_parse_release_notes_groups_by_section_header() {
+        let body = "## What's Changed\n\n\
+                    ### Features\n\
+                    - Add polis update --json\n\
+                    - Cache update checks\n\n\
+                    ### Fixes\r\n\
+                    * Handle IPv6 Valkey URLs\r\n\n\
+                    ### Internal\n\n\
+                    **Full Changelog**: v0.3.0...v0.4.0\n";
+        assert_eq!(
+            parse_release_notes(body),
+            vec![
+                (
+                    "Features".to_string(),
+                    vec![
+                        "Add polis update --json".to_string(),
+                        "Cache update checks".to_string()
+                    ]
+                ),
+                (
+                    "Fixes".to_string(),
+                    vec!["Handle IPv6 Valkey URLs".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_release_notes_limits_each_section_to_five_items() {
+        let bullets = (1..=10)
+            .map(|i| format!("- item {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let body = format!("### Features\n{bullets}\n### Fixes\n{bullets}");
+        let sections = parse_release_notes(&body);
+        assert_eq!(sections.len(), 2);
+        assert!(sections.iter().all(|(_, notes)| notes.len() == 5));
+    }
+
     #[test]
     fn test_parse_release_notes_empty_body_returns_empty() {
         let notes = parse_release_notes("");
@@ -229,7 +659,7 @@ mod tests {
     #[test]
     fn test_parse_release_notes_non_bullet_lines_are_ignored() {
         let body = "# v0.3.0\n\nSome prose.\n\n- actual item";
-        let notes = parse_release_notes(body);
+        let notes = flat(parse_release_notes(body));
         assert_eq!(notes, vec!["actual item"]);
     }
 
@@ -239,10 +669,37 @@ mod tests {
             .map(|i| format!("- item {i}"))
             .collect::<Vec<_>>()
             .join("\n");
-        let notes = parse_release_notes(&body);
+        let notes = flat(parse_release_notes(&body));
         assert_eq!(notes.len(), 5);
     }
 
+    // -----------------------------------------------------------------------
+    // offline mode — unit
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_offline_checker_skips_every_network_entry_point() {
+        let checker = GithubUpdateChecker::new(true, HttpTimeouts::default());
+        let errors = [
+            checker.check("0.4.0").map(|_| ()).expect_err("check"),
+            checker
+                .verify_signature("https://example.com/polis.tar.gz")
+                .map(|_| ())
+                .expect_err("verify_signature"),
+            checker.perform_update("9.9.9").expect_err("perform_update"),
+            checker
+                .list_releases()
+                .map(|_| ())
+                .expect_err("list_releases"),
+        ];
+        for err in errors {
+            assert!(
+                err.to_string().starts_with("offline mode; skipping"),
+                "{err}"
+            );
+        }
+    }
+
     // -----------------------------------------------------------------------
     // get_asset_name — unit
     // -----------------------------------------------------------------------
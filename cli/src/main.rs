@@ -9,8 +9,11 @@ use polis_cli::cli::Cli;
 async fn main() {
     let cli = Cli::parse();
 
-    // REL-002: Handle Ctrl+C gracefully
+    // REL-002: Handle Ctrl+C gracefully. `biased` polls the command first so
+    // one that handles Ctrl+C itself (`polis status --watch`) gets to exit
+    // cleanly instead of being cut off here.
     tokio::select! {
+        biased;
         result = cli.run() => {
             match result {
                 Ok(code) => std::process::exit(match code {
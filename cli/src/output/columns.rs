@@ -0,0 +1,119 @@
+//! Column selection for `agent list --columns` (table and CSV output).
+
+use anyhow::Result;
+
+use crate::domain::agent::AgentInfo;
+
+/// A column of `agent list` output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentColumn {
+    /// Agent name.
+    Name,
+    /// Agent version, empty if unknown.
+    Version,
+    /// Agent description, empty if unknown.
+    Description,
+    /// Whether the agent is the active one.
+    Active,
+}
+
+impl AgentColumn {
+    /// Every column, in default order.
+    pub const ALL: [Self; 4] = [Self::Name, Self::Version, Self::Description, Self::Active];
+
+    /// Column name as used in `--columns` and the CSV header.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Version => "version",
+            Self::Description => "description",
+            Self::Active => "active",
+        }
+    }
+
+    /// Plain value of this column for `agent`.
+    #[must_use]
+    pub fn value(self, agent: &AgentInfo) -> &str {
+        match self {
+            Self::Name => &agent.name,
+            Self::Version => agent.version.as_deref().unwrap_or(""),
+            Self::Description => agent.description.as_deref().unwrap_or(""),
+            Self::Active => {
+                if agent.active {
+                    "true"
+                } else {
+                    "false"
+                }
+            }
+        }
+    }
+}
+
+/// Parse a `--columns` list such as `name,version,active`, keeping the
+/// given order. `None` selects every column.
+///
+/// # Errors
+///
+/// Returns an error naming the valid columns if an entry is unknown, empty,
+/// or repeated.
+pub fn parse_agent_columns(spec: Option<&str>) -> Result<Vec<AgentColumn>> {
+    let Some(spec) = spec else {
+        return Ok(AgentColumn::ALL.to_vec());
+    };
+    let valid = AgentColumn::ALL.map(AgentColumn::name).join(", ");
+    let mut columns = Vec::new();
+    for name in spec.split(',').map(str::trim) {
+        let column = AgentColumn::ALL
+            .into_iter()
+            .find(|c| c.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow::anyhow!("unknown column '{name}'\n\nValid columns: {valid}"))?;
+        anyhow::ensure!(
+            !columns.contains(&column),
+            "column '{name}' listed more than once"
+        );
+        columns.push(column);
+    }
+    Ok(columns)
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_agent_columns_defaults_to_all() {
+        assert_eq!(
+            parse_agent_columns(None).expect("default"),
+            AgentColumn::ALL.to_vec()
+        );
+    }
+
+    #[test]
+    fn test_parse_agent_columns_keeps_requested_order() {
+        assert_eq!(
+            parse_agent_columns(Some("active, NAME,version")).expect("columns"),
+            vec![AgentColumn::Active, AgentColumn::Name, AgentColumn::Version]
+        );
+    }
+
+    #[test]
+    fn test_parse_agent_columns_rejects_unknown_column() {
+        let msg = parse_agent_columns(Some("name,owner"))
+            .expect_err("unknown")
+            .to_string();
+        assert!(msg.contains("unknown column 'owner'"), "{msg}");
+        assert!(msg.contains("name, version, description, active"), "{msg}");
+    }
+
+    #[test]
+    fn test_parse_agent_columns_rejects_empty_and_duplicate_entries() {
+        assert!(parse_agent_columns(Some("name,,version")).is_err());
+        assert!(parse_agent_columns(Some("")).is_err());
+        let msg = parse_agent_columns(Some("name,Name"))
+            .expect_err("duplicate")
+            .to_string();
+        assert!(msg.contains("more than once"), "{msg}");
+    }
+}
@@ -0,0 +1,110 @@
+//! CSV rendering for list-like commands (`--format csv`).
+//!
+//! Fields are quoted per RFC 4180: a field containing a comma, double quote,
+//! or line break is wrapped in double quotes, with embedded quotes doubled.
+
+use std::borrow::Cow;
+
+use crate::domain::agent::AgentInfo;
+use crate::output::columns::AgentColumn;
+
+/// Quote `field` if it needs it; plain fields are returned unchanged.
+#[must_use]
+pub fn escape_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// One CSV line, terminated by `\n`.
+#[must_use]
+pub fn record(fields: &[&str]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|f| escape_field(f))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
+
+/// `agent list` as CSV: a header of column names, then one row per agent.
+#[must_use]
+pub fn agent_list(agents: &[AgentInfo], columns: &[AgentColumn]) -> String {
+    let header: Vec<&str> = columns.iter().map(|c| c.name()).collect();
+    let mut out = record(&header);
+    for agent in agents {
+        let row: Vec<&str> = columns.iter().map(|c| c.value(agent)).collect();
+        out.push_str(&record(&row));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(description: &str, active: bool) -> AgentInfo {
+        AgentInfo {
+            name: "openclaw".to_string(),
+            display_name: None,
+            version: Some("1.2.0".to_string()),
+            description: Some(description.to_string()),
+            active,
+            labels: std::collections::BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_escape_field_leaves_plain_fields_unquoted() {
+        assert_eq!(escape_field("claude-dev"), "claude-dev");
+        assert_eq!(escape_field(""), "");
+    }
+
+    #[test]
+    fn test_escape_field_quotes_commas_quotes_and_newlines() {
+        assert_eq!(escape_field("a,b"), "\"a,b\"");
+        assert_eq!(escape_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_agent_list_escapes_description_with_comma_and_quote() {
+        let csv = agent_list(&[agent("Fast, \"safe\" agent", true)], &AgentColumn::ALL);
+        assert_eq!(
+            csv,
+            "name,version,description,active\n\
+             openclaw,1.2.0,\"Fast, \"\"safe\"\" agent\",true\n"
+        );
+    }
+
+    #[test]
+    fn test_agent_list_empty_prints_header_only() {
+        assert_eq!(
+            agent_list(&[], &AgentColumn::ALL),
+            "name,version,description,active\n"
+        );
+    }
+
+    #[test]
+    fn test_agent_list_selected_columns_in_order() {
+        let columns = [AgentColumn::Active, AgentColumn::Name];
+        assert_eq!(
+            agent_list(&[agent("ignored", false)], &columns),
+            "active,name\nfalse,openclaw\n"
+        );
+    }
+
+    #[test]
+    fn test_agent_list_missing_fields_are_empty() {
+        let mut a = agent("", false);
+        a.version = None;
+        a.description = None;
+        assert_eq!(
+            agent_list(&[a], &AgentColumn::ALL),
+            "name,version,description,active\nopenclaw,,,false\n"
+        );
+    }
+}
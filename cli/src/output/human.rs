@@ -2,7 +2,7 @@
 
 use owo_colors::OwoColorize as _;
 use polis_common::types::StatusOutput;
-use polis_common::types::{AgentHealth, WorkspaceState};
+use polis_common::types::{AgentHealth, ConfigState, WorkspaceState};
 
 use crate::domain::health::DoctorChecks;
 use crate::output::OutputContext;
@@ -26,6 +26,82 @@ impl<'a> HumanRenderer<'a> {
         Self { ctx }
     }
 
+    /// Render release notes grouped by section. A section with an empty
+    /// title (release body without headers) is printed as a flat list.
+    pub fn render_release_notes(&self, version: &str, sections: &[(String, Vec<String>)]) {
+        if self.ctx.quiet || sections.is_empty() {
+            return;
+        }
+        println!("  Changes in v{version}:");
+        self.render_note_sections(sections);
+    }
+
+    /// Render the per-service table of a `polis update` run, one row per
+    /// service in the order given.
+    pub fn render_container_updates(&self, containers: &[crate::domain::update::ContainerUpdate]) {
+        use crate::domain::update::ContainerResult;
+
+        if self.ctx.quiet || containers.is_empty() {
+            return;
+        }
+        println!();
+        for c in containers {
+            let result = match c.result {
+                ContainerResult::Updated => "updated",
+                ContainerResult::Unchanged => "unchanged",
+            };
+            let tags = format!("{} → {}", c.from, c.to);
+            println!("  {:<16} {tags:<20} {result}", c.name);
+        }
+    }
+
+    /// Render `polis changelog` for one release.
+    pub fn render_changelog(&self, release: &crate::domain::update::ReleaseNotes) {
+        if self.ctx.quiet {
+            return;
+        }
+        println!();
+        println!(
+            "  {}",
+            format!("polis v{}", release.version).style(self.ctx.styles.header)
+        );
+        println!();
+        if release.sections.is_empty() {
+            self.ctx
+                .info("No release notes published for this version.");
+        }
+        self.render_note_sections(&release.sections);
+        println!();
+    }
+
+    fn render_note_sections(&self, sections: &[(String, Vec<String>)]) {
+        for (title, notes) in sections {
+            let indent = if title.is_empty() {
+                "    "
+            } else {
+                println!("    {}", title.style(self.ctx.styles.bold));
+                "      "
+            };
+            for note in notes {
+                println!("{indent}• {note}");
+            }
+        }
+    }
+
+    /// Redraw the status for `polis status --watch`, clearing the screen
+    /// first when stdout is a terminal.
+    pub fn render_status_refresh(&self, status: &StatusOutput) {
+        if self.ctx.is_tty {
+            let _ = console::Term::stdout().clear_screen();
+        }
+        self.render_status(status);
+        self.ctx.blank();
+        self.ctx.info(&format!(
+            "updated {} — Ctrl-C to stop",
+            chrono::Local::now().format("%H:%M:%S")
+        ));
+    }
+
     /// Render workspace/agent/security status.
     pub fn render_status(&self, status: &StatusOutput) {
         self.ctx.kv(
@@ -40,8 +116,10 @@ impl<'a> HumanRenderer<'a> {
             );
         }
 
-        if let Some(uptime) = status.workspace.uptime_seconds {
-            self.ctx.kv("Uptime:", &format_uptime(uptime));
+        self.ctx
+            .kv("Uptime:", &uptime_display(status.workspace.uptime_seconds));
+        if let Some(config) = status.config {
+            self.ctx.kv("Config:", config_state_display(config));
         }
 
         println!();
@@ -69,10 +147,33 @@ impl<'a> HumanRenderer<'a> {
                 .warn(&format!("{} security events", status.events.count));
             self.ctx.info("Run: polis logs --security");
         }
+
+        if let Some(updates) = &status.updates {
+            println!();
+            self.ctx.header("Updates:");
+            if let Some(version) = &updates.latest_version {
+                self.ctx.warn(&format!("polis v{version} available"));
+            }
+            if !updates.outdated_services.is_empty() {
+                self.ctx.warn(&format!(
+                    "Outdated containers: {}",
+                    updates.outdated_services.join(", ")
+                ));
+            }
+            if updates.updates_available {
+                self.ctx.info("Run: polis update");
+            } else {
+                self.ctx.success("Up to date");
+            }
+        }
     }
 
     /// Render the list of installed agents.
-    pub fn render_agent_list(&self, agents: &[crate::domain::agent::AgentInfo]) {
+    pub fn render_agent_list(
+        &self,
+        agents: &[crate::domain::agent::AgentInfo],
+        columns: &[crate::output::columns::AgentColumn],
+    ) {
         if agents.is_empty() {
             if !self.ctx.quiet {
                 println!("No agents installed. Install one: polis agent add --path <folder>");
@@ -81,12 +182,8 @@ impl<'a> HumanRenderer<'a> {
         }
 
         println!("Available agents:\n");
-        for agent in agents {
-            let name = &agent.name;
-            let version = agent.version.as_deref().unwrap_or("");
-            let desc = agent.description.as_deref().unwrap_or("");
-            let marker = if agent.active { "  [active]" } else { "" };
-            println!("  {name:<16} {version:<10} {desc}{marker}");
+        for row in agent_list_rows(agents, columns, terminal_width()) {
+            println!("  {row}");
         }
         println!("\nStart an agent: polis start --agent <name>");
     }
@@ -104,6 +201,16 @@ impl<'a> HumanRenderer<'a> {
         );
         println!();
         println!("  {:<20} {}", "security.level:", config.security.level);
+        println!("  {:<20} {}", "update.check:", config.update.check);
+        println!(
+            "  {:<20} {}",
+            "registry.prefix:",
+            config
+                .registry
+                .prefix
+                .as_deref()
+                .unwrap_or(crate::domain::config::DEFAULT_REGISTRY_PREFIX)
+        );
         println!();
         println!("  {}", "Environment:".style(self.ctx.styles.bold));
         println!(
@@ -116,7 +223,179 @@ impl<'a> HumanRenderer<'a> {
             "NO_COLOR:",
             std::env::var("NO_COLOR").unwrap_or_else(|_| "(not set)".to_string())
         );
+        println!(
+            "    {:<18} {}",
+            "POLIS_REGISTRY_PREFIX:",
+            std::env::var("POLIS_REGISTRY_PREFIX").unwrap_or_else(|_| "(not set)".to_string())
+        );
+        println!();
+    }
+
+    /// Print a single setting bare, so it can be used in shell scripts.
+    /// Written even in quiet mode, since the value is the output.
+    pub fn render_config_value(value: &serde_json::Value) {
+        match value.as_str() {
+            Some(s) => println!("{s}"),
+            None => println!("{value}"),
+        }
+    }
+
+    /// Print each previewed artifact under a `==> file <==` separator.
+    /// Written even in quiet mode, since the contents are the output.
+    pub fn render_artifact_preview(files: &[crate::domain::agent::artifacts::GeneratedFile]) {
+        for (i, f) in files.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            println!("==> {} <==", f.file);
+            print!("{}", f.content);
+            if !f.content.is_empty() && !f.content.ends_with('\n') {
+                println!();
+            }
+        }
+    }
+
+    /// Render `polis agent validate` results.
+    pub fn render_manifest_violations(
+        &self,
+        path: &str,
+        violations: &[crate::domain::agent::validate::ManifestViolation],
+        explain: bool,
+    ) {
+        let manifest = std::path::Path::new(path).join("agent.yaml");
+        if violations.is_empty() {
+            self.ctx
+                .success(&format!("{} is valid", manifest.display()));
+            return;
+        }
+        let noun = if violations.len() == 1 {
+            "problem"
+        } else {
+            "problems"
+        };
+        self.ctx.error(&format!(
+            "{} has {} {noun}:",
+            manifest.display(),
+            violations.len()
+        ));
+        for v in violations {
+            self.print_check(false, &format!("{}: {}", v.field, v.message));
+            if explain {
+                println!("      {}", v.rule.explanation());
+                println!("      {}", "Example:".style(self.ctx.styles.dim));
+                for line in v.rule.example().lines() {
+                    println!("        {line}");
+                }
+                println!();
+            }
+        }
+    }
+
+    /// Render `polis agent doctor` results.
+    pub fn render_agent_doctor(&self, diagnosis: &crate::domain::agent::doctor::AgentDiagnosis) {
+        use owo_colors::OwoColorize;
+
+        println!();
+        println!(
+            "  {}",
+            format!("Agent Health Check ({})", diagnosis.agent).style(self.ctx.styles.header)
+        );
         println!();
+        for check in &diagnosis.checks {
+            self.print_check(check.ok, &check.detail);
+        }
+        println!();
+        if diagnosis.health_probes.is_empty() {
+            println!("  Health probes: none recorded");
+        } else {
+            println!("  Recent health probes:");
+            for probe in &diagnosis.health_probes {
+                let output = probe.output.trim();
+                self.print_check(
+                    probe.exit_code == 0,
+                    format!("{} exit {} {output}", probe.start, probe.exit_code).trim_end(),
+                );
+            }
+        }
+        println!();
+    }
+
+    /// Render the `polis self-test` phase results.
+    pub fn render_self_test(&self, report: &crate::domain::self_test::SelfTestReport) {
+        use crate::domain::self_test::PhaseOutcome;
+
+        println!();
+        self.ctx.header("Self-test");
+        for result in &report.phases {
+            let label = result.phase.label();
+            match &result.outcome {
+                PhaseOutcome::Passed => self.print_check(true, label),
+                PhaseOutcome::Failed { detail } => {
+                    self.print_check(false, &format!("{label}: {detail}"));
+                }
+                PhaseOutcome::Skipped => println!("    - {label} (skipped)"),
+            }
+        }
+        println!();
+        if report.passed() {
+            self.ctx.success("self-test passed");
+        } else {
+            self.ctx.error("self-test failed");
+        }
+    }
+
+    /// Render the `polis start --dry-run` plan.
+    pub fn render_start_plan(&self, plan: &crate::domain::workspace::StartPlan) {
+        use crate::domain::workspace::StartAction;
+
+        let action = match plan.action {
+            StartAction::Create => "create a new workspace VM",
+            StartAction::StartExisting => "start the existing workspace VM",
+            StartAction::AddAgent => "install the agent into the running workspace",
+            StartAction::AlreadyRunning => "nothing (workspace already running)",
+        };
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        let ctx = &self.ctx;
+        ctx.header("Start plan (dry run)");
+        ctx.kv("Action   ", action);
+        ctx.kv("Agent    ", plan.agent.as_deref().unwrap_or("none"));
+        if plan.action == StartAction::Create {
+            ctx.kv(
+                "Resources",
+                &format!(
+                    "{} CPUs, {} memory, {} disk",
+                    plan.cpus, plan.memory, plan.disk
+                ),
+            );
+        }
+        ctx.kv("Pull     ", yes_no(plan.pull_images));
+        let reprovision = match (plan.action, plan.reprovision) {
+            (StartAction::StartExisting, false) => "if config changed",
+            (_, b) => yes_no(b),
+        };
+        ctx.kv("Config   ", reprovision);
+        for (i, file) in plan.compose_files.iter().enumerate() {
+            ctx.kv(if i == 0 { "Compose  " } else { "         " }, file);
+        }
+    }
+
+    /// Render `polis start --timings` as a table of stage durations.
+    pub fn render_start_timings(&self, timings: &[crate::domain::workspace::StageTiming]) {
+        let ctx = &self.ctx;
+        let width = timings.iter().map(|t| t.stage.len()).max().unwrap_or(0);
+        ctx.blank();
+        ctx.header("Timings");
+        for t in timings {
+            let mark = if t.ok { "" } else { " (failed)" };
+            ctx.kv(
+                &format!("{:width$}", t.stage),
+                &format!("{}{mark}", seconds_display(t.duration_ms)),
+            );
+        }
+        ctx.kv(
+            &format!("{:width$}", "total"),
+            &seconds_display(crate::domain::workspace::total_duration_ms(timings)),
+        );
     }
 
     /// Render doctor health check results.
@@ -147,6 +426,27 @@ impl<'a> HumanRenderer<'a> {
                 ),
             );
         }
+        if let Some(drift) = &checks.workspace.version_drift {
+            if drift.is_empty() {
+                self.print_check(true, "Service versions match CLI");
+            } else {
+                self.print_check(false, "Service versions differ from CLI");
+                for d in drift {
+                    println!("      {}: {} (expected {})", d.var, d.deployed, d.expected);
+                }
+                println!("      Fix: polis update");
+            }
+        }
+        match checks.workspace.env_modified {
+            Some(false) => self.print_check(true, "Config unchanged since provisioning"),
+            Some(true) => self.print_check(false, "VM .env modified outside polis"),
+            None => {}
+        }
+        match checks.workspace.host_key_pinned {
+            Some(true) => self.print_check(true, "SSH host key pinned"),
+            Some(false) => self.print_check(false, "SSH host key not pinned"),
+            None => {}
+        }
         println!();
 
         // Network
@@ -258,6 +558,112 @@ impl<'a> HumanRenderer<'a> {
 
 // ── Display helpers (used by tests and output layer) ─────────────────────────
 
+/// Width assumed when stdout is not a terminal.
+pub const FALLBACK_TERMINAL_WIDTH: usize = 100;
+
+/// Narrowest a truncated description gets, however small the terminal.
+const MIN_DESCRIPTION_WIDTH: usize = 10;
+
+/// Columns available on stdout, or [`FALLBACK_TERMINAL_WIDTH`] when it is
+/// not a terminal.
+#[must_use]
+pub fn terminal_width() -> usize {
+    console::Term::stdout()
+        .size_checked()
+        .map_or(FALLBACK_TERMINAL_WIDTH, |(_, cols)| usize::from(cols))
+}
+
+/// Shorten `text` to at most `width` display columns, ending in `…` when
+/// anything was cut.
+#[must_use]
+pub fn truncate_ellipsis(text: &str, width: usize) -> std::borrow::Cow<'_, str> {
+    console::truncate_str(text, width, "…")
+}
+
+/// Lines of the `agent list` table, without the leading indent. Every
+/// column but the last is padded to its widest cell; only the description
+/// gives way when a row would not fit in `width` columns.
+fn agent_list_rows(
+    agents: &[crate::domain::agent::AgentInfo],
+    columns: &[crate::output::columns::AgentColumn],
+    width: usize,
+) -> Vec<String> {
+    use crate::output::columns::AgentColumn;
+
+    let mut rows: Vec<Vec<String>> = agents
+        .iter()
+        .map(|agent| {
+            columns
+                .iter()
+                .map(|column| match column {
+                    AgentColumn::Name => agent_display_label(agent),
+                    AgentColumn::Active if agent.active => "[active]".to_string(),
+                    AgentColumn::Active => String::new(),
+                    AgentColumn::Version | AgentColumn::Description => {
+                        column.value(agent).to_string()
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    let column_width = |rows: &[Vec<String>], i: usize| {
+        rows.iter()
+            .map(|cells| console::measure_text_width(&cells[i]))
+            .max()
+            .unwrap_or(0)
+    };
+    // Only the description gives way; every other cell stays whole.
+    if let Some(i) = columns.iter().position(|c| *c == AgentColumn::Description) {
+        let others: usize = (0..columns.len())
+            .filter(|j| *j != i)
+            .map(|j| column_width(&rows, j) + 1)
+            .sum();
+        let budget = width.saturating_sub(2 + others).max(MIN_DESCRIPTION_WIDTH);
+        for cells in &mut rows {
+            cells[i] = truncate_ellipsis(&cells[i], budget).into_owned();
+        }
+    }
+    let widths: Vec<usize> = (0..columns.len()).map(|i| column_width(&rows, i)).collect();
+    rows.iter()
+        .map(|cells| {
+            let last = cells.len().saturating_sub(1);
+            cells
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(i, (cell, &w))| {
+                    if i == last {
+                        cell.clone()
+                    } else {
+                        console::pad_str(cell, w, console::Alignment::Left, None).into_owned()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+/// Primary label for an agent in human output: its `displayName`, with the
+/// internal name in parentheses when the two differ.
+#[must_use]
+pub fn agent_display_label(agent: &crate::domain::agent::AgentInfo) -> String {
+    match agent.display_name.as_deref() {
+        Some(display) if !display.is_empty() && display != agent.name => {
+            format!("{display} ({})", agent.name)
+        }
+        _ => agent.name.clone(),
+    }
+}
+
+/// Uptime as reported by the VM, or `—` when it is stopped or did not say.
+#[must_use]
+pub fn uptime_display(seconds: Option<u64>) -> String {
+    seconds.map_or_else(|| "\u{2014}".to_string(), format_uptime)
+}
+
 #[must_use]
 pub fn format_uptime(seconds: u64) -> String {
     let hours = seconds / 3600;
@@ -291,6 +697,21 @@ pub fn agent_health_display(health: AgentHealth) -> &'static str {
     }
 }
 
+/// Milliseconds as seconds with one decimal, e.g. `12.3s`.
+#[must_use]
+pub fn seconds_display(ms: u64) -> String {
+    format!("{}.{}s", ms / 1000, ms % 1000 / 100)
+}
+
+#[must_use]
+pub fn config_state_display(state: ConfigState) -> &'static str {
+    match state {
+        ConfigState::Current => "up to date",
+        ConfigState::Stale => "stale (re-applied on next start)",
+        ConfigState::Missing => "not recorded (re-applied on next start)",
+    }
+}
+
 #[cfg(test)]
 #[must_use]
 pub fn format_agent_line(name: &str, health: AgentHealth) -> String {
@@ -314,6 +735,144 @@ mod tests {
         WorkspaceState, WorkspaceStatus,
     };
 
+    #[test]
+    fn test_truncate_ellipsis_fits_long_text_to_width() {
+        let long = "Autonomous coding agent with a very long description";
+        let cut = truncate_ellipsis(long, 20);
+        assert_eq!(cut, "Autonomous coding a…");
+        assert_eq!(console::measure_text_width(&cut), 20);
+    }
+
+    #[test]
+    fn test_truncate_ellipsis_keeps_text_that_fits() {
+        assert_eq!(truncate_ellipsis("short", 20), "short");
+        assert_eq!(truncate_ellipsis("exactly ten", 11), "exactly ten");
+    }
+
+    #[test]
+    fn test_truncate_ellipsis_counts_display_columns() {
+        let cut = truncate_ellipsis("日本語の説明文です", 7);
+        assert!(console::measure_text_width(&cut) <= 7, "{cut}");
+        assert!(cut.ends_with('…'), "{cut}");
+    }
+
+    fn agent_info(name: &str, display_name: Option<&str>) -> crate::domain::agent::AgentInfo {
+        crate::domain::agent::AgentInfo {
+            name: name.to_string(),
+            display_name: display_name.map(String::from),
+            version: None,
+            description: None,
+            active: false,
+            labels: std::collections::BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_agent_display_label_prefers_display_name() {
+        let agent = agent_info("openclaw", Some("OpenClaw"));
+        assert_eq!(agent_display_label(&agent), "OpenClaw (openclaw)");
+    }
+
+    #[test]
+    fn test_agent_display_label_falls_back_to_name() {
+        assert_eq!(
+            agent_display_label(&agent_info("openclaw", None)),
+            "openclaw"
+        );
+        assert_eq!(
+            agent_display_label(&agent_info("openclaw", Some("openclaw"))),
+            "openclaw"
+        );
+        assert_eq!(
+            agent_display_label(&agent_info("openclaw", Some(""))),
+            "openclaw"
+        );
+    }
+
+    #[test]
+    fn test_agent_list_rows_fit_name_column_to_longest_label() {
+        use crate::output::columns::AgentColumn;
+        let mut long = agent_info("longagent", Some("A Rather Long Display Name"));
+        long.version = Some("1.0.0".into());
+        let mut short = agent_info("short", None);
+        short.version = Some("2.0.0".into());
+        let rows = agent_list_rows(
+            &[long, short],
+            &[AgentColumn::Name, AgentColumn::Version],
+            FALLBACK_TERMINAL_WIDTH,
+        );
+        let label = "A Rather Long Display Name (longagent)";
+        assert_eq!(rows[0], format!("{label} 1.0.0"));
+        assert_eq!(rows[1], format!("{:<w$} 2.0.0", "short", w = label.len()));
+    }
+
+    #[test]
+    fn test_agent_list_rows_pad_every_column_but_the_last() {
+        use crate::output::columns::AgentColumn;
+        let mut alpha = agent_info("alpha", None);
+        alpha.description = Some("Short".into());
+        alpha.version = Some("1.0.0".into());
+        alpha.active = true;
+        let mut beta = agent_info("beta", None);
+        beta.description = Some("A longer description".into());
+        beta.version = Some("10.2.0".into());
+        let rows = agent_list_rows(
+            &[alpha, beta],
+            &[
+                AgentColumn::Description,
+                AgentColumn::Version,
+                AgentColumn::Active,
+            ],
+            FALLBACK_TERMINAL_WIDTH,
+        );
+        assert_eq!(rows[0], "Short                1.0.0  [active]");
+        assert_eq!(rows[1], "A longer description 10.2.0");
+    }
+
+    #[test]
+    fn test_agent_list_rows_truncate_description_to_its_budget() {
+        use crate::output::columns::AgentColumn;
+        let mut alpha = agent_info("alpha", None);
+        alpha.description = Some("An agent with a description far too long to fit".into());
+        alpha.version = Some("1.0.0".into());
+        let rows = agent_list_rows(
+            &[alpha],
+            &[AgentColumn::Description, AgentColumn::Version],
+            30,
+        );
+        // 30 columns, less the 2-column indent and "1.0.0" plus a separator.
+        assert_eq!(rows[0], "An agent with a descr… 1.0.0");
+    }
+
+    #[test]
+    fn test_agent_list_json_includes_name_and_display_name() {
+        let json = serde_json::to_value(agent_info("openclaw", Some("OpenClaw"))).expect("json");
+        assert_eq!(json["name"], "openclaw");
+        assert_eq!(json["display_name"], "OpenClaw");
+        let json = serde_json::to_value(agent_info("openclaw", None)).expect("json");
+        assert!(json["display_name"].is_null());
+    }
+
+    #[test]
+    fn test_agent_list_json_schema_is_stable() {
+        let agents = [agent_info("openclaw", None)];
+        let json = serde_json::to_value(crate::domain::agent::AgentListOutput { agents: &agents })
+            .expect("json");
+        let agent = json["agents"][0].as_object().expect("agent object");
+        let keys: Vec<&str> = agent.keys().map(String::as_str).collect();
+        assert_eq!(
+            keys,
+            [
+                "active",
+                "description",
+                "display_name",
+                "labels",
+                "name",
+                "version"
+            ]
+        );
+    }
+
     #[test]
     fn test_format_uptime_hours_and_minutes() {
         assert_eq!(format_uptime(9240), "2h 34m");
@@ -329,6 +888,26 @@ mod tests {
         assert_eq!(format_uptime(0), "0m");
     }
 
+    #[test]
+    fn test_uptime_display_dashes_missing_uptime() {
+        assert_eq!(uptime_display(Some(8040)), "2h 14m");
+        assert_eq!(uptime_display(None), "\u{2014}");
+    }
+
+    #[test]
+    fn test_seconds_display_truncates_to_tenths() {
+        assert_eq!(seconds_display(0), "0.0s");
+        assert_eq!(seconds_display(12_345), "12.3s");
+        assert_eq!(seconds_display(999), "0.9s");
+    }
+
+    #[test]
+    fn test_config_state_display_all() {
+        assert_eq!(config_state_display(ConfigState::Current), "up to date");
+        assert!(config_state_display(ConfigState::Stale).starts_with("stale"));
+        assert!(config_state_display(ConfigState::Missing).starts_with("not recorded"));
+    }
+
     #[test]
     fn test_workspace_state_display_all() {
         assert_eq!(workspace_state_display(WorkspaceState::Running), "running");
@@ -400,6 +979,8 @@ mod tests {
                 count: 2,
                 severity: EventSeverity::Warning,
             },
+            updates: None,
+            config: None,
         }
     }
 
@@ -429,6 +1010,8 @@ mod tests {
                 count: 0,
                 severity: EventSeverity::None,
             },
+            updates: None,
+            config: None,
         };
         let json = serde_json::to_string(&status).expect("serialize");
         assert!(!json.contains("uptime_seconds"));
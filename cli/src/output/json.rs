@@ -1,7 +1,10 @@
 //! JSON output helpers.
 
+use std::io::Write;
+
 use anyhow::{Context, Result};
 use polis_common::types::StatusOutput;
+use serde::Serialize;
 
 use crate::domain::health::DoctorChecks;
 
@@ -19,7 +22,7 @@ impl JsonRenderer {
             "version": version,
             "build_date": build_date
         });
-        println!("{}", serde_json::to_string_pretty(&val)?);
+        print_json(&val)?;
         Ok(())
     }
     /// Render workspace/agent/security status as JSON.
@@ -28,27 +31,128 @@ impl JsonRenderer {
     ///
     /// This function will return an error if the underlying operations fail.
     pub fn render_status(status: &StatusOutput) -> Result<()> {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(status).context("JSON serialization")?
-        );
+        print_json(status)?;
         Ok(())
     }
 
+    /// Render status as a single line of JSON, for `polis status --watch`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    pub fn render_status_line(status: &StatusOutput) -> Result<()> {
+        write_json_line(&mut std::io::stdout().lock(), status)
+    }
+
     /// Render the list of installed agents as JSON.
     ///
     /// # Errors
     ///
     /// This function will return an error if the underlying operations fail.
     pub fn render_agent_list(agents: &[crate::domain::agent::AgentInfo]) -> Result<()> {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({ "agents": agents }))
-                .context("JSON serialization")?
-        );
+        print_json(&crate::domain::agent::AgentListOutput { agents })?;
+        Ok(())
+    }
+
+    /// Render `polis agent add --dry-run` artifacts as JSON.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    pub fn render_artifact_preview(
+        files: &[crate::domain::agent::artifacts::GeneratedFile],
+    ) -> Result<()> {
+        print_json(&serde_json::json!({ "artifacts": files }))?;
         Ok(())
     }
 
+    /// Render `polis agent validate` results as JSON.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    pub fn render_manifest_violations(
+        path: &str,
+        violations: &[crate::domain::agent::validate::ManifestViolation],
+        explain: bool,
+    ) -> Result<()> {
+        let mut entries = serde_json::to_value(violations).context("JSON serialization")?;
+        if explain && let Some(entries) = entries.as_array_mut() {
+            for (entry, v) in entries.iter_mut().zip(violations) {
+                entry["explanation"] = v.rule.explanation().into();
+                entry["example"] = v.rule.example().into();
+            }
+        }
+        print_json(&serde_json::json!({
+            "path": path,
+            "valid": violations.is_empty(),
+            "violations": entries,
+        }))?;
+        Ok(())
+    }
+
+    /// Render the JSON Schema for `agent.yaml`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    pub fn render_agent_schema(schema: &serde_json::Value) -> Result<()> {
+        print_json(schema)
+    }
+
+    /// Render `polis agent doctor` results as JSON.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    pub fn render_agent_doctor(
+        diagnosis: &crate::domain::agent::doctor::AgentDiagnosis,
+    ) -> Result<()> {
+        let status = if diagnosis.healthy() {
+            "healthy"
+        } else {
+            "unhealthy"
+        };
+        let mut out = serde_json::to_value(diagnosis).context("JSON serialization")?;
+        out["status"] = serde_json::json!(status);
+        print_json(&out)?;
+        Ok(())
+    }
+
+    /// Render the `polis start --dry-run` plan as JSON.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    pub fn render_start_plan(plan: &crate::domain::workspace::StartPlan) -> Result<()> {
+        print_json(plan)?;
+        Ok(())
+    }
+
+    /// Render `polis start --timings` stage durations as JSON.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    pub fn render_start_timings(timings: &[crate::domain::workspace::StageTiming]) -> Result<()> {
+        print_json(&serde_json::json!({
+            "timings": timings,
+            "total_ms": crate::domain::workspace::total_duration_ms(timings),
+        }))
+    }
+
+    /// Render the `polis self-test` phase results as JSON.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    pub fn render_self_test(report: &crate::domain::self_test::SelfTestReport) -> Result<()> {
+        print_json(&serde_json::json!({
+            "passed": report.passed(),
+            "phases": report.phases,
+        }))
+    }
+
     /// Render the current polis configuration as JSON.
     ///
     /// # Errors
@@ -57,22 +161,40 @@ impl JsonRenderer {
     pub fn render_config(config: &crate::domain::config::PolisConfig) -> Result<()> {
         let polis_config_env = std::env::var("POLIS_CONFIG").ok();
         let no_color_env = std::env::var("NO_COLOR").ok();
+        let registry_prefix_env = std::env::var("POLIS_REGISTRY_PREFIX").ok();
         let val = serde_json::json!({
             "security": {
                 "level": config.security.level
             },
+            "update": {
+                "check": config.update.check
+            },
+            "registry": {
+                "prefix": config
+                    .registry
+                    .prefix
+                    .as_deref()
+                    .unwrap_or(crate::domain::config::DEFAULT_REGISTRY_PREFIX)
+            },
             "environment": {
                 "polis_config": polis_config_env,
-                "no_color": no_color_env
+                "no_color": no_color_env,
+                "polis_registry_prefix": registry_prefix_env
             }
         });
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&val).context("JSON serialization")?
-        );
+        print_json(&val)?;
         Ok(())
     }
 
+    /// Render a single setting as a JSON value.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    pub fn render_config_value(value: &serde_json::Value) -> Result<()> {
+        print_json(value)
+    }
+
     /// Render doctor health check results as JSON.
     ///
     /// # Errors
@@ -97,6 +219,9 @@ impl JsonRenderer {
                     "disk_space_gb": checks.workspace.disk_space_gb,
                     "disk_space_ok": checks.workspace.disk_space_ok,
                     "image": checks.workspace.image,
+                    "version_drift": checks.workspace.version_drift,
+                    "env_modified": checks.workspace.env_modified,
+                    "host_key_pinned": checks.workspace.host_key_pinned,
                 },
                 "network": {
                     "internet": checks.network.internet,
@@ -113,12 +238,76 @@ impl JsonRenderer {
             },
             "issues": issues,
         });
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&out).context("JSON serialization")?
-        );
+        print_json(&out)?;
         Ok(())
     }
+
+    /// Render the `polis update --json` report.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    pub fn render_update(report: &crate::domain::update::UpdateReport) -> Result<()> {
+        print_json(report)?;
+        Ok(())
+    }
+
+    /// Render `polis changelog --json`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying operations fail.
+    pub fn render_changelog(release: &crate::domain::update::ReleaseNotes) -> Result<()> {
+        let sections: Vec<_> = release
+            .sections
+            .iter()
+            .map(|(title, notes)| serde_json::json!({ "title": title, "notes": notes }))
+            .collect();
+        let val = serde_json::json!({
+            "version": release.version,
+            "sections": sections
+        });
+        print_json(&val)?;
+        Ok(())
+    }
+}
+
+/// Write `value` to stdout as one pretty-printed document.
+///
+/// # Errors
+///
+/// Returns an error if serialization or the write fails.
+fn print_json(value: &impl Serialize) -> Result<()> {
+    write_json(&mut std::io::stdout().lock(), value)
+}
+
+/// Write `value` as pretty-printed JSON followed by a newline, in a single
+/// write and flush, so a reader on the other end of a pipe never sees a
+/// partial document.
+///
+/// # Errors
+///
+/// Returns an error if serialization or the write fails.
+pub fn write_json(out: &mut impl Write, value: &impl Serialize) -> Result<()> {
+    let mut doc = serde_json::to_string_pretty(value).context("JSON serialization")?;
+    doc.push('\n');
+    out.write_all(doc.as_bytes())
+        .context("writing JSON output")?;
+    out.flush().context("flushing JSON output")
+}
+
+/// Write `value` as compact JSON on one line, in a single write and flush,
+/// so a stream of them is newline-delimited JSON.
+///
+/// # Errors
+///
+/// Returns an error if serialization or the write fails.
+pub fn write_json_line(out: &mut impl Write, value: &impl Serialize) -> Result<()> {
+    let mut line = serde_json::to_string(value).context("JSON serialization")?;
+    line.push('\n');
+    out.write_all(line.as_bytes())
+        .context("writing JSON output")?;
+    out.flush().context("flushing JSON output")
 }
 
 /// Format a JSON error object per the spec error schema (issue 18 §2.7).
@@ -134,3 +323,44 @@ pub fn format_error(message: &str, code: &str) -> Result<String> {
     });
     serde_json::to_string_pretty(&obj).context("JSON serialization failed")
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn write_json_emits_one_plain_document() {
+        let mut buf = Vec::new();
+        write_json(
+            &mut buf,
+            &serde_json::json!({ "workspace": { "status": "running" } }),
+        )
+        .expect("write");
+
+        assert!(!buf.contains(&0x1b), "no ANSI escapes");
+        assert_eq!(buf.last(), Some(&b'\n'));
+        let _: serde_json::Value = serde_json::from_slice(&buf).expect("valid JSON");
+    }
+
+    #[test]
+    fn write_json_line_emits_one_compact_line_per_value() {
+        let mut buf = Vec::new();
+        for status in ["starting", "running"] {
+            write_json_line(
+                &mut buf,
+                &serde_json::json!({ "workspace": { "status": status } }),
+            )
+            .expect("write");
+        }
+
+        let text = String::from_utf8(buf).expect("utf-8");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            let value: serde_json::Value = serde_json::from_str(line).expect("valid JSON");
+            assert!(value["workspace"]["status"].is_string());
+        }
+    }
+}
@@ -2,6 +2,8 @@
 
 #![allow(dead_code)] // Presentation layer helpers — not all adopted by every command
 
+pub mod columns;
+pub mod csv;
 pub mod human;
 pub mod json;
 pub mod progress;
@@ -19,6 +21,17 @@ use polis_common::types::StatusOutput;
 
 use crate::domain::health::DoctorChecks;
 
+/// Output format for list-like commands (`--format`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ListFormat {
+    /// Aligned, human-readable table.
+    Table,
+    /// JSON document, as with `--json`.
+    Json,
+    /// Comma-separated values with a header row.
+    Csv,
+}
+
 /// Enum-dispatched output renderer.
 ///
 /// Use `AppContext::renderer()` to obtain the appropriate variant based on
@@ -61,18 +74,47 @@ impl Renderer<'_> {
         }
     }
 
-    /// Render the list of installed agents.
+    /// Render one refresh of `polis status --watch`: the human view redrawn
+    /// on a cleared screen, or one JSON object per line.
     ///
     /// # Errors
     ///
-    /// Returns an error if JSON serialization fails.
-    pub fn render_agent_list(&self, agents: &[crate::domain::agent::AgentInfo]) -> Result<()> {
+    /// Returns an error if JSON serialization or the write fails.
+    pub fn render_status_refresh(&self, status: &StatusOutput) -> Result<()> {
         match self {
             Renderer::Human(r) => {
-                r.render_agent_list(agents);
+                r.render_status_refresh(status);
+                Ok(())
+            }
+            Renderer::Json(_) => JsonRenderer::render_status_line(status),
+        }
+    }
+
+    /// Render the list of installed agents in `format` (see
+    /// `AppContext::list_format`). `columns` applies to table and CSV
+    /// output; JSON always carries every field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn render_agent_list(
+        &self,
+        agents: &[crate::domain::agent::AgentInfo],
+        format: ListFormat,
+        columns: &[columns::AgentColumn],
+    ) -> Result<()> {
+        match (self, format) {
+            (_, ListFormat::Csv) => {
+                print!("{}", csv::agent_list(agents, columns));
                 Ok(())
             }
-            Renderer::Json(_) => JsonRenderer::render_agent_list(agents),
+            (Renderer::Human(r), ListFormat::Table) => {
+                r.render_agent_list(agents, columns);
+                Ok(())
+            }
+            (Renderer::Json(_), _) | (_, ListFormat::Json) => {
+                JsonRenderer::render_agent_list(agents)
+            }
         }
     }
 
@@ -95,6 +137,52 @@ impl Renderer<'_> {
         }
     }
 
+    /// Render a single setting for `polis config get`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn render_config_value(&self, value: &serde_json::Value) -> Result<()> {
+        match self {
+            Renderer::Human(_) => {
+                HumanRenderer::render_config_value(value);
+                Ok(())
+            }
+            Renderer::Json(_) => JsonRenderer::render_config_value(value),
+        }
+    }
+
+    /// Render the `polis update` report. Human progress is printed as the
+    /// update runs, so human mode only adds the per-service table here.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn render_update(&self, report: &crate::domain::update::UpdateReport) -> Result<()> {
+        match self {
+            Renderer::Human(r) => {
+                r.render_container_updates(&report.containers);
+                Ok(())
+            }
+            Renderer::Json(_) => JsonRenderer::render_update(report),
+        }
+    }
+
+    /// Render the release notes shown by `polis changelog`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn render_changelog(&self, release: &crate::domain::update::ReleaseNotes) -> Result<()> {
+        match self {
+            Renderer::Human(r) => {
+                r.render_changelog(release);
+                Ok(())
+            }
+            Renderer::Json(_) => JsonRenderer::render_changelog(release),
+        }
+    }
+
     /// Render doctor health check results.
     ///
     /// # Errors
@@ -114,6 +202,127 @@ impl Renderer<'_> {
             Renderer::Json(_) => JsonRenderer::render_doctor(checks, issues),
         }
     }
+
+    /// Render the artifacts `polis agent add --dry-run` would install.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn render_artifact_preview(
+        &self,
+        files: &[crate::domain::agent::artifacts::GeneratedFile],
+    ) -> Result<()> {
+        match self {
+            Renderer::Human(_) => {
+                HumanRenderer::render_artifact_preview(files);
+                Ok(())
+            }
+            Renderer::Json(_) => JsonRenderer::render_artifact_preview(files),
+        }
+    }
+
+    /// Render `polis agent validate` results for the manifest at `path`;
+    /// `explain` adds each broken rule's rationale and a corrected example.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn render_manifest_violations(
+        &self,
+        path: &str,
+        violations: &[crate::domain::agent::validate::ManifestViolation],
+        explain: bool,
+    ) -> Result<()> {
+        match self {
+            Renderer::Human(r) => {
+                r.render_manifest_violations(path, violations, explain);
+                Ok(())
+            }
+            Renderer::Json(_) => {
+                JsonRenderer::render_manifest_violations(path, violations, explain)
+            }
+        }
+    }
+
+    /// Render the JSON Schema for `agent.yaml`. It is JSON in either mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn render_agent_schema(&self, schema: &serde_json::Value) -> Result<()> {
+        match self {
+            Renderer::Human(_) | Renderer::Json(_) => JsonRenderer::render_agent_schema(schema),
+        }
+    }
+
+    /// Render the `polis start --dry-run` plan.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn render_start_plan(&self, plan: &crate::domain::workspace::StartPlan) -> Result<()> {
+        match self {
+            Renderer::Human(r) => {
+                r.render_start_plan(plan);
+                Ok(())
+            }
+            Renderer::Json(_) => JsonRenderer::render_start_plan(plan),
+        }
+    }
+
+    /// Render the per-stage durations printed by `polis start --timings`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn render_start_timings(
+        &self,
+        timings: &[crate::domain::workspace::StageTiming],
+    ) -> Result<()> {
+        match self {
+            Renderer::Human(r) => {
+                r.render_start_timings(timings);
+                Ok(())
+            }
+            Renderer::Json(_) => JsonRenderer::render_start_timings(timings),
+        }
+    }
+
+    /// Render the `polis self-test` phase results.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn render_self_test(
+        &self,
+        report: &crate::domain::self_test::SelfTestReport,
+    ) -> Result<()> {
+        match self {
+            Renderer::Human(r) => {
+                r.render_self_test(report);
+                Ok(())
+            }
+            Renderer::Json(_) => JsonRenderer::render_self_test(report),
+        }
+    }
+
+    /// Render `polis agent doctor` results for one agent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn render_agent_doctor(
+        &self,
+        diagnosis: &crate::domain::agent::doctor::AgentDiagnosis,
+    ) -> Result<()> {
+        match self {
+            Renderer::Human(r) => {
+                r.render_agent_doctor(diagnosis);
+                Ok(())
+            }
+            Renderer::Json(_) => JsonRenderer::render_agent_doctor(diagnosis),
+        }
+    }
 }
 
 /// Output context carrying styling and terminal state.
@@ -124,6 +333,8 @@ pub struct OutputContext {
     pub is_tty: bool,
     /// Whether to suppress non-error output.
     pub quiet: bool,
+    /// Whether to disable spinners and progress bars (`--no-progress`).
+    pub no_progress: bool,
 }
 
 impl OutputContext {
@@ -142,13 +353,21 @@ impl OutputContext {
             styles,
             is_tty,
             quiet,
+            no_progress: false,
         }
     }
 
+    /// Disable spinners and progress bars while keeping other output.
+    #[must_use]
+    pub fn with_no_progress(mut self, no_progress: bool) -> Self {
+        self.no_progress = no_progress;
+        self
+    }
+
     /// Check if progress indicators should be shown.
     #[must_use]
     pub fn show_progress(&self) -> bool {
-        self.is_tty && !self.quiet
+        self.is_tty && !self.quiet && !self.no_progress
     }
 
     /// Print a success message prefixed with `✓`. Suppressed when `quiet`.
@@ -225,3 +444,28 @@ impl OutputContext {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tty_context() -> OutputContext {
+        OutputContext {
+            styles: Styles::default(),
+            is_tty: true,
+            quiet: false,
+            no_progress: false,
+        }
+    }
+
+    #[test]
+    fn test_show_progress_on_tty_unless_disabled() {
+        assert!(tty_context().show_progress());
+        assert!(!tty_context().with_no_progress(true).show_progress());
+        let quiet = OutputContext {
+            quiet: true,
+            ..tty_context()
+        };
+        assert!(!quiet.show_progress());
+    }
+}
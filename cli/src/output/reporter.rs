@@ -11,6 +11,7 @@ use indicatif::{ProgressBar, ProgressStyle};
 use owo_colors::OwoColorize as _;
 
 use crate::application::ports::ProgressReporter;
+use crate::domain::workspace::StageTiming;
 use crate::output::OutputContext;
 
 /// Terminal progress reporter that wraps an `OutputContext`.
@@ -21,9 +22,13 @@ use crate::output::OutputContext;
 /// - `begin_stage()` starts a timed spinner on TTY, auto-completing any prior stage
 /// - `complete_stage()` finishes the spinner with ✓ and elapsed time
 /// - `fail_stage()` finishes the spinner with ✗ and elapsed time
+///
+/// Stage durations are recorded even when `quiet`; read them with
+/// [`TerminalReporter::timings`].
 pub struct TerminalReporter<'a> {
     ctx: &'a OutputContext,
     stage: RefCell<Option<ActiveStage>>,
+    timings: RefCell<Vec<StageTiming>>,
 }
 
 /// A currently-running timed stage.
@@ -41,15 +46,26 @@ impl<'a> TerminalReporter<'a> {
         Self {
             ctx,
             stage: RefCell::new(None),
+            timings: RefCell::new(Vec::new()),
         }
     }
 
+    /// Durations of the stages finished so far, in order.
+    #[must_use]
+    pub fn timings(&self) -> Vec<StageTiming> {
+        self.timings.borrow().clone()
+    }
+
     /// Finish the active stage, printing a final status line.
     fn finish_active_stage(&self, success: bool) {
         let Some(stage) = self.stage.borrow_mut().take() else {
             return;
         };
-        let elapsed = stage.start.elapsed().as_secs();
+        let elapsed = stage.start.elapsed();
+        self.timings
+            .borrow_mut()
+            .push(StageTiming::new(&stage.message, elapsed, success));
+        let elapsed = elapsed.as_secs();
         let time = format!("{}:{:02}", elapsed / 60, elapsed % 60);
 
         if let Some(pb) = &stage.spinner {
@@ -86,14 +102,10 @@ impl ProgressReporter for TerminalReporter<'_> {
     }
 
     fn begin_stage(&self, message: &str) {
-        if self.ctx.quiet {
-            return;
-        }
-
         // Auto-complete any active stage with success.
         self.finish_active_stage(true);
 
-        let spinner = if self.ctx.is_tty {
+        let spinner = if self.ctx.show_progress() {
             let pb = ProgressBar::new_spinner();
             #[allow(clippy::expect_used)] // Template is a compile-time constant.
             pb.set_style(
@@ -122,8 +134,10 @@ impl ProgressReporter for TerminalReporter<'_> {
             });
             Some(pb)
         } else {
-            // Non-TTY: print a plain step line as a breadcrumb.
-            println!("  {} {message}", "→".cyan());
+            // Non-TTY or --no-progress: print a plain step line as a breadcrumb.
+            if !self.ctx.quiet {
+                println!("  {} {message}", "→".cyan());
+            }
             None
         };
 
@@ -142,3 +156,32 @@ impl ProgressReporter for TerminalReporter<'_> {
         self.finish_active_stage(false);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timings_record_each_finished_stage_in_order() {
+        let ctx = OutputContext::new(true, true);
+        let reporter = TerminalReporter::new(&ctx);
+        reporter.begin_stage("preparing workspace...");
+        reporter.complete_stage();
+        reporter.begin_stage("securing workspace...");
+        // A new stage auto-completes the active one.
+        reporter.begin_stage("starting services...");
+        reporter.fail_stage();
+        reporter.complete_stage();
+
+        let timings = reporter.timings();
+        let stages: Vec<_> = timings.iter().map(|t| (t.stage.as_str(), t.ok)).collect();
+        assert_eq!(
+            stages,
+            [
+                ("preparing workspace", true),
+                ("securing workspace", true),
+                ("starting services", false),
+            ]
+        );
+    }
+}
@@ -1 +1,3 @@
 //! Integration tests for polis CLI
+
+mod pipe_output;
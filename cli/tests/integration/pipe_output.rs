@@ -0,0 +1,26 @@
+//! Output written to a pipe rather than a terminal.
+
+#![allow(clippy::expect_used)]
+
+use assert_cmd::Command;
+
+#[test]
+fn json_output_to_pipe_has_no_ansi_escapes() {
+    let output = Command::cargo_bin("polis")
+        .expect("polis binary")
+        .args(["version", "--json"])
+        .env_remove("NO_COLOR")
+        .env("CLICOLOR_FORCE", "1")
+        .output()
+        .expect("run polis");
+
+    assert!(output.status.success());
+    assert!(
+        !output.stdout.contains(&0x1b),
+        "stdout contains ANSI escapes: {:?}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let doc: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout is one JSON document");
+    assert!(doc["version"].is_string());
+}
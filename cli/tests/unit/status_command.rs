@@ -1,7 +1,7 @@
 use anyhow::Result;
 use polis_cli::application::ports::{InstanceInspector, ShellExecutor};
-use polis_cli::application::services::workspace_status::gather_status;
-use polis_common::types::{AgentHealth, WorkspaceState};
+use polis_cli::application::services::workspace_status::{docker_missing_hint, gather_status};
+use polis_common::types::{AgentHealth, ConfigState, WorkspaceState};
 use std::process::{ExitStatus, Output};
 
 #[cfg(unix)]
@@ -112,7 +112,7 @@ async fn status_parses_healthy_response() {
             true,
         );
 
-    let result = gather_status(&mock).await;
+    let result = gather_status(&mock, None).await;
     assert_eq!(result.workspace.status, WorkspaceState::Running);
     assert_eq!(result.workspace.uptime_seconds, Some(1764));
     assert_eq!(
@@ -132,13 +132,31 @@ async fn status_degrades_gracefully_when_script_missing() {
             &["/opt/polis/scripts/polis-query.sh", "status"],
             b"command not found",
             false,
-        );
+        )
+        .with_exec(&["docker", "--version"], b"Docker version 27.5.1", true);
 
-    let result = gather_status(&mock).await;
+    let result = gather_status(&mock, None).await;
     assert_eq!(result.workspace.status, WorkspaceState::Starting);
     assert!(!result.security.traffic_inspection);
 }
 
+#[tokio::test]
+async fn status_reports_error_when_docker_missing() {
+    let mock = MockVm::new()
+        .with_info(br#"{"info":{"polis":{"state":"Running"}}}"#)
+        .with_exec(
+            &["/opt/polis/scripts/polis-query.sh", "status"],
+            b"docker: command not found",
+            false,
+        )
+        .with_exec(&["docker", "--version"], b"", false);
+
+    let result = gather_status(&mock, None).await;
+    assert_eq!(result.workspace.status, WorkspaceState::Error);
+    assert!(result.agent.is_none());
+    assert!(docker_missing_hint(&mock).await.is_some());
+}
+
 #[tokio::test]
 async fn status_handles_malformed_json() {
     let mock = MockVm::new()
@@ -149,6 +167,34 @@ async fn status_handles_malformed_json() {
             true,
         );
 
-    let result = gather_status(&mock).await;
+    let result = gather_status(&mock, None).await;
     assert_eq!(result.workspace.status, WorkspaceState::Starting);
 }
+
+#[tokio::test]
+async fn status_reports_config_hash_state_when_requested() {
+    let running = || {
+        MockVm::new()
+            .with_info(br#"{"info":{"polis":{"state":"Running"}}}"#)
+            .with_exec(
+                &["/opt/polis/scripts/polis-query.sh", "status"],
+                br#"{"uptime":5,"containers":[]}"#,
+                true,
+            )
+    };
+
+    let current = running().with_exec(&["cat", "/opt/polis/.config-hash"], b"abc123\n", true);
+    assert_eq!(
+        gather_status(&current, Some("abc123")).await.config,
+        Some(ConfigState::Current)
+    );
+    assert_eq!(
+        gather_status(&current, Some("def456")).await.config,
+        Some(ConfigState::Stale)
+    );
+    assert_eq!(
+        gather_status(&running(), Some("abc123")).await.config,
+        Some(ConfigState::Missing)
+    );
+    assert_eq!(gather_status(&current, None).await.config, None);
+}
@@ -1,6 +1,6 @@
 // lib/crates/polis-common/src/agent.rs
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::{Deserialize, Serialize};
 
@@ -33,6 +33,9 @@ pub struct AgentMetadata {
     /// User-facing capability tags (e.g. `["code-generation"]`).
     #[serde(default)]
     pub capabilities: Vec<String>,
+    /// Free-form organising labels (e.g. `team: payments`).
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
 }
 
 impl AgentMetadata {
@@ -163,6 +166,10 @@ pub struct AgentRequirements {
     pub env_one_of: Vec<String>,
     #[serde(rename = "envOptional", default)]
     pub env_optional: Vec<String>,
+    /// Control-plane services (e.g. `scanner`) that must be running for
+    /// the agent to work.
+    #[serde(default)]
+    pub services: Vec<String>,
 }
 
 /// Named persistent volume.
@@ -207,6 +214,9 @@ metadata:
     - code-generation
     - code-review
     - documentation
+  labels:
+    team: payments
+    env: prod
 spec:
   packaging: script
   install: install.sh
@@ -276,6 +286,11 @@ spec:
             manifest.metadata.capabilities,
             vec!["code-generation", "code-review", "documentation"]
         );
+        assert_eq!(
+            manifest.metadata.labels.get("team").map(String::as_str),
+            Some("payments")
+        );
+        assert_eq!(manifest.metadata.labels.len(), 2);
     }
 
     #[test]
@@ -314,6 +329,12 @@ spec:
         assert!(manifest.metadata.capabilities.is_empty());
     }
 
+    #[test]
+    fn test_agent_metadata_labels_absent_defaults_to_empty_map() {
+        let manifest: AgentManifest = serde_yaml::from_str(TEMPLATE_YAML).expect("should parse");
+        assert!(manifest.metadata.labels.is_empty());
+    }
+
     #[test]
     fn test_agent_spec_onboarding_absent_defaults_to_empty_vec() {
         let manifest: AgentManifest = serde_yaml::from_str(TEMPLATE_YAML).expect("should parse");
@@ -555,6 +576,7 @@ spec:
             let reqs = AgentRequirements {
                 env_one_of: vec![env_key],
                 env_optional: vec![],
+                services: vec![],
             };
             let meta = AgentMetadata {
                 name: "test".to_string(),
@@ -565,6 +587,7 @@ spec:
                 license: None,
                 provider,
                 capabilities: vec![],
+                labels: BTreeMap::new(),
             };
             // Must not panic — result is either a string or "Unknown"
             let result = meta.effective_provider(Some(&reqs));
@@ -577,7 +600,11 @@ spec:
             provider in "[\\PC]{1,50}",
             env_keys in proptest::collection::vec("[A-Z_]{1,30}", 0usize..5),
         ) {
-            let reqs = AgentRequirements { env_one_of: env_keys, env_optional: vec![] };
+            let reqs = AgentRequirements {
+                env_one_of: env_keys,
+                env_optional: vec![],
+                services: vec![],
+            };
             let meta = AgentMetadata {
                 name: "t".to_string(),
                 display_name: "T".to_string(),
@@ -587,6 +614,7 @@ spec:
                 license: None,
                 provider: Some(provider.clone()),
                 capabilities: vec![],
+                labels: BTreeMap::new(),
             };
             prop_assert_eq!(meta.effective_provider(Some(&reqs)), provider);
         }
@@ -606,6 +634,7 @@ spec:
                 license: None,
                 provider: provider.clone(),
                 capabilities: capabilities.clone(),
+                labels: BTreeMap::new(),
             };
             let json = serde_json::to_string(&meta).expect("serialize");
             let back: AgentMetadata = serde_json::from_str(&json).expect("deserialize");
@@ -5,7 +5,7 @@ pub mod types;
 
 pub use config::{AdminServerConfig, AgentServerConfig};
 pub use redis_keys::{
-    approval, approved_key, auto_approve_key, blocked_key, keys, ott_key, ttl, validate_ott_code,
-    validate_request_id,
+    approval, approved_key, auto_approve_key, blocked_key, generate_ott_code, keys, ott_key, ttl,
+    validate_ott_code, validate_request_id,
 };
 pub use types::*;
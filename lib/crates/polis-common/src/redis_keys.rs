@@ -48,6 +48,34 @@ pub mod ttl {
 
     /// Event log retention (24 hours)
     pub const EVENT_LOG_SECS: u64 = 86400;
+
+    /// Format a TTL in seconds for display, using the two most significant
+    /// units: `"30d"`, `"1d 2h"`, `"2h 15m"`, `"5m"`, `"45s"`.
+    /// Lower units are truncated, and a zero lower unit is omitted.
+    #[must_use]
+    pub fn describe(secs: u64) -> String {
+        const MINUTE: u64 = 60;
+        const HOUR: u64 = 60 * MINUTE;
+        const DAY: u64 = 24 * HOUR;
+
+        let pair = |major: u64, major_unit: &str, minor: u64, minor_unit: &str| {
+            if minor == 0 {
+                format!("{major}{major_unit}")
+            } else {
+                format!("{major}{major_unit} {minor}{minor_unit}")
+            }
+        };
+
+        if secs >= DAY {
+            pair(secs / DAY, "d", (secs % DAY) / HOUR, "h")
+        } else if secs >= HOUR {
+            pair(secs / HOUR, "h", (secs % HOUR) / MINUTE, "m")
+        } else if secs >= MINUTE {
+            format!("{}m", secs / MINUTE)
+        } else {
+            format!("{secs}s")
+        }
+    }
 }
 
 /// Approval command constants and OTT (One-Time Token) configuration
@@ -70,6 +98,11 @@ pub mod approval {
     pub const OTT_PREFIX: &str = "ott-";
     pub const OTT_RANDOM_LEN: usize = 8;
 
+    /// Alphabet for the random part of an OTT code ([a-zA-Z0-9], 62 chars).
+    /// Must stay in sync with OTT_CHARSET in the sentinel approval module.
+    pub const OTT_CHARSET: &[u8] =
+        b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
     /// Default approval domain allowlist (dot-prefixed for suffix-safe matching).
     /// RESPMOD only scans responses from these domains for approval codes.
     /// Dot-prefix prevents spoofing: ".slack.com" won't match "evil-slack.com".
@@ -127,6 +160,12 @@ pub fn validate_request_id(request_id: &str) -> Result<(), &'static str> {
 }
 
 /// Validate that an OTT code matches the expected format: ott-[a-zA-Z0-9]{8}
+///
+/// The code is exactly 12 ASCII characters: the `approval::OTT_PREFIX`
+/// ("ott-") followed by `approval::OTT_RANDOM_LEN` (8) characters from
+/// `approval::OTT_CHARSET`. The length deliberately equals a request_id so
+/// REQMOD can substitute one for the other without changing body length.
+/// Codes from [`generate_ott_code`] always pass.
 /// Returns Ok(()) if valid, Err with description if invalid.
 pub fn validate_ott_code(ott_code: &str) -> Result<(), &'static str> {
     if ott_code.len() != 12 {
@@ -141,6 +180,30 @@ pub fn validate_ott_code(ott_code: &str) -> Result<(), &'static str> {
     Ok(())
 }
 
+/// Generate a new OTT code in the format accepted by [`validate_ott_code`].
+///
+/// Draws from the OS CSPRNG and uses rejection sampling (bytes >= 248 are
+/// discarded) to avoid modulo bias, matching the sentinel's C generator
+/// (CWE-330). Fails closed if the random source is unavailable.
+pub fn generate_ott_code() -> Result<String, &'static str> {
+    // Largest multiple of the charset length that fits in a byte (62 * 4).
+    const LIMIT: u8 = 248;
+
+    let len = approval::OTT_PREFIX.len() + approval::OTT_RANDOM_LEN;
+    let mut code = String::with_capacity(len);
+    code.push_str(approval::OTT_PREFIX);
+
+    let mut byte = [0u8; 1];
+    while code.len() < len {
+        getrandom::fill(&mut byte).map_err(|_| "OS random source unavailable")?;
+        if byte[0] < LIMIT {
+            let idx = usize::from(byte[0]) % approval::OTT_CHARSET.len();
+            code.push(char::from(approval::OTT_CHARSET[idx]));
+        }
+    }
+    Ok(code)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,6 +233,35 @@ mod tests {
         assert_eq!(ott_key("ott-x7k9m2p4"), "polis:ott:ott-x7k9m2p4");
     }
 
+    // --- ttl::describe tests ---
+
+    #[test]
+    fn ttl_describe_zero_and_seconds() {
+        assert_eq!(ttl::describe(0), "0s");
+        assert_eq!(ttl::describe(59), "59s");
+    }
+
+    #[test]
+    fn ttl_describe_under_an_hour() {
+        assert_eq!(ttl::describe(60), "1m");
+        assert_eq!(ttl::describe(ttl::APPROVED_REQUEST_SECS), "5m");
+        assert_eq!(ttl::describe(3599), "59m");
+    }
+
+    #[test]
+    fn ttl_describe_hours_and_minutes() {
+        assert_eq!(ttl::describe(ttl::BLOCKED_REQUEST_SECS), "1h");
+        assert_eq!(ttl::describe(2 * 3600 + 15 * 60), "2h 15m");
+        assert_eq!(ttl::describe(86399), "23h 59m");
+    }
+
+    #[test]
+    fn ttl_describe_exact_and_partial_days() {
+        assert_eq!(ttl::describe(ttl::EVENT_LOG_SECS), "1d");
+        assert_eq!(ttl::describe(30 * 86400), "30d");
+        assert_eq!(ttl::describe(86400 + 2 * 3600 + 59), "1d 2h");
+    }
+
     // --- Approval command test (Requirements 4.2–4.4) ---
 
     #[test]
@@ -258,4 +350,28 @@ mod tests {
     fn validate_ott_code_rejects_too_short() {
         assert!(validate_ott_code("ott-abc").is_err());
     }
+
+    // --- generate_ott_code tests ---
+
+    #[test]
+    fn generated_ott_codes_always_validate() {
+        for _ in 0..1000 {
+            let code = generate_ott_code().unwrap_or_else(|e| panic!("generation failed: {e}"));
+            assert_eq!(validate_ott_code(&code), Ok(()), "invalid code {code:?}");
+        }
+    }
+
+    #[test]
+    fn generated_ott_codes_are_distinct() {
+        let a = generate_ott_code();
+        let b = generate_ott_code();
+        assert!(a.is_ok());
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn ott_charset_matches_validator_alphabet() {
+        assert_eq!(approval::OTT_CHARSET.len(), 62);
+        assert!(approval::OTT_CHARSET.iter().all(u8::is_ascii_alphanumeric));
+    }
 }
@@ -222,7 +222,37 @@ pub struct StatusOutput {
     pub agent: Option<AgentStatus>,
     pub security: SecurityStatus,
     pub events: SecurityEvents,
+    /// Update availability, only reported by `polis status --check-updates`.
+    #[serde(flatten, default)]
+    pub updates: Option<UpdateStatus>,
+    /// Deployed config versus this CLI's, only reported for a running
+    /// workspace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<ConfigState>,
 }
+
+/// Whether the config hash recorded in the VM matches the config this CLI
+/// would deploy. `polis start` re-applies a stale or missing config.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigState {
+    Current,
+    Stale,
+    Missing,
+}
+
+/// Whether a newer CLI or newer control-plane containers are available.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateStatus {
+    pub updates_available: bool,
+    /// Newer CLI release (without leading `v`), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_version: Option<String>,
+    /// Deployed services whose tag lags behind this CLI.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outdated_services: Vec<String>,
+}
+
 /// Workspace state enum.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -549,6 +579,8 @@ mod tests {
                 count: 0,
                 severity: EventSeverity::None,
             },
+            updates: None,
+            config: None,
         };
         let json = serde_json::to_string(&status).expect("serialize StatusOutput");
         let deserialized: StatusOutput =
@@ -560,6 +592,43 @@ mod tests {
             Some("claude-dev")
         );
         assert!(deserialized.security.traffic_inspection);
+        assert!(deserialized.updates.is_none());
+        assert!(!json.contains("updates_available"));
+        assert!(!json.contains("\"config\""));
+    }
+
+    #[test]
+    fn test_status_output_update_fields_are_top_level() {
+        let status = StatusOutput {
+            workspace: WorkspaceStatus {
+                status: WorkspaceState::Stopped,
+                uptime_seconds: None,
+            },
+            agent: None,
+            security: SecurityStatus {
+                traffic_inspection: false,
+                credential_protection: false,
+                malware_scanning: false,
+            },
+            events: SecurityEvents {
+                count: 0,
+                severity: EventSeverity::None,
+            },
+            updates: Some(UpdateStatus {
+                updates_available: true,
+                latest_version: Some("0.5.0".to_string()),
+                outdated_services: vec![],
+            }),
+            config: Some(ConfigState::Stale),
+        };
+        let value = serde_json::to_value(&status).expect("serialize StatusOutput");
+        assert_eq!(value["updates_available"], true);
+        assert_eq!(value["latest_version"], "0.5.0");
+        assert!(value.get("outdated_services").is_none());
+        assert_eq!(value["config"], "stale");
+        let back: StatusOutput = serde_json::from_value(value).expect("deserialize StatusOutput");
+        assert_eq!(back.updates, status.updates);
+        assert_eq!(back.config, status.config);
     }
 }
 
@@ -0,0 +1,180 @@
+//! In-memory Valkey fake for handler tests.
+//!
+//! Implements [`redis::aio::ConnectionLike`] over a small key/value store so
+//! handlers generic over the connection can be exercised without a live
+//! server. Only the commands used by this CLI are supported; anything else
+//! returns an error so an unexpected call fails the test loudly.
+
+use std::collections::BTreeMap;
+
+use redis::aio::ConnectionLike;
+use redis::{Arg, Cmd, ErrorKind, Pipeline, RedisError, RedisFuture, RedisResult, Value};
+
+/// Seedable fake that records every write command it receives.
+pub struct FakeValkey {
+    /// String keys: key → (value, remaining TTL in seconds or `None` for no expiry).
+    pub strings: BTreeMap<String, (String, Option<i64>)>,
+    /// Sorted sets: key → (score, member) in insertion order.
+    pub zsets: BTreeMap<String, Vec<(f64, String)>>,
+    /// Number of keys returned per SCAN page (forces multi-page cursors).
+    pub page_size: usize,
+    /// Names of all mutating commands executed (`SET`, `DEL`, `ZADD`, ...).
+    pub writes: Vec<String>,
+}
+
+impl FakeValkey {
+    pub fn new() -> Self {
+        Self {
+            strings: BTreeMap::new(),
+            zsets: BTreeMap::new(),
+            page_size: 2,
+            writes: Vec::new(),
+        }
+    }
+
+    /// Seed a string key without recording a write.
+    pub fn seed(&mut self, key: &str, value: &str) -> &mut Self {
+        self.strings
+            .insert(key.to_string(), (value.to_string(), None));
+        self
+    }
+
+    /// Seed a string key with a TTL without recording a write.
+    pub fn seed_with_ttl(&mut self, key: &str, value: &str, ttl: i64) -> &mut Self {
+        self.strings
+            .insert(key.to_string(), (value.to_string(), Some(ttl)));
+        self
+    }
+
+    fn execute(&mut self, cmd: &Cmd) -> RedisResult<Value> {
+        let args: Vec<String> = cmd
+            .args_iter()
+            .map(|arg| match arg {
+                Arg::Simple(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+                _ => String::new(),
+            })
+            .collect();
+        let name = args.first().map(|s| s.to_uppercase()).unwrap_or_default();
+        match (name.as_str(), &args[1..]) {
+            ("GET", [key]) => Ok(self.strings.get(key).map_or(Value::Nil, |(v, _)| {
+                Value::BulkString(v.clone().into_bytes())
+            })),
+            ("TTL", [key]) => Ok(Value::Int(match self.strings.get(key) {
+                None => -2,
+                Some((_, None)) => -1,
+                Some((_, Some(ttl))) => *ttl,
+            })),
+            ("SET", [key, value]) => {
+                self.writes.push(name);
+                self.strings.insert(key.clone(), (value.clone(), None));
+                Ok(Value::Okay)
+            }
+            ("SETEX", [key, ttl, value]) => {
+                self.writes.push(name);
+                let ttl = ttl.parse().map_err(|_| bad_arg("SETEX ttl"))?;
+                self.strings.insert(key.clone(), (value.clone(), Some(ttl)));
+                Ok(Value::Okay)
+            }
+            ("DEL", keys) => {
+                self.writes.push(name);
+                let removed = keys
+                    .iter()
+                    .filter(|k| {
+                        self.strings.remove(*k).is_some() || self.zsets.remove(*k).is_some()
+                    })
+                    .count();
+                Ok(Value::Int(i64::try_from(removed).unwrap_or(i64::MAX)))
+            }
+            ("ZADD", [key, score, member]) => {
+                self.writes.push(name);
+                let score = score.parse().map_err(|_| bad_arg("ZADD score"))?;
+                self.zsets
+                    .entry(key.clone())
+                    .or_default()
+                    .push((score, member.clone()));
+                Ok(Value::Int(1))
+            }
+            ("SCAN", [cursor, rest @ ..]) => self.scan(cursor, rest),
+            _ => Err(RedisError::from((
+                ErrorKind::Client,
+                "FakeValkey: unsupported command",
+                args.join(" "),
+            ))),
+        }
+    }
+
+    fn scan(&self, cursor: &str, rest: &[String]) -> RedisResult<Value> {
+        let pattern = match rest {
+            [m, p, ..] if m.eq_ignore_ascii_case("MATCH") => p.as_str(),
+            _ => "*",
+        };
+        let start: usize = cursor.parse().map_err(|_| bad_arg("SCAN cursor"))?;
+        let keys: Vec<&String> = self
+            .strings
+            .keys()
+            .chain(self.zsets.keys())
+            .filter(|k| glob_match(pattern, k))
+            .collect();
+        let end = (start + self.page_size).min(keys.len());
+        let next = if end >= keys.len() { 0 } else { end };
+        let page = keys[start.min(end)..end]
+            .iter()
+            .map(|k| Value::BulkString(k.as_bytes().to_vec()))
+            .collect();
+        Ok(Value::Array(vec![
+            Value::BulkString(next.to_string().into_bytes()),
+            Value::Array(page),
+        ]))
+    }
+}
+
+impl ConnectionLike for FakeValkey {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        let result = self.execute(cmd);
+        Box::pin(std::future::ready(result))
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        pipeline: &'a Pipeline,
+        _offset: usize,
+        _count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        let results: RedisResult<Vec<Value>> =
+            pipeline.cmd_iter().map(|cmd| self.execute(cmd)).collect();
+        let result = results.map(|values| {
+            if pipeline.is_transaction() {
+                vec![Value::Array(values)]
+            } else {
+                values
+            }
+        });
+        Box::pin(std::future::ready(result))
+    }
+
+    fn get_db(&self) -> i64 {
+        0
+    }
+}
+
+fn bad_arg(what: &'static str) -> RedisError {
+    RedisError::from((ErrorKind::Client, what))
+}
+
+/// Minimal glob matcher supporting `*` wildcards (sufficient for SCAN MATCH).
+fn glob_match(pattern: &str, key: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == key,
+        Some((prefix, rest)) => {
+            let Some(tail) = key.strip_prefix(prefix) else {
+                return false;
+            };
+            if rest.is_empty() {
+                return true;
+            }
+            (0..=tail.len())
+                .filter(|&i| tail.is_char_boundary(i))
+                .any(|i| glob_match(rest, &tail[i..]))
+        }
+    }
+}
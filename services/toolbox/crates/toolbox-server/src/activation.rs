@@ -0,0 +1,91 @@
+//! systemd socket activation.
+//!
+//! When the server is started by a `.socket` unit, systemd has already bound
+//! the listening socket and passes it as file descriptor 3, announcing it
+//! through `LISTEN_FDS` and `LISTEN_PID`. Binding `listen_addr` again would
+//! conflict with it, so the inherited socket is used instead.
+
+use anyhow::Result;
+
+/// First file descriptor systemd passes (`SD_LISTEN_FDS_START`).
+#[cfg(unix)]
+const LISTEN_FDS_START: std::os::fd::RawFd = 3;
+
+/// Number of sockets systemd passed to process `pid`, or `None` when the
+/// variables are absent, malformed, or addressed to another process (such
+/// as a parent whose environment we inherited).
+#[cfg_attr(not(unix), allow(dead_code))]
+fn passed_fd_count(listen_pid: Option<&str>, listen_fds: Option<&str>, pid: u32) -> Option<u32> {
+    let listen_pid: u32 = listen_pid?.trim().parse().ok()?;
+    if listen_pid != pid {
+        return None;
+    }
+    listen_fds?.trim().parse().ok().filter(|&n| n > 0)
+}
+
+/// Take the listening socket systemd passed to this process, if any.
+///
+/// Only the first socket is used; a unit should declare a single
+/// `ListenStream=`.
+#[cfg(unix)]
+pub fn inherited_listener() -> Result<Option<std::net::TcpListener>> {
+    use anyhow::Context;
+    use std::os::fd::FromRawFd;
+
+    let count = passed_fd_count(
+        std::env::var("LISTEN_PID").ok().as_deref(),
+        std::env::var("LISTEN_FDS").ok().as_deref(),
+        std::process::id(),
+    );
+    let Some(count) = count else {
+        return Ok(None);
+    };
+    if count > 1 {
+        tracing::warn!(count, "systemd passed several sockets; using the first");
+    }
+
+    // SAFETY: LISTEN_PID names this process, so systemd guarantees that
+    // descriptors from LISTEN_FDS_START on are open and owned by us, and
+    // nothing else in the server takes ownership of them.
+    #[allow(unsafe_code)]
+    let listener = unsafe { std::net::TcpListener::from_raw_fd(LISTEN_FDS_START) };
+    let addr = listener
+        .local_addr()
+        .context("inherited file descriptor 3 is not a TCP socket")?;
+    listener
+        .set_nonblocking(true)
+        .context("failed to make inherited socket non-blocking")?;
+    tracing::info!(%addr, "using socket passed by systemd");
+    Ok(Some(listener))
+}
+
+/// Socket activation is a systemd feature; other platforms always bind.
+#[cfg(not(unix))]
+pub fn inherited_listener() -> Result<Option<std::net::TcpListener>> {
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sockets_for_this_process_are_counted() {
+        assert_eq!(passed_fd_count(Some("42"), Some("1"), 42), Some(1));
+        assert_eq!(passed_fd_count(Some("42"), Some("2\n"), 42), Some(2));
+    }
+
+    #[test]
+    fn sockets_for_another_process_are_ignored() {
+        assert_eq!(passed_fd_count(Some("41"), Some("1"), 42), None);
+    }
+
+    #[test]
+    fn missing_or_malformed_variables_are_ignored() {
+        assert_eq!(passed_fd_count(None, Some("1"), 42), None);
+        assert_eq!(passed_fd_count(Some("42"), None, 42), None);
+        assert_eq!(passed_fd_count(Some("42"), Some("0"), 42), None);
+        assert_eq!(passed_fd_count(Some("42"), Some("two"), 42), None);
+        assert_eq!(passed_fd_count(Some("pid"), Some("1"), 42), None);
+    }
+}
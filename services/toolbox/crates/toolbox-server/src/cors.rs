@@ -0,0 +1,95 @@
+//! Optional CORS layer for the `/mcp` route.
+//!
+//! Disabled unless `polis_AGENT_CORS_ORIGINS` is set, so the default
+//! behaviour (no CORS headers, browsers refuse cross-origin calls) is
+//! unchanged.
+
+use anyhow::{bail, Context, Result};
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Session header used by the MCP Streamable-HTTP transport.
+const MCP_SESSION_ID: HeaderName = HeaderName::from_static("mcp-session-id");
+/// Protocol version header sent by MCP clients.
+const MCP_PROTOCOL_VERSION: HeaderName = HeaderName::from_static("mcp-protocol-version");
+/// SSE resumption header used when a client reconnects to a stream.
+const LAST_EVENT_ID: HeaderName = HeaderName::from_static("last-event-id");
+
+/// Build a CORS layer from a comma-separated origin allowlist.
+///
+/// `*` allows any origin but cannot be combined with credentials: browsers
+/// ignore such responses, and `tower-http` would panic at startup.
+pub fn cors_layer(origins: &str, allow_credentials: bool) -> Result<CorsLayer> {
+    let entries: Vec<&str> = origins
+        .split(',')
+        .map(str::trim)
+        .filter(|o| !o.is_empty())
+        .collect();
+    if entries.is_empty() {
+        bail!("polis_AGENT_CORS_ORIGINS is set but lists no origins");
+    }
+
+    let allow_origin = if entries.contains(&"*") {
+        if entries.len() > 1 {
+            bail!("polis_AGENT_CORS_ORIGINS: '*' cannot be combined with other origins");
+        }
+        if allow_credentials {
+            bail!(
+                "polis_AGENT_CORS_ORIGINS='*' cannot be used with \
+                 polis_AGENT_CORS_ALLOW_CREDENTIALS=true; list the origins explicitly"
+            );
+        }
+        AllowOrigin::any()
+    } else {
+        let values = entries
+            .iter()
+            .map(|o| HeaderValue::from_str(o).with_context(|| format!("invalid CORS origin '{o}'")))
+            .collect::<Result<Vec<_>>>()?;
+        AllowOrigin::list(values)
+    };
+
+    Ok(CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([Method::GET, Method::POST, Method::DELETE])
+        .allow_headers([
+            header::ACCEPT,
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            LAST_EVENT_ID,
+            MCP_SESSION_ID,
+            MCP_PROTOCOL_VERSION,
+        ])
+        .expose_headers([MCP_SESSION_ID])
+        .allow_credentials(allow_credentials))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_with_credentials_is_rejected() {
+        let err = cors_layer("*", true).unwrap_err();
+        assert!(err.to_string().contains("CORS_ALLOW_CREDENTIALS"));
+    }
+
+    #[test]
+    fn wildcard_without_credentials_is_accepted() {
+        assert!(cors_layer("*", false).is_ok());
+    }
+
+    #[test]
+    fn wildcard_mixed_with_origins_is_rejected() {
+        assert!(cors_layer("https://ui.example, *", false).is_err());
+    }
+
+    #[test]
+    fn empty_allowlist_is_rejected() {
+        assert!(cors_layer(" , ", false).is_err());
+    }
+
+    #[test]
+    fn explicit_origins_with_credentials_are_accepted() {
+        assert!(cors_layer("https://ui.example, http://localhost:5173", true).is_ok());
+    }
+}
@@ -0,0 +1,146 @@
+//! Valkey health checks: the `/health/ready` probe and an optional
+//! background connectivity watcher for local development.
+//!
+//! The watcher is enabled by `polis_AGENT_HEALTH_WATCH_SECS`. Every interval
+//! it PINGs Valkey and logs only when connectivity changes, so a restarted
+//! Valkey shows up as one `lost` line followed by one `restored` line.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::state::AppState;
+
+/// Longest a readiness PING may take before the server reports not ready.
+pub const READY_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Something that can PING Valkey; lets the readiness probe be tested
+/// without a live connection.
+pub trait Pinger: Send + Sync + 'static {
+    fn ping(&self) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+impl Pinger for AppState {
+    fn ping(&self) -> impl Future<Output = anyhow::Result<()>> + Send {
+        AppState::ping(self)
+    }
+}
+
+/// Whether Valkey answers a PING within [`READY_TIMEOUT`].
+pub async fn is_ready<P: Pinger>(pinger: &P) -> bool {
+    match tokio::time::timeout(READY_TIMEOUT, pinger.ping()).await {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            tracing::debug!(error = %e, "readiness PING failed");
+            false
+        }
+        Err(_) => {
+            tracing::debug!("readiness PING timed out");
+            false
+        }
+    }
+}
+
+/// Connectivity change worth logging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    /// A PING failed after the previous one succeeded.
+    Lost,
+    /// A PING succeeded after one or more failures.
+    Restored,
+}
+
+/// Tracks whether the last PING succeeded. Starts connected, because
+/// `AppState::new` only returns after a successful startup PING.
+#[derive(Debug)]
+pub struct HealthTracker {
+    connected: bool,
+}
+
+impl Default for HealthTracker {
+    fn default() -> Self {
+        Self { connected: true }
+    }
+}
+
+impl HealthTracker {
+    /// Record a PING result and return the transition it caused, if any.
+    pub fn observe(&mut self, ping_ok: bool) -> Option<Transition> {
+        let transition = match (self.connected, ping_ok) {
+            (true, false) => Some(Transition::Lost),
+            (false, true) => Some(Transition::Restored),
+            _ => None,
+        };
+        self.connected = ping_ok;
+        transition
+    }
+}
+
+/// Spawn the watcher task. It runs until the runtime shuts down.
+pub fn spawn_watch(state: Arc<AppState>, interval: Duration) {
+    tracing::info!(
+        interval_secs = interval.as_secs(),
+        "Valkey health watch enabled"
+    );
+    tokio::spawn(async move {
+        let mut tracker = HealthTracker::default();
+        let mut ticker = tokio::time::interval(interval);
+        // The first tick completes immediately; startup already pinged.
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let result = state.ping().await;
+            match (tracker.observe(result.is_ok()), result) {
+                (Some(Transition::Lost), Err(e)) => {
+                    tracing::warn!(error = %e, "Valkey connectivity lost");
+                }
+                (Some(Transition::Restored), _) => {
+                    tracing::info!("Valkey connectivity restored");
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transitions(pings: &[bool]) -> Vec<Option<Transition>> {
+        let mut tracker = HealthTracker::default();
+        pings.iter().map(|&ok| tracker.observe(ok)).collect()
+    }
+
+    #[test]
+    fn steady_connection_logs_nothing() {
+        assert_eq!(transitions(&[true, true, true]), [None, None, None]);
+    }
+
+    #[test]
+    fn outage_logs_lost_once_then_restored_once() {
+        assert_eq!(
+            transitions(&[true, false, false, true, true]),
+            [
+                None,
+                Some(Transition::Lost),
+                None,
+                Some(Transition::Restored),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn repeated_outages_are_each_reported() {
+        assert_eq!(
+            transitions(&[false, true, false, true]),
+            [
+                Some(Transition::Lost),
+                Some(Transition::Restored),
+                Some(Transition::Lost),
+                Some(Transition::Restored),
+            ]
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Tracing initialisation with a selectable output format.
+//!
+//! `POLIS_LOG_FORMAT=json` emits one JSON object per event for log
+//! aggregation; any other value (or unset) keeps the human formatter.
+//! Verbosity is controlled by `RUST_LOG` in both modes (default `info`).
+
+use tracing_subscriber::EnvFilter;
+
+/// Environment variable selecting the log format.
+pub const LOG_FORMAT_ENV: &str = "POLIS_LOG_FORMAT";
+
+/// Output format for tracing events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable single-line output (the default).
+    Text,
+    /// Newline-delimited JSON.
+    Json,
+}
+
+impl LogFormat {
+    /// Select the format from the raw `POLIS_LOG_FORMAT` value.
+    pub fn from_env_value(value: Option<&str>) -> Self {
+        match value.map(str::trim) {
+            Some(v) if v.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// Install the global tracing subscriber in the format chosen by
+/// `POLIS_LOG_FORMAT`.
+pub fn init() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    match LogFormat::from_env_value(std::env::var(LOG_FORMAT_ENV).ok().as_deref()) {
+        LogFormat::Json => builder.json().init(),
+        LogFormat::Text => builder.init(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_selects_text() {
+        assert_eq!(LogFormat::from_env_value(None), LogFormat::Text);
+    }
+
+    #[test]
+    fn json_selects_json_case_insensitively() {
+        assert_eq!(LogFormat::from_env_value(Some("json")), LogFormat::Json);
+        assert_eq!(LogFormat::from_env_value(Some(" JSON ")), LogFormat::Json);
+    }
+
+    #[test]
+    fn other_values_select_text() {
+        assert_eq!(LogFormat::from_env_value(Some("")), LogFormat::Text);
+        assert_eq!(LogFormat::from_env_value(Some("pretty")), LogFormat::Text);
+    }
+}
@@ -4,16 +4,24 @@
 //! (prefixed with `polis_AGENT_`), connects to Valkey with ACL auth,
 //! and starts a Streamable-HTTP MCP server exposing 5 read-only tools.
 
+mod activation;
+mod cors;
+mod health;
+mod logging;
 mod state;
 mod tools;
 
+use std::convert::Infallible;
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
+use axum::extract::{Request, State};
 use axum::http::StatusCode;
+use axum::response::IntoResponse;
 use axum_server::tls_rustls::RustlsConfig;
 use serde::Deserialize;
-use tracing_subscriber::EnvFilter;
+use tower_http::cors::CorsLayer;
+use tower_http::limit::RequestBodyLimitLayer;
 
 use rmcp::transport::streamable_http_server::{
     session::local::LocalSessionManager, StreamableHttpService,
@@ -35,6 +43,12 @@ use crate::tools::PolisAgentTools;
 ///   - `polis_AGENT_VALKEY_PASS_FILE` (required, path to Docker secret)
 ///   - `polis_AGENT_TLS_CERT`        (optional, path to TLS cert)
 ///   - `polis_AGENT_TLS_KEY`         (optional, path to TLS key)
+///   - `polis_AGENT_HEALTH_WATCH_SECS` (optional, Valkey PING interval)
+///   - `polis_AGENT_CORS_ORIGINS`    (optional, comma-separated allowlist)
+///   - `polis_AGENT_CORS_ALLOW_CREDENTIALS` (optional, default `false`)
+///   - `polis_AGENT_MAX_BODY_BYTES`  (default 1 MiB)
+///   - `polis_AGENT_ENABLED_TOOLS`   (optional, comma-separated allowlist)
+///   - `polis_AGENT_SHUTDOWN_TIMEOUT_SECS` (default 10, HTTPS drain timeout)
 #[derive(Debug, Deserialize)]
 struct Config {
     /// Socket address to bind the HTTP server to.
@@ -56,6 +70,30 @@ struct Config {
 
     /// Path to TLS private key.
     tls_key: Option<String>,
+
+    /// Interval in seconds for the background Valkey PING watcher
+    /// (disabled when unset or 0).
+    health_watch_secs: Option<u64>,
+
+    /// Comma-separated origins allowed to call `/mcp` from a browser.
+    /// CORS is disabled when unset.
+    cors_origins: Option<String>,
+
+    /// Whether CORS responses allow credentials (cookies, auth headers).
+    #[serde(default)]
+    cors_allow_credentials: bool,
+
+    /// Largest request body accepted on `/mcp`; larger requests get 413.
+    #[serde(default = "default_max_body_bytes")]
+    max_body_bytes: usize,
+
+    /// Comma-separated subset of MCP tools to register (all when unset).
+    enabled_tools: Option<String>,
+
+    /// Seconds in-flight HTTPS connections get to finish after a shutdown
+    /// signal before they are closed.
+    #[serde(default = "default_shutdown_timeout_secs")]
+    shutdown_timeout_secs: u64,
 }
 
 fn default_listen_addr() -> String {
@@ -66,27 +104,95 @@ fn default_valkey_url() -> String {
     "redis://state:6379".to_string()
 }
 
+/// MCP requests are small JSON-RPC messages; 1 MiB leaves ample headroom.
+const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+fn default_max_body_bytes() -> usize {
+    DEFAULT_MAX_BODY_BYTES
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    10
+}
+
+/// Read the Valkey ACL password from a Docker secret file.
+///
+/// An empty (or whitespace-only) file is rejected here, because Valkey would
+/// otherwise fail ACL auth with an error that does not point at the file.
+fn read_password(path: &str) -> Result<String> {
+    let password = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read password from {path}"))?
+        .trim()
+        .to_string();
+    anyhow::ensure!(!password.is_empty(), "password file {path} is empty");
+    Ok(password)
+}
+
 // ===================================================================
-// Health endpoint
+// Health endpoints
 // ===================================================================
 
-/// Minimal health-check handler for Docker / load-balancer probes.
-async fn health() -> StatusCode {
+/// Liveness probe: 200 for as long as the process can serve requests.
+async fn health_live() -> StatusCode {
     StatusCode::OK
 }
 
+/// Readiness probe: 200 only while Valkey answers a PING in time, so a load
+/// balancer stops routing traffic when the backing store is unreachable.
+async fn health_ready<P: health::Pinger>(State(pinger): State<Arc<P>>) -> StatusCode {
+    if health::is_ready(pinger.as_ref()).await {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+// ===================================================================
+// Router
+// ===================================================================
+
+/// Compose the axum router:
+///   - `/mcp`          → MCP Streamable-HTTP transport, body-size capped, CORS if set
+///   - `/health/live`  → liveness probe (`/health` is a legacy alias)
+///   - `/health/ready` → readiness probe, PINGs Valkey via `pinger`
+///
+/// Neither the body limit nor CORS applies to the health probes.
+fn build_router<S, P>(
+    mcp: S,
+    pinger: Arc<P>,
+    cors: Option<CorsLayer>,
+    max_body_bytes: usize,
+) -> axum::Router
+where
+    S: tower::Service<Request, Error = Infallible> + Clone + Send + Sync + 'static,
+    S::Response: IntoResponse,
+    S::Future: Send + 'static,
+    P: health::Pinger,
+{
+    let mut router = axum::Router::new()
+        .nest_service("/mcp", mcp)
+        .layer(RequestBodyLimitLayer::new(max_body_bytes));
+    // CORS goes outermost so 413 responses still carry CORS headers.
+    if let Some(cors) = cors {
+        router = router.layer(cors);
+    }
+    let probes = axum::Router::new()
+        .route("/health", axum::routing::get(health_live))
+        .route("/health/live", axum::routing::get(health_live))
+        .route("/health/ready", axum::routing::get(health_ready::<P>))
+        .with_state(pinger);
+    router.merge(probes)
+}
+
 // ===================================================================
 // Entry point
 // ===================================================================
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // 1. Initialise tracing with RUST_LOG env filter.
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .init();
+    // 1. Initialise tracing with RUST_LOG env filter, in the format
+    //    selected by POLIS_LOG_FORMAT (text or json).
+    logging::init();
 
     tracing::info!("polis-hitl-agent starting");
 
@@ -98,16 +204,23 @@ async fn main() -> Result<()> {
     )?;
 
     // 3. Read password from Docker secret file
-    let valkey_pass = std::fs::read_to_string(&config.valkey_pass_file)
-        .with_context(|| format!("failed to read password from {}", config.valkey_pass_file))?
-        .trim()
-        .to_string();
+    let valkey_pass = read_password(&config.valkey_pass_file)?;
+
+    // Validate the tool allowlist before connecting, so a typo fails fast.
+    let enabled_tools = config
+        .enabled_tools
+        .as_deref()
+        .map(tools::parse_enabled_tools)
+        .transpose()
+        .map_err(anyhow::Error::msg)?;
 
     tracing::info!(
         listen_addr = %config.listen_addr,
         valkey_url  = %config.valkey_url,
         valkey_user = %config.valkey_user,
         tls_enabled = config.tls_cert.is_some(),
+        max_body_bytes = config.max_body_bytes,
+        enabled_tools = ?enabled_tools,
         "configuration loaded",
     );
 
@@ -119,22 +232,36 @@ async fn main() -> Result<()> {
 
     let state = Arc::new(app_state);
 
+    if let Some(secs) = config.health_watch_secs.filter(|&s| s > 0) {
+        health::spawn_watch(state.clone(), std::time::Duration::from_secs(secs));
+    }
+
     // 4. Build the Streamable-HTTP MCP service.
     //    The factory closure creates a fresh PolisAgentTools per
-    //    session, each sharing the same Arc<AppState>.
+    //    session, each sharing the same Arc<AppState> and allowlist.
     let state_for_factory = state.clone();
     let service = StreamableHttpService::new(
-        move || Ok(PolisAgentTools::new(state_for_factory.clone())),
+        move || {
+            Ok(PolisAgentTools::new(
+                state_for_factory.clone(),
+                enabled_tools.as_deref(),
+            ))
+        },
         LocalSessionManager::default().into(),
         Default::default(),
     );
 
-    // 5. Compose the axum router:
-    //    - `/mcp`    → MCP Streamable-HTTP transport
-    //    - `/health` → Docker health-check probe
-    let router = axum::Router::new()
-        .nest_service("/mcp", service)
-        .route("/health", axum::routing::get(health));
+    // 5. Compose the axum router, with CORS on `/mcp` when configured.
+    let cors = config
+        .cors_origins
+        .as_deref()
+        .map(|origins| cors::cors_layer(origins, config.cors_allow_credentials))
+        .transpose()
+        .context("invalid CORS configuration")?;
+    if let Some(origins) = &config.cors_origins {
+        tracing::info!(origins = %origins, "CORS enabled for /mcp");
+    }
+    let router = build_router(service, state, cors, config.max_body_bytes);
 
     // 6. Bind and serve (TLS or plaintext).
     let addr: std::net::SocketAddr = config
@@ -142,28 +269,65 @@ async fn main() -> Result<()> {
         .parse()
         .context("invalid listen address")?;
 
+    // Under systemd socket activation the socket is already bound for us.
+    let inherited = activation::inherited_listener()?;
+
     if let (Some(cert_path), Some(key_path)) = (&config.tls_cert, &config.tls_key) {
         tracing::info!("TLS enabled — loading cert from {}", cert_path);
         let tls_config = RustlsConfig::from_pem_file(cert_path, key_path)
             .await
             .context("failed to load TLS certificates")?;
 
-        tracing::info!("MCP server ready — https://{}/mcp", config.listen_addr,);
-
-        axum_server::bind_rustls(addr, tls_config)
+        // On SIGINT or SIGTERM, stop accepting connections and give
+        // in-flight MCP sessions up to the drain timeout to finish.
+        let handle = axum_server::Handle::new();
+        let drain = handle.clone();
+        let drain_timeout = std::time::Duration::from_secs(config.shutdown_timeout_secs);
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            tracing::info!(
+                timeout_secs = drain_timeout.as_secs(),
+                "draining in-flight connections",
+            );
+            drain.graceful_shutdown(Some(drain_timeout));
+        });
+
+        let ready_addr = match &inherited {
+            Some(listener) => listener
+                .local_addr()
+                .context("failed to read listener address")?,
+            None => addr,
+        };
+        let server = match inherited {
+            Some(listener) => axum_server::from_tcp_rustls(listener, tls_config)
+                .context("failed to use socket passed by systemd")?,
+            None => axum_server::bind_rustls(addr, tls_config),
+        };
+
+        tracing::info!("MCP server ready — https://{}/mcp", ready_addr);
+
+        server
+            .handle(handle)
             .serve(router.into_make_service())
             .await
             .context("HTTPS server error")?;
+        tracing::info!("connection drain complete");
     } else {
+        let listener = match inherited {
+            Some(listener) => tokio::net::TcpListener::from_std(listener)
+                .context("failed to use socket passed by systemd")?,
+            None => tokio::net::TcpListener::bind(&config.listen_addr)
+                .await
+                .context("failed to bind TCP listener")?,
+        };
+
         tracing::info!(
             "MCP server ready — http://{}/mcp (TLS disabled)",
-            config.listen_addr,
+            listener
+                .local_addr()
+                .context("failed to read listener address")?,
         );
 
-        let listener = tokio::net::TcpListener::bind(&config.listen_addr)
-            .await
-            .context("failed to bind TCP listener")?;
-
         axum::serve(listener, router)
             .with_graceful_shutdown(shutdown_signal())
             .await
@@ -174,10 +338,188 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-/// Wait for SIGINT (Ctrl-C) for graceful shutdown.
+/// Wait for SIGINT (Ctrl-C) or, on Unix, SIGTERM — what Docker and systemd
+/// send on stop — for graceful shutdown.
 async fn shutdown_signal() {
-    tokio::signal::ctrl_c()
-        .await
-        .expect("failed to install Ctrl-C handler");
-    tracing::info!("received shutdown signal");
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl-C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+    // No SIGTERM elsewhere; only Ctrl-C triggers shutdown.
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    let signal = tokio::select! {
+        () = ctrl_c => "SIGINT",
+        () = terminate => "SIGTERM",
+    };
+    tracing::info!(signal, "received shutdown signal");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::header::{ACCESS_CONTROL_ALLOW_ORIGIN, CONTENT_LENGTH, ORIGIN};
+    use tower::ServiceExt;
+
+    /// Stand-in for the Valkey connection behind `/health/ready`.
+    #[derive(Clone, Copy)]
+    enum StubPinger {
+        Up,
+        Down,
+        Hung,
+    }
+
+    impl health::Pinger for StubPinger {
+        fn ping(&self) -> impl std::future::Future<Output = Result<()>> + Send {
+            let stub = *self;
+            async move {
+                match stub {
+                    StubPinger::Up => Ok(()),
+                    StubPinger::Down => anyhow::bail!("connection refused"),
+                    StubPinger::Hung => std::future::pending().await,
+                }
+            }
+        }
+    }
+
+    fn router_with(pinger: StubPinger, cors: Option<CorsLayer>) -> axum::Router {
+        let mcp = tower::service_fn(|_req: Request| async {
+            Ok::<_, Infallible>(StatusCode::OK.into_response())
+        });
+        build_router(mcp, Arc::new(pinger), cors, DEFAULT_MAX_BODY_BYTES)
+    }
+
+    fn router(cors: Option<CorsLayer>) -> axum::Router {
+        router_with(StubPinger::Up, cors)
+    }
+
+    async fn get_status(router: axum::Router, path: &str) -> StatusCode {
+        let request = Request::builder().uri(path).body(Body::empty()).unwrap();
+        router.oneshot(request).await.unwrap().status()
+    }
+
+    async fn post_status(router: axum::Router, path: &str, len: usize) -> StatusCode {
+        let request = Request::builder()
+            .method("POST")
+            .uri(path)
+            .header(CONTENT_LENGTH, len)
+            .body(Body::from(vec![b'x'; len]))
+            .unwrap();
+        router.oneshot(request).await.unwrap().status()
+    }
+
+    async fn allow_origin(router: axum::Router, path: &str, origin: &str) -> Option<String> {
+        let request = Request::builder()
+            .method("GET")
+            .uri(path)
+            .header(ORIGIN, origin)
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        response
+            .headers()
+            .get(ACCESS_CONTROL_ALLOW_ORIGIN)
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn cors_allows_listed_origin_on_mcp() {
+        let cors = cors::cors_layer("https://ui.example", false).unwrap();
+        let header = allow_origin(router(Some(cors)), "/mcp", "https://ui.example").await;
+        assert_eq!(header.as_deref(), Some("https://ui.example"));
+    }
+
+    #[tokio::test]
+    async fn cors_rejects_unlisted_origin_on_mcp() {
+        let cors = cors::cors_layer("https://ui.example", false).unwrap();
+        let header = allow_origin(router(Some(cors)), "/mcp", "https://evil.example").await;
+        assert_eq!(header, None);
+    }
+
+    #[tokio::test]
+    async fn cors_disabled_by_default() {
+        let header = allow_origin(router(None), "/mcp", "https://ui.example").await;
+        assert_eq!(header, None);
+    }
+
+    #[tokio::test]
+    async fn cors_not_applied_to_health() {
+        let cors = cors::cors_layer("https://ui.example", false).unwrap();
+        let header = allow_origin(router(Some(cors)), "/health", "https://ui.example").await;
+        assert_eq!(header, None);
+    }
+
+    #[tokio::test]
+    async fn liveness_ok_even_when_valkey_is_down() {
+        for path in ["/health", "/health/live"] {
+            let status = get_status(router_with(StubPinger::Down, None), path).await;
+            assert_eq!(status, StatusCode::OK, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn readiness_ok_when_valkey_answers() {
+        let status = get_status(router_with(StubPinger::Up, None), "/health/ready").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn readiness_unavailable_when_ping_fails() {
+        let status = get_status(router_with(StubPinger::Down, None), "/health/ready").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn readiness_unavailable_when_ping_times_out() {
+        let status = get_status(router_with(StubPinger::Hung, None), "/health/ready").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn mcp_body_over_limit_returns_413() {
+        let status = post_status(router(None), "/mcp", DEFAULT_MAX_BODY_BYTES + 1).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn mcp_body_at_limit_is_accepted() {
+        let status = post_status(router(None), "/mcp", DEFAULT_MAX_BODY_BYTES).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    fn write_temp(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn read_password_rejects_whitespace_only_file() {
+        let path = write_temp("polis-empty-pass", " \n\t\n");
+        let err = read_password(path.to_str().unwrap()).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            err.to_string(),
+            format!("password file {} is empty", path.display())
+        );
+    }
+
+    #[test]
+    fn read_password_trims_surrounding_whitespace() {
+        let path = write_temp("polis-pass", "s3cret\n");
+        let password = read_password(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(password, "s3cret");
+    }
 }
@@ -1,12 +1,26 @@
 //! Application state wrapping a Fred Redis/Valkey client with mTLS via rustls.
+//!
+//! Fred reconnects on its own, but gives up after its reconnect policy is
+//! exhausted (e.g. a long Valkey restart). Commands therefore go through
+//! [`AppState::with_reconnect`] or [`AppState::write_with_reconnect`], which
+//! re-establish the connection (ACL auth + PING) when a command fails with a
+//! connection error. Reads are then retried once, so a read only fails if
+//! Valkey is still unreachable. Writes are not: the failed attempt may
+//! already have been applied, so the error is returned instead.
+//!
+//! Concurrent failures share one reconnect through a [`ReconnectGate`].
 
 use anyhow::{Context, Result};
+use fred::error::{Error as ValkeyError, ErrorKind};
 use fred::prelude::*;
 use fred::types::config::{TlsConfig, TlsConnector, TlsHostMapping};
 use fred::types::scan::Scanner;
 use std::fs::File;
+use std::future::Future;
 use std::io::BufReader;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use polis_common::{
     approved_key, blocked_key,
@@ -18,9 +32,46 @@ const DEFAULT_VALKEY_CA_PATH: &str = "/etc/valkey/tls/ca.crt";
 const DEFAULT_VALKEY_CLIENT_CERT_PATH: &str = "/etc/valkey/tls/client.crt";
 const DEFAULT_VALKEY_CLIENT_KEY_PATH: &str = "/etc/valkey/tls/client.key";
 
+/// Longest a reconnect (TLS handshake, ACL auth and PING) may take before
+/// the original command error is surfaced.
+const RECONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(Clone)]
 pub struct AppState {
     client: Client,
+    reconnects: Arc<ReconnectGate>,
+}
+
+/// Serializes reconnects so that requests failing at the same time trigger
+/// one reconnect, not one each.
+#[derive(Default)]
+struct ReconnectGate {
+    /// Number of successful reconnects so far.
+    generation: AtomicU64,
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl ReconnectGate {
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Run `reconnect` unless another task has reconnected since `seen`
+    /// was read from [`Self::generation`], i.e. since the caller's command
+    /// was sent.
+    async fn reconnect<F, Fut>(&self, seen: u64, reconnect: F) -> Result<()>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let _guard = self.lock.lock().await;
+        if self.generation() != seen {
+            return Ok(());
+        }
+        reconnect().await?;
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
 }
 
 impl AppState {
@@ -102,22 +153,83 @@ impl AppState {
             "Valkey connection ready with mTLS (rustls)"
         );
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            reconnects: Arc::default(),
+        })
+    }
+
+    /// PING Valkey; used by the optional health watcher.
+    pub async fn ping(&self) -> Result<()> {
+        self.client.ping::<String>(None).await?;
+        Ok(())
+    }
+
+    /// Run the read-only Valkey command `op`, reconnecting and retrying it
+    /// once if it fails with a connection error. Other errors are returned
+    /// as-is.
+    pub async fn with_reconnect<T, F, Fut>(&self, op: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ValkeyError>>,
+    {
+        let seen = self.reconnects.generation();
+        retry_after_reconnect(op, self.reconnect(seen)).await
+    }
+
+    /// Run the Valkey write `op`, reconnecting if it fails with a connection
+    /// error so the next command finds a working connection. The write is
+    /// not retried, since it may have been applied before the connection
+    /// dropped; its error is returned.
+    pub async fn write_with_reconnect<T, Fut>(&self, op: Fut) -> Result<T>
+    where
+        Fut: Future<Output = Result<T, ValkeyError>>,
+    {
+        let seen = self.reconnects.generation();
+        reconnect_after_failure(op, self.reconnect(seen)).await
+    }
+
+    /// Reconnect, unless another task already did since generation `seen`.
+    async fn reconnect(&self, seen: u64) -> Result<()> {
+        self.reconnects
+            .reconnect(seen, || self.reconnect_now())
+            .await
+    }
+
+    /// Re-establish the connection and PING, within [`RECONNECT_TIMEOUT`].
+    /// The client config carries the ACL credentials, so AUTH is re-run as
+    /// part of the handshake.
+    async fn reconnect_now(&self) -> Result<()> {
+        let attempt = async {
+            if self.client.is_connected() {
+                self.client.force_reconnection().await?;
+            } else {
+                // The reconnect policy gave up; start a fresh connection task.
+                self.client.init().await?;
+            }
+            self.client.ping::<String>(None).await?;
+            Ok::<_, ValkeyError>(())
+        };
+        tokio::time::timeout(RECONNECT_TIMEOUT, attempt)
+            .await
+            .context("Valkey reconnect timed out")?
+            .context("Valkey reconnect failed")?;
+        tracing::info!("Valkey connection re-established");
+        Ok(())
     }
 
     pub async fn store_blocked_request(&self, request: &BlockedRequest) -> Result<()> {
         let key = blocked_key(&request.request_id);
         let json = serde_json::to_string(request)?;
 
-        self.client
-            .set::<(), _, _>(
-                &key,
-                json,
-                Some(Expiration::EX(ttl::BLOCKED_REQUEST_SECS as i64)),
-                None,
-                false,
-            )
-            .await?;
+        self.write_with_reconnect(self.client.set::<(), _, _>(
+            &key,
+            json.as_str(),
+            Some(Expiration::EX(ttl::BLOCKED_REQUEST_SECS as i64)),
+            None,
+            false,
+        ))
+        .await?;
 
         tracing::info!(
             request_id = %request.request_id,
@@ -136,7 +248,9 @@ impl AppState {
     }
 
     pub async fn get_security_level(&self) -> Result<SecurityLevel> {
-        let raw: Option<String> = self.client.get(keys::SECURITY_LEVEL).await?;
+        let raw: Option<String> = self
+            .with_reconnect(|| self.client.get(keys::SECURITY_LEVEL))
+            .await?;
 
         match raw {
             Some(val) => {
@@ -155,7 +269,9 @@ impl AppState {
             return Ok(Vec::new());
         }
 
-        let values: Vec<Value> = self.client.mget(matched_keys.clone()).await?;
+        let values: Vec<Value> = self
+            .with_reconnect(|| self.client.mget(matched_keys.clone()))
+            .await?;
 
         let mut results = Vec::with_capacity(values.len());
         for (i, value) in values.into_iter().enumerate() {
@@ -182,13 +298,17 @@ impl AppState {
         let approved_key = approved_key(request_id);
         let blocked_key = blocked_key(request_id);
 
-        let approved_exists: bool = self.client.exists(&approved_key).await?;
+        let approved_exists: bool = self
+            .with_reconnect(|| self.client.exists(&approved_key))
+            .await?;
 
         if approved_exists {
             return Ok(RequestStatus::Approved);
         }
 
-        let blocked_exists: bool = self.client.exists(&blocked_key).await?;
+        let blocked_exists: bool = self
+            .with_reconnect(|| self.client.exists(&blocked_key))
+            .await?;
 
         if blocked_exists {
             Ok(RequestStatus::Pending)
@@ -202,19 +322,21 @@ impl AppState {
         let blocked_key = blocked_key(request_id);
         let approved_key = approved_key(request_id);
 
-        let json: Option<String> = self.client.get(&blocked_key).await?;
+        let json: Option<String> = self
+            .with_reconnect(|| self.client.get(&blocked_key))
+            .await?;
         let json = json.context("blocked request not found")?;
 
-        self.client
-            .set::<(), _, _>(
-                &approved_key,
-                json,
-                Some(Expiration::EX(ttl::APPROVED_REQUEST_SECS as i64)),
-                None,
-                false,
-            )
+        self.write_with_reconnect(self.client.set::<(), _, _>(
+            &approved_key,
+            json.as_str(),
+            Some(Expiration::EX(ttl::APPROVED_REQUEST_SECS as i64)),
+            None,
+            false,
+        ))
+        .await?;
+        self.write_with_reconnect(self.client.del::<(), _>(&blocked_key))
             .await?;
-        self.client.del::<(), _>(&blocked_key).await?;
 
         tracing::info!(request_id, "approved request");
         Ok(())
@@ -224,23 +346,27 @@ impl AppState {
         let json = serde_json::to_string(entry)?;
         let score = entry.timestamp.timestamp() as f64;
 
-        self.client
-            .zadd::<(), _, _>(
-                keys::EVENT_LOG,
-                None,
-                None,
-                false,
-                false,
-                (score, json.as_str()),
-            )
-            .await?;
+        self.write_with_reconnect(self.client.zadd::<(), _, _>(
+            keys::EVENT_LOG,
+            None,
+            None,
+            false,
+            false,
+            (score, json.as_str()),
+        ))
+        .await?;
 
         // Trim to last 1000 entries (remove oldest by rank)
-        let count: i64 = self.client.zcard(keys::EVENT_LOG).await?;
+        let count: i64 = self
+            .with_reconnect(|| self.client.zcard(keys::EVENT_LOG))
+            .await?;
         if count > 1000 {
-            self.client
-                .zremrangebyrank::<(), _>(keys::EVENT_LOG, 0, count - 1001)
-                .await?;
+            self.write_with_reconnect(self.client.zremrangebyrank::<(), _>(
+                keys::EVENT_LOG,
+                0,
+                count - 1001,
+            ))
+            .await?;
         }
 
         Ok(())
@@ -248,8 +374,10 @@ impl AppState {
 
     pub async fn get_security_log(&self, limit: usize) -> Result<Vec<SecurityLogEntry>> {
         let entries: Vec<String> = self
-            .client
-            .zrevrange(keys::EVENT_LOG, 0, (limit as i64) - 1, false)
+            .with_reconnect(|| {
+                self.client
+                    .zrevrange(keys::EVENT_LOG, 0, (limit as i64) - 1, false)
+            })
             .await?;
 
         let mut results = Vec::with_capacity(entries.len());
@@ -270,6 +398,10 @@ impl AppState {
     }
 
     async fn scan_keys(&self, pattern: &str) -> Result<Vec<String>> {
+        self.with_reconnect(|| self.scan_keys_once(pattern)).await
+    }
+
+    async fn scan_keys_once(&self, pattern: &str) -> Result<Vec<String>, ValkeyError> {
         use futures::stream::TryStreamExt;
 
         let mut keys = Vec::new();
@@ -286,3 +418,189 @@ impl AppState {
         Ok(keys)
     }
 }
+
+/// Whether `err` means the connection is unusable, as opposed to a bad
+/// command or reply; only these are worth a reconnect.
+fn is_connection_error(err: &ValkeyError) -> bool {
+    matches!(
+        err.kind(),
+        ErrorKind::IO | ErrorKind::Canceled | ErrorKind::Timeout
+    )
+}
+
+/// Run `op`; on a connection error, await `reconnect` and run `op` once
+/// more. If the reconnect fails, that error is returned with the original
+/// one as context.
+async fn retry_after_reconnect<T, F, Fut, R>(mut op: F, reconnect: R) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ValkeyError>>,
+    R: Future<Output = Result<()>>,
+{
+    match op().await {
+        Err(e) if is_connection_error(&e) => {
+            tracing::warn!(error = %e, "Valkey command failed; reconnecting");
+            reconnect
+                .await
+                .with_context(|| format!("Valkey command failed: {e}"))?;
+            Ok(op().await?)
+        }
+        result => Ok(result?),
+    }
+}
+
+/// Await the write `op`; on a connection error, await `reconnect` and
+/// return the write's error without retrying it. A failed reconnect is
+/// logged; the next command will try again.
+async fn reconnect_after_failure<T, Fut, R>(op: Fut, reconnect: R) -> Result<T>
+where
+    Fut: Future<Output = Result<T, ValkeyError>>,
+    R: Future<Output = Result<()>>,
+{
+    match op.await {
+        Err(e) if is_connection_error(&e) => {
+            tracing::warn!(error = %e, "Valkey write failed; reconnecting without retrying it");
+            if let Err(re) = reconnect.await {
+                tracing::warn!(error = %format!("{re:#}"), "Valkey reconnect failed");
+            }
+            Err(anyhow::Error::new(e).context("Valkey write failed and was not retried"))
+        }
+        result => Ok(result?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Mock Valkey command: fails with `errors` in order, then succeeds.
+    struct MockCommand {
+        errors: Vec<ErrorKind>,
+        calls: AtomicUsize,
+    }
+
+    impl MockCommand {
+        fn new(errors: Vec<ErrorKind>) -> Self {
+            Self {
+                errors,
+                calls: AtomicUsize::new(0),
+            }
+        }
+
+        fn run(&self) -> std::future::Ready<Result<&'static str, ValkeyError>> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst);
+            std::future::ready(match self.errors.get(n) {
+                Some(kind) => Err(ValkeyError::new(kind.clone(), "mock failure")),
+                None => Ok("OK"),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn dropped_connection_is_reconnected_and_retried_once() {
+        let command = MockCommand::new(vec![ErrorKind::IO]);
+        let reconnects = AtomicUsize::new(0);
+        let reconnect = async {
+            reconnects.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        };
+
+        let reply = retry_after_reconnect(|| command.run(), reconnect)
+            .await
+            .unwrap();
+        assert_eq!(reply, "OK");
+        assert_eq!(command.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(reconnects.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn second_failure_is_returned_without_another_retry() {
+        let command = MockCommand::new(vec![ErrorKind::IO, ErrorKind::IO, ErrorKind::IO]);
+        let result = retry_after_reconnect(|| command.run(), async { Ok(()) }).await;
+        assert!(result.is_err());
+        assert_eq!(command.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_reconnect_surfaces_with_original_error() {
+        let command = MockCommand::new(vec![ErrorKind::Canceled]);
+        let reconnect = async { anyhow::bail!("connection refused") };
+        let err = retry_after_reconnect(|| command.run(), reconnect)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().starts_with("Valkey command failed"),
+            "{err:#}"
+        );
+        assert!(format!("{err:#}").contains("connection refused"), "{err:#}");
+        assert_eq!(command.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn command_errors_do_not_reconnect() {
+        let command = MockCommand::new(vec![ErrorKind::InvalidArgument]);
+        let reconnect = async { panic!("must not reconnect on a command error") };
+        let result = retry_after_reconnect(|| command.run(), reconnect).await;
+        assert!(result.is_err());
+        assert_eq!(command.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_write_reconnects_without_retrying() {
+        let command = MockCommand::new(vec![ErrorKind::Timeout]);
+        let reconnects = AtomicUsize::new(0);
+        let reconnect = async {
+            reconnects.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        };
+
+        let err = reconnect_after_failure(command.run(), reconnect)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not retried"), "{err:#}");
+        assert_eq!(command.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(reconnects.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn concurrent_failures_share_one_reconnect() {
+        let gate = ReconnectGate::default();
+        let reconnects = AtomicUsize::new(0);
+        let reconnect = || async {
+            reconnects.fetch_add(1, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            Ok(())
+        };
+
+        let seen = gate.generation();
+        let (a, b) = tokio::join!(
+            gate.reconnect(seen, reconnect),
+            gate.reconnect(seen, reconnect)
+        );
+        a.unwrap();
+        b.unwrap();
+        assert_eq!(reconnects.load(Ordering::SeqCst), 1);
+
+        gate.reconnect(gate.generation(), reconnect).await.unwrap();
+        assert_eq!(reconnects.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_reconnect_lets_the_next_caller_try_again() {
+        let gate = ReconnectGate::default();
+        let seen = gate.generation();
+        assert!(gate
+            .reconnect(seen, || async { anyhow::bail!("connection refused") })
+            .await
+            .is_err());
+        let reconnected = AtomicUsize::new(0);
+        gate.reconnect(seen, || async {
+            reconnected.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+        .await
+        .unwrap();
+        assert_eq!(reconnected.load(Ordering::SeqCst), 1);
+    }
+}
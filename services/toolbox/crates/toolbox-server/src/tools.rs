@@ -7,6 +7,9 @@
 //!   - `get_security_log`
 //!   - `check_request_status`
 //!
+//! `polis_AGENT_ENABLED_TOOLS` can narrow this to a subset; see
+//! [`parse_enabled_tools`].
+//!
 //! **Security constraint**: No `approve_request`, `deny_request`,
 //! `configure_auto_approve`, or `set_security_level` tools are exposed.
 //! These operations are reserved for the CLI / MCP-Admin (spec 10).
@@ -28,6 +31,15 @@ use polis_common::{
 
 use crate::state::AppState;
 
+/// Names of every tool registered by [`PolisAgentTools`].
+pub const TOOL_NAMES: [&str; 5] = [
+    "report_block",
+    "get_security_status",
+    "list_pending_approvals",
+    "get_security_log",
+    "check_request_status",
+];
+
 // ===================================================================
 // Input structs
 // ===================================================================
@@ -134,12 +146,28 @@ impl std::fmt::Debug for PolisAgentTools {
 
 impl PolisAgentTools {
     /// Create a new `PolisAgentTools` with the given application state.
-    pub fn new(state: Arc<AppState>) -> Self {
+    ///
+    /// `enabled` restricts the registered tools to the listed names;
+    /// `None` registers all of [`TOOL_NAMES`].
+    pub fn new(state: Arc<AppState>, enabled: Option<&[&str]>) -> Self {
         Self {
             state,
-            tool_router: Self::tool_router(),
+            tool_router: Self::enabled_router(enabled),
         }
     }
+
+    /// Build the tool router, dropping every tool not in `enabled`.
+    fn enabled_router(enabled: Option<&[&str]>) -> ToolRouter<Self> {
+        let mut router = Self::tool_router();
+        if let Some(enabled) = enabled {
+            for name in TOOL_NAMES {
+                if !enabled.contains(&name) {
+                    router.remove_route(name);
+                }
+            }
+        }
+        router
+    }
 }
 
 // -------------------------------------------------------------------
@@ -368,6 +396,29 @@ fn parse_block_reason(reason: &str) -> Result<polis_common::BlockReason, String>
     })
 }
 
+/// Parse the comma-separated `polis_AGENT_ENABLED_TOOLS` allowlist.
+///
+/// Every entry must be one of [`TOOL_NAMES`]; an unknown name or an empty
+/// list is an error so a typo cannot silently disable every tool.
+pub fn parse_enabled_tools(raw: &str) -> Result<Vec<&'static str>, String> {
+    let mut enabled = Vec::new();
+    for name in raw.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let known = TOOL_NAMES.iter().find(|t| **t == name).ok_or_else(|| {
+            format!(
+                "Unknown tool '{name}' in polis_AGENT_ENABLED_TOOLS. Expected any of: {}",
+                TOOL_NAMES.join(", "),
+            )
+        })?;
+        if !enabled.contains(known) {
+            enabled.push(*known);
+        }
+    }
+    if enabled.is_empty() {
+        return Err("polis_AGENT_ENABLED_TOOLS is set but lists no tools".to_string());
+    }
+    Ok(enabled)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -385,4 +436,34 @@ mod tests {
         assert!(parse_block_reason("unknown").is_err());
         assert!(parse_block_reason("").is_err());
     }
+
+    #[test]
+    fn tool_names_match_registered_tools() {
+        let router = PolisAgentTools::enabled_router(None);
+        assert_eq!(router.list_all().len(), TOOL_NAMES.len());
+        for name in TOOL_NAMES {
+            assert!(router.has_route(name), "{name} is not registered");
+        }
+    }
+
+    #[test]
+    fn parse_enabled_tools_rejects_unknown_name() {
+        let err = parse_enabled_tools("report_block, approve_request").unwrap_err();
+        assert!(err.contains("approve_request"));
+    }
+
+    #[test]
+    fn parse_enabled_tools_rejects_empty_list() {
+        assert!(parse_enabled_tools(" , ").is_err());
+    }
+
+    #[test]
+    fn enabled_subset_registers_only_listed_tools() {
+        let enabled = parse_enabled_tools("get_security_status,check_request_status").unwrap();
+        let router = PolisAgentTools::enabled_router(Some(&enabled));
+        assert_eq!(router.list_all().len(), 2);
+        assert!(router.has_route("get_security_status"));
+        assert!(router.has_route("check_request_status"));
+        assert!(!router.has_route("report_block"));
+    }
 }